@@ -0,0 +1,312 @@
+//! Generic calibration of parameterised models priced through a `PriceEngine` <br>
+//! 通过`PriceEngine`定价的参数化模型的通用校准
+//!
+//! A model describes its free parameters and their bounds through [`Calibratable`] and supplies
+//! the engine that prices instruments under it. The [`Calibrator`] minimises the weighted price
+//! errors over a list of instruments with a chain of [`CalibrationOptimizer`]s: each optimizer
+//! starts from the best point found so far and the chain stops at the first converged result.
+//! Everything is deterministic as long as the engines are (fix the seed of Monte Carlo engines). <br>
+//! 模型通过[`Calibratable`]描述其自由参数及约束，并提供在该模型下为产品定价的引擎。
+//! [`Calibrator`]以一组[`CalibrationOptimizer`]依次最小化加权价格误差：每个优化器从当前最优点
+//! 出发，首个收敛的结果即停止。只要引擎本身是确定性的（蒙特卡洛引擎需固定种子），校准结果即可复现
+use std::sync::Arc;
+use crate::calibration::{CalibrationReport,OptionQuote,Optimizer,WeightScheme};
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::traits::engine::PriceEngine;
+use crate::traits::exercise::{EuropeanExercise,ExerciseRule};
+use crate::traits::payoff::{CallPayoff,Payoff,PutPayoff};
+use crate::utils::optimization::{Minimum,levenberg_marquardt,nelder_mead};
+
+/// Volatility carried by quote instruments whose weights do not need the implied volatility <br>
+/// 权重不需要隐含波动率时报价产品携带的波动率
+const PLACEHOLDER_VOLATILITY:f64=0.2;
+
+/// A model whose parameters can be fitted to market prices <br>
+/// 参数可拟合到市场价格的模型
+pub trait Calibratable:Sized{
+    /// Engine pricing instruments under the model 在该模型下为产品定价的引擎
+    type Engine:PriceEngine;
+
+    /// Names of the free parameters, in the order of `parameters` 自由参数名称（与`parameters`同序）
+    fn parameter_names(&self)->Vec<&'static str>;
+
+    /// Current values of the free parameters 自由参数的当前值
+    fn parameters(&self)->Vec<f64>;
+
+    /// Default (lower, upper) bound of every free parameter 每个自由参数的默认(下界, 上界)
+    fn bounds(&self)->Vec<(f64,f64)>;
+
+    /// Copy of the model with new parameter values 以新参数值创建模型副本
+    fn with_parameters(&self,parameters:&[f64])->Result<Self>;
+
+    /// Engine for the current parameter values 当前参数值对应的引擎
+    fn engine(&self)->Result<Self::Engine>;
+
+    /// Market parameters passed to the engine for an instrument; models that act through the
+    /// volatility (e.g. parametric local or implied volatility) override this <br>
+    /// 传给引擎的产品市场参数；通过波动率起作用的模型（如参数化局部/隐含波动率）需重写
+    fn market_params(&self,params:&CommonParams,_payoff:&dyn Payoff)->Result<CommonParams>{
+        Ok(*params)
+    }
+}
+
+/// An instrument with its market price 带市场价格的产品
+#[derive(Clone)]
+pub struct CalibrationInstrument{
+    pub params:CommonParams,
+    pub payoff:Arc<dyn Payoff>,
+    pub exercise:Arc<dyn ExerciseRule>,
+    /// Reference strike shown in the report 报告中显示的参考行权价
+    pub strike:f64,
+    pub market_price:f64,
+    pub weight:f64,
+}
+
+impl CalibrationInstrument{
+    pub fn new(
+        params:CommonParams,
+        payoff:Arc<dyn Payoff>,
+        exercise:Arc<dyn ExerciseRule>,
+        strike:f64,
+        market_price:f64,
+    )->Self{
+        Self{params,payoff,exercise,strike,market_price,weight:1.0}
+    }
+
+    pub fn with_weight(mut self,weight:f64)->Self{
+        self.weight=weight;
+        self
+    }
+
+    /// European vanilla instruments for `quotes`, weighted by `weights`. The volatility in their
+    /// `CommonParams` is the Black-Scholes implied volatility of the quote when the weights use
+    /// it (`WeightScheme::Vega`) and a placeholder otherwise, so quotes the Black-Scholes
+    /// inverter rejects are still accepted; models that act through the volatility set it in
+    /// `Calibratable::market_params` <br>
+    /// 由`quotes`构造按`weights`加权的欧式普通期权产品。权重使用隐含波动率时
+    /// （`WeightScheme::Vega`），其`CommonParams`中的波动率为报价的Black-Scholes隐含波动率，
+    /// 否则为占位值，因此Black-Scholes反解失败的报价仍可使用；通过波动率起作用的模型应在
+    /// `Calibratable::market_params`中设置波动率
+    pub fn from_quotes(
+        quotes:&[OptionQuote],
+        spot:f64,
+        risk_free_rate:f64,
+        dividend_yield:f64,
+        weights:WeightScheme,
+    )->Result<Vec<Self>>{
+        let implied=weights.uses_implied_volatility();
+        let weights=weights.weights(quotes,spot,risk_free_rate,dividend_yield)?;
+        quotes.iter().zip(weights).map(|(quote,weight)|{
+            let volatility=if implied{
+                quote.implied_volatility(spot,risk_free_rate,dividend_yield)?
+            }else{
+                PLACEHOLDER_VOLATILITY
+            };
+            let params=CommonParams::new(spot,risk_free_rate,volatility,dividend_yield,quote.expiry)?;
+            let payoff:Arc<dyn Payoff>=if quote.is_call{
+                Arc::new(CallPayoff::new(quote.strike))
+            }else{
+                Arc::new(PutPayoff::new(quote.strike))
+            };
+            Ok(Self::new(params,payoff,Arc::new(EuropeanExercise),quote.strike,quote.price).with_weight(weight))
+        }).collect()
+    }
+}
+
+/// Bounded least-squares optimizer used by the calibrator 校准器使用的带约束最小二乘优化器
+pub trait CalibrationOptimizer:Send+Sync{
+    fn kind(&self)->Optimizer;
+
+    /// Minimise ‖residuals(x)‖² within [lower, upper] 在[lower, upper]内最小化‖residuals(x)‖²
+    fn minimize(
+        &self,
+        residuals:&dyn Fn(&[f64])->Vec<f64>,
+        initial:&[f64],
+        lower:&[f64],
+        upper:&[f64],
+    )->Result<Minimum>;
+}
+
+/// Levenberg-Marquardt with projected steps 投影步长的Levenberg-Marquardt
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct LevenbergMarquardtOptimizer{
+    pub tolerance:f64,
+    pub max_iterations:usize,
+}
+
+impl Default for LevenbergMarquardtOptimizer{
+    fn default()->Self{
+        Self{tolerance:1e-12,max_iterations:200}
+    }
+}
+
+impl CalibrationOptimizer for LevenbergMarquardtOptimizer{
+    fn kind(&self)->Optimizer{
+        Optimizer::LevenbergMarquardt
+    }
+
+    fn minimize(&self,residuals:&dyn Fn(&[f64])->Vec<f64>,initial:&[f64],lower:&[f64],upper:&[f64])->Result<Minimum>{
+        levenberg_marquardt(residuals,initial,lower,upper,self.tolerance,self.max_iterations)
+    }
+}
+
+/// Nelder-Mead with points outside the bounds rejected 拒绝约束外点的Nelder-Mead
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct NelderMeadOptimizer{
+    pub tolerance:f64,
+    pub max_iterations:usize,
+}
+
+impl Default for NelderMeadOptimizer{
+    fn default()->Self{
+        Self{tolerance:1e-12,max_iterations:10000}
+    }
+}
+
+impl CalibrationOptimizer for NelderMeadOptimizer{
+    fn kind(&self)->Optimizer{
+        Optimizer::NelderMead
+    }
+
+    fn minimize(&self,residuals:&dyn Fn(&[f64])->Vec<f64>,initial:&[f64],lower:&[f64],upper:&[f64])->Result<Minimum>{
+        let n=initial.len();
+        let objective=|x:&[f64]|{
+            if (0..n).any(|i| x[i]<lower[i] || x[i]>upper[i]){
+                return f64::INFINITY;
+            }
+            residuals(x).iter().map(|e| e*e).sum()
+        };
+        let step:Vec<f64>=(0..n).map(|i|{
+            let width=upper[i]-lower[i];
+            let step=if width.is_finite(){0.1*width}else{0.1*initial[i].abs().max(0.1)};
+            // 初始单纯形朝可行方向展开
+            if initial[i]+step>upper[i]{-step}else{step}
+        }).collect();
+        nelder_mead(objective,initial,&step,self.tolerance,self.max_iterations)
+    }
+}
+
+/// Calibrated model with the fit report 校准后的模型与拟合报告
+#[derive(Debug,Clone)]
+pub struct CalibrationResult<M>{
+    pub model:M,
+    pub report:CalibrationReport,
+}
+
+/// Calibrator over any [`Calibratable`] model 适用于任意[`Calibratable`]模型的校准器
+#[derive(Clone)]
+pub struct Calibrator{
+    optimizers:Vec<Arc<dyn CalibrationOptimizer>>,
+    bounds:Option<Vec<(f64,f64)>>,
+}
+
+impl Calibrator{
+    /// Levenberg-Marquardt followed, if it does not converge, by Nelder-Mead <br>
+    /// Levenberg-Marquardt，不收敛时接Nelder-Mead
+    pub fn new()->Self{
+        Self{
+            optimizers:vec![
+                Arc::new(LevenbergMarquardtOptimizer::default()),
+                Arc::new(NelderMeadOptimizer::default()),
+            ],
+            bounds:None,
+        }
+    }
+
+    /// Replace the optimizer chain 替换优化器链
+    pub fn with_optimizers(mut self,optimizers:Vec<Arc<dyn CalibrationOptimizer>>)->Result<Self>{
+        if optimizers.is_empty(){
+            return Err(OptionError::InvalidParameter("The calibrator needs at least one optimizer".into()));
+        }
+        self.optimizers=optimizers;
+        Ok(self)
+    }
+
+    /// Override the bounds reported by the model 覆盖模型给出的参数约束
+    pub fn with_bounds(mut self,bounds:Vec<(f64,f64)>)->Result<Self>{
+        if bounds.iter().any(|(lower,upper)| lower>upper){
+            return Err(OptionError::InvalidParameter("Lower bounds must not exceed upper bounds".into()));
+        }
+        self.bounds=Some(bounds);
+        Ok(self)
+    }
+
+    /// Model prices of the instruments 产品的模型价格
+    pub fn model_prices<M:Calibratable>(model:&M,instruments:&[CalibrationInstrument])->Result<Vec<f64>>{
+        let engine=model.engine()?;
+        instruments.iter().map(|instrument|{
+            let params=model.market_params(&instrument.params,instrument.payoff.as_ref())?;
+            engine.calculate_price(&params,instrument.payoff.as_ref(),instrument.exercise.as_ref())
+        }).collect()
+    }
+
+    /// Run the optimizer chain on `residuals` within [lower, upper]: each optimizer starts from
+    /// the best point so far and the chain stops at the first converged result <br>
+    /// 在[lower, upper]内对`residuals`运行优化器链：每个优化器从当前最优点出发，首个收敛的结果即停止
+    pub fn minimize_residuals(
+        &self,
+        residuals:&dyn Fn(&[f64])->Vec<f64>,
+        initial:&[f64],
+        lower:&[f64],
+        upper:&[f64],
+    )->Result<(Minimum,Optimizer)>{
+        let mut point=initial.to_vec();
+        let mut best:Option<(Minimum,Optimizer)>=None;
+        for optimizer in &self.optimizers{
+            let Ok(minimum)=optimizer.minimize(residuals,&point,lower,upper) else{
+                continue;
+            };
+            let converged=minimum.converged;
+            if best.as_ref().is_none_or(|(previous,_)| minimum.value<previous.value){
+                point=minimum.point.clone();
+                best=Some((minimum,optimizer.kind()));
+            }
+            if converged{
+                break;
+            }
+        }
+        best.ok_or_else(|| OptionError::ConvergenceError("Every optimizer failed".into()))
+    }
+
+    /// Fit `initial` to the instruments 将`initial`拟合到产品
+    pub fn calibrate<M:Calibratable>(&self,initial:&M,instruments:&[CalibrationInstrument])->Result<CalibrationResult<M>>{
+        let start=initial.parameters();
+        let n=start.len();
+        let bounds=self.bounds.clone().unwrap_or_else(|| initial.bounds());
+        if n==0 || bounds.len()!=n{
+            return Err(OptionError::InvalidParameter("Bounds must match the model parameters".into()));
+        }
+        if initial.parameter_names().len()!=n{
+            return Err(OptionError::InvalidParameter("Parameter names must match the model parameters".into()));
+        }
+        if instruments.len()<n{
+            return Err(OptionError::InvalidParameter("Calibration needs at least as many instruments as parameters".into()));
+        }
+        let lower:Vec<f64>=bounds.iter().map(|b| b.0).collect();
+        let upper:Vec<f64>=bounds.iter().map(|b| b.1).collect();
+        let residuals=|x:&[f64]|->Vec<f64>{
+            let prices=initial.with_parameters(x).and_then(|model| Self::model_prices(&model,instruments));
+            match prices{
+                Ok(prices)=>prices.iter().zip(instruments)
+                    .map(|(price,instrument)| instrument.weight*(price-instrument.market_price))
+                    .collect(),
+                Err(_)=>vec![f64::NAN;instruments.len()],
+            }
+        };
+
+        let point:Vec<f64>=(0..n).map(|i| start[i].clamp(lower[i],upper[i])).collect();
+        let (minimum,optimizer)=self.minimize_residuals(&residuals,&point,&lower,&upper)?;
+        let model=initial.with_parameters(&minimum.point)?;
+        let prices=Self::model_prices(&model,instruments)?;
+        let parameters=model.parameter_names().into_iter().zip(model.parameters()).collect();
+        let report=CalibrationReport::new(parameters,instruments,&prices,optimizer,minimum.iterations,minimum.converged);
+        Ok(CalibrationResult{model,report})
+    }
+}
+
+impl Default for Calibrator{
+    fn default()->Self{
+        Self::new()
+    }
+}
@@ -0,0 +1,162 @@
+//! Calibration of the Heston model to European vanilla prices <br>
+//! Heston模型对欧式普通期权价格的校准
+//!
+//! The parameters (v₀, κ, θ, ξ, ρ) are fitted by Levenberg-Marquardt within box bounds, with
+//! a Nelder-Mead fallback when it fails to converge; every model price comes from the
+//! semi-analytic Heston engine through the generic [`Calibrator`]. <br>
+//! 参数(v₀, κ, θ, ξ, ρ)在区间约束内以Levenberg-Marquardt法拟合，不收敛时回退到Nelder-Mead；
+//! 模型价格均由Heston半解析引擎经通用[`Calibrator`]计算
+use std::sync::Arc;
+use crate::calibration::{
+    Calibratable,CalibrationInstrument,CalibrationReport,Calibrator,LevenbergMarquardtOptimizer,
+    NelderMeadOptimizer,OptionQuote,WeightScheme,
+};
+use crate::core::analytic::{HestonAnalyticEngine,HestonPricer};
+use crate::errors::*;
+use crate::params::heston::{HestonModelParams,HestonParams};
+
+/// Box bounds on (v₀, κ, θ, ξ, ρ) 参数(v₀, κ, θ, ξ, ρ)的区间约束
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct HestonBounds{
+    pub lower:[f64;5],
+    pub upper:[f64;5],
+}
+
+impl Default for HestonBounds{
+    fn default()->Self{
+        Self{
+            lower:[1e-4,1e-2,1e-4,1e-2,-0.999],
+            upper:[1.0,20.0,1.0,5.0,0.999],
+        }
+    }
+}
+
+/// Calibrated parameters with the fit report 校准参数与拟合报告
+#[derive(Debug,Clone)]
+pub struct HestonCalibration{
+    /// Calibrated model, with the longest quoted maturity 校准的模型（期限为报价的最长期限）
+    pub params:HestonParams,
+    pub report:CalibrationReport,
+}
+
+/// Heston calibrator 校准器
+#[derive(Debug,Clone)]
+pub struct HestonCalibrator{
+    spot:f64,
+    risk_free_rate:f64,
+    dividend_yield:f64,
+    weights:WeightScheme,
+    bounds:HestonBounds,
+    initial:[f64;5],
+    tolerance:f64,
+    max_iterations:usize,
+    engine:HestonAnalyticEngine,
+}
+
+impl HestonCalibrator{
+    /// Calibrator with uniform weights, default bounds and the initial guess
+    /// v₀ = θ = 0.04, κ = 2, ξ = 0.5, ρ = -0.5 <br>
+    /// 等权、默认约束，初值v₀ = θ = 0.04, κ = 2, ξ = 0.5, ρ = -0.5的校准器
+    pub fn new(spot:f64,risk_free_rate:f64,dividend_yield:f64)->Result<Self>{
+        if spot<=0.0{
+            return Err(OptionError::InvalidParameter("Spot must be greater than zero.".into()));
+        }
+        Ok(Self{
+            spot,
+            risk_free_rate,
+            dividend_yield,
+            weights:WeightScheme::default(),
+            bounds:HestonBounds::default(),
+            initial:[0.04,2.0,0.04,0.5,-0.5],
+            tolerance:1e-12,
+            max_iterations:200,
+            engine:HestonAnalyticEngine::new(),
+        })
+    }
+
+    pub fn with_weights(mut self,weights:WeightScheme)->Self{
+        self.weights=weights;
+        self
+    }
+
+    pub fn with_bounds(mut self,bounds:HestonBounds)->Result<Self>{
+        if (0..5).any(|i| bounds.lower[i]>bounds.upper[i]) || bounds.lower[0]<0.0 || bounds.lower[1]<=0.0
+            || bounds.lower[2]<=0.0 || bounds.lower[3]<=0.0 || bounds.lower[4]< -1.0 || bounds.upper[4]>1.0{
+            return Err(OptionError::InvalidParameter("Heston bounds must be ordered and admissible".into()));
+        }
+        self.bounds=bounds;
+        Ok(self)
+    }
+
+    /// Start the optimizer from `model` 以`model`作为初值
+    pub fn with_initial_guess(mut self,model:&HestonModelParams)->Self{
+        self.initial=[model.variance(),model.mean_reversion(),model.long_run_variance(),model.vol_of_vol(),model.correlation()];
+        self
+    }
+
+    pub fn with_tolerance(mut self,tolerance:f64)->Self{
+        self.tolerance=tolerance;
+        self
+    }
+
+    pub fn with_max_iterations(mut self,max_iterations:usize)->Self{
+        self.max_iterations=max_iterations;
+        self
+    }
+
+    pub fn with_engine(mut self,engine:HestonAnalyticEngine)->Self{
+        self.engine=engine;
+        self
+    }
+
+    /// Fit the model to `quotes` 将模型拟合到`quotes`
+    pub fn calibrate(&self,quotes:&[OptionQuote])->Result<HestonCalibration>{
+        if quotes.len()<5{
+            return Err(OptionError::InvalidParameter("Heston calibration needs at least 5 quotes".into()));
+        }
+        let instruments=CalibrationInstrument::from_quotes(quotes,self.spot,self.risk_free_rate,self.dividend_yield,self.weights)?;
+        let [v0,kappa,theta,xi,rho]=self.initial;
+        let model=HestonModelParams::new(v0,kappa,theta,xi,rho)?;
+        let calibrator=Calibrator::new()
+            .with_optimizers(vec![
+                Arc::new(LevenbergMarquardtOptimizer{tolerance:self.tolerance,max_iterations:self.max_iterations}),
+                Arc::new(NelderMeadOptimizer{tolerance:self.tolerance,max_iterations:50*self.max_iterations}),
+            ])?
+            .with_bounds((0..5).map(|i| (self.bounds.lower[i],self.bounds.upper[i])).collect())?;
+        let result=calibrator.calibrate(&HestonPricer::new(model).with_engine(self.engine.clone()),&instruments)?;
+        let maturity=quotes.iter().map(|q| q.expiry).fold(0.0,f64::max);
+        Ok(HestonCalibration{
+            params:HestonParams::from_model(self.spot,*result.model.model(),self.risk_free_rate,self.dividend_yield,maturity)?,
+            report:result.report,
+        })
+    }
+}
+
+impl Calibratable for HestonPricer{
+    type Engine=HestonPricer;
+
+    fn parameter_names(&self)->Vec<&'static str>{
+        vec!["variance","mean_reversion","long_run_variance","vol_of_vol","correlation"]
+    }
+
+    fn parameters(&self)->Vec<f64>{
+        let model=self.model();
+        vec![model.variance(),model.mean_reversion(),model.long_run_variance(),model.vol_of_vol(),model.correlation()]
+    }
+
+    fn bounds(&self)->Vec<(f64,f64)>{
+        let bounds=HestonBounds::default();
+        (0..5).map(|i| (bounds.lower[i],bounds.upper[i])).collect()
+    }
+
+    fn with_parameters(&self,parameters:&[f64])->Result<Self>{
+        let [variance,mean_reversion,long_run_variance,vol_of_vol,correlation]=parameters else{
+            return Err(OptionError::InvalidParameter("Heston has 5 parameters".into()));
+        };
+        Ok(self.with_model(HestonModelParams::new(*variance,*mean_reversion,*long_run_variance,*vol_of_vol,*correlation)?))
+    }
+
+    fn engine(&self)->Result<HestonPricer>{
+        Ok(self.clone())
+    }
+}
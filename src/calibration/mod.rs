@@ -0,0 +1,159 @@
+//! Model calibration to market option prices 模型对市场期权价格的校准
+pub mod calibrator;
+pub mod heston;
+
+pub use calibrator::{
+    Calibratable,CalibrationInstrument,CalibrationOptimizer,CalibrationResult,Calibrator,
+    LevenbergMarquardtOptimizer,NelderMeadOptimizer,
+};
+pub use heston::{HestonBounds,HestonCalibrator,HestonCalibration};
+
+use crate::errors::*;
+use crate::utils::statistics::{black_scholes_implied_volatility,calculate_d1_d2,norm_pdf};
+
+/// Market price of a European vanilla option, optionally with its bid-ask quote <br>
+/// 欧式普通期权的市场价格，可附带买卖报价
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct OptionQuote{
+    pub strike:f64,
+    pub expiry:f64,
+    pub price:f64,
+    pub is_call:bool,
+    pub bid_ask:Option<(f64,f64)>,
+}
+
+impl OptionQuote{
+    pub fn new(strike:f64,expiry:f64,price:f64,is_call:bool)->Result<Self>{
+        if strike<=0.0 || expiry<=0.0 || price<0.0{
+            return Err(OptionError::InvalidParameter("Quotes need positive strike and expiry and a non-negative price".into()));
+        }
+        Ok(Self{strike,expiry,price,is_call,bid_ask:None})
+    }
+
+    /// Quote at the mid of `bid` and `ask` 以买卖价中间价报价
+    pub fn from_bid_ask(strike:f64,expiry:f64,bid:f64,ask:f64,is_call:bool)->Result<Self>{
+        if bid<0.0 || ask<bid{
+            return Err(OptionError::InvalidParameter("Bid-ask quotes need 0 ≤ bid ≤ ask".into()));
+        }
+        let mut quote=Self::new(strike,expiry,0.5*(bid+ask),is_call)?;
+        quote.bid_ask=Some((bid,ask));
+        Ok(quote)
+    }
+
+    /// Black-Scholes implied volatility of the quote 报价的Black-Scholes隐含波动率
+    pub fn implied_volatility(&self,spot:f64,risk_free_rate:f64,dividend_yield:f64)->Result<f64>{
+        black_scholes_implied_volatility(self.price,spot,self.strike,risk_free_rate,dividend_yield,self.expiry,self.is_call)
+    }
+}
+
+/// Weighting of the price residuals in the calibration objective <br>
+/// 校准目标函数中价格残差的权重
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum WeightScheme{
+    /// Equal weights on price errors 价格误差等权
+    #[default]
+    Uniform,
+    /// Price errors divided by the Black-Scholes vega, approximately implied volatility errors <br>
+    /// 价格误差除以Black-Scholes vega，近似为隐含波动率误差
+    Vega,
+    /// Price errors divided by the bid-ask spread (every quote needs a bid-ask) <br>
+    /// 价格误差除以买卖价差（每条报价都需要买卖价）
+    BidAsk,
+}
+
+impl WeightScheme{
+    /// Whether the weights depend on the Black-Scholes implied volatility of the quotes <br>
+    /// 权重是否依赖报价的Black-Scholes隐含波动率
+    pub fn uses_implied_volatility(&self)->bool{
+        matches!(self,WeightScheme::Vega)
+    }
+
+    /// Residual weight of each quote 每条报价的残差权重
+    pub fn weights(&self,quotes:&[OptionQuote],spot:f64,risk_free_rate:f64,dividend_yield:f64)->Result<Vec<f64>>{
+        quotes.iter().map(|quote| match self{
+            WeightScheme::Uniform=>Ok(1.0),
+            WeightScheme::Vega=>{
+                let volatility=quote.implied_volatility(spot,risk_free_rate,dividend_yield)?;
+                let (d1,_)=calculate_d1_d2(spot,quote.strike,risk_free_rate,dividend_yield,volatility,quote.expiry)?;
+                let vega=spot*(-dividend_yield*quote.expiry).exp()*norm_pdf(d1)*quote.expiry.sqrt();
+                // 深度虚值期权的vega下限，避免权重发散
+                Ok(1.0/vega.max(1e-4*spot))
+            }
+            WeightScheme::BidAsk=>{
+                let (bid,ask)=quote.bid_ask.ok_or_else(|| OptionError::NotSet("Bid-ask weights need a bid-ask on every quote".into()))?;
+                Ok(1.0/(ask-bid).max(1e-8))
+            }
+        }).collect()
+    }
+}
+
+/// Optimizer that produced a calibration 产生校准结果的优化器
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum Optimizer{
+    #[default]
+    LevenbergMarquardt,
+    NelderMead,
+    /// User-supplied optimizer 用户提供的优化器
+    Custom(&'static str),
+}
+
+/// Fit of one instrument 单个产品的拟合结果
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct QuoteResidual{
+    pub strike:f64,
+    pub expiry:f64,
+    pub market_price:f64,
+    pub model_price:f64,
+    pub weight:f64,
+}
+
+impl QuoteResidual{
+    /// Model minus market price 模型价格减市场价格
+    pub fn error(&self)->f64{
+        self.model_price-self.market_price
+    }
+}
+
+/// Per-instrument residuals and optimizer diagnostics 逐产品残差与优化器诊断信息
+#[derive(Debug,Clone,PartialEq)]
+pub struct CalibrationReport{
+    /// Fitted parameters by name 按名称列出的拟合参数
+    pub parameters:Vec<(&'static str,f64)>,
+    pub residuals:Vec<QuoteResidual>,
+    /// Root mean square of the unweighted price errors 未加权价格误差的均方根
+    pub rmse:f64,
+    /// Weighted objective Σ(wᵢ·errorᵢ)² 加权目标函数值
+    pub objective:f64,
+    pub optimizer:Optimizer,
+    pub iterations:usize,
+    pub converged:bool,
+}
+
+impl CalibrationReport{
+    pub(crate) fn new(
+        parameters:Vec<(&'static str,f64)>,
+        instruments:&[CalibrationInstrument],
+        model_prices:&[f64],
+        optimizer:Optimizer,
+        iterations:usize,
+        converged:bool,
+    )->Self{
+        let residuals:Vec<QuoteResidual>=instruments.iter().zip(model_prices)
+            .map(|(instrument,&model_price)| QuoteResidual{
+                strike:instrument.strike,
+                expiry:instrument.params.time_to_maturity(),
+                market_price:instrument.market_price,
+                model_price,
+                weight:instrument.weight,
+            })
+            .collect();
+        let rmse=(residuals.iter().map(|r| r.error().powi(2)).sum::<f64>()/residuals.len().max(1) as f64).sqrt();
+        let objective=residuals.iter().map(|r| (r.weight*r.error()).powi(2)).sum();
+        Self{parameters,residuals,rmse,objective,optimizer,iterations,converged}
+    }
+
+    /// Largest absolute price error 最大绝对价格误差
+    pub fn max_error(&self)->f64{
+        self.residuals.iter().map(|r| r.error().abs()).fold(0.0,f64::max)
+    }
+}
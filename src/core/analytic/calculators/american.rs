@@ -0,0 +1,297 @@
+//! Analytic approximations of American vanilla options <br>
+//! 美式普通期权的解析近似
+//! - Barone-Adesi-Whaley (1987) quadratic approximation
+//! - Bjerksund-Stensland (2002) two-step flat boundary
+//! - Ju-Zhong (1999) refinement of the quadratic approximation
+//!
+//! Used by `AnalyticEngine` when the exercise rule is `AmericanExercise`. <br>
+//! 行权规则为`AmericanExercise`时由`AnalyticEngine`调用
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::traits::engine::AnalyticCalculator;
+use crate::traits::payoff::{AnalyticPayoffType, CallPayoff, Payoff, PutPayoff};
+use crate::utils::math::brent_root;
+use crate::utils::statistics::{bivariate_norm_cdf, black_scholes_price, norm_cdf, norm_pdf};
+
+/// American approximation method 美式期权近似方法
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum AmericanApproximation{
+    BaroneAdesiWhaley,
+    #[default]
+    BjerksundStensland2002,
+    JuZhong,
+}
+
+/// 美式普通期权解析近似计算器
+#[derive(Debug,Clone,Default)]
+pub struct AmericanCalculator{
+    pub approximation:AmericanApproximation,
+}
+
+impl AmericanCalculator{
+    pub fn new(approximation:AmericanApproximation)->Self{
+        Self{approximation}
+    }
+}
+
+/// Early exercise boundary S*(t) of the Barone-Adesi-Whaley quadratic approximation with `t`
+/// years remaining; used as the starting guess of iterative American engines <br>
+/// 剩余期限`t`时Barone-Adesi-Whaley二次近似的提前行权边界S*(t)，用作迭代型美式引擎的初值
+pub fn quadratic_exercise_boundary(strike:f64,r:f64,q:f64,sigma:f64,t:f64,is_call:bool)->Result<f64>{
+    let inputs=Inputs{s:strike,x:strike,r,b:r-q,sigma,t};
+    let phi=if is_call{1.0}else{-1.0};
+    let n=2.0*inputs.b/(sigma*sigma);
+    let m=2.0*r/(sigma*sigma);
+    let k=1.0-(-r*t).exp();
+    let lambda=0.5*(-(n-1.0)+phi*((n-1.0).powi(2)+4.0*m/k).sqrt());
+    inputs.quadratic_critical_price(lambda,is_call)
+}
+
+/// Shared inputs of the approximations 各近似方法的公共输入
+#[derive(Debug,Clone,Copy)]
+struct Inputs{
+    s:f64,
+    x:f64,
+    r:f64,
+    b:f64,
+    sigma:f64,
+    t:f64,
+}
+
+impl Inputs{
+    fn european(&self,spot:f64,is_call:bool)->f64{
+        black_scholes_price(spot,self.x,self.r,self.r-self.b,self.sigma,self.t,is_call)
+    }
+
+    fn d1(&self,spot:f64)->f64{
+        ((spot/self.x).ln()+(self.b+0.5*self.sigma*self.sigma)*self.t)/(self.sigma*self.t.sqrt())
+    }
+
+    /// Critical price S* of the quadratic approximation with exponent λ:
+    /// φ(S*-X) = V_E(S*) + φ·(1 - e^{(b-r)T}·N(φ·d1(S*)))·S*/λ <br>
+    /// 二次近似的临界价格S*
+    fn quadratic_critical_price(&self,lambda:f64,is_call:bool)->Result<f64>{
+        let phi=if is_call{1.0}else{-1.0};
+        let carry=((self.b-self.r)*self.t).exp();
+        let g=|spot:f64|{
+            phi*(spot-self.x)-self.european(spot,is_call)
+                -phi*(1.0-carry*norm_cdf(phi*self.d1(spot)))*spot/lambda
+        };
+        // 看涨临界价格高于执行价，看跌临界价格低于执行价
+        let (mut lower,mut upper)=if is_call{(self.x,2.0*self.x)}else{(0.5*self.x,self.x)};
+        let mut iter=0;
+        while g(lower)*g(upper)>0.0{
+            if is_call{upper*=2.0}else{lower*=0.5}
+            iter+=1;
+            if iter>100{
+                return Err(OptionError::ConvergenceError("Cannot bracket the critical price of the American option".into()));
+            }
+        }
+        brent_root(g,lower,upper,1e-10*self.x,200)
+    }
+
+    fn barone_adesi_whaley(&self,is_call:bool)->Result<f64>{
+        let (s,x,r,b,sigma,t)=(self.s,self.x,self.r,self.b,self.sigma,self.t);
+        let phi=if is_call{1.0}else{-1.0};
+        let n=2.0*b/(sigma*sigma);
+        let m=2.0*r/(sigma*sigma);
+        let k=1.0-(-r*t).exp();
+        let lambda=0.5*(-(n-1.0)+phi*((n-1.0).powi(2)+4.0*m/k).sqrt());
+        let critical=self.quadratic_critical_price(lambda,is_call)?;
+        if phi*(s-critical)>=0.0{
+            return Ok(phi*(s-x));
+        }
+        let a=phi*(critical/lambda)*(1.0-((b-r)*t).exp()*norm_cdf(phi*self.d1(critical)));
+        Ok(self.european(s,is_call)+a*(s/critical).powf(lambda))
+    }
+
+    fn ju_zhong(&self,is_call:bool)->Result<f64>{
+        let (s,x,r,b,sigma,t)=(self.s,self.x,self.r,self.b,self.sigma,self.t);
+        let phi=if is_call{1.0}else{-1.0};
+        let alpha=2.0*r/(sigma*sigma);
+        let beta=2.0*b/(sigma*sigma);
+        let h=1.0-(-r*t).exp();
+        let root=((beta-1.0).powi(2)+4.0*alpha/h).sqrt();
+        let lambda=0.5*(-(beta-1.0)+phi*root);
+        let critical=self.quadratic_critical_price(lambda,is_call)?;
+        if phi*(s-critical)>=0.0{
+            return Ok(phi*(s-x));
+        }
+
+        let european_critical=self.european(critical,is_call);
+        let h_a=phi*(critical-x)-european_critical;
+        // ∂V_E/∂h = (∂V_E/∂T)/(dh/dT)，dh/dT = r(1-h)
+        let d1=self.d1(critical);
+        let d2=d1-sigma*t.sqrt();
+        let carry=((b-r)*t).exp();
+        let dv_dt=critical*carry*norm_pdf(d1)*sigma/(2.0*t.sqrt())
+            +phi*(b-r)*critical*carry*norm_cdf(phi*d1)
+            +phi*r*x*(-r*t).exp()*norm_cdf(phi*d2);
+        let dv_dh=dv_dt/(r*(1.0-h));
+        let lambda_h=-phi*alpha/(h*h*root);
+        let denom=2.0*lambda+beta-1.0;
+        let b_coef=(1.0-h)*alpha*lambda_h/(2.0*denom);
+        let c_coef=-(1.0-h)*alpha/denom*(dv_dh/h_a+1.0/h+lambda_h/denom);
+        let log_ratio=(s/critical).ln();
+        let chi=b_coef*log_ratio*log_ratio+c_coef*log_ratio;
+        Ok(self.european(s,is_call)+h_a*(s/critical).powf(lambda)/(1.0-chi))
+    }
+
+    /// Bjerksund-Stensland (2002) call; puts use the put-call transformation
+    /// P(S,X,r,b) = C(X,S,r-b,-b) <br>
+    /// Bjerksund-Stensland (2002)看涨公式，看跌期权通过看涨看跌变换计算
+    fn bjerksund_stensland_call(&self)->f64{
+        let (s,x,r,b,sigma,t)=(self.s,self.x,self.r,self.b,self.sigma,self.t);
+        if b>=r{
+            return self.european(s,true);
+        }
+        let sigma2=sigma*sigma;
+        let t1=0.5*(5f64.sqrt()-1.0)*t;
+        let beta=(0.5-b/sigma2)+((b/sigma2-0.5).powi(2)+2.0*r/sigma2).sqrt();
+        let b_infinity=beta/(beta-1.0)*x;
+        let b_zero=x.max(r/(r-b)*x);
+        let h1=-(b*t1+2.0*sigma*t1.sqrt())*x*x/((b_infinity-b_zero)*b_zero);
+        let h2=-(b*t+2.0*sigma*t.sqrt())*x*x/((b_infinity-b_zero)*b_zero);
+        let i1=b_zero+(b_infinity-b_zero)*(1.0-h1.exp());
+        let i2=b_zero+(b_infinity-b_zero)*(1.0-h2.exp());
+        if s>=i2{
+            return s-x;
+        }
+        let alpha1=(i1-x)*i1.powf(-beta);
+        let alpha2=(i2-x)*i2.powf(-beta);
+
+        let phi=|gamma:f64,h:f64,i:f64| self.bs_phi(t1,gamma,h,i);
+        let psi=|gamma:f64,h:f64| self.bs_psi(t1,gamma,h,i2,i1);
+        alpha2*s.powf(beta)-alpha2*phi(beta,i2,i2)
+            +phi(1.0,i2,i2)-phi(1.0,i1,i2)
+            -x*phi(0.0,i2,i2)+x*phi(0.0,i1,i2)
+            +alpha1*phi(beta,i1,i2)-alpha1*psi(beta,i1)
+            +psi(1.0,i1)-psi(1.0,x)
+            -x*psi(0.0,i1)+x*psi(0.0,x)
+    }
+
+    /// φ(S,T,γ,H,I) of Bjerksund-Stensland
+    fn bs_phi(&self,t:f64,gamma:f64,h:f64,i:f64)->f64{
+        let (s,r,b,sigma)=(self.s,self.r,self.b,self.sigma);
+        let sigma2=sigma*sigma;
+        let vol=sigma*t.sqrt();
+        let lambda=(-r+gamma*b+0.5*gamma*(gamma-1.0)*sigma2)*t;
+        let d=-((s/h).ln()+(b+(gamma-0.5)*sigma2)*t)/vol;
+        let kappa=2.0*b/sigma2+2.0*gamma-1.0;
+        lambda.exp()*s.powf(gamma)*(norm_cdf(d)-(i/s).powf(kappa)*norm_cdf(d-2.0*(i/s).ln()/vol))
+    }
+
+    /// ψ(S,T,γ,H,I₂,I₁,t₁) of Bjerksund-Stensland (2002)
+    fn bs_psi(&self,t1:f64,gamma:f64,h:f64,i2:f64,i1:f64)->f64{
+        let (s,r,b,sigma,t)=(self.s,self.r,self.b,self.sigma,self.t);
+        let sigma2=sigma*sigma;
+        let drift=b+(gamma-0.5)*sigma2;
+        let (vol1,vol)=(sigma*t1.sqrt(),sigma*t.sqrt());
+        let e1=((s/i1).ln()+drift*t1)/vol1;
+        let e2=((i2*i2/(s*i1)).ln()+drift*t1)/vol1;
+        let e3=((s/i1).ln()-drift*t1)/vol1;
+        let e4=((i2*i2/(s*i1)).ln()-drift*t1)/vol1;
+        let f1=((s/h).ln()+drift*t)/vol;
+        let f2=((i2*i2/(s*h)).ln()+drift*t)/vol;
+        let f3=((i1*i1/(s*h)).ln()+drift*t)/vol;
+        let f4=((s*i1*i1/(h*i2*i2)).ln()+drift*t)/vol;
+        let rho=(t1/t).sqrt();
+        let lambda=-r+gamma*b+0.5*gamma*(gamma-1.0)*sigma2;
+        let kappa=2.0*b/sigma2+2.0*gamma-1.0;
+        (lambda*t).exp()*s.powf(gamma)*(
+            bivariate_norm_cdf(-e1,-f1,rho)
+                -(i2/s).powf(kappa)*bivariate_norm_cdf(-e2,-f2,rho)
+                -(i1/s).powf(kappa)*bivariate_norm_cdf(-e3,-f3,-rho)
+                +(i1/i2).powf(kappa)*bivariate_norm_cdf(-e4,-f4,-rho)
+        )
+    }
+}
+
+impl AnalyticCalculator for AmericanCalculator{
+    fn supported_types(&self) -> Vec<AnalyticPayoffType> {
+        vec![AnalyticPayoffType::VanillaCall,AnalyticPayoffType::VanillaPut]
+    }
+
+    fn calculate(&self, params: &CommonParams, payoff: &dyn Payoff) -> Result<f64> {
+        let (s,r,sigma,q,t)=params.all_params();
+        let (strike,is_call)=match payoff.as_any().downcast_ref::<CallPayoff>(){
+            Some(call)=>(call.strike,true),
+            None=>match payoff.as_any().downcast_ref::<PutPayoff>(){
+                Some(put)=>(put.strike,false),
+                None=>return Err(OptionError::InvalidParameter("American calculator only support \
+                vanilla call/put option".into())),
+            }
+        };
+        if strike<=0.0{
+            return Err(OptionError::InvalidParameter("Strike must be greater than zero.".into()));
+        }
+        if t==0.0{
+            return Ok(payoff.payoff(s));
+        }
+        let inputs=Inputs{s,x:strike,r,b:r-q,sigma,t};
+        // 看涨期权在b≥r（无股息）时、看跌期权在r≤0时不会提前行权
+        if (is_call && q<=0.0) || (!is_call && r<=0.0){
+            return Ok(inputs.european(s,is_call));
+        }
+        let price=match self.approximation{
+            AmericanApproximation::BaroneAdesiWhaley=>inputs.barone_adesi_whaley(is_call)?,
+            AmericanApproximation::JuZhong=>inputs.ju_zhong(is_call)?,
+            AmericanApproximation::BjerksundStensland2002=>if is_call{
+                inputs.bjerksund_stensland_call()
+            }else{
+                Inputs{s:strike,x:s,r:r-inputs.b,b:-inputs.b,sigma,t}.bjerksund_stensland_call()
+            },
+        };
+        // 美式期权价值不低于内在价值
+        Ok(price.max(payoff.payoff(s)))
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_barone_adesi_whaley()->Result<()>{
+        // Haug (2007) 表3-1: X=100, T=0.1, r=0.1, b=0, σ=0.15
+        let calculator=AmericanCalculator::new(AmericanApproximation::BaroneAdesiWhaley);
+        let payoff=CallPayoff::new(100.0);
+        for (spot,expected) in [(90.0,0.0206),(100.0,1.8771)]{
+            let price=calculator.calculate(&CommonParams::new(spot,0.1,0.15,0.1,0.1)?,&payoff)?;
+            assert_approx_eq!(price,expected,1e-3);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_bjerksund_stensland_2002()->Result<()>{
+        // Haug (2007) Bjerksund-Stensland (2002)数值表: X=100, T=0.1, r=0.1, b=0, σ=0.15
+        let calculator=AmericanCalculator::new(AmericanApproximation::BjerksundStensland2002);
+        let payoff=CallPayoff::new(100.0);
+        for (spot,expected) in [(90.0,0.0205),(100.0,1.8757),(110.0,10.0)]{
+            let price=calculator.calculate(&CommonParams::new(spot,0.1,0.15,0.1,0.1)?,&payoff)?;
+            assert_approx_eq!(price,expected,1e-3);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_ju_zhong()->Result<()>{
+        // Ju and Zhong (1999) 表1: 美式看跌, S=40, r=0.0488, q=0
+        let calculator=AmericanCalculator::new(AmericanApproximation::JuZhong);
+        let cases=[
+            // (K, T, σ, price)
+            (35.0,0.0833,0.2,0.006),(35.0,0.3333,0.2,0.201),(35.0,0.5833,0.2,0.433),
+            (40.0,0.0833,0.2,0.851),(40.0,0.3333,0.2,1.576),(40.0,0.5833,0.2,1.984),
+            (45.0,0.0833,0.2,5.000),(45.0,0.3333,0.2,5.084),(45.0,0.5833,0.2,5.260),
+            (35.0,0.0833,0.3,0.077),(40.0,0.3333,0.3,2.477),(45.0,0.5833,0.3,6.231),
+            (35.0,0.0833,0.4,0.247),(40.0,0.3333,0.4,3.381),(45.0,0.5833,0.4,7.367),
+        ];
+        for (strike,t,sigma,expected) in cases{
+            let price=calculator.calculate(&CommonParams::new(40.0,0.0488,sigma,0.0,t)?,&PutPayoff::new(strike))?;
+            assert_approx_eq!(price,expected,1e-3);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+//! Bachelier (normal model) vanilla options <br>
+//! Bachelier（正态模型）普通期权
+//!
+//! The forward F = S·e^{(r-q)T} follows dF = σ·dW with an absolute volatility σ, so spot,
+//! forward and strike may be zero or negative (rates, spreads, commodity calendar spreads).
+//! With d = (F-K)/(σ√T) the call is worth e^{-rT}·[(F-K)·N(d) + σ√T·φ(d)]. <br>
+//! 远期F = S·e^{(r-q)T}服从dF = σ·dW（σ为绝对波动率），因此现价、远期与行权价可以为零或负
+//! （利率、价差、商品跨期价差等）；记d = (F-K)/(σ√T)，看涨期权价值为e^{-rT}·[(F-K)·N(d) + σ√T·φ(d)]
+//!
+//! Used by `AnalyticEngine` when the dynamics of the `CommonParams` is `Dynamics::Normal`. <br>
+//! `CommonParams`的动态为`Dynamics::Normal`时由`AnalyticEngine`调用
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::traits::engine::AnalyticCalculator;
+use crate::traits::payoff::{AnalyticPayoffType, CallPayoff, Payoff, PutPayoff};
+use crate::utils::statistics::{bachelier_price, norm_cdf, norm_pdf};
+
+/// Closed-form sensitivities of a Bachelier vanilla option; theta is the change of value as
+/// calendar time passes (-∂V/∂T) and vega is per unit of absolute volatility <br>
+/// Bachelier普通期权的解析敏感度；theta为随日历时间流逝的价值变化(-∂V/∂T)，vega按单位绝对波动率计
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct BachelierGreeks{
+    pub delta:f64,
+    pub gamma:f64,
+    pub vega:f64,
+    pub theta:f64,
+    pub rho:f64,
+}
+
+/// Bachelier普通期权计算器
+#[derive(Debug,Clone)]
+pub struct BachelierCalculator;
+
+impl BachelierCalculator{
+    fn strike_and_kind(payoff:&dyn Payoff)->Result<(f64,bool)>{
+        match payoff.as_any().downcast_ref::<CallPayoff>(){
+            Some(call)=>Ok((call.strike,true)),
+            None=>match payoff.as_any().downcast_ref::<PutPayoff>(){
+                Some(put)=>Ok((put.strike,false)),
+                None=>Err(OptionError::InvalidParameter("Bachelier calculator only support \
+                vanilla call/put option".into())),
+            }
+        }
+    }
+
+    /// Price and closed-form Greeks of a vanilla call/put; needs a positive maturity and volatility <br>
+    /// 普通看涨/看跌期权的价格与解析希腊字母；要求期限与波动率为正
+    pub fn greeks(params:&CommonParams,payoff:&dyn Payoff)->Result<BachelierGreeks>{
+        let (s,r,sigma,q,t)=params.all_params();
+        if t<=0.0 || sigma<=0.0{
+            return Err(OptionError::InvalidParameter("Bachelier Greeks need positive maturity and volatility".into()));
+        }
+        let (strike,is_call)=Self::strike_and_kind(payoff)?;
+        let price=bachelier_price(s,strike,r,q,sigma,t,is_call);
+        let growth=((r-q)*t).exp();
+        let forward=s*growth;
+        let exp_rt=(-r*t).exp();
+        let sqrt_t=t.sqrt();
+        let d=(forward-strike)/(sigma*sqrt_t);
+        let density=norm_pdf(d);
+        // 未贴现价格对远期的导数
+        let forward_delta=if is_call{norm_cdf(d)}else{norm_cdf(d)-1.0};
+        let value_t=-r*price+exp_rt*(forward_delta*(r-q)*forward+sigma*density/(2.0*sqrt_t));
+        Ok(BachelierGreeks{
+            delta:exp_rt*forward_delta*growth,
+            gamma:exp_rt*density*growth*growth/(sigma*sqrt_t),
+            vega:exp_rt*sqrt_t*density,
+            theta:-value_t,
+            rho:-t*price+exp_rt*forward_delta*t*forward,
+        })
+    }
+}
+
+impl AnalyticCalculator for BachelierCalculator{
+    fn supported_types(&self) -> Vec<AnalyticPayoffType> {
+        vec![AnalyticPayoffType::VanillaCall,AnalyticPayoffType::VanillaPut]
+    }
+
+    fn calculate(&self, params: &CommonParams, payoff: &dyn Payoff) -> Result<f64> {
+        let (s,r,sigma,q,t)=params.all_params();
+        if t==0.0{
+            return Ok(payoff.payoff(s));
+        }
+        let (strike,is_call)=Self::strike_and_kind(payoff)?;
+        Ok(bachelier_price(s,strike,r,q,sigma,t,is_call))
+    }
+}
@@ -1,16 +1,71 @@
+//! Binary options <br>
+//! 二元期权解析解
+//! - cash-or-nothing / asset-or-nothing: Reiner-Rubinstein (1991)
+//! - gap options
+//! - one-touch / no-touch paid at expiry (American digital, continuous monitoring)
 use crate::errors::*;
 use crate::params::common::CommonParams;
 use crate::traits::engine::AnalyticCalculator;
-use crate::traits::payoff::{AnalyticPayoffType, Payoff,CashOrNothingCallPayoff};
+use crate::traits::payoff::{
+    AnalyticPayoffType, Payoff,
+    CashOrNothingCallPayoff, CashOrNothingPutPayoff,
+    AssetOrNothingCallPayoff, AssetOrNothingPutPayoff,
+    GapCallPayoff, GapPutPayoff,
+    OneTouchPayoff, NoTouchPayoff,
+};
 use crate::utils::statistics::{calculate_d1_d2, norm_cdf};
 
 /// 二元期权
 #[derive(Debug,Clone)]
 pub struct BinaryCalculator;
 
+impl BinaryCalculator{
+    fn validate_payout(payout:f64)->Result<()>{
+        if payout<0.0{
+            return Err(OptionError::InvalidParameter("The payout of binary option cannot be negative".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Risk neutral probability that the barrier is touched before expiry <br>
+    /// 风险中性测度下到期前触碰障碍的概率
+    pub fn touch_probability(params:&CommonParams,barrier:f64,is_up:bool)->Result<f64>{
+        let (s,r,sigma,q,t)=params.all_params();
+        if barrier<=0.0{
+            return Err(OptionError::InvalidParameter("Barrier must be greater than zero.".to_string()));
+        }
+        if (is_up && s>=barrier) || (!is_up && s<=barrier){
+            return Ok(1.0);
+        }
+        if t==0.0{
+            return Ok(0.0);
+        }
+        // 对数价格漂移ν=b-σ²/2，h=ln(B/S)
+        let nu=r-q-0.5*sigma*sigma;
+        let h=(barrier/s).ln();
+        let vol=sigma*t.sqrt();
+        let reflection=(2.0*nu*h/(sigma*sigma)).exp();
+        let probability=if is_up{
+            norm_cdf((-h+nu*t)/vol)+reflection*norm_cdf((-h-nu*t)/vol)
+        }else{
+            norm_cdf((h-nu*t)/vol)+reflection*norm_cdf((h+nu*t)/vol)
+        };
+        Ok(probability.clamp(0.0,1.0))
+    }
+}
+
 impl AnalyticCalculator for BinaryCalculator {
     fn supported_types(&self) -> Vec<AnalyticPayoffType> {
-        vec![AnalyticPayoffType::CashOrNothingCall,AnalyticPayoffType::CashOrNothingPut]
+        vec![
+            AnalyticPayoffType::CashOrNothingCall,
+            AnalyticPayoffType::CashOrNothingPut,
+            AnalyticPayoffType::AssertOrNothingCall,
+            AnalyticPayoffType::AssertOrNothingPut,
+            AnalyticPayoffType::GapCall,
+            AnalyticPayoffType::GapPut,
+            AnalyticPayoffType::OneTouch,
+            AnalyticPayoffType::NoTouch,
+        ]
     }
 
     fn calculate(&self, params: &CommonParams, payoff: &dyn Payoff) -> Result<f64> {
@@ -19,27 +74,104 @@ impl AnalyticCalculator for BinaryCalculator {
         let q=params.dividend_yield();
         let sigma=params.volatility();
         let t=params.time_to_maturity();
+        let exp_rt=(-r*t).exp();
+        let exp_qt=(-q*t).exp();
+
+        let any=payoff.as_any();
+        // 触碰期权在t=0时也需要判断是否已越过障碍，单独处理
+        if let Some(touch)=any.downcast_ref::<OneTouchPayoff>(){
+            Self::validate_payout(touch.payout)?;
+            return Ok(touch.payout*exp_rt*Self::touch_probability(params,touch.barrier,touch.is_up)?);
+        }
+        if let Some(touch)=any.downcast_ref::<NoTouchPayoff>(){
+            Self::validate_payout(touch.payout)?;
+            return Ok(touch.payout*exp_rt*(1.0-Self::touch_probability(params,touch.barrier,touch.is_up)?));
+        }
 
         if t==0.0{
             return Ok(payoff.payoff(s));
         }
 
-        let (strike,payout,_is_call)=match payoff.as_any().downcast_ref::<CashOrNothingCallPayoff>(){
-            Some(binary_call)=>{
-                if binary_call.payout<0.0{
-                    return Err(OptionError::InvalidParameter("The payout of binary call option must be greater than 0".to_string()));
-                }
-                (binary_call.strike,binary_call.payout,true)
-            },
-            // 此处后续扩展CashOrNothingPutPayoff、AssetOrNothingCallPayoff、AssetOrNothingPutPayoff
-            None=>{
-                return Err(OptionError::NotImplemented("Now only support cash-or-nothing call option.".to_string()));
-            }
-        };
-        let (_,d2)=calculate_d1_d2(s,strike,r,q,sigma,t)?;
-        let exp_rt=(-r*t).exp();
+        if let Some(binary)=any.downcast_ref::<CashOrNothingCallPayoff>(){
+            Self::validate_payout(binary.payout)?;
+            let (_,d2)=calculate_d1_d2(s,binary.strike,r,q,sigma,t)?;
+            return Ok(binary.payout*exp_rt*norm_cdf(d2));
+        }
+        if let Some(binary)=any.downcast_ref::<CashOrNothingPutPayoff>(){
+            Self::validate_payout(binary.payout)?;
+            let (_,d2)=calculate_d1_d2(s,binary.strike,r,q,sigma,t)?;
+            return Ok(binary.payout*exp_rt*norm_cdf(-d2));
+        }
+        if let Some(binary)=any.downcast_ref::<AssetOrNothingCallPayoff>(){
+            let (d1,_)=calculate_d1_d2(s,binary.strike,r,q,sigma,t)?;
+            return Ok(s*exp_qt*norm_cdf(d1));
+        }
+        if let Some(binary)=any.downcast_ref::<AssetOrNothingPutPayoff>(){
+            let (d1,_)=calculate_d1_d2(s,binary.strike,r,q,sigma,t)?;
+            return Ok(s*exp_qt*norm_cdf(-d1));
+        }
+        // 缺口期权可以为负值，不做截断
+        if let Some(gap)=any.downcast_ref::<GapCallPayoff>(){
+            let (d1,d2)=calculate_d1_d2(s,gap.trigger,r,q,sigma,t)?;
+            return Ok(s*exp_qt*norm_cdf(d1)-gap.strike*exp_rt*norm_cdf(d2));
+        }
+        if let Some(gap)=any.downcast_ref::<GapPutPayoff>(){
+            let (d1,d2)=calculate_d1_d2(s,gap.trigger,r,q,sigma,t)?;
+            return Ok(gap.strike*exp_rt*norm_cdf(-d2)-s*exp_qt*norm_cdf(-d1));
+        }
+        Err(OptionError::InvalidParameter("Binary calculator only support cash-or-nothing, \
+        asset-or-nothing, gap and touch options".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::traits::payoff::DigitalPayoff;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_binary_options()->Result<()>{
+        // Haug (2007): 现金或无看跌 S=100, K=80, Q=10, T=0.75, r=0.06, b=0, σ=0.35 → 2.6710
+        let params=CommonParams::new(100.0,0.06,0.35,0.06,0.75)?;
+        let cash_put=BinaryCalculator.calculate(&params,&CashOrNothingPutPayoff::new(80.0,10.0))?;
+        assert_approx_eq!(cash_put,2.6710,1e-3);
+        // Haug (2007): 资产或无看跌 S=70, K=65, T=0.5, r=0.07, b=0.02, σ=0.27 → 20.2069
+        let params=CommonParams::new(70.0,0.07,0.27,0.05,0.5)?;
+        let asset_put=BinaryCalculator.calculate(&params,&AssetOrNothingPutPayoff::new(65.0))?;
+        assert_approx_eq!(asset_put,20.2069,1e-3);
+        // Haug (2007): 缺口看涨 S=50, 触发价50, 支付执行价57, T=0.5, r=0.09, b=0.09, σ=0.2 → -0.0053
+        let params=CommonParams::new(50.0,0.09,0.2,0.0,0.5)?;
+        let gap=BinaryCalculator.calculate(&params,&GapCallPayoff::new(57.0,50.0))?;
+        assert_approx_eq!(gap,-0.0053,1e-3);
+
+        // 资产或无看涨 - 现金或无看涨(Q=K) = 普通看涨
+        let params=CommonParams::new(100.0,0.05,0.25,0.02,1.5)?;
+        let asset_call=BinaryCalculator.calculate(&params,&AssetOrNothingCallPayoff::new(95.0))?;
+        let cash_call=BinaryCalculator.calculate(&params,&CashOrNothingCallPayoff{strike:95.0,payout:95.0})?;
+        let vanilla=super::super::VanillaCalculator.calculate(&params,&crate::traits::payoff::CallPayoff::new(95.0))?;
+        assert_approx_eq!(asset_call-cash_call,vanilla,1e-10);
+
+        // 平滑只改变收益的跳跃，解析解仍按被包装的收益定价
+        let smoothed=CashOrNothingCallPayoff{strike:95.0,payout:95.0}.with_smoothing(2.0);
+        assert_approx_eq!(smoothed.payoff(95.5),0.75*95.0,1e-10);
+        assert_approx_eq!(BinaryCalculator.calculate(&params,&smoothed)?,cash_call,1e-12);
+        Ok(())
+    }
 
-        let price=payout*exp_rt*norm_cdf(d2);
-        Ok(price.max(0.0))
+    #[test]
+    fn test_touch_options()->Result<()>{
+        let params=CommonParams::new(100.0,0.05,0.2,0.02,1.0)?;
+        let one_touch=BinaryCalculator.calculate(&params,&OneTouchPayoff::new(120.0,1.0,true))?;
+        let no_touch=BinaryCalculator.calculate(&params,&NoTouchPayoff::new(120.0,1.0,true))?;
+        // 一触即付 + 无触碰 = 零息债券
+        assert_approx_eq!(one_touch+no_touch,(-0.05f64).exp(),1e-12);
+        // 触碰概率不低于到期时位于障碍之外的概率
+        let digital=BinaryCalculator.calculate(&params,&CashOrNothingCallPayoff::new(120.0,1.0))?;
+        assert!(one_touch>digital);
+        // 已越过障碍
+        let touched=BinaryCalculator.calculate(&params,&OneTouchPayoff::new(90.0,1.0,true))?;
+        assert_approx_eq!(touched,(-0.05f64).exp(),1e-12);
+        Ok(())
     }
 }
@@ -0,0 +1,92 @@
+//! Black-76 options on forwards and futures <br>
+//! 远期/期货期权的Black-76公式
+//!
+//! C = e^{-rT}·[F·N(d₁) - K·N(d₂)], P = e^{-rT}·[K·N(-d₂) - F·N(-d₁)],
+//! d₁ = (ln(F/K) + σ²T/2)/(σ√T), d₂ = d₁ - σ√T.
+//! The generic engines price the same contract through `Black76Params::to_common`; as an
+//! `AnalyticCalculator` it reads `CommonParams` through `Black76Params::from_common`. <br>
+//! 通用引擎可经`Black76Params::to_common`为同一合约定价；作为`AnalyticCalculator`时经
+//! `Black76Params::from_common`读取`CommonParams`
+use crate::errors::*;
+use crate::params::black76::Black76Params;
+use crate::params::common::CommonParams;
+use crate::traits::engine::AnalyticCalculator;
+use crate::traits::payoff::{AnalyticPayoffType, CallPayoff, Payoff, PutPayoff};
+use crate::utils::statistics::{black_scholes_price, norm_cdf, norm_pdf};
+
+/// Closed-form sensitivities of a Black-76 option; delta and gamma are with respect to the
+/// forward, theta is -∂V/∂T at a fixed forward <br>
+/// Black-76期权的解析敏感度；delta与gamma针对远期价格，theta为远期不变时的-∂V/∂T
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct Black76Greeks{
+    pub delta:f64,
+    pub gamma:f64,
+    pub vega:f64,
+    pub theta:f64,
+    pub rho:f64,
+}
+
+/// Black-76计算器
+#[derive(Debug,Clone)]
+pub struct Black76Calculator;
+
+impl Black76Calculator{
+    /// Price of a vanilla call/put on the forward 远期上普通看涨/看跌期权的价格
+    pub fn price(params:&Black76Params,payoff:&dyn Payoff)->Result<f64>{
+        let (strike,is_call)=Self::strike_and_kind(payoff)?;
+        let f=params.forward();
+        let t=params.time_to_maturity();
+        if t==0.0{
+            return Ok(payoff.payoff(f));
+        }
+        let r=params.risk_free_rate();
+        Ok(black_scholes_price(f,strike,r,r,params.volatility(),t,is_call))
+    }
+
+    /// Price sensitivities of a vanilla call/put; needs a positive maturity <br>
+    /// 普通看涨/看跌期权的敏感度；要求期限为正
+    pub fn greeks(params:&Black76Params,payoff:&dyn Payoff)->Result<Black76Greeks>{
+        let (strike,is_call)=Self::strike_and_kind(payoff)?;
+        let (f,r,sigma,t)=(params.forward(),params.risk_free_rate(),params.volatility(),params.time_to_maturity());
+        if t<=0.0{
+            return Err(OptionError::InvalidParameter("Black-76 Greeks need a positive maturity".into()));
+        }
+        let price=black_scholes_price(f,strike,r,r,sigma,t,is_call);
+        let discount=params.discount_factor();
+        let sqrt_t=t.sqrt();
+        let d1=((f/strike).ln()+0.5*sigma*sigma*t)/(sigma*sqrt_t);
+        let density=norm_pdf(d1);
+        Ok(Black76Greeks{
+            delta:if is_call{discount*norm_cdf(d1)}else{-discount*norm_cdf(-d1)},
+            gamma:discount*density/(f*sigma*sqrt_t),
+            vega:discount*f*density*sqrt_t,
+            theta:r*price-discount*f*density*sigma/(2.0*sqrt_t),
+            rho:-t*price,
+        })
+    }
+
+    fn strike_and_kind(payoff:&dyn Payoff)->Result<(f64,bool)>{
+        let (strike,is_call)=match payoff.as_any().downcast_ref::<CallPayoff>(){
+            Some(call)=>(call.strike,true),
+            None=>match payoff.as_any().downcast_ref::<PutPayoff>(){
+                Some(put)=>(put.strike,false),
+                None=>return Err(OptionError::InvalidParameter("Black-76 calculator only support \
+                vanilla call/put option".into())),
+            }
+        };
+        if strike<=0.0{
+            return Err(OptionError::InvalidParameter("Strike must be greater than zero.".into()));
+        }
+        Ok((strike,is_call))
+    }
+}
+
+impl AnalyticCalculator for Black76Calculator{
+    fn supported_types(&self) -> Vec<AnalyticPayoffType> {
+        vec![AnalyticPayoffType::VanillaCall,AnalyticPayoffType::VanillaPut]
+    }
+
+    fn calculate(&self, params: &CommonParams, payoff: &dyn Payoff) -> Result<f64> {
+        Self::price(&Black76Params::from_common(params)?,payoff)
+    }
+}
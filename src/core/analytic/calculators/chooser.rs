@@ -0,0 +1,149 @@
+//! Chooser options <br>
+//! 选择期权解析解
+//! - simple chooser: Rubinstein (1991)
+//! - complex chooser: Rubinstein (1991), with the generalized cost of carry of Haug
+//!
+//! The time to maturity of the common parameters is the choice date t. <br>
+//! 通用参数中的到期时间为选择日t
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::traits::engine::AnalyticCalculator;
+use crate::traits::payoff::{AnalyticPayoffType, ComplexChooserPayoff, Payoff, SimpleChooserPayoff};
+use crate::utils::math::brent_root;
+use crate::utils::statistics::{bivariate_norm_cdf, norm_cdf};
+
+#[derive(Debug,Clone)]
+pub struct ChooserCalculator;
+
+impl ChooserCalculator{
+    fn simple(params:&CommonParams,chooser:&SimpleChooserPayoff)->Result<f64>{
+        let (s,r,sigma,q,t)=params.all_params();
+        let x=chooser.strike;
+        let big_t=t+chooser.remaining_time;
+        let b=r-q;
+        let d=((s/x).ln()+(b+0.5*sigma*sigma)*big_t)/(sigma*big_t.sqrt());
+        let y=((s/x).ln()+b*big_t+0.5*sigma*sigma*t)/(sigma*t.sqrt());
+        let forward_pv=s*(-q*big_t).exp();
+        let strike_pv=x*(-r*big_t).exp();
+        Ok(forward_pv*norm_cdf(d)-strike_pv*norm_cdf(d-sigma*big_t.sqrt())
+            -forward_pv*norm_cdf(-y)+strike_pv*norm_cdf(-y+sigma*t.sqrt()))
+    }
+
+    /// Critical spot I at the choice date where the call and the put are worth the same <br>
+    /// 临界价格I：选择日看涨与看跌期权价值相等
+    fn critical_price(params:&CommonParams,chooser:&ComplexChooserPayoff)->Result<f64>{
+        let f=|spot:f64| chooser.call_minus_put(params,spot);
+        // 看涨减看跌随S严格递增：S→0时为-Xp·e^{-rτp}，S→∞时趋于∞
+        let lower=chooser.put_strike.min(chooser.call_strike)*1e-8;
+        let mut upper=chooser.put_strike.max(chooser.call_strike);
+        let mut iter=0;
+        while f(upper)<=0.0{
+            upper*=2.0;
+            iter+=1;
+            if iter>100{
+                return Err(OptionError::ConvergenceError("Cannot bracket the critical price of the chooser option".into()));
+            }
+        }
+        brent_root(f,lower,upper,1e-10*upper,200)
+    }
+
+    fn complex(params:&CommonParams,chooser:&ComplexChooserPayoff)->Result<f64>{
+        let (s,r,sigma,q,t)=params.all_params();
+        let (xc,xp)=(chooser.call_strike,chooser.put_strike);
+        let tc=t+chooser.call_remaining_time;
+        let tp=t+chooser.put_remaining_time;
+        let b=r-q;
+        let critical=Self::critical_price(params,chooser)?;
+
+        let d1=((s/critical).ln()+(b+0.5*sigma*sigma)*t)/(sigma*t.sqrt());
+        let d2=d1-sigma*t.sqrt();
+        let y1=((s/xc).ln()+(b+0.5*sigma*sigma)*tc)/(sigma*tc.sqrt());
+        let y2=((s/xp).ln()+(b+0.5*sigma*sigma)*tp)/(sigma*tp.sqrt());
+        let rho1=(t/tc).sqrt();
+        let rho2=(t/tp).sqrt();
+
+        Ok(s*(-q*tc).exp()*bivariate_norm_cdf(d1,y1,rho1)
+            -xc*(-r*tc).exp()*bivariate_norm_cdf(d2,y1-sigma*tc.sqrt(),rho1)
+            -s*(-q*tp).exp()*bivariate_norm_cdf(-d1,-y2,rho2)
+            +xp*(-r*tp).exp()*bivariate_norm_cdf(-d2,-y2+sigma*tp.sqrt(),rho2))
+    }
+}
+
+impl AnalyticCalculator for ChooserCalculator{
+    fn supported_types(&self) -> Vec<AnalyticPayoffType> {
+        vec![AnalyticPayoffType::SimpleChooser,AnalyticPayoffType::ComplexChooser]
+    }
+
+    fn calculate(&self, params: &CommonParams, payoff: &dyn Payoff) -> Result<f64> {
+        let s=params.spot();
+        let t=params.time_to_maturity();
+        if t==0.0{
+            // 选择日的Payoff为所选期权在当前市场数据下的价值
+            return Ok(payoff.bind_market(params).map_or_else(|| payoff.payoff(s),|bound| bound.payoff(s)));
+        }
+        let any=payoff.as_any();
+        if let Some(chooser)=any.downcast_ref::<SimpleChooserPayoff>(){
+            if chooser.strike<=0.0{
+                return Err(OptionError::InvalidParameter("Strike must be greater than zero.".into()));
+            }
+            if chooser.remaining_time<0.0{
+                return Err(OptionError::InvalidParameter("Chooser option cannot expire before the choice date".into()));
+            }
+            return Self::simple(params,chooser);
+        }
+        if let Some(chooser)=any.downcast_ref::<ComplexChooserPayoff>(){
+            if chooser.call_strike<=0.0 || chooser.put_strike<=0.0{
+                return Err(OptionError::InvalidParameter("Strike must be greater than zero.".into()));
+            }
+            if chooser.call_remaining_time<=0.0 || chooser.put_remaining_time<=0.0{
+                return Err(OptionError::InvalidParameter("Options of a complex chooser must \
+                expire after the choice date".into()));
+            }
+            return Self::complex(params,chooser);
+        }
+        Err(OptionError::InvalidParameter("Chooser calculator only support \
+        simple/complex chooser option".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_chooser_options()->Result<()>{
+        // Haug (2007) 表4-2: S=50, X=50, t=0.25, T=0.5, r=0.08, b=0.08, σ=0.25
+        let simple=SimpleChooserPayoff{
+            strike:50.0,
+            remaining_time:0.25,
+        };
+        let price=ChooserCalculator.calculate(&CommonParams::new(50.0,0.08,0.25,0.0,0.25)?,&simple)?;
+        assert_approx_eq!(price,6.1071,1e-3);
+
+        // Haug (2007) 表4-3: S=50, Xc=55, Xp=48, t=0.25, Tc=0.5, Tp=0.5833, r=0.1, b=0.05, σ=0.35
+        let complex=ComplexChooserPayoff{
+            call_strike:55.0,
+            put_strike:48.0,
+            call_remaining_time:0.25,
+            put_remaining_time:0.3333,
+        };
+        let price=ChooserCalculator.calculate(&CommonParams::new(50.0,0.1,0.35,0.05,0.25)?,&complex)?;
+        assert_approx_eq!(price,6.0508,1e-3);
+
+        // 执行价和到期日相同时复杂选择期权退化为简单选择期权
+        let as_complex=ComplexChooserPayoff{
+            call_strike:50.0,
+            put_strike:50.0,
+            call_remaining_time:0.25,
+            put_remaining_time:0.25,
+        };
+        let params=CommonParams::new(50.0,0.08,0.25,0.0,0.25)?;
+        assert_approx_eq!(
+            ChooserCalculator.calculate(&params,&as_complex)?,
+            ChooserCalculator.calculate(&params,&simple)?,
+            1e-6
+        );
+        Ok(())
+    }
+}
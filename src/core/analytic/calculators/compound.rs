@@ -0,0 +1,148 @@
+//! Compound options (options on options) <br>
+//! 复合期权（期权的期权）解析解
+//! - Geske (1979), with the generalized cost of carry of Haug
+//!
+//! The outer option expires at T₁ (the time to maturity of the common parameters),
+//! the inner option at T₂ = T₁ + inner_time. <br>
+//! 外层期权在T₁（通用参数中的到期时间）到期，内层期权在T₂ = T₁ + inner_time到期
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::traits::engine::AnalyticCalculator;
+use crate::traits::payoff::{AnalyticPayoffType, CompoundOptionPayoff, Payoff};
+use crate::utils::math::brent_root;
+use crate::utils::statistics::{bivariate_norm_cdf, black_scholes_price, norm_cdf};
+
+#[derive(Debug,Clone)]
+pub struct CompoundCalculator;
+
+impl CompoundCalculator{
+    /// Critical spot I* at T₁ where the inner option is worth the outer strike.
+    /// None if the inner put can never be worth the outer strike <br>
+    /// 临界价格I*：T₁时内层期权价值恰好等于外层执行价；内层看跌期权价值始终低于外层执行价时返回None
+    fn critical_price(params:&CommonParams,payoff:&CompoundOptionPayoff)->Result<Option<f64>>{
+        let k1=payoff.outer_strike;
+        let k2=payoff.inner_strike;
+        let f=|spot:f64| payoff.inner_value(params,spot)-k1;
+        let lower=k2*1e-8;
+        if !payoff.inner_is_call && f(lower)<=0.0{
+            return Ok(None);
+        }
+        // 看涨期权价值随S递增，看跌期权价值随S递减，向上扩张区间直到变号
+        let mut upper=k2.max(k1);
+        let mut iter=0;
+        while f(upper)*f(lower)>0.0{
+            upper*=2.0;
+            iter+=1;
+            if iter>100{
+                return Err(OptionError::ConvergenceError("Cannot bracket the critical price of the compound option".into()));
+            }
+        }
+        brent_root(f,lower,upper,1e-10*k2,200).map(Some)
+    }
+}
+
+impl AnalyticCalculator for CompoundCalculator{
+    fn supported_types(&self) -> Vec<AnalyticPayoffType> {
+        vec![
+            AnalyticPayoffType::CompoundCallOnCall,
+            AnalyticPayoffType::CompoundCallOnPut,
+            AnalyticPayoffType::CompoundPutOnCall,
+            AnalyticPayoffType::CompoundPutOnPut,
+        ]
+    }
+
+    fn calculate(&self, params: &CommonParams, payoff: &dyn Payoff) -> Result<f64> {
+        let compound=payoff.as_any().downcast_ref::<CompoundOptionPayoff>()
+            .ok_or_else(|| OptionError::InvalidParameter("Compound calculator only support \
+            compound option".into()))?;
+        let (s,r,sigma,q,t1)=params.all_params();
+        if compound.outer_strike<=0.0 || compound.inner_strike<=0.0{
+            return Err(OptionError::InvalidParameter("Strike must be greater than zero.".into()));
+        }
+        if compound.inner_time<=0.0{
+            return Err(OptionError::InvalidParameter("Inner option must expire after the outer option".into()));
+        }
+        if t1==0.0{
+            // 外层到期日的Payoff为内层期权在当前市场数据下的价值
+            return Ok(payoff.bind_market(params).map_or_else(|| payoff.payoff(s),|bound| bound.payoff(s)));
+        }
+        let (k1,k2)=(compound.outer_strike,compound.inner_strike);
+        let t2=t1+compound.inner_time;
+        let disc1=(-r*t1).exp();
+        let disc2=(-r*t2).exp();
+        let forward_pv=s*(-q*t2).exp();
+
+        let critical=match Self::critical_price(params,compound)?{
+            Some(i)=>i,
+            None=>{
+                // 内层看跌期权价值恒低于外层执行价：看涨复合期权永不行权，看跌复合期权必然行权
+                return Ok(if compound.outer_is_call{
+                    0.0
+                }else{
+                    k1*disc1-black_scholes_price(s,k2,r,q,sigma,t2,false)
+                });
+            }
+        };
+
+        let b=r-q;
+        let (v1,v2)=(sigma*t1.sqrt(),sigma*t2.sqrt());
+        let y1=((s/critical).ln()+(b+0.5*sigma*sigma)*t1)/v1;
+        let y2=y1-v1;
+        let z1=((s/k2).ln()+(b+0.5*sigma*sigma)*t2)/v2;
+        let z2=z1-v2;
+        let rho=(t1/t2).sqrt();
+
+        let price=match (compound.outer_is_call,compound.inner_is_call){
+            (true,true)=>forward_pv*bivariate_norm_cdf(z1,y1,rho)
+                -k2*disc2*bivariate_norm_cdf(z2,y2,rho)
+                -k1*disc1*norm_cdf(y2),
+            (false,true)=>k2*disc2*bivariate_norm_cdf(z2,-y2,-rho)
+                -forward_pv*bivariate_norm_cdf(z1,-y1,-rho)
+                +k1*disc1*norm_cdf(-y2),
+            (true,false)=>k2*disc2*bivariate_norm_cdf(-z2,-y2,rho)
+                -forward_pv*bivariate_norm_cdf(-z1,-y1,rho)
+                -k1*disc1*norm_cdf(-y2),
+            (false,false)=>forward_pv*bivariate_norm_cdf(-z1,y1,-rho)
+                -k2*disc2*bivariate_norm_cdf(-z2,y2,-rho)
+                +k1*disc1*norm_cdf(y2),
+        };
+        Ok(price.max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn payoff(outer_is_call:bool,inner_is_call:bool)->CompoundOptionPayoff{
+        CompoundOptionPayoff{
+            outer_is_call,
+            inner_is_call,
+            outer_strike:50.0,
+            inner_strike:520.0,
+            inner_time:0.25,
+        }
+    }
+
+    #[test]
+    fn test_compound_option_geske()->Result<()>{
+        // S=500, K1=50, K2=520, T1=0.25, T2=0.5, r=0.08, q=0.05, σ=0.35
+        // 参考值由对S(T1)的对数正态分布数值积分得到
+        let params=CommonParams::new(500.0,0.08,0.35,0.05,0.25)?;
+        let put_on_call=CompoundCalculator.calculate(&params,&payoff(false,true))?;
+        assert_approx_eq!(put_on_call,22.1824,1e-3);
+
+        // 复合期权平价：C(C) - P(C) = C(S,K2,T2) - K1·e^{-rT1}
+        let call_on_call=CompoundCalculator.calculate(&params,&payoff(true,true))?;
+        let inner_call=black_scholes_price(500.0,520.0,0.08,0.05,0.35,0.5,true);
+        assert_approx_eq!(call_on_call-put_on_call,inner_call-50.0*(-0.08f64*0.25).exp(),1e-8);
+
+        let call_on_put=CompoundCalculator.calculate(&params,&payoff(true,false))?;
+        let put_on_put=CompoundCalculator.calculate(&params,&payoff(false,false))?;
+        assert_approx_eq!(call_on_put,20.2038,1e-3);
+        let inner_put=black_scholes_price(500.0,520.0,0.08,0.05,0.35,0.5,false);
+        assert_approx_eq!(call_on_put-put_on_put,inner_put-50.0*(-0.08f64*0.25).exp(),1e-8);
+        Ok(())
+    }
+}
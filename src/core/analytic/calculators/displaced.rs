@@ -0,0 +1,71 @@
+//! Displaced-diffusion (shifted lognormal) vanilla options <br>
+//! 平移扩散（平移对数正态）普通期权
+//!
+//! F + shift follows a driftless geometric Brownian motion, so a call on F struck at K is a
+//! Black-Scholes call on F + shift struck at K + shift. The shift lets the forward and the
+//! strike go down to -shift, which is how shifted-Black volatilities of negative rates are quoted. <br>
+//! F + shift服从无漂移几何布朗运动，因此远期F上行权价K的看涨期权即F + shift上行权价K + shift的
+//! Black-Scholes看涨期权；平移使远期与行权价可低至-shift（负利率下平移Black波动率的报价方式）
+//!
+//! Used by `AnalyticEngine` when the dynamics is `Dynamics::DisplacedDiffusion`. <br>
+//! 动态为`Dynamics::DisplacedDiffusion`时由`AnalyticEngine`调用
+use crate::errors::*;
+use crate::params::common::{CommonParams, Dynamics};
+use crate::traits::engine::AnalyticCalculator;
+use crate::traits::payoff::{AnalyticPayoffType, CallPayoff, Payoff, PutPayoff};
+use super::VanillaCalculator;
+
+/// 平移扩散普通期权计算器
+#[derive(Debug,Clone)]
+pub struct DisplacedDiffusionCalculator;
+
+impl DisplacedDiffusionCalculator{
+    fn shift(params:&CommonParams)->Result<f64>{
+        match params.dynamics(){
+            Dynamics::DisplacedDiffusion{shift}=>Ok(shift),
+            _=>Err(OptionError::InvalidParameter("Displaced diffusion calculator needs displaced diffusion dynamics".into())),
+        }
+    }
+
+    /// Lognormal parameters of the shifted underlying: the spot becomes S + shift·e^{-(r-q)T} so
+    /// that its forward is F + shift <br>
+    /// 平移标的的对数正态参数：现价取S + shift·e^{-(r-q)T}，使其远期为F + shift
+    pub fn shifted_params(params:&CommonParams)->Result<CommonParams>{
+        let shift=Self::shift(params)?;
+        let (s,r,sigma,q,t)=params.all_params();
+        CommonParams::new(s+shift*(-(r-q)*t).exp(),r,sigma,q,t)
+    }
+}
+
+impl AnalyticCalculator for DisplacedDiffusionCalculator{
+    fn supported_types(&self) -> Vec<AnalyticPayoffType> {
+        vec![AnalyticPayoffType::VanillaCall,AnalyticPayoffType::VanillaPut]
+    }
+
+    fn calculate(&self, params: &CommonParams, payoff: &dyn Payoff) -> Result<f64> {
+        if params.time_to_maturity()==0.0{
+            return Ok(payoff.payoff(params.spot()));
+        }
+        let shift=Self::shift(params)?;
+        let shifted=Self::shifted_params(params)?;
+        let (strike,is_call)=match payoff.as_any().downcast_ref::<CallPayoff>(){
+            Some(call)=>(call.strike,true),
+            None=>match payoff.as_any().downcast_ref::<PutPayoff>(){
+                Some(put)=>(put.strike,false),
+                None=>return Err(OptionError::InvalidParameter("Displaced diffusion calculator only support \
+                vanilla call/put option".into())),
+            }
+        };
+        let shifted_strike=strike+shift;
+        if shifted_strike<=0.0{
+            // F + shift > 0 ≥ K + shift：看涨期权必然行权，看跌期权价值为零
+            let discount=(-params.risk_free_rate()*params.time_to_maturity()).exp();
+            return Ok(if is_call{discount*(params.forward()-strike)}else{0.0});
+        }
+        if is_call{
+            VanillaCalculator.calculate(&shifted,&CallPayoff::new(shifted_strike))
+        }else{
+            VanillaCalculator.calculate(&shifted,&PutPayoff::new(shifted_strike))
+        }
+    }
+}
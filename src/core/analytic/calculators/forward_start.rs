@@ -0,0 +1,56 @@
+//! Forward start options <br>
+//! 远期生效期权解析解
+//! - Rubinstein (1990): the option starting at t₁ with strike α·S(t₁) is worth
+//!   S·e^{-q·t₁} units of the option on a unit spot with strike α
+//!
+//! The time to maturity of the common parameters is the start date t₁. <br>
+//! 通用参数中的到期时间为生效日t₁
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::traits::engine::AnalyticCalculator;
+use crate::traits::payoff::{AnalyticPayoffType, ForwardStartPayoff, Payoff};
+use crate::utils::statistics::black_scholes_price;
+
+#[derive(Debug,Clone)]
+pub struct ForwardStartCalculator;
+
+impl AnalyticCalculator for ForwardStartCalculator{
+    fn supported_types(&self) -> Vec<AnalyticPayoffType> {
+        vec![AnalyticPayoffType::ForwardStartCall,AnalyticPayoffType::ForwardStartPut]
+    }
+
+    fn calculate(&self, params: &CommonParams, payoff: &dyn Payoff) -> Result<f64> {
+        let forward=payoff.as_any().downcast_ref::<ForwardStartPayoff>()
+            .ok_or_else(|| OptionError::InvalidParameter("Forward start calculator only support \
+            forward start call/put option".into()))?;
+        if forward.strike_ratio<=0.0{
+            return Err(OptionError::InvalidParameter("Strike ratio must be greater than zero.".into()));
+        }
+        if forward.remaining_time<0.0{
+            return Err(OptionError::InvalidParameter("Option cannot expire before the start date".into()));
+        }
+        let (s,r,sigma,q,t1)=params.all_params();
+        // 期权价值关于标的价格一次齐次
+        let unit_value=black_scholes_price(1.0,forward.strike_ratio,r,q,sigma,forward.remaining_time,forward.is_call);
+        Ok(s*(-q*t1).exp()*unit_value)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_forward_start_rubinstein()->Result<()>{
+        // Haug (2007) 表4-1: S=60, α=1.1, t1=0.25, T=1, r=0.08, b=0.04, σ=0.3
+        let payoff=ForwardStartPayoff{
+            is_call:true,
+            strike_ratio:1.1,
+            remaining_time:0.75,
+        };
+        let price=ForwardStartCalculator.calculate(&CommonParams::new(60.0,0.08,0.3,0.04,0.25)?,&payoff)?;
+        assert_approx_eq!(price,4.4064,1e-3);
+        Ok(())
+    }
+}
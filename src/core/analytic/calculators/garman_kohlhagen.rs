@@ -0,0 +1,187 @@
+//! Garman-Kohlhagen (1983) FX options <br>
+//! Garman-Kohlhagen (1983) 外汇期权
+//!
+//! C = S·e^{-r_f·T}·N(d₁) - K·e^{-r_d·T}·N(d₂), d₁ = (ln(S/K) + (r_d - r_f + σ²/2)·T)/(σ√T),
+//! in domestic currency per unit of foreign notional; `FxPremium` converts it to the other
+//! quoting conventions. As an `AnalyticCalculator` it reads `CommonParams` through
+//! `GarmanKohlhagenParams::from_common` and prices in domestic pips. <br>
+//! 结果为每单位外币名义本金的本币金额，`FxPremium`将其换算为其他报价约定；作为
+//! `AnalyticCalculator`时经`GarmanKohlhagenParams::from_common`读取`CommonParams`，以本币点数定价
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::params::fx::{FxAtmConvention, FxDeltaConvention, FxPremium, GarmanKohlhagenParams};
+use crate::utils::math::brent_root;
+use crate::traits::engine::AnalyticCalculator;
+use crate::traits::payoff::{AnalyticPayoffType, CallPayoff, Payoff, PutPayoff};
+use crate::utils::statistics::{black_scholes_price, norm_cdf, norm_inv, norm_pdf};
+
+/// Closed-form sensitivities of an FX option in domestic currency per unit of foreign notional <br>
+/// 外汇期权的解析敏感度（每单位外币名义本金的本币金额）
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct GarmanKohlhagenGreeks{
+    /// ∂V/∂S, the spot delta 即期delta
+    pub delta:f64,
+    /// ∂V/∂F·e^{r_d·T}, the forward delta N(d₁) (call) or -N(-d₁) (put) 远期delta
+    pub forward_delta:f64,
+    pub gamma:f64,
+    pub vega:f64,
+    /// -∂V/∂T 随日历时间流逝的价值变化
+    pub theta:f64,
+    /// ∂V/∂r_d
+    pub domestic_rho:f64,
+    /// ∂V/∂r_f
+    pub foreign_rho:f64,
+}
+
+/// Garman-Kohlhagen计算器
+#[derive(Debug,Clone)]
+pub struct GarmanKohlhagenCalculator;
+
+impl GarmanKohlhagenCalculator{
+    /// Premium of a vanilla call/put in the given quoting convention <br>
+    /// 指定报价约定下普通看涨/看跌期权的期权费
+    pub fn price(params:&GarmanKohlhagenParams,payoff:&dyn Payoff,premium:FxPremium)->Result<f64>{
+        let (strike,is_call)=Self::strike_and_kind(payoff)?;
+        let s=params.spot();
+        let t=params.time_to_maturity();
+        let value=if t==0.0{
+            payoff.payoff(s)
+        }else{
+            black_scholes_price(s,strike,params.domestic_rate(),params.foreign_rate(),params.volatility(),t,is_call)
+        };
+        Ok(premium.from_domestic_pips(value,s,strike))
+    }
+
+    /// Price sensitivities of a vanilla call/put; needs a positive maturity <br>
+    /// 普通看涨/看跌期权的敏感度；要求期限为正
+    pub fn greeks(params:&GarmanKohlhagenParams,payoff:&dyn Payoff)->Result<GarmanKohlhagenGreeks>{
+        let (strike,is_call)=Self::strike_and_kind(payoff)?;
+        let (s,rd,rf,sigma,t)=(
+            params.spot(),params.domestic_rate(),params.foreign_rate(),params.volatility(),params.time_to_maturity()
+        );
+        if t<=0.0{
+            return Err(OptionError::InvalidParameter("Garman-Kohlhagen Greeks need a positive maturity".into()));
+        }
+        let (dom,fgn)=(params.domestic_discount(),params.foreign_discount());
+        let sqrt_t=t.sqrt();
+        let d1=((s/strike).ln()+(rd-rf+0.5*sigma*sigma)*t)/(sigma*sqrt_t);
+        let d2=d1-sigma*sqrt_t;
+        let density=norm_pdf(d1);
+        let sign=if is_call{1.0}else{-1.0};
+        let (n1,n2)=(norm_cdf(sign*d1),norm_cdf(sign*d2));
+        Ok(GarmanKohlhagenGreeks{
+            delta:sign*fgn*n1,
+            forward_delta:sign*n1,
+            gamma:fgn*density/(s*sigma*sqrt_t),
+            vega:s*fgn*density*sqrt_t,
+            theta:-s*fgn*density*sigma/(2.0*sqrt_t)+sign*(rf*s*fgn*n1-rd*strike*dom*n2),
+            domestic_rho:sign*strike*t*dom*n2,
+            foreign_rho:-sign*s*t*fgn*n1,
+        })
+    }
+
+    /// Delta of a call/put struck at `strike` in the given convention, at the volatility of `params` <br>
+    /// 按`params`的波动率计算行权价`strike`处看涨/看跌期权在指定约定下的delta
+    pub fn delta(params:&GarmanKohlhagenParams,strike:f64,is_call:bool,convention:FxDeltaConvention)->Result<f64>{
+        let (sigma,t)=(params.volatility(),params.time_to_maturity());
+        if t<=0.0 || strike<=0.0{
+            return Err(OptionError::InvalidParameter("FX delta needs positive maturity and strike".into()));
+        }
+        let forward=params.forward();
+        let sigma_sqrt_t=sigma*t.sqrt();
+        let d1=((forward/strike).ln()+0.5*sigma_sqrt_t*sigma_sqrt_t)/sigma_sqrt_t;
+        let sign=if is_call{1.0}else{-1.0};
+        let delta=if convention.is_premium_adjusted(){
+            sign*strike/forward*norm_cdf(sign*(d1-sigma_sqrt_t))
+        }else{
+            sign*norm_cdf(sign*d1)
+        };
+        Ok(if convention.is_spot(){params.foreign_discount()*delta}else{delta})
+    }
+
+    /// Strike whose delta in the given convention is `delta` (positive for calls, negative for
+    /// puts), at the volatility of `params`. Premium adjusted call deltas are not monotonic in the
+    /// strike: the strike above the one maximising the delta is returned <br>
+    /// 按`params`的波动率求指定约定下delta为`delta`（看涨为正、看跌为负）的行权价；期权费调整的
+    /// 看涨delta关于行权价不单调，返回使delta最大的行权价之上的解
+    pub fn strike_from_delta(params:&GarmanKohlhagenParams,delta:f64,is_call:bool,convention:FxDeltaConvention)->Result<f64>{
+        let (sigma,t)=(params.volatility(),params.time_to_maturity());
+        if t<=0.0{
+            return Err(OptionError::InvalidParameter("FX strikes from delta need a positive maturity".into()));
+        }
+        let sign=if is_call{1.0}else{-1.0};
+        let scale=if convention.is_spot(){params.foreign_discount()}else{1.0};
+        let magnitude=sign*delta/scale;
+        if !(magnitude>0.0 && magnitude<1.0){
+            return Err(OptionError::InvalidParameter(format!("Delta {} is out of range for a {}",delta,if is_call{"call"}else{"put"})));
+        }
+        let forward=params.forward();
+        let sigma_sqrt_t=sigma*t.sqrt();
+        // 非调整delta：d₁ = ±N⁻¹(|Δ|)，K = F·exp(-d₁σ√T + σ²T/2)
+        let d1=sign*norm_inv(magnitude);
+        let unadjusted=forward*(-d1*sigma_sqrt_t+0.5*sigma_sqrt_t*sigma_sqrt_t).exp();
+        if !convention.is_premium_adjusted(){
+            return Ok(unadjusted);
+        }
+        // 调整delta小于非调整delta，解落在非调整行权价之下
+        let excess=|strike:f64| Self::delta(params,strike,is_call,convention).map_or(f64::NAN,|value| value-delta);
+        let lower=if is_call{
+            // (K/F)·N(d₂)在σ√T·N(d₂) = φ(d₂)处取得最大值
+            let d2=brent_root(|d2| sigma_sqrt_t*norm_cdf(d2)-norm_pdf(d2),-10.0,10.0,1e-14,200)?;
+            let peak=forward*(-d2*sigma_sqrt_t-0.5*sigma_sqrt_t*sigma_sqrt_t).exp();
+            if excess(peak)<0.0{
+                return Err(OptionError::InvalidParameter(format!("No strike has a premium adjusted call delta of {}",delta)));
+            }
+            peak
+        }else{
+            let mut lower=0.5*unadjusted;
+            while excess(lower)<0.0{
+                lower*=0.5;
+                if lower<1e-12*forward{
+                    return Err(OptionError::ConvergenceError("Cannot bracket the strike of the put delta".into()));
+                }
+            }
+            lower
+        };
+        brent_root(excess,lower,unadjusted,1e-14*forward,200)
+    }
+
+    /// At-the-money strike at the volatility of `params` 按`params`的波动率计算平值行权价
+    ///
+    /// The delta neutral strike is F·e^{σ²T/2}, or F·e^{-σ²T/2} with premium adjusted deltas <br>
+    /// delta中性行权价为F·e^{σ²T/2}，期权费调整delta下为F·e^{-σ²T/2}
+    pub fn atm_strike(params:&GarmanKohlhagenParams,atm:FxAtmConvention,convention:FxDeltaConvention)->f64{
+        let forward=params.forward();
+        let variance=params.volatility().powi(2)*params.time_to_maturity();
+        match atm{
+            FxAtmConvention::Forward=>forward,
+            FxAtmConvention::DeltaNeutral if convention.is_premium_adjusted()=>forward*(-0.5*variance).exp(),
+            FxAtmConvention::DeltaNeutral=>forward*(0.5*variance).exp(),
+        }
+    }
+
+    fn strike_and_kind(payoff:&dyn Payoff)->Result<(f64,bool)>{
+        let (strike,is_call)=match payoff.as_any().downcast_ref::<CallPayoff>(){
+            Some(call)=>(call.strike,true),
+            None=>match payoff.as_any().downcast_ref::<PutPayoff>(){
+                Some(put)=>(put.strike,false),
+                None=>return Err(OptionError::InvalidParameter("Garman-Kohlhagen calculator only support \
+                vanilla call/put option".into())),
+            }
+        };
+        if strike<=0.0{
+            return Err(OptionError::InvalidParameter("Strike must be greater than zero.".into()));
+        }
+        Ok((strike,is_call))
+    }
+}
+
+impl AnalyticCalculator for GarmanKohlhagenCalculator{
+    fn supported_types(&self) -> Vec<AnalyticPayoffType> {
+        vec![AnalyticPayoffType::VanillaCall,AnalyticPayoffType::VanillaPut]
+    }
+
+    fn calculate(&self, params: &CommonParams, payoff: &dyn Payoff) -> Result<f64> {
+        Self::price(&GarmanKohlhagenParams::from_common(params)?,payoff,FxPremium::DomesticPips)
+    }
+}
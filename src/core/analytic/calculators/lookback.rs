@@ -0,0 +1,191 @@
+//! Continuously monitored lookback options <br>
+//! 连续监测回望期权解析解
+//! - floating strike: Goldman-Sosin-Gatto (1979)
+//! - fixed strike: Conze-Viswanathan (1991)
+//!
+//! Seasoned contracts are supported through the already observed running minimum/maximum
+//! stored in the payoff. <br>
+//! 通过Payoff中已观测的最低/最高价支持存续期中的回望期权定价
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::traits::engine::AnalyticCalculator;
+use crate::traits::payoff::{
+    AnalyticPayoffType, Payoff,
+    FloatingStrikeLookbackCallPayoff, FloatingStrikeLookbackPutPayoff,
+    FixedStrikeLookbackCallPayoff, FixedStrikeLookbackPutPayoff,
+};
+use crate::utils::statistics::norm_cdf;
+
+/// 持有成本b=r-q接近0时公式中σ²/(2b)项出现0/0，此时将b推离0
+const MIN_COST_OF_CARRY:f64=1e-7;
+
+#[derive(Debug,Clone)]
+pub struct LookbackCalculator;
+
+/// Shared quantities of the lookback formulas <br>
+/// 回望期权公式中的公共量
+struct LookbackTerms{
+    spot:f64,
+    r:f64,
+    b:f64,
+    sigma:f64,
+    t:f64,
+}
+
+impl LookbackTerms{
+    fn new(params:&CommonParams)->Self{
+        let (spot,r,sigma,q,t)=params.all_params();
+        let mut b=r-q;
+        if b.abs()<MIN_COST_OF_CARRY{
+            b=if b<0.0{-MIN_COST_OF_CARRY}else{MIN_COST_OF_CARRY};
+        }
+        Self{spot,r,b,sigma,t}
+    }
+
+    fn d1(&self,reference:f64)->f64{
+        ((self.spot/reference).ln()+(self.b+0.5*self.sigma*self.sigma)*self.t)/(self.sigma*self.t.sqrt())
+    }
+
+    /// 2b/σ²
+    fn y(&self)->f64{
+        2.0*self.b/(self.sigma*self.sigma)
+    }
+
+    /// PV[(M_T - x)⁺] for a level x not below the running maximum,
+    /// shared by the floating strike put and the fixed strike call <br>
+    /// 不低于当前最高价的水平x对应的PV[(M_T - x)⁺]（浮动看跌与固定看涨共用）
+    fn max_part(&self,x:f64)->f64{
+        let (s,r,b,t)=(self.spot,self.r,self.b,self.t);
+        let vs=self.sigma*t.sqrt();
+        let y=self.y();
+        let e1=self.d1(x);
+        let e2=e1-vs;
+        s*((b-r)*t).exp()*norm_cdf(e1)-x*(-r*t).exp()*norm_cdf(e2)
+            +s*(-r*t).exp()/y*(-(s/x).powf(-y)*norm_cdf(e1-y*vs)+(b*t).exp()*norm_cdf(e1))
+    }
+
+    /// PV[(x - m_T)⁺] for a level x not above the running minimum,
+    /// shared by the floating strike call and the fixed strike put <br>
+    /// 不高于当前最低价的水平x对应的PV[(x - m_T)⁺]（浮动看涨与固定看跌共用）
+    fn min_part(&self,x:f64)->f64{
+        let (s,r,b,t)=(self.spot,self.r,self.b,self.t);
+        let vs=self.sigma*t.sqrt();
+        let y=self.y();
+        let f1=self.d1(x);
+        let f2=f1-vs;
+        -s*((b-r)*t).exp()*norm_cdf(-f1)+x*(-r*t).exp()*norm_cdf(-f2)
+            +s*(-r*t).exp()/y*((s/x).powf(-y)*norm_cdf(-f1+y*vs)-(b*t).exp()*norm_cdf(-f1))
+    }
+}
+
+impl LookbackCalculator{
+    fn validate_extreme(value:Option<f64>)->Result<()>{
+        if let Some(v)=value && v<=0.0{
+            return Err(OptionError::InvalidParameter("Observed extreme of a lookback option must be positive".into()));
+        }
+        Ok(())
+    }
+}
+
+impl AnalyticCalculator for LookbackCalculator{
+    fn supported_types(&self) -> Vec<AnalyticPayoffType> {
+        vec![
+            AnalyticPayoffType::FloatingStrikeLookbackCall,
+            AnalyticPayoffType::FloatingStrikeLookbackPut,
+            AnalyticPayoffType::FixedStrikeLookbackCall,
+            AnalyticPayoffType::FixedStrikeLookbackPut,
+        ]
+    }
+
+    fn calculate(&self, params: &CommonParams, payoff: &dyn Payoff) -> Result<f64> {
+        let s=params.spot();
+        let t=params.time_to_maturity();
+
+        if t==0.0{
+            return Ok(payoff.payoff(s));
+        }
+        let terms=LookbackTerms::new(params);
+        let disc=(-params.risk_free_rate()*t).exp();
+
+        let any=payoff.as_any();
+        if let Some(call)=any.downcast_ref::<FloatingStrikeLookbackCallPayoff>(){
+            Self::validate_extreme(call.observed_min)?;
+            // 浮动看涨 = S_T的现值 - m_T的现值，其中 PV(m_T) = m·e^{-rT} - PV[(m-m_T)⁺]
+            let running_min=call.observed_min.unwrap_or(s).min(s);
+            let forward_pv=s*(-params.dividend_yield()*t).exp();
+            return Ok(forward_pv-running_min*disc+terms.min_part(running_min));
+        }
+        if let Some(put)=any.downcast_ref::<FloatingStrikeLookbackPutPayoff>(){
+            Self::validate_extreme(put.observed_max)?;
+            // 浮动看跌 = M_T的现值 - S_T的现值，其中 PV(M_T) = M·e^{-rT} + PV[(M_T-M)⁺]
+            let running_max=put.observed_max.unwrap_or(s).max(s);
+            let forward_pv=s*(-params.dividend_yield()*t).exp();
+            return Ok(running_max*disc+terms.max_part(running_max)-forward_pv);
+        }
+        if let Some(call)=any.downcast_ref::<FixedStrikeLookbackCallPayoff>(){
+            Self::validate_extreme(call.observed_max)?;
+            if call.strike<=0.0{
+                return Err(OptionError::InvalidParameter("Strike must be greater than zero.".into()));
+            }
+            let running_max=call.observed_max.unwrap_or(s).max(s);
+            let x=call.strike.max(running_max);
+            return Ok(disc*(x-call.strike)+terms.max_part(x));
+        }
+        if let Some(put)=any.downcast_ref::<FixedStrikeLookbackPutPayoff>(){
+            Self::validate_extreme(put.observed_min)?;
+            if put.strike<=0.0{
+                return Err(OptionError::InvalidParameter("Strike must be greater than zero.".into()));
+            }
+            let running_min=put.observed_min.unwrap_or(s).min(s);
+            let x=put.strike.min(running_min);
+            return Ok(disc*(put.strike-x)+terms.min_part(x));
+        }
+        Err(OptionError::InvalidParameter("Lookback calculator only support \
+        floating/fixed strike lookback call/put option".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_floating_strike_lookback_zero_cost_of_carry()->Result<()>{
+        // b=r-q=0时σ²/(2b)项为0/0，价格应与b接近0时连续
+        let payoff=FloatingStrikeLookbackCallPayoff::new(None);
+        let at_zero=LookbackCalculator.calculate(&CommonParams::new(100.0,0.05,0.3,0.05,0.5)?,&payoff)?;
+        let below=LookbackCalculator.calculate(&CommonParams::new(100.0,0.05,0.3,0.0501,0.5)?,&payoff)?;
+        let above=LookbackCalculator.calculate(&CommonParams::new(100.0,0.05,0.3,0.0499,0.5)?,&payoff)?;
+        assert!(at_zero.is_finite());
+        assert_approx_eq!(at_zero,0.5*(below+above),1e-3);
+
+        // 已观测最低价等于现价时与新合约相同
+        let seasoned=LookbackCalculator.calculate(
+            &CommonParams::new(100.0,0.05,0.3,0.02,0.5)?,
+            &FloatingStrikeLookbackCallPayoff::new(Some(100.0))
+        )?;
+        let fresh=LookbackCalculator.calculate(&CommonParams::new(100.0,0.05,0.3,0.02,0.5)?,&payoff)?;
+        assert_approx_eq!(seasoned,fresh,1e-12);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fixed_strike_lookback_bounds()->Result<()>{
+        // 回望期权价值不低于同执行价的欧式期权，且已观测极值越极端价值越高
+        let params=CommonParams::new(100.0,0.05,0.25,0.01,1.0)?;
+        let fresh=LookbackCalculator.calculate(&params,&FixedStrikeLookbackCallPayoff::new(100.0,None))?;
+        let seasoned=LookbackCalculator.calculate(&params,&FixedStrikeLookbackCallPayoff::new(100.0,Some(120.0)))?;
+        let vanilla=crate::core::analytic::calculators::VanillaCalculator
+            .calculate(&params,&crate::traits::payoff::CallPayoff::new(100.0))?;
+        assert!(fresh>vanilla);
+        assert!(seasoned>fresh);
+        // 已观测最高价120时，价值至少是(120-100)的贴现值
+        assert!(seasoned>20.0*(-0.05f64).exp());
+
+        let fresh_put=LookbackCalculator.calculate(&params,&FixedStrikeLookbackPutPayoff::new(100.0,None))?;
+        let floating_put=LookbackCalculator.calculate(&params,&FloatingStrikeLookbackPutPayoff::new(None))?;
+        assert!(fresh_put>0.0 && floating_put>0.0);
+        Ok(())
+    }
+}
@@ -1,7 +1,27 @@
 pub mod vanilla;
 pub mod binary;
 pub mod barrier;
+pub mod lookback;
+pub mod spread;
+pub mod compound;
+pub mod chooser;
+pub mod forward_start;
+pub mod american;
+pub mod bachelier;
+pub mod displaced;
+pub mod black76;
+pub mod garman_kohlhagen;
 
 pub use vanilla::VanillaCalculator;
 pub use binary::BinaryCalculator;
-pub use barrier::BarrierCalculator;
\ No newline at end of file
+pub use barrier::BarrierCalculator;
+pub use lookback::LookbackCalculator;
+pub use spread::SpreadCalculator;
+pub use compound::CompoundCalculator;
+pub use chooser::ChooserCalculator;
+pub use forward_start::ForwardStartCalculator;
+pub use american::{AmericanCalculator, AmericanApproximation};
+pub use bachelier::{BachelierCalculator, BachelierGreeks};
+pub use displaced::DisplacedDiffusionCalculator;
+pub use black76::{Black76Calculator, Black76Greeks};
+pub use garman_kohlhagen::{GarmanKohlhagenCalculator, GarmanKohlhagenGreeks};
@@ -0,0 +1,143 @@
+//! Two-asset spread and exchange options <br>
+//! 两资产价差期权与交换期权
+//! - K=0: Margrabe (1978) exchange option, exact under correlated GBM
+//! - K≠0: Kirk (1995) approximation, treating F2+K as lognormal
+//!
+//! The calculator works on [`TwoAssetParams`] instead of [`CommonParams`](crate::params::common::CommonParams),
+//! so `AnalyticEngine` reaches it through [`TwoAssetPriceEngine`](crate::traits::engine::TwoAssetPriceEngine)
+//! rather than the single-asset registry. <br>
+//! 该计算器使用两资产参数，AnalyticEngine通过两资产定价引擎接口调用，而非单资产计算器注册表
+use crate::errors::*;
+use crate::params::two_asset::TwoAssetParams;
+use crate::traits::payoff::{TwoAssetPayoff,SpreadCallPayoff,SpreadPutPayoff};
+use crate::utils::statistics::norm_cdf;
+
+#[derive(Debug,Clone)]
+pub struct SpreadCalculator;
+
+impl SpreadCalculator{
+    /// Price a spread payoff: Margrabe when the strike is zero, Kirk otherwise <br>
+    /// 计算价差期权价格：执行价为0时使用Margrabe公式，否则使用Kirk近似
+    pub fn calculate(&self,params:&TwoAssetParams,payoff:&dyn TwoAssetPayoff)->Result<f64>{
+        let (strike,is_call)=match payoff.as_any().downcast_ref::<SpreadCallPayoff>(){
+            Some(call)=>(call.strike,true),
+            None=>match payoff.as_any().downcast_ref::<SpreadPutPayoff>(){
+                Some(put)=>(put.strike,false),
+                None=>return Err(OptionError::InvalidParameter("Spread calculator only support \
+                spread call/put option".into())),
+            }
+        };
+        if params.time_to_maturity()==0.0{
+            return Ok(payoff.payoff(params.spot1(),params.spot2()));
+        }
+        if strike==0.0{
+            self.margrabe(params,is_call)
+        }else{
+            self.kirk(params,strike,is_call)
+        }
+    }
+
+    /// Margrabe formula for the option to exchange asset 2 for asset 1
+    /// (call: max(S1-S2,0), put: max(S2-S1,0)) <br>
+    /// Margrabe交换期权公式
+    pub fn margrabe(&self,params:&TwoAssetParams,is_call:bool)->Result<f64>{
+        let t=params.time_to_maturity();
+        let (s1,s2)=(params.spot1(),params.spot2());
+        if t==0.0{
+            let diff=if is_call{s1-s2}else{s2-s1};
+            return Ok(diff.max(0.0));
+        }
+        let sigma=Self::exchange_volatility(params.volatility1(),params.volatility2(),params.correlation());
+        if sigma<=0.0{
+            return Err(OptionError::CalculationError("Exchange volatility is zero \
+            for perfectly correlated assets with equal volatility".into()));
+        }
+        let (q1,q2)=(params.dividend_yield1(),params.dividend_yield2());
+        let sigma_sqrt_t=sigma*t.sqrt();
+        let d1=((s1/s2).ln()+(q2-q1+0.5*sigma*sigma)*t)/sigma_sqrt_t;
+        let d2=d1-sigma_sqrt_t;
+        let pv1=s1*(-q1*t).exp();
+        let pv2=s2*(-q2*t).exp();
+        let price=if is_call{
+            pv1*norm_cdf(d1)-pv2*norm_cdf(d2)
+        }else{
+            pv2*norm_cdf(-d2)-pv1*norm_cdf(-d1)
+        };
+        Ok(price)
+    }
+
+    /// Kirk approximation for the spread option max(ω(S1-S2-K),0) <br>
+    /// Kirk价差期权近似公式
+    pub fn kirk(&self,params:&TwoAssetParams,strike:f64,is_call:bool)->Result<f64>{
+        let t=params.time_to_maturity();
+        let r=params.risk_free_rate();
+        if t==0.0{
+            let diff=params.spot1()-params.spot2()-strike;
+            return Ok(if is_call{diff.max(0.0)}else{(-diff).max(0.0)});
+        }
+        let f1=params.spot1()*((r-params.dividend_yield1())*t).exp();
+        let f2=params.spot2()*((r-params.dividend_yield2())*t).exp();
+        let f2_k=f2+strike;
+        if f2_k<=0.0{
+            return Err(OptionError::InvalidParameter("Kirk approximation requires \
+            F2+K to be positive".into()));
+        }
+        // 将F2+K视为对数正态，其波动率按F2/(F2+K)缩放
+        let weight=f2/f2_k;
+        let sigma=Self::exchange_volatility(params.volatility1(),params.volatility2()*weight,params.correlation());
+        if sigma<=0.0{
+            return Err(OptionError::CalculationError("Kirk volatility is zero".into()));
+        }
+        let sigma_sqrt_t=sigma*t.sqrt();
+        let d1=((f1/f2_k).ln()+0.5*sigma*sigma*t)/sigma_sqrt_t;
+        let d2=d1-sigma_sqrt_t;
+        let disc=(-r*t).exp();
+        let price=if is_call{
+            disc*(f1*norm_cdf(d1)-f2_k*norm_cdf(d2))
+        }else{
+            disc*(f2_k*norm_cdf(-d2)-f1*norm_cdf(-d1))
+        };
+        Ok(price)
+    }
+
+    /// σ = √(σ1² + σ2² - 2ρσ1σ2)
+    fn exchange_volatility(sigma1:f64,sigma2:f64,rho:f64)->f64{
+        (sigma1*sigma1+sigma2*sigma2-2.0*rho*sigma1*sigma2).max(0.0).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_margrabe_symmetry_and_kirk_limit()->Result<()>{
+        let params=TwoAssetParams::new(100.0,95.0,0.25,0.2,0.01,0.03,0.4,0.05,1.0)?;
+        let swapped=TwoAssetParams::new(95.0,100.0,0.2,0.25,0.03,0.01,0.4,0.05,1.0)?;
+        let calc=SpreadCalculator;
+
+        // 交换S1与S2的看涨期权等于交换S2与S1的看跌期权
+        assert_approx_eq!(calc.margrabe(&params,true)?,calc.margrabe(&swapped,false)?,1e-12);
+        // 平价关系：C - P = S1e^{-q1T} - S2e^{-q2T}
+        let parity=100.0*(-0.01f64).exp()-95.0*(-0.03f64).exp();
+        assert_approx_eq!(calc.margrabe(&params,true)?-calc.margrabe(&params,false)?,parity,1e-12);
+        // K=0时Kirk退化为Margrabe
+        assert_approx_eq!(calc.kirk(&params,0.0,true)?,calc.margrabe(&params,true)?,1e-12);
+        assert_approx_eq!(calc.calculate(&params,&SpreadCallPayoff::new(0.0))?,calc.margrabe(&params,true)?,1e-12);
+        Ok(())
+    }
+
+    #[test]
+    fn test_kirk_put_call_parity()->Result<()>{
+        // C - P = e^{-rT}(F1 - F2 - K)
+        let params=TwoAssetParams::new(110.0,100.0,0.3,0.25,0.0,0.0,0.6,0.03,0.5)?;
+        let calc=SpreadCalculator;
+        let strike=5.0;
+        let call=calc.calculate(&params,&SpreadCallPayoff::new(strike))?;
+        let put=calc.calculate(&params,&SpreadPutPayoff::new(strike))?;
+        let forward_diff=(110.0-100.0)*(0.015f64).exp()-strike;
+        assert_approx_eq!(call-put,(-0.015f64).exp()*forward_diff,1e-10);
+        Ok(())
+    }
+}
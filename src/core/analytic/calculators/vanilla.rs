@@ -46,4 +46,19 @@ impl AnalyticCalculator for VanillaCalculator{
         };
         Ok(price)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_vanilla_reference_prices()->crate::errors::Result<()>{
+        // Hull《期权、期货及其他衍生产品》例15.6: S=42, K=40, r=0.1, σ=0.2, T=0.5
+        let params=CommonParams::new(42.0,0.1,0.2,0.0,0.5)?;
+        assert_approx_eq!(VanillaCalculator.calculate(&params,&CallPayoff{strike:40.0})?,4.7594,1e-4);
+        assert_approx_eq!(VanillaCalculator.calculate(&params,&PutPayoff{strike:40.0})?,0.8086,1e-4);
+        Ok(())
+    }
+}
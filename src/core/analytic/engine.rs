@@ -2,18 +2,30 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::sync::Arc;
 use crate::errors::OptionError;
-use crate::params::common::CommonParams;
-use crate::traits::payoff::{AnalyticPayoffType, Payoff};
-use crate::traits::engine::{AnalyticCalculator, AnalyticCalculatorRef, PriceEngine};
-use crate::traits::exercise::ExerciseRule;
-use super::calculators::{VanillaCalculator, BinaryCalculator, BarrierCalculator};
+use crate::params::common::{CommonParams,Dynamics};
+use crate::params::two_asset::TwoAssetParams;
+use crate::traits::payoff::{AnalyticPayoffType, Payoff, TwoAssetPayoff};
+use crate::traits::engine::{AnalyticCalculator, AnalyticCalculatorRef, PriceEngine, TwoAssetPriceEngine};
+use crate::traits::exercise::{ExerciseRule,AmericanExercise};
+use super::calculators::{
+    VanillaCalculator, BinaryCalculator, BarrierCalculator, LookbackCalculator,
+    CompoundCalculator, ChooserCalculator, ForwardStartCalculator,
+    AmericanCalculator, AmericanApproximation, BachelierCalculator, DisplacedDiffusionCalculator,
+    SpreadCalculator,
+};
 use crate::errors::*;
 #[derive(Debug,Clone)]
 pub struct AnalyticEngine{
     /// 解析解的计算器注册表:
     /// - key: option type
     /// - value: corresponding calculator plugin
-    calculators: HashMap<AnalyticPayoffType,AnalyticCalculatorRef>
+    calculators: HashMap<AnalyticPayoffType,AnalyticCalculatorRef>,
+    /// 美式期权解析近似计算器注册表（行权规则为AmericanExercise时使用）
+    american_calculators: HashMap<AnalyticPayoffType,AnalyticCalculatorRef>,
+    /// 正态（Bachelier）动态的计算器注册表（`Dynamics::Normal`时使用）
+    normal_calculators: HashMap<AnalyticPayoffType,AnalyticCalculatorRef>,
+    /// 平移扩散动态的计算器注册表（`Dynamics::DisplacedDiffusion`时使用）
+    displaced_calculators: HashMap<AnalyticPayoffType,AnalyticCalculatorRef>,
 }
 
 impl AnalyticEngine {
@@ -34,7 +46,67 @@ impl AnalyticEngine {
         for typ in barrier_calc.supported_types() {
             calculators.insert(typ,barrier_calc.clone());
         }
-        Self{calculators}
+        // register lookback calculator
+        let lookback_calc=Arc::new(LookbackCalculator) as AnalyticCalculatorRef;
+        for typ in lookback_calc.supported_types() {
+            calculators.insert(typ,lookback_calc.clone());
+        }
+        // register compound calculator
+        let compound_calc=Arc::new(CompoundCalculator) as AnalyticCalculatorRef;
+        for typ in compound_calc.supported_types() {
+            calculators.insert(typ,compound_calc.clone());
+        }
+        // register chooser calculator
+        let chooser_calc=Arc::new(ChooserCalculator) as AnalyticCalculatorRef;
+        for typ in chooser_calc.supported_types() {
+            calculators.insert(typ,chooser_calc.clone());
+        }
+        // register forward start calculator
+        let forward_start_calc=Arc::new(ForwardStartCalculator) as AnalyticCalculatorRef;
+        for typ in forward_start_calc.supported_types() {
+            calculators.insert(typ,forward_start_calc.clone());
+        }
+        let mut engine=Self{
+            calculators,
+            american_calculators:HashMap::new(),
+            normal_calculators:HashMap::new(),
+            displaced_calculators:HashMap::new(),
+        };
+        // register American approximation calculator (Bjerksund-Stensland 2002 by default)
+        engine.register_american_calculator(Arc::new(AmericanCalculator::default()));
+        // register Bachelier and shifted lognormal vanilla calculators
+        engine.register_normal_calculator(Arc::new(BachelierCalculator));
+        engine.register_displaced_calculator(Arc::new(DisplacedDiffusionCalculator));
+        engine
+    }
+
+    /// Analytic engine using the given approximation for American vanilla options <br>
+    /// 使用指定美式近似方法的解析解引擎
+    pub fn with_american_approximation(approximation:AmericanApproximation)->AnalyticEngine{
+        let mut engine=Self::new();
+        engine.register_american_calculator(Arc::new(AmericanCalculator::new(approximation)));
+        engine
+    }
+
+    /// 注册美式期权解析近似计算器
+    pub fn register_american_calculator(&mut self,calculator:AnalyticCalculatorRef){
+        for typ in calculator.supported_types() {
+            self.american_calculators.insert(typ,calculator.clone());
+        }
+    }
+
+    /// 注册正态（Bachelier）动态的计算器
+    pub fn register_normal_calculator(&mut self,calculator:AnalyticCalculatorRef){
+        for typ in calculator.supported_types() {
+            self.normal_calculators.insert(typ,calculator.clone());
+        }
+    }
+
+    /// 注册平移扩散动态的计算器
+    pub fn register_displaced_calculator(&mut self,calculator:AnalyticCalculatorRef){
+        for typ in calculator.supported_types() {
+            self.displaced_calculators.insert(typ,calculator.clone());
+        }
     }
 
     /// 动态注册新的解析解计算器（插件化核心：热扩展）
@@ -57,11 +129,12 @@ impl AnalyticEngine {
 
 impl PriceEngine for AnalyticEngine {
     fn calculate_price(&self, params: &CommonParams, payoff: &dyn Payoff, exercise_rule: &dyn ExerciseRule) -> Result<f64> {
-        // 解析解只支持欧式期权
-        if !exercise_rule.is_european(){
+        // 解析解支持欧式期权，以及有解析近似的美式期权
+        let american=exercise_rule.as_any().is::<AmericanExercise>();
+        if !exercise_rule.is_european() && !american{
             return Err(
                 OptionError::InvalidParameter(
-                    "The pricing of analytical solutions only support European rules".into()
+                    "The pricing of analytical solutions only support European and American rules".into()
                 )
             );
         }
@@ -76,12 +149,20 @@ impl PriceEngine for AnalyticEngine {
                         )
                     )
             )?;
-        let calculator=self.get_calculator(analytic_type)
+        // 美式近似仅适用于对数正态动态；非对数正态动态按远期动态选择注册表
+        let (registry,context)=match (params.dynamics(),american){
+            (Dynamics::Lognormal,false)=>(&self.calculators,""),
+            (Dynamics::Lognormal,true)=>(&self.american_calculators," for American exercise"),
+            (_,true)=>return Err(OptionError::NotImplemented(
+                "American analytic approximations assume lognormal dynamics".into()
+            )),
+            (Dynamics::Normal,false)=>(&self.normal_calculators," for normal dynamics"),
+            (Dynamics::DisplacedDiffusion{..},false)=>(&self.displaced_calculators," for displaced diffusion dynamics"),
+        };
+        let calculator=registry.get(&analytic_type).cloned()
             .ok_or_else(
                 || OptionError::NotImplemented(
-                    format!(
-                        "Not found {:?} calculator",analytic_type
-                    )
+                    format!("Not found {:?} calculator{}",analytic_type,context)
                 )
             )?;
         calculator.calculate(params,payoff)
@@ -91,6 +172,13 @@ impl PriceEngine for AnalyticEngine {
     }
 }
 
+/// 两资产期权的解析解：价差与交换期权由SpreadCalculator（Margrabe/Kirk）计算
+impl TwoAssetPriceEngine for AnalyticEngine {
+    fn calculate_two_asset_price(&self, params: &TwoAssetParams, payoff: &dyn TwoAssetPayoff) -> Result<f64> {
+        SpreadCalculator.calculate(params,payoff)
+    }
+}
+
 /// 默认解析解引擎实例
 impl Default for AnalyticEngine {
     fn default() -> Self {
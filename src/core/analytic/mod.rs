@@ -1,4 +1,6 @@
 pub mod engine;
 pub mod calculators;
+pub mod heston;
 
-pub use engine::AnalyticEngine;
\ No newline at end of file
+pub use engine::AnalyticEngine;
+pub use heston::{HestonAnalyticEngine,HestonPricer};
\ No newline at end of file
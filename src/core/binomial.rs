@@ -1,8 +1,20 @@
 use std::any::Any;
 use crate::errors::*;
-use crate::traits::engine::{PriceEngine,GreeksEngine,BinomialEngineExt};
+use crate::traits::engine::{PriceEngine,GreeksEngine,BinomialEngineExt,ExerciseBoundaryEngine};
 use crate::params::common::CommonParams;
-use crate::traits::{payoff::Payoff, exercise::ExerciseRule};
+use crate::traits::{payoff::{Payoff,OneTouchPayoff,NoTouchPayoff}, exercise::{ExerciseRule,ExerciseBoundary}};
+use crate::utils::statistics::require_lognormal_dynamics;
+
+/// Barrier of a touch payoff, monitored at every node: (barrier, is_up, amount paid at expiry
+/// once the barrier is touched) <br>
+/// 触碰期权的障碍，在每个节点监测：(障碍, 是否向上, 触碰后于到期日支付的金额)
+fn touch_barrier(payoff:&dyn Payoff)->Option<(f64,bool,f64)>{
+    let any=payoff.as_any();
+    if let Some(touch)=any.downcast_ref::<OneTouchPayoff>(){
+        return Some((touch.barrier,touch.is_up,touch.payout));
+    }
+    any.downcast_ref::<NoTouchPayoff>().map(|touch| (touch.barrier,touch.is_up,0.0))
+}
 
 #[derive(Debug,Clone)]
 pub struct BinomialEngine{
@@ -21,13 +33,25 @@ impl BinomialEngine {
     }
 }
 
-impl PriceEngine for BinomialEngine {
-    fn calculate_price(
+impl BinomialEngine {
+    /// Backward induction on the CRR tree, optionally recording the exercise boundary <br>
+    /// CRR二叉树反向归纳，可选记录提前行权边界
+    #[allow(clippy::needless_range_loop)]
+    fn backward_induction(
         &self,
         params: &CommonParams,
         payoff: &dyn Payoff,
-        exercise_rule: &dyn ExerciseRule
+        exercise_rule: &dyn ExerciseRule,
+        mut boundary: Option<&mut ExerciseBoundary>,
     ) -> Result<f64> {
+        require_lognormal_dynamics(params,"Binomial engine")?;
+        exercise_rule.check_maturity(params.time_to_maturity())?;
+        if payoff.requires_path(){
+            return Err(OptionError::NotImplemented("Binomial engine cannot value payoffs defined on a whole path".into()));
+        }
+        // 到期时交付期权的Payoff以当前市场数据估值
+        let bound=payoff.bind_market(params);
+        let payoff=bound.as_deref().unwrap_or(payoff);
         let s=params.spot();
         let r=params.risk_free_rate();
         let q=params.dividend_yield();
@@ -35,7 +59,7 @@ impl PriceEngine for BinomialEngine {
         let t=params.time_to_maturity();
 
         if t<=0.0{
-            return Ok(payoff.payoff(s));
+            return Ok(exercise_rule.exercise_value(0.0,s,payoff.payoff(s)));
         }
 
         let dt=t/self.steps as f64;
@@ -47,36 +71,79 @@ impl PriceEngine for BinomialEngine {
         let p_u=p*disc;
         let p_d=(1.0-p)*disc;
 
+        let barrier=touch_barrier(payoff);
         let mut option_values=vec![0.0;self.steps+1];
         let mut s_current=s*d.powi(self.steps as i32);
 
         for i in 0..=self.steps{
-            option_values[i]=payoff.payoff(s_current);
+            option_values[i]=exercise_rule.exercise_value(0.0,s_current,payoff.payoff(s_current));
             s_current*=u*u;
         }
 
+        let mut spots=Vec::with_capacity(self.steps+1);
+        let mut exercised=Vec::with_capacity(self.steps+1);
         for j in (0..self.steps).rev(){
+            spots.clear();
+            exercised.clear();
             for i in 0..=j{
                 let continuation_value=p_u*option_values[i+1]+p_d*option_values[i];
                 let s_current=s*u.powi(2*i as i32-j as i32);
-                let intrinsic_value=payoff.payoff(s_current);
                 let remaining_time=t-j as f64*dt;
+                let intrinsic_value=exercise_rule.exercise_value(remaining_time,s_current,payoff.payoff(s_current));
 
-                option_values[i]=if exercise_rule.should_exercise(remaining_time,s_current,intrinsic_value,continuation_value){
+                let exercise=exercise_rule.can_exercise(remaining_time,dt)
+                    && exercise_rule.should_exercise(remaining_time,s_current,intrinsic_value,continuation_value);
+                option_values[i]=if exercise{
                     intrinsic_value
                 }else{
                     continuation_value
                 };
+                if let Some((level,is_up,touched_value))=barrier
+                    && (if is_up{s_current>=level}else{s_current<=level}){
+                    option_values[i]=touched_value*(-r*remaining_time).exp();
+                }
+                spots.push(s_current);
+                exercised.push(exercise && intrinsic_value>0.0);
+            }
+            if let Some(boundary)=boundary.as_deref_mut(){
+                boundary.push(j as f64*dt,ExerciseBoundary::critical_price(&spots,&exercised));
             }
         }
+        if let Some(boundary)=boundary{
+            boundary.sort_by_time();
+        }
         Ok(option_values[0])
     }
+}
+
+impl PriceEngine for BinomialEngine {
+    fn calculate_price(
+        &self,
+        params: &CommonParams,
+        payoff: &dyn Payoff,
+        exercise_rule: &dyn ExerciseRule
+    ) -> Result<f64> {
+        self.backward_induction(params,payoff,exercise_rule,None)
+    }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
 }
 
+impl ExerciseBoundaryEngine for BinomialEngine {
+    fn calculate_price_with_boundary(
+        &self,
+        params: &CommonParams,
+        payoff: &dyn Payoff,
+        exercise_rule: &dyn ExerciseRule
+    ) -> Result<(f64,ExerciseBoundary)> {
+        let mut boundary=ExerciseBoundary::new();
+        let price=self.backward_induction(params,payoff,exercise_rule,Some(&mut boundary))?;
+        Ok((price,boundary))
+    }
+}
+
 impl BinomialEngineExt for BinomialEngine {
     fn set_steps(&mut self, steps: usize) -> Result<()> {
         if steps<10{
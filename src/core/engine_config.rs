@@ -2,14 +2,16 @@ use std::any::Any;
 use std::sync::Arc;
 
 use crate::traits::engine::BoundaryCondition;
-use crate::traits::engine::{GreeksEngine, PriceEngine};
+use crate::traits::engine::{PriceEngine,ExerciseBoundaryEngine};
 use crate::params::common::CommonParams;
-use crate::traits::{payoff::Payoff, exercise::ExerciseRule};
+use crate::traits::{payoff::Payoff, exercise::{ExerciseRule,ExerciseBoundary}};
 
-use super::analytic::AnalyticEngine;
+use super::analytic::{AnalyticEngine,HestonPricer};
 use super::monte_carlo::MonteCarloEngine;
 use super::binomial::BinomialEngine;
 use super::pde::{PDEEngine,engine::FiniteDifferenceMethod};
+use super::integral_equation::IntegralEquationEngine;
+use crate::params::heston::HestonModelParams;
 use crate::errors::*;
 
 #[derive(Debug,Clone)]
@@ -18,6 +20,8 @@ pub enum EngineConfig{
     Binomial(Arc<BinomialEngine>),
     MonteCarlo(Arc<MonteCarloEngine>),
     PDE(Arc<PDEEngine>),
+    IntegralEquation(Arc<IntegralEquationEngine>),
+    Heston(Arc<HestonPricer>),
 }
 
 impl PriceEngine for EngineConfig{
@@ -32,6 +36,8 @@ impl PriceEngine for EngineConfig{
             EngineConfig::Binomial(engine) => {engine.calculate_price(params, payoff, exercise_rule)},
             EngineConfig::MonteCarlo(engine) => {engine.calculate_price(params, payoff, exercise_rule)},
             EngineConfig::PDE(engine) => {engine.calculate_price(params, payoff, exercise_rule)},
+            EngineConfig::IntegralEquation(engine) => {engine.calculate_price(params, payoff, exercise_rule)},
+            EngineConfig::Heston(engine) => {engine.calculate_price(params, payoff, exercise_rule)},
         }
     }
 
@@ -41,6 +47,24 @@ impl PriceEngine for EngineConfig{
             EngineConfig::MonteCarlo(engine)=>engine.as_any(),
             EngineConfig::Binomial(engine)=>engine.as_any(),
             EngineConfig::PDE(engine)=>engine.as_any(),
+            EngineConfig::IntegralEquation(engine)=>engine.as_any(),
+            EngineConfig::Heston(engine)=>engine.as_any(),
+        }
+    }
+}
+
+impl ExerciseBoundaryEngine for EngineConfig{
+    fn calculate_price_with_boundary(
+        &self,
+        params: &CommonParams,
+        payoff: &dyn Payoff,
+        exercise_rule: &dyn ExerciseRule
+    ) -> Result<(f64,ExerciseBoundary)> {
+        match self{
+            EngineConfig::Binomial(engine) => engine.calculate_price_with_boundary(params, payoff, exercise_rule),
+            EngineConfig::PDE(engine) => engine.calculate_price_with_boundary(params, payoff, exercise_rule),
+            EngineConfig::IntegralEquation(engine) => engine.calculate_price_with_boundary(params, payoff, exercise_rule),
+            _ => Err(OptionError::NotImplemented("Only the binomial, PDE and integral equation engines report the exercise boundary".into())),
         }
     }
 }
@@ -56,6 +80,20 @@ impl EngineConfig{
         )
     }
 
+    /// Analytic engine pricing American vanilla options with the given approximation <br>
+    /// 使用指定近似方法为美式普通期权定价的解析解引擎
+    pub fn analytic_american(
+        approximation:super::analytic::calculators::AmericanApproximation
+    )->Result<Self>{
+        Ok(
+            EngineConfig::Analytic(
+                Arc::new(
+                    AnalyticEngine::with_american_approximation(approximation)
+                )
+            )
+        )
+    }
+
     pub fn binomial(
         steps:usize
     )->Result<Self>{
@@ -113,4 +151,42 @@ impl EngineConfig{
             )
         )
     }
+
+    /// Integral equation (Andersen-Lake-Offengelt) engine for American vanilla options <br>
+    /// 美式普通期权的积分方程（Andersen-Lake-Offengelt）引擎
+    pub fn integral_equation(
+        collocation_points:usize,
+        integration_points:usize,
+        iterations:usize,
+        pricing_points:usize
+    )->Result<Self>{
+        Ok(
+            EngineConfig::IntegralEquation(
+                Arc::new(
+                    IntegralEquationEngine::new(
+                        collocation_points,
+                        integration_points,
+                        iterations,
+                        pricing_points
+                    )?
+                )
+            )
+        )
+    }
+
+    /// Semi-analytic Heston engine for European vanillas: (v₀, κ, θ, ξ, ρ) are taken from
+    /// `model`, spot, rates and maturity from the priced option's `CommonParams` <br>
+    /// 欧式普通期权的Heston半解析引擎：(v₀, κ, θ, ξ, ρ)取自`model`，现价、利率与期限取自
+    /// 所定价期权的`CommonParams`
+    pub fn heston(
+        model:HestonModelParams
+    )->Result<Self>{
+        Ok(
+            EngineConfig::Heston(
+                Arc::new(
+                    HestonPricer::new(model)
+                )
+            )
+        )
+    }
 }
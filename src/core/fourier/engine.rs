@@ -0,0 +1,367 @@
+//! Fourier pricing engine for characteristic function models <br>
+//! 特征函数模型的Fourier定价引擎
+//!
+//! - Carr-Madan (1999): the damped call e^{αk}·C(k) is transformed to log strike space by one
+//!   FFT, which prices a whole strip of N strikes spaced λ = 2π/(Nη) at once (Simpson weights).
+//! - COS (Fang and Oosterlee, 2008): the density of ln(S_T/K) is expanded in a Fourier cosine
+//!   series on [c₁ - L·√(c₂ + √c₄), c₁ + L·√(c₂ + √c₄)]; puts are priced directly and calls by
+//!   put-call parity. Bermudan options of Lévy models follow the COS backward recursion
+//!   (Fang and Oosterlee, 2009): at each exercise date the early exercise point is found by
+//!   Brent's method and the cosine coefficients of the continuation value are integrated in
+//!   closed form. <br>
+//! - Carr-Madan (1999)：对阻尼看涨价格e^{αk}·C(k)做一次FFT，一次性得到对数行权价间距
+//!   λ = 2π/(Nη)的N个行权价的价格（Simpson权重）。
+//! - COS（Fang与Oosterlee, 2008）：在[c₁ - L·√(c₂ + √c₄), c₁ + L·√(c₂ + √c₄)]上将ln(S_T/K)的密度
+//!   展开为傅里叶余弦级数；直接为看跌定价，看涨由平价关系得到。Lévy模型的百慕大期权采用COS
+//!   倒向递推（Fang与Oosterlee, 2009）：在每个行权日以Brent法求提前行权点，继续持有价值的余弦系数
+//!   以闭式积分得到
+use std::any::Any;
+use std::f64::consts::PI;
+use std::sync::Arc;
+use num_complex::Complex64;
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::traits::characteristic::CharacteristicFunction;
+use crate::traits::engine::PriceEngine;
+use crate::traits::exercise::{BermudanExercise,ExerciseRule};
+use crate::traits::payoff::{CallPayoff,Payoff,PutPayoff};
+use crate::utils::math::{brent_root,fft,quadratic_interpolate};
+use crate::utils::statistics::require_lognormal_dynamics;
+
+/// Fourier inversion method Fourier反演方法
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum FourierMethod{
+    /// Carr-Madan FFT with `points` (a power of two) nodes of spacing `eta` and damping `alpha` <br>
+    /// 节点数`points`（2的幂）、间距`eta`、阻尼系数`alpha`的Carr-Madan FFT
+    CarrMadan{points:usize,eta:f64,alpha:f64},
+    /// COS method with `terms` cosine terms on a range of `truncation` cumulant widths <br>
+    /// `terms`个余弦项、截断宽度为`truncation`倍累积量宽度的COS方法
+    Cos{terms:usize,truncation:f64},
+}
+
+impl FourierMethod{
+    /// 4096 nodes, η = 0.25, α = 1.5 4096个节点，η = 0.25，α = 1.5
+    pub fn carr_madan()->Self{
+        FourierMethod::CarrMadan{points:4096,eta:0.25,alpha:1.5}
+    }
+
+    /// 256 terms on L = 10 256个余弦项，L = 10
+    pub fn cos()->Self{
+        FourierMethod::Cos{terms:256,truncation:10.0}
+    }
+
+    // Carr-Madan网格（COS方法取默认网格）
+    fn carr_madan_grid(&self)->(usize,f64,f64){
+        match *self{
+            FourierMethod::CarrMadan{points,eta,alpha}=>(points,eta,alpha),
+            FourierMethod::Cos{..}=>Self::carr_madan().carr_madan_grid(),
+        }
+    }
+
+    // COS展开（Carr-Madan方法取默认展开）
+    fn cos_expansion(&self)->(usize,f64){
+        match *self{
+            FourierMethod::Cos{terms,truncation}=>(terms,truncation),
+            FourierMethod::CarrMadan{..}=>Self::cos().cos_expansion(),
+        }
+    }
+}
+
+/// Fourier engine for European vanillas, and for Bermudan vanillas of Lévy models with the
+/// COS method. Spot, rates and maturity come from `CommonParams`; its volatility is ignored,
+/// the dynamics being those of the characteristic function model <br>
+/// 欧式普通期权的Fourier引擎；COS方法下还支持Lévy模型的百慕大普通期权。现价、利率与期限取自
+/// `CommonParams`，忽略其波动率，动态由特征函数模型给出
+#[derive(Debug,Clone)]
+pub struct FourierEngine{
+    model:Arc<dyn CharacteristicFunction>,
+    method:FourierMethod,
+}
+
+impl FourierEngine{
+    pub fn new(model:Arc<dyn CharacteristicFunction>,method:FourierMethod)->Result<Self>{
+        match method{
+            FourierMethod::CarrMadan{points,eta,alpha}=>{
+                if points<4 || !points.is_power_of_two(){
+                    return Err(OptionError::InvalidParameter("Carr-Madan needs a power of two number of points".into()));
+                }
+                if eta<=0.0 || alpha<=0.0{
+                    return Err(OptionError::InvalidParameter("Carr-Madan needs a positive spacing and damping".into()));
+                }
+            }
+            FourierMethod::Cos{terms,truncation}=>{
+                if terms<2 || truncation<=0.0{
+                    return Err(OptionError::InvalidParameter("COS needs at least 2 terms and a positive truncation".into()));
+                }
+            }
+        }
+        Ok(Self{model,method})
+    }
+
+    /// Carr-Madan engine with the default grid 默认网格的Carr-Madan引擎
+    pub fn carr_madan(model:Arc<dyn CharacteristicFunction>)->Self{
+        Self{model,method:FourierMethod::carr_madan()}
+    }
+
+    /// COS engine with the default expansion 默认展开的COS引擎
+    pub fn cos(model:Arc<dyn CharacteristicFunction>)->Self{
+        Self{model,method:FourierMethod::cos()}
+    }
+
+    // Getter method
+    pub fn model(&self)->&Arc<dyn CharacteristicFunction>{&self.model}
+    pub fn method(&self)->FourierMethod{self.method}
+
+    /// Characteristic function of ln S_T for the market data of `params` 给定市场数据下ln S_T的特征函数
+    pub fn log_price_characteristic_function(&self,params:&CommonParams,u:Complex64)->Complex64{
+        let (s,r,_,q,t)=params.all_params();
+        (Complex64::i()*u*(s.ln()+(r-q)*t)).exp()*self.model.characteristic_function(u,t)
+    }
+
+    /// Carr-Madan call prices on the FFT log strike grid centered on `center`, as (strike, call) <br>
+    /// 以`center`为中心的FFT对数行权价网格上的Carr-Madan看涨价格，返回(行权价, 看涨价格)
+    pub fn carr_madan_calls(&self,params:&CommonParams,center:f64)->Result<Vec<(f64,f64)>>{
+        let (points,eta,alpha)=self.method.carr_madan_grid();
+        validate_market(params,center)?;
+        let (r,t)=(params.risk_free_rate(),params.time_to_maturity());
+        if t<=0.0{
+            return Err(OptionError::InvalidParameter("Carr-Madan needs a positive maturity".into()));
+        }
+        let i=Complex64::i();
+        let lambda=2.0*PI/(points as f64*eta);
+        let lower=center.ln()-0.5*points as f64*lambda;
+        let discount=(-r*t).exp();
+        let mut values:Vec<Complex64>=(0..points).map(|j|{
+            let v=eta*j as f64;
+            let phi=self.log_price_characteristic_function(params,Complex64::new(v,-(alpha+1.0)));
+            let psi=discount*phi/(alpha*alpha+alpha-v*v+i*(2.0*alpha+1.0)*v);
+            // Simpson权重
+            let simpson=(3.0+if j%2==0{-1.0}else{1.0}-if j==0{1.0}else{0.0})/3.0;
+            (-i*v*lower).exp()*psi*eta*simpson
+        }).collect();
+        fft(&mut values)?;
+        Ok(values.iter().enumerate().map(|(u,value)|{
+            let k=lower+lambda*u as f64;
+            (k.exp(),(-alpha*k).exp()/PI*value.re)
+        }).collect())
+    }
+
+    /// European vanilla prices of a strike strip; Carr-Madan interpolates one FFT grid centered
+    /// on the forward, COS expands each strike <br>
+    /// 一组行权价的欧式普通期权价格；Carr-Madan在以远期为中心的单个FFT网格上插值，COS对每个行权价展开
+    pub fn price_strip(&self,params:&CommonParams,strikes:&[f64],is_call:bool)->Result<Vec<f64>>{
+        match self.method{
+            FourierMethod::CarrMadan{..}=>{
+                let grid=self.carr_madan_calls(params,params.forward())?;
+                let log_strikes:Vec<f64>=grid.iter().map(|(k,_)| k.ln()).collect();
+                let calls:Vec<f64>=grid.iter().map(|(_,c)| *c).collect();
+                strikes.iter().map(|&strike|{
+                    validate_market(params,strike)?;
+                    if strike<grid[0].0 || strike>grid[grid.len()-1].0{
+                        return Err(OptionError::InvalidParameter(format!("Strike {} lies outside the FFT grid",strike)));
+                    }
+                    let call=quadratic_interpolate(strike.ln(),&log_strikes,&calls)?;
+                    Ok(if is_call{call}else{put_from_call(params,strike,call)})
+                }).collect()
+            }
+            FourierMethod::Cos{..}=>strikes.iter().map(|&strike| self.cos_european(params,strike,is_call)).collect(),
+        }
+    }
+
+    /// European vanilla price of one strike; Carr-Madan centers the FFT grid on the strike <br>
+    /// 单个行权价的欧式普通期权价格；Carr-Madan以行权价为FFT网格中心
+    pub fn european_price(&self,params:&CommonParams,strike:f64,is_call:bool)->Result<f64>{
+        match self.method{
+            FourierMethod::CarrMadan{points,..}=>{
+                let call=self.carr_madan_calls(params,strike)?[points/2].1;
+                Ok(if is_call{call}else{put_from_call(params,strike,call)})
+            }
+            FourierMethod::Cos{..}=>self.cos_european(params,strike,is_call),
+        }
+    }
+
+    // COS截断区间（相对ln(S/K)的漂移后坐标）与余弦项数
+    fn cos_range(&self,t:f64)->(usize,f64,f64){
+        let (terms,truncation)=self.method.cos_expansion();
+        let (c1,c2,c4)=self.model.cumulants(t);
+        let width=truncation*(c2.abs()+c4.abs().sqrt()).sqrt();
+        (terms,c1-width,c1+width)
+    }
+
+    fn cos_european(&self,params:&CommonParams,strike:f64,is_call:bool)->Result<f64>{
+        validate_market(params,strike)?;
+        let (s,r,_,q,t)=params.all_params();
+        if t==0.0{
+            return Ok(if is_call{(s-strike).max(0.0)}else{(strike-s).max(0.0)});
+        }
+        // y = ln(S_T/K) = x + X_T，x = ln(S/K) + (r-q)T
+        let x=(s/strike).ln()+(r-q)*t;
+        let (terms,a,b)=self.cos_range(t);
+        let (a,b)=(x+a,x+b);
+        let put_coefficients=put_coefficients(strike,a,b,terms,a,0.0_f64.clamp(a,b));
+        let put=(-r*t).exp()*(0..terms).map(|k|{
+            let u=k as f64*PI/(b-a);
+            let phi=(Complex64::i()*u*(x-a)).exp()*self.model.characteristic_function(Complex64::new(u,0.0),t);
+            let weight=if k==0{0.5}else{1.0};
+            weight*phi.re*put_coefficients[k]
+        }).sum::<f64>();
+        let put=put.max(0.0);
+        Ok(if is_call{put+s*(-q*t).exp()-strike*(-r*t).exp()}else{put})
+    }
+
+    /// Bermudan vanilla by the COS backward recursion; the model must be a Lévy process <br>
+    /// 以COS倒向递推为百慕大普通期权定价，模型须为Lévy过程
+    pub fn cos_bermudan(&self,params:&CommonParams,strike:f64,is_call:bool,exercise:&BermudanExercise)->Result<f64>{
+        validate_market(params,strike)?;
+        if !self.model.is_levy(){
+            return Err(OptionError::NotImplemented("The Bermudan COS recursion needs a Lévy model".into()));
+        }
+        let (s,r,_,q,t)=params.all_params();
+        exercise.check_maturity(t)?;
+        let intrinsic=|x:f64| if is_call{strike*(x.exp()-1.0).max(0.0)}else{strike*(1.0-x.exp()).max(0.0)};
+        let x0=(s/strike).ln();
+        if t==0.0{
+            return Ok(intrinsic(x0));
+        }
+        let (terms,a,b)=self.cos_range(t);
+        let (a,b)=(x0+(r-q)*t+a,x0+(r-q)*t+b);
+        let width=b-a;
+        let frequencies:Vec<f64>=(0..terms).map(|k| k as f64*PI/width).collect();
+
+        // 到期日的收益系数
+        let (c,d)=if is_call{(0.0_f64.clamp(a,b),b)}else{(a,0.0_f64.clamp(a,b))};
+        let mut coefficients=if is_call{
+            call_coefficients(strike,a,b,terms,c,d)
+        }else{
+            put_coefficients(strike,a,b,terms,c,d)
+        };
+
+        // 倒向递推：行权日期（不含到期日）及估值日
+        let mut dates:Vec<f64>=exercise.exercise_times().iter().copied().filter(|&time| time<t-1e-12).collect();
+        let exercise_at_start=dates.first().is_some_and(|&time| time<=1e-12);
+        dates.retain(|&time| time>1e-12);
+        let mut later=t;
+        for &date in dates.iter().rev(){
+            let dt=later-date;
+            let transition=self.transition(&frequencies,r,q,dt,&coefficients);
+            let continuation=|x:f64| continuation_value(&transition,&frequencies,a,x);
+            // 提前行权边界：看跌在[a, 0]、看涨在[0, b]上求继续持有价值与内在价值之差的根
+            let gap=|x:f64| continuation(x)-intrinsic(x);
+            let (lower,upper)=if is_call{(0.0_f64.clamp(a,b),b)}else{(a,0.0_f64.clamp(a,b))};
+            let boundary=if gap(lower)*gap(upper)<0.0{
+                brent_root(gap,lower,upper,1e-12,200)?
+            }else if is_call{
+                b
+            }else{
+                a
+            };
+            let (exercise_coefficients,hold)=if is_call{
+                (call_coefficients(strike,a,b,terms,boundary,b),(a,boundary))
+            }else{
+                (put_coefficients(strike,a,b,terms,a,boundary),(boundary,b))
+            };
+            let held=continuation_coefficients(&transition,&frequencies,a,width,hold.0,hold.1);
+            coefficients=exercise_coefficients.iter().zip(&held).map(|(e,h)| e+h).collect();
+            later=date;
+        }
+        let transition=self.transition(&frequencies,r,q,later,&coefficients);
+        let value=continuation_value(&transition,&frequencies,a,x0);
+        Ok(if exercise_at_start{value.max(intrinsic(x0))}else{value})
+    }
+
+    // 一个时间步的转移：e^{-rΔt}·φ(u_k, Δt)·e^{iu_k(r-q)Δt}·V_k（首项减半）
+    fn transition(&self,frequencies:&[f64],r:f64,q:f64,dt:f64,coefficients:&[f64])->Vec<Complex64>{
+        let i=Complex64::i();
+        frequencies.iter().zip(coefficients).enumerate().map(|(k,(&u,&v))|{
+            let phi=self.model.characteristic_function(Complex64::new(u,0.0),dt)*(i*u*(r-q)*dt).exp();
+            let weight=if k==0{0.5}else{1.0};
+            (-r*dt).exp()*weight*v*phi
+        }).collect()
+    }
+}
+
+// 看涨-看跌平价
+fn put_from_call(params:&CommonParams,strike:f64,call:f64)->f64{
+    let (s,r,_,q,t)=params.all_params();
+    call-s*(-q*t).exp()+strike*(-r*t).exp()
+}
+
+fn validate_market(params:&CommonParams,strike:f64)->Result<()>{
+    require_lognormal_dynamics(params,"Fourier engine")?;
+    if params.spot()<=0.0 || strike<=0.0{
+        return Err(OptionError::InvalidParameter("Spot and strike must be greater than zero.".into()));
+    }
+    Ok(())
+}
+
+// 余弦展开的χ_k(c, d) = ∫_c^d e^y·cos(u_k(y - a))dy与ψ_k(c, d) = ∫_c^d cos(u_k(y - a))dy
+fn chi_psi(k:usize,a:f64,b:f64,c:f64,d:f64)->(f64,f64){
+    let u=k as f64*PI/(b-a);
+    let (cos_d,sin_d)=((u*(d-a)).cos(),(u*(d-a)).sin());
+    let (cos_c,sin_c)=((u*(c-a)).cos(),(u*(c-a)).sin());
+    let chi=(cos_d*d.exp()-cos_c*c.exp()+u*(sin_d*d.exp()-sin_c*c.exp()))/(1.0+u*u);
+    let psi=if k==0{d-c}else{(sin_d-sin_c)/u};
+    (chi,psi)
+}
+
+// 看跌收益K(1 - e^y)在[c, d]上的余弦系数
+fn put_coefficients(strike:f64,a:f64,b:f64,terms:usize,c:f64,d:f64)->Vec<f64>{
+    (0..terms).map(|k|{
+        let (chi,psi)=chi_psi(k,a,b,c,d);
+        2.0/(b-a)*strike*(psi-chi)
+    }).collect()
+}
+
+// 看涨收益K(e^y - 1)在[c, d]上的余弦系数
+fn call_coefficients(strike:f64,a:f64,b:f64,terms:usize,c:f64,d:f64)->Vec<f64>{
+    (0..terms).map(|k|{
+        let (chi,psi)=chi_psi(k,a,b,c,d);
+        2.0/(b-a)*strike*(chi-psi)
+    }).collect()
+}
+
+// 继续持有价值c(x) = Re Σ_j T_j·e^{iu_j(x - a)}
+fn continuation_value(transition:&[Complex64],frequencies:&[f64],a:f64,x:f64)->f64{
+    transition.iter().zip(frequencies).map(|(value,&u)| (value*Complex64::new(0.0,u*(x-a)).exp()).re).sum()
+}
+
+// 继续持有价值在[x1, x2]上的余弦系数：2/(b - a)·Re Σ_j T_j·∫e^{iu_j y}cos(u_k y)dy（y = x - a）
+fn continuation_coefficients(transition:&[Complex64],frequencies:&[f64],a:f64,width:f64,x1:f64,x2:f64)->Vec<f64>{
+    let (y1,y2)=(x1-a,x2-a);
+    let integral=|w:f64|->Complex64{
+        if w.abs()<1e-14{
+            Complex64::new(y2-y1,0.0)
+        }else{
+            (Complex64::new(0.0,w*y2).exp()-Complex64::new(0.0,w*y1).exp())/Complex64::new(0.0,w)
+        }
+    };
+    frequencies.iter().map(|&uk|{
+        let sum:f64=transition.iter().zip(frequencies).map(|(value,&uj)|{
+            (value*0.5*(integral(uj+uk)+integral(uj-uk))).re
+        }).sum();
+        2.0/width*sum
+    }).collect()
+}
+
+impl PriceEngine for FourierEngine{
+    fn calculate_price(&self,params:&CommonParams,payoff:&dyn Payoff,exercise_rule:&dyn ExerciseRule)->Result<f64>{
+        let (strike,is_call)=if let Some(call)=payoff.as_any().downcast_ref::<CallPayoff>(){
+            (call.strike,true)
+        }else if let Some(put)=payoff.as_any().downcast_ref::<PutPayoff>(){
+            (put.strike,false)
+        }else{
+            return Err(OptionError::NotImplemented("The Fourier engine only supports vanilla calls and puts".into()));
+        };
+        if exercise_rule.is_european(){
+            return self.european_price(params,strike,is_call);
+        }
+        match (exercise_rule.as_any().downcast_ref::<BermudanExercise>(),self.method){
+            (Some(bermudan),FourierMethod::Cos{..})=>self.cos_bermudan(params,strike,is_call,bermudan),
+            _=>Err(OptionError::NotImplemented("The Fourier engine prices Bermudan exercise with the COS method only".into())),
+        }
+    }
+
+    fn as_any(&self)->&dyn Any{
+        self
+    }
+}
@@ -0,0 +1,5 @@
+pub mod engine;
+pub mod models;
+
+pub use engine::{FourierEngine,FourierMethod};
+pub use models::{BatesModel,BlackScholesModel,CgmyModel,NigModel,VarianceGammaModel};
\ No newline at end of file
@@ -0,0 +1,233 @@
+//! Characteristic functions of the Fourier pricing models <br>
+//! Fourier定价模型的特征函数
+//!
+//! Every model returns the characteristic function of the martingale part
+//! X_t = ln(S_t/S₀) - (r-q)·t, whose drift ω is fixed by E[e^{X_t}] = 1. <br>
+//! 各模型返回鞅部分X_t = ln(S_t/S₀) - (r-q)·t的特征函数，漂移ω由E[e^{X_t}] = 1确定
+use num_complex::Complex64;
+use statrs::function::gamma::gamma;
+use crate::core::analytic::heston::heston_model_characteristic_function;
+use crate::errors::*;
+use crate::params::heston::HestonModelParams;
+use crate::traits::characteristic::CharacteristicFunction;
+
+/// Geometric Brownian motion: X_t = -σ²t/2 + σW_t 几何布朗运动
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct BlackScholesModel{
+    volatility:f64,
+}
+
+impl BlackScholesModel{
+    pub fn new(volatility:f64)->Result<Self>{
+        if volatility<=0.0{
+            return Err(OptionError::InvalidParameter("Volatility must be greater than zero.".into()));
+        }
+        Ok(Self{volatility})
+    }
+
+    pub fn volatility(&self)->f64{self.volatility}
+}
+
+impl CharacteristicFunction for BlackScholesModel{
+    fn characteristic_function(&self,u:Complex64,t:f64)->Complex64{
+        let i=Complex64::i();
+        (-0.5*self.volatility*self.volatility*t*(i*u+u*u)).exp()
+    }
+
+    fn is_levy(&self)->bool{
+        true
+    }
+
+    fn cumulants(&self,t:f64)->(f64,f64,f64){
+        let variance=self.volatility*self.volatility*t;
+        (-0.5*variance,variance,0.0)
+    }
+}
+
+/// Heston stochastic volatility model (v₀, κ, θ, ξ, ρ), priced from its model parameters <br>
+/// Heston随机波动率模型，直接以其模型参数定价
+impl CharacteristicFunction for HestonModelParams{
+    fn characteristic_function(&self,u:Complex64,t:f64)->Complex64{
+        heston_model_characteristic_function(self,u,t)
+    }
+}
+
+/// Bates model: Heston with Merton lognormal jumps of intensity λ, log jump mean μ_J and
+/// log jump volatility δ_J <br>
+/// Bates模型：带Merton对数正态跳跃（强度λ、对数跳跃均值μ_J、对数跳跃波动率δ_J）的Heston模型
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct BatesModel{
+    heston:HestonModelParams,
+    jump_intensity:f64,
+    jump_mean:f64,
+    jump_volatility:f64,
+}
+
+impl BatesModel{
+    pub fn new(heston:HestonModelParams,jump_intensity:f64,jump_mean:f64,jump_volatility:f64)->Result<Self>{
+        if jump_intensity<0.0 || jump_volatility<0.0{
+            return Err(OptionError::InvalidParameter("Jump intensity and jump volatility cannot be negative.".into()));
+        }
+        Ok(Self{heston,jump_intensity,jump_mean,jump_volatility})
+    }
+
+    // Getter method
+    pub fn heston(&self)->&HestonModelParams{&self.heston}
+    pub fn jump_intensity(&self)->f64{self.jump_intensity}
+    pub fn jump_mean(&self)->f64{self.jump_mean}
+    pub fn jump_volatility(&self)->f64{self.jump_volatility}
+}
+
+impl CharacteristicFunction for BatesModel{
+    fn characteristic_function(&self,u:Complex64,t:f64)->Complex64{
+        let i=Complex64::i();
+        let (mu,delta)=(self.jump_mean,self.jump_volatility);
+        // 补偿后的复合泊松跳跃
+        let compensator=(mu+0.5*delta*delta).exp()-1.0;
+        let jumps=self.jump_intensity*t*((i*u*mu-0.5*delta*delta*u*u).exp()-1.0-i*u*compensator);
+        self.heston.characteristic_function(u,t)*jumps.exp()
+    }
+}
+
+/// Variance Gamma model (Madan, Carr and Chang, 1998): Brownian motion with drift θ and
+/// volatility σ time changed by a gamma process of variance rate ν <br>
+/// Variance Gamma模型（Madan、Carr与Chang, 1998）：漂移θ、波动率σ的布朗运动以方差率ν的Gamma过程时间变换
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct VarianceGammaModel{
+    sigma:f64,
+    nu:f64,
+    theta:f64,
+}
+
+impl VarianceGammaModel{
+    pub fn new(sigma:f64,nu:f64,theta:f64)->Result<Self>{
+        if sigma<=0.0 || nu<=0.0{
+            return Err(OptionError::InvalidParameter("VG σ and ν must be greater than zero.".into()));
+        }
+        if 1.0-theta*nu-0.5*sigma*sigma*nu<=0.0{
+            return Err(OptionError::InvalidParameter("VG needs 1 - θν - σ²ν/2 > 0 for a finite forward".into()));
+        }
+        Ok(Self{sigma,nu,theta})
+    }
+
+    // Getter method
+    pub fn sigma(&self)->f64{self.sigma}
+    pub fn nu(&self)->f64{self.nu}
+    pub fn theta(&self)->f64{self.theta}
+}
+
+impl CharacteristicFunction for VarianceGammaModel{
+    fn characteristic_function(&self,u:Complex64,t:f64)->Complex64{
+        let i=Complex64::i();
+        let (sigma,nu,theta)=(self.sigma,self.nu,self.theta);
+        let omega=(1.0-theta*nu-0.5*sigma*sigma*nu).ln()/nu;
+        (i*u*omega*t).exp()*(1.0-i*u*theta*nu+0.5*sigma*sigma*nu*u*u).powf(-t/nu)
+    }
+
+    fn is_levy(&self)->bool{
+        true
+    }
+
+    fn cumulants(&self,t:f64)->(f64,f64,f64){
+        let (sigma,nu,theta)=(self.sigma,self.nu,self.theta);
+        let omega=(1.0-theta*nu-0.5*sigma*sigma*nu).ln()/nu;
+        (
+            (omega+theta)*t,
+            (sigma*sigma+nu*theta*theta)*t,
+            3.0*(sigma.powi(4)*nu+2.0*theta.powi(4)*nu.powi(3)+4.0*sigma*sigma*theta*theta*nu*nu)*t,
+        )
+    }
+}
+
+/// Normal Inverse Gaussian model (Barndorff-Nielsen, 1997) with tail heaviness α, skew β and
+/// scale δ <br>
+/// 正态逆高斯模型（Barndorff-Nielsen, 1997），尾部参数α、偏斜β、尺度δ
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct NigModel{
+    alpha:f64,
+    beta:f64,
+    delta:f64,
+}
+
+impl NigModel{
+    pub fn new(alpha:f64,beta:f64,delta:f64)->Result<Self>{
+        if delta<=0.0{
+            return Err(OptionError::InvalidParameter("NIG δ must be greater than zero.".into()));
+        }
+        if alpha<=beta.abs() || alpha<=(beta+1.0).abs(){
+            return Err(OptionError::InvalidParameter("NIG needs α > |β| and α > |β + 1| for a finite forward".into()));
+        }
+        Ok(Self{alpha,beta,delta})
+    }
+
+    // Getter method
+    pub fn alpha(&self)->f64{self.alpha}
+    pub fn beta(&self)->f64{self.beta}
+    pub fn delta(&self)->f64{self.delta}
+}
+
+impl CharacteristicFunction for NigModel{
+    fn characteristic_function(&self,u:Complex64,t:f64)->Complex64{
+        let i=Complex64::i();
+        let (alpha,beta,delta)=(self.alpha,self.beta,self.delta);
+        let gamma=(alpha*alpha-beta*beta).sqrt();
+        let omega=delta*((alpha*alpha-(beta+1.0).powi(2)).sqrt()-gamma);
+        let exponent=i*u*omega+delta*(gamma-(alpha*alpha-(beta+i*u)*(beta+i*u)).sqrt());
+        (exponent*t).exp()
+    }
+
+    fn is_levy(&self)->bool{
+        true
+    }
+}
+
+/// CGMY model (Carr, Geman, Madan and Yor, 2002): tempered stable jumps with activity C,
+/// left/right tempering G and M and fine structure Y < 2 (Y ≠ 0, 1) <br>
+/// CGMY模型（Carr、Geman、Madan与Yor, 2002）：活跃度C、左右回火G与M、细结构Y < 2（Y ≠ 0, 1）的回火稳定跳跃
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct CgmyModel{
+    c:f64,
+    g:f64,
+    m:f64,
+    y:f64,
+}
+
+impl CgmyModel{
+    pub fn new(c:f64,g:f64,m:f64,y:f64)->Result<Self>{
+        if c<=0.0 || g<=0.0{
+            return Err(OptionError::InvalidParameter("CGMY C and G must be greater than zero.".into()));
+        }
+        if m<=1.0{
+            return Err(OptionError::InvalidParameter("CGMY needs M > 1 for a finite forward".into()));
+        }
+        if y>=2.0 || y==0.0 || y==1.0{
+            return Err(OptionError::InvalidParameter("CGMY needs Y < 2 with Y ≠ 0 and Y ≠ 1".into()));
+        }
+        Ok(Self{c,g,m,y})
+    }
+
+    // Getter method
+    pub fn c(&self)->f64{self.c}
+    pub fn g(&self)->f64{self.g}
+    pub fn m(&self)->f64{self.m}
+    pub fn y(&self)->f64{self.y}
+
+    // 未补偿的特征指数CΓ(-Y)[(M - iu)^Y - M^Y + (G + iu)^Y - G^Y]
+    fn exponent(&self,u:Complex64)->Complex64{
+        let i=Complex64::i();
+        let (c,g,m,y)=(self.c,self.g,self.m,self.y);
+        c*gamma(-y)*((m-i*u).powf(y)-m.powf(y)+(g+i*u).powf(y)-g.powf(y))
+    }
+}
+
+impl CharacteristicFunction for CgmyModel{
+    fn characteristic_function(&self,u:Complex64,t:f64)->Complex64{
+        let i=Complex64::i();
+        let omega=-self.exponent(-i).re;
+        ((i*u*omega+self.exponent(u))*t).exp()
+    }
+
+    fn is_levy(&self)->bool{
+        true
+    }
+}
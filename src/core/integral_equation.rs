@@ -0,0 +1,338 @@
+//! Andersen-Lake-Offengelt (2016) integral equation engine for American vanilla options <br>
+//! 美式普通期权的Andersen-Lake-Offengelt (2016) 积分方程引擎
+//!
+//! The early exercise boundary B(τ) of the put is found by fixed-point iteration on the
+//! boundary integral equation, collocated at Chebyshev nodes in ξ=√τ on the transformed
+//! function H(ξ)=ln²(B(τ)/X). The price is the European value plus the early exercise
+//! premium integral. Calls use the put-call symmetry C(S,K,r,q)=P(K,S,q,r). <br>
+//! 在ξ=√τ的Chebyshev节点上对变换函数H(ξ)=ln²(B(τ)/X)做配置，通过不动点迭代求解看跌期权的
+//! 提前行权边界积分方程；价格为欧式价格加提前行权溢价积分；看涨期权利用看涨看跌对称性计算
+use std::any::Any;
+use std::f64::consts::PI;
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::traits::engine::{PriceEngine,GreeksEngine,ExerciseBoundaryEngine};
+use crate::traits::exercise::{ExerciseRule,AmericanExercise,ExerciseBoundary};
+use crate::traits::payoff::{Payoff,CallPayoff,PutPayoff};
+use crate::core::analytic::calculators::american::quadratic_exercise_boundary;
+use crate::utils::math::gauss_legendre;
+use crate::utils::statistics::{black_scholes_price,norm_cdf,norm_pdf,require_lognormal_dynamics,validate_common_params};
+
+/// Fixed-point system used to update the boundary <br>
+/// 更新边界所用的不动点方程组
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum FixedPointSystem{
+    /// B = K·e^{-(r-q)τ}·N/D built from the value-matching condition, stable for all inputs <br>
+    /// 由价值匹配条件构造的方程组，对所有输入均稳定
+    #[default]
+    A,
+    /// B = K·e^{-(r-q)τ}·N'/D' built from the smooth-pasting condition; converges faster
+    /// when q≥r but may oscillate when r≫q with low volatility <br>
+    /// 由光滑粘贴条件构造的方程组，q≥r时收敛更快，但r≫q且低波动率时可能振荡
+    B,
+}
+
+/// Integral equation engine for American vanilla options <br>
+/// 美式普通期权积分方程引擎
+#[derive(Debug,Clone)]
+pub struct IntegralEquationEngine{
+    /// Chebyshev collocation nodes of the boundary (n) 边界的Chebyshev配置节点数
+    collocation_points:usize,
+    /// Gauss-Legendre nodes of the boundary integrals (l) 边界积分的Gauss-Legendre节点数
+    integration_points:usize,
+    /// maximum fixed-point iterations (m) 最大不动点迭代次数
+    iterations:usize,
+    /// Gauss-Legendre nodes of the premium integral (p) 溢价积分的Gauss-Legendre节点数
+    pricing_points:usize,
+    system:FixedPointSystem,
+}
+
+impl IntegralEquationEngine{
+    pub fn new(
+        collocation_points:usize,
+        integration_points:usize,
+        iterations:usize,
+        pricing_points:usize,
+    )->Result<Self>{
+        if collocation_points<2 || integration_points<2 || pricing_points<2{
+            return Err(OptionError::InvalidParameter("The integral equation engine needs at least 2 collocation and quadrature nodes".into()));
+        }
+        if iterations==0{
+            return Err(OptionError::InvalidParameter("The integral equation engine needs at least 1 fixed-point iteration".into()));
+        }
+        Ok(Self{collocation_points,integration_points,iterations,pricing_points,system:FixedPointSystem::default()})
+    }
+
+    /// Use the given fixed-point system 使用指定的不动点方程组
+    pub fn with_system(mut self,system:FixedPointSystem)->Self{
+        self.system=system;
+        self
+    }
+
+    /// Early exercise boundary of the unit-strike put with `tau_max` years to maturity <br>
+    /// 执行价为1、期限为`tau_max`的看跌期权的提前行权边界
+    fn put_boundary(&self,r:f64,q:f64,sigma:f64,tau_max:f64)->Result<PutBoundary>{
+        let n=self.collocation_points;
+        let x_star=if q>r{r/q}else{1.0};
+        let nodes:Vec<f64>=(0..=n).map(|k| -(PI*k as f64/n as f64).cos()).collect();
+        let xi_max=tau_max.sqrt();
+        let taus:Vec<f64>=nodes.iter().map(|z| (0.5*xi_max*(1.0+z)).powi(2)).collect();
+        let mut h=vec![0.0;n+1];
+        for k in 1..=n{
+            let guess=quadratic_exercise_boundary(1.0,r,q,sigma,taus[k],false)?.min(x_star);
+            h[k]=(guess/x_star).ln().powi(2);
+        }
+        let mut boundary=PutBoundary{x_star,xi_max,nodes,h};
+
+        let (gl_nodes,gl_weights)=gauss_legendre(self.integration_points);
+        let d=|t:f64,z:f64,sign:f64| (z.ln()+(r-q)*t+sign*0.5*sigma*sigma*t)/(sigma*t.sqrt());
+        for _ in 0..self.iterations{
+            let mut h_new=boundary.h.clone();
+            let mut change:f64=0.0;
+            for k in 1..=n{
+                let tau=taus[k];
+                let b=boundary.at(tau);
+                let (d_minus,d_plus)=(d(tau,b,-1.0),d(tau,b,1.0));
+                let sqrt_tau=tau.sqrt();
+                let (mut numerator,mut denominator)=match self.system{
+                    FixedPointSystem::A=>(norm_cdf(d_minus),norm_cdf(d_plus)),
+                    FixedPointSystem::B=>(
+                        norm_pdf(d_minus)/(sigma*sqrt_tau),
+                        norm_pdf(d_plus)/(sigma*sqrt_tau)+norm_cdf(d_plus),
+                    ),
+                };
+                // u=τ·sin²θ使√u与√(τ-u)均为θ的光滑函数，消除两端的奇异性
+                for (y,w) in gl_nodes.iter().zip(&gl_weights){
+                    let theta=0.25*PI*(1.0+y);
+                    let (sin,cos)=theta.sin_cos();
+                    let u=tau*sin*sin;
+                    let v=sqrt_tau*cos;
+                    let weight=w*0.25*PI*2.0*tau*sin*cos;
+                    let ratio=b/boundary.at(u);
+                    let (dm,dp)=(d(v*v,ratio,-1.0),d(v*v,ratio,1.0));
+                    match self.system{
+                        FixedPointSystem::A=>{
+                            numerator+=r*weight*(r*u).exp()*norm_cdf(dm);
+                            denominator+=q*weight*(q*u).exp()*norm_cdf(dp);
+                        },
+                        FixedPointSystem::B=>{
+                            numerator+=r*weight*(r*u).exp()*norm_pdf(dm)/(sigma*v);
+                            denominator+=q*weight*(q*u).exp()*(norm_cdf(dp)+norm_pdf(dp)/(sigma*v));
+                        },
+                    }
+                }
+                let updated=((-(r-q)*tau).exp()*numerator/denominator).clamp(f64::MIN_POSITIVE,x_star);
+                if !updated.is_finite(){
+                    return Err(OptionError::CalculationError("Non-finite early exercise boundary in the integral equation".into()));
+                }
+                h_new[k]=(updated/x_star).ln().powi(2);
+                change=change.max((updated-b).abs());
+            }
+            boundary.h=h_new;
+            if change<1e-13{
+                break;
+            }
+        }
+        Ok(boundary)
+    }
+
+    /// Unit-strike put value at `spot` together with its boundary <br>
+    /// 执行价为1的看跌期权在`spot`处的价值及其边界
+    fn put_value(&self,spot:f64,r:f64,q:f64,sigma:f64,t:f64)->Result<(f64,Option<PutBoundary>)>{
+        let european=black_scholes_price(spot,1.0,r,q,sigma,t,false);
+        if r<=0.0{
+            // r<0且q<r时存在双边界，不在本引擎处理范围内
+            if q<r{
+                return Err(OptionError::NotImplemented("Double exercise boundaries (q<r<0) are not supported by the integral equation engine".into()));
+            }
+            return Ok((european,None));
+        }
+        let boundary=self.put_boundary(r,q,sigma,t)?;
+        if spot<=boundary.at(t){
+            return Ok((1.0-spot,Some(boundary)));
+        }
+        let (gl_nodes,gl_weights)=gauss_legendre(self.pricing_points);
+        let mut premium=0.0;
+        // z=T·sin²θ平滑边界在z→0处的√z行为及到期附近的√(T-z)行为
+        for (y,weight) in gl_nodes.iter().zip(&gl_weights){
+            let theta=0.25*PI*(1.0+y);
+            let (sin,cos)=theta.sin_cos();
+            let z=t*sin*sin;
+            let remaining=t*cos*cos;
+            if remaining<=0.0{
+                continue;
+            }
+            let b=boundary.at(z);
+            let d_plus=((spot/b).ln()+(r-q+0.5*sigma*sigma)*remaining)/(sigma*remaining.sqrt());
+            let d_minus=d_plus-sigma*remaining.sqrt();
+            let integrand=r*(-r*remaining).exp()*norm_cdf(-d_minus)
+                -q*spot*(-q*remaining).exp()*norm_cdf(-d_plus);
+            premium+=weight*0.25*PI*2.0*t*sin*cos*integrand;
+        }
+        Ok(((european+premium).max(1.0-spot),Some(boundary)))
+    }
+
+    fn solve(
+        &self,
+        params:&CommonParams,
+        payoff:&dyn Payoff,
+        exercise_rule:&dyn ExerciseRule,
+        boundary:Option<&mut ExerciseBoundary>,
+    )->Result<f64>{
+        validate_common_params(params)?;
+        require_lognormal_dynamics(params,"Integral equation engine")?;
+        let (s,r,sigma,q,t)=params.all_params();
+        let (strike,is_call)=match payoff.as_any().downcast_ref::<CallPayoff>(){
+            Some(call)=>(call.strike,true),
+            None=>match payoff.as_any().downcast_ref::<PutPayoff>(){
+                Some(put)=>(put.strike,false),
+                None=>return Err(OptionError::InvalidParameter("Integral equation engine only support \
+                vanilla call/put option".into())),
+            }
+        };
+        if strike<=0.0{
+            return Err(OptionError::InvalidParameter("Strike must be greater than zero.".into()));
+        }
+        if t<=0.0{
+            return Ok(payoff.payoff(s));
+        }
+        if exercise_rule.is_european(){
+            return Ok(black_scholes_price(s,strike,r,q,sigma,t,is_call));
+        }
+        if !exercise_rule.as_any().is::<AmericanExercise>(){
+            return Err(OptionError::InvalidParameter("Integral equation engine only support European and American rules".into()));
+        }
+        // 看涨期权：C(S,K,r,q)=S·P_1(K/S;q,r)，边界B_c(τ)=K/b(τ)，b为互换利率后的单位看跌边界
+        let (value,put_boundary)=if is_call{
+            let (value,put_boundary)=self.put_value(strike/s,q,r,sigma,t)?;
+            (s*value,put_boundary)
+        }else{
+            let (value,put_boundary)=self.put_value(s/strike,r,q,sigma,t)?;
+            (strike*value,put_boundary)
+        };
+        if let (Some(boundary),Some(put_boundary))=(boundary,put_boundary){
+            for z in &put_boundary.nodes{
+                let tau=(0.5*put_boundary.xi_max*(1.0+z)).powi(2);
+                let b=put_boundary.at(tau);
+                boundary.push(t-tau,Some(if is_call{strike/b}else{strike*b}));
+            }
+            boundary.sort_by_time();
+        }
+        Ok(value)
+    }
+}
+
+impl Default for IntegralEquationEngine{
+    fn default()->Self{
+        Self{collocation_points:32,integration_points:32,iterations:20,pricing_points:64,system:FixedPointSystem::default()}
+    }
+}
+
+/// Chebyshev interpolation of H(ξ)=ln²(B/X) on ξ∈[0,√τ_max] (unit strike put) <br>
+/// 单位执行价看跌期权边界在ξ∈[0,√τ_max]上H(ξ)=ln²(B/X)的Chebyshev插值
+#[derive(Debug,Clone)]
+struct PutBoundary{
+    x_star:f64,
+    xi_max:f64,
+    /// Chebyshev-Lobatto nodes z_k=-cos(kπ/n) on [-1,1] 升序Chebyshev-Lobatto节点
+    nodes:Vec<f64>,
+    h:Vec<f64>,
+}
+
+impl PutBoundary{
+    /// Boundary B(τ) 边界B(τ)
+    fn at(&self,tau:f64)->f64{
+        if tau<=0.0{
+            return self.x_star;
+        }
+        let z=(2.0*tau.sqrt()/self.xi_max-1.0).clamp(-1.0,1.0);
+        self.x_star*(-self.interpolate(z).max(0.0).sqrt()).exp()
+    }
+
+    /// Barycentric interpolation on Chebyshev-Lobatto nodes 重心插值
+    fn interpolate(&self,z:f64)->f64{
+        let n=self.nodes.len()-1;
+        let (mut numerator,mut denominator)=(0.0,0.0);
+        for (k,(node,h)) in self.nodes.iter().zip(&self.h).enumerate(){
+            let diff=z-node;
+            if diff==0.0{
+                return *h;
+            }
+            let mut w=if k%2==0{1.0}else{-1.0};
+            if k==0 || k==n{
+                w*=0.5;
+            }
+            numerator+=w*h/diff;
+            denominator+=w/diff;
+        }
+        numerator/denominator
+    }
+}
+
+impl PriceEngine for IntegralEquationEngine{
+    fn calculate_price(
+        &self,
+        params:&CommonParams,
+        payoff:&dyn Payoff,
+        exercise_rule:&dyn ExerciseRule
+    )->Result<f64>{
+        self.solve(params,payoff,exercise_rule,None)
+    }
+
+    fn as_any(&self)->&dyn Any{
+        self
+    }
+}
+
+impl ExerciseBoundaryEngine for IntegralEquationEngine{
+    fn calculate_price_with_boundary(
+        &self,
+        params:&CommonParams,
+        payoff:&dyn Payoff,
+        exercise_rule:&dyn ExerciseRule
+    )->Result<(f64,ExerciseBoundary)>{
+        let mut boundary=ExerciseBoundary::new();
+        let price=self.solve(params,payoff,exercise_rule,Some(&mut boundary))?;
+        Ok((price,boundary))
+    }
+}
+
+impl GreeksEngine for IntegralEquationEngine{}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_converges_to_reference()->Result<()>{
+        // S=K=100, r=5%, q=0, σ=20%, T=1的美式看跌期权，精细网格参考值6.0903705910
+        let params=CommonParams::new(100.0,0.05,0.2,0.0,1.0)?;
+        let payoff=PutPayoff::new(100.0);
+        let exercise=AmericanExercise::new();
+        let price=IntegralEquationEngine::default().calculate_price(&params,&payoff,&exercise)?;
+        assert_approx_eq!(price,6.0903705910,1e-8);
+        // 两种不动点方程组收敛到同一边界
+        let fine=IntegralEquationEngine::new(64,128,60,256)?;
+        for system in [FixedPointSystem::A,FixedPointSystem::B]{
+            let price=fine.clone().with_system(system).calculate_price(&params,&payoff,&exercise)?;
+            assert_approx_eq!(price,6.0903705910,1e-9);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_call_put_symmetry()->Result<()>{
+        // C(S,K,r,q)=P(K,S,q,r)
+        let engine=IntegralEquationEngine::default();
+        let exercise=AmericanExercise::new();
+        let call=engine.calculate_price(&CommonParams::new(110.0,0.03,0.25,0.07,2.0)?,&CallPayoff::new(100.0),&exercise)?;
+        let put=engine.calculate_price(&CommonParams::new(100.0,0.07,0.25,0.03,2.0)?,&PutPayoff::new(110.0),&exercise)?;
+        assert_approx_eq!(call,put,1e-10);
+        // 无股息看涨期权不会提前行权
+        let params=CommonParams::new(100.0,0.05,0.2,0.0,1.0)?;
+        let american=engine.calculate_price(&params,&CallPayoff::new(100.0),&exercise)?;
+        assert_approx_eq!(american,black_scholes_price(100.0,100.0,0.05,0.0,0.2,1.0,true),1e-12);
+        Ok(())
+    }
+}
@@ -2,4 +2,8 @@ pub mod engine_config;
 pub mod binomial;
 pub mod monte_carlo;
 pub mod pde;
-pub mod analytic;
\ No newline at end of file
+pub mod analytic;
+pub mod integral_equation;
+pub mod vanna_volga;
+pub mod variance_swap;
+pub mod fourier;
\ No newline at end of file
@@ -3,10 +3,15 @@
 use rand::{SeedableRng, rngs::StdRng, RngCore};
 use std::any::Any;
 use std::sync::Arc;
-use crate::traits::engine::{PriceEngine, GreeksEngine, MonteCarloEngineExt};
-use crate::traits::{payoff::Payoff,exercise::ExerciseRule,process::StochasticProcess};
+use crate::traits::engine::{PriceEngine, GreeksEngine, MonteCarloEngineExt, TwoAssetPriceEngine};
+use crate::traits::{payoff::{Payoff,TwoAssetPayoff,MultiAssetPayoff},exercise::ExerciseRule,process::{StochasticProcess,MultiAssetProcess}};
 use crate::params::common::CommonParams;
+use crate::params::two_asset::TwoAssetParams;
+use crate::params::multi_asset::MultiAssetParams;
+use crate::simulation::correlated::CorrelatedGeometricBrownianMotion;
 use crate::errors::*;
+use crate::utils::linear_algebra::least_squares;
+use crate::utils::statistics::require_lognormal_dynamics;
 use rayon::prelude::*;
 use indicatif::{ProgressBar, ProgressStyle};
 
@@ -16,6 +21,7 @@ pub struct MonteCarloEngine{
     num_simulations: usize,        //模拟次数
     time_steps: usize,             //时间步数
     process: Option<Arc<dyn StochasticProcess>>, //随机过程
+    multi_asset_process: Option<Arc<dyn MultiAssetProcess>>, //多资产随机过程
     use_antithetic:bool,           //是否启用对偶
     use_parallel:bool,            //是否开启并行
     seed:u64,                   //随机数种子
@@ -41,6 +47,7 @@ impl MonteCarloEngine {
             num_simulations,
             time_steps,
             process,
+            multi_asset_process:None,
             use_antithetic,
             use_parallel,
             seed,
@@ -60,6 +67,13 @@ impl MonteCarloEngine {
         self.use_antithetic = use_antithetic;
     }
 
+    /// Set the process used by multi-asset pricing.
+    /// Without it the risk neutral correlated GBM is built from the parameters <br>
+    /// 设置多资产定价使用的随机过程，未设置时由参数生成风险中性相关几何布朗运动
+    pub fn set_multi_asset_process(&mut self,process:Arc<dyn MultiAssetProcess>){
+        self.multi_asset_process=Some(process);
+    }
+
     fn simulate_single_path(
         &self,
         initial_price:f64,
@@ -155,11 +169,20 @@ impl MonteCarloEngine {
         paths
     }
 
+    /// Value of a simulated path at expiry: the payoff passed through the exercise rule,
+    /// so a strike schedule settles at its last strike <br>
+    /// 模拟路径在到期时的价值：payoff经行权规则转换，执行价安排按最后一个执行价结算
+    fn terminal_value(path:&[f64],payoff:&dyn Payoff,exercise_rule:&dyn ExerciseRule)->f64{
+        let spot=path.last().copied().unwrap_or(0.0);
+        exercise_rule.exercise_value(0.0,spot,payoff.path_dependent_payoff(path))
+    }
+
     fn calculate_total_payoff_serial(
         &self,
         s0:f64,
         t:f64,
         payoff:&dyn Payoff,
+        exercise_rule:&dyn ExerciseRule,
     )->Result<f64>{
         let mut rng=self.create_rng()?;
         let mut total_payoff=0.0f64;
@@ -174,10 +197,10 @@ impl MonteCarloEngine {
 
             if self.use_antithetic{
                 let (path1,path2)=process.simulate_antithetic_path(s0,t,self.time_steps)?;
-                total_payoff+=payoff.path_dependent_payoff(&path1)+payoff.path_dependent_payoff(&path2);
+                total_payoff+=Self::terminal_value(&path1,payoff,exercise_rule)+Self::terminal_value(&path2,payoff,exercise_rule);
             }else{
                 let path=process.simulate_path(s0,t,self.time_steps)?;
-                total_payoff+=payoff.path_dependent_payoff(&path);
+                total_payoff+=Self::terminal_value(&path,payoff,exercise_rule);
             }
             pb.inc(1);
         }
@@ -190,6 +213,7 @@ impl MonteCarloEngine {
         s0:f64,
         t:f64,
         payoff:&dyn Payoff,
+        exercise_rule:&dyn ExerciseRule,
     )->Result<f64>{
         let mut master_rng=self.create_rng()?;
         let num_seeds=if self.use_antithetic{self.num_simulations/2}else{self.num_simulations};
@@ -203,11 +227,11 @@ impl MonteCarloEngine {
 
             let val=if self.use_antithetic{
                 process.simulate_antithetic_path(s0,t,self.time_steps)
-                    .map(|(path1,path2)| payoff.path_dependent_payoff(&path1)+payoff.path_dependent_payoff(&path2))
+                    .map(|(path1,path2)| Self::terminal_value(&path1,payoff,exercise_rule)+Self::terminal_value(&path2,payoff,exercise_rule))
                     .unwrap_or(0.0)
             }else{
                 process.simulate_path(s0,t,self.time_steps)
-                    .map(|path| payoff.path_dependent_payoff(&path))
+                    .map(|path| Self::terminal_value(&path,payoff,exercise_rule))
                     .unwrap_or(0.0)
             };
             pb.inc(1);
@@ -218,8 +242,133 @@ impl MonteCarloEngine {
 
     }
 
+    /// Longstaff-Schwartz least squares Monte Carlo for early exercise rules.
+    /// At every exercisable step the continuation value of in-the-money paths is regressed
+    /// on the cubic polynomial of S/S₀ <br>
+    /// 提前行权规则下的Longstaff-Schwartz最小二乘蒙特卡洛：在每个可行权时点，
+    /// 对实值路径的继续持有价值以S/S₀的三次多项式做回归
+    fn longstaff_schwartz(
+        &self,
+        params:&CommonParams,
+        payoff:&dyn Payoff,
+        exercise_rule:&dyn ExerciseRule,
+    )->Result<f64>{
+        let (s0,r,_,_,t)=params.all_params();
+        exercise_rule.check_maturity(t)?;
+        let paths=if self.use_parallel{
+            self.simulate_paths_parallel(s0,t)?
+        }else{
+            self.simulate_paths(s0,t)?
+        };
+        let steps=self.time_steps;
+        let dt=t/steps as f64;
+        let discount=(-r*dt).exp();
+        let basis=|s:f64|{
+            let x=s/s0;
+            vec![1.0,x,x*x,x*x*x]
+        };
+
+        // 各路径的现金流，折现到当前时点
+        let mut cashflows:Vec<f64>=paths.iter().map(|path| Self::terminal_value(path,payoff,exercise_rule)).collect();
+        for j in (1..steps).rev(){
+            cashflows.iter_mut().for_each(|cashflow| *cashflow*=discount);
+            let remaining_time=t-j as f64*dt;
+            if !exercise_rule.can_exercise(remaining_time,dt){
+                continue;
+            }
+            let in_the_money:Vec<usize>=(0..paths.len())
+                .filter(|&i| exercise_rule.exercise_value(remaining_time,paths[i][j],payoff.payoff(paths[i][j]))>0.0)
+                .collect();
+            if in_the_money.len()<=4{
+                continue;
+            }
+            let design:Vec<Vec<f64>>=in_the_money.iter().map(|&i| basis(paths[i][j])).collect();
+            let observed:Vec<f64>=in_the_money.iter().map(|&i| cashflows[i]).collect();
+            let coefficients=least_squares(&design,&observed)?;
+            for (&i,row) in in_the_money.iter().zip(&design){
+                let spot=paths[i][j];
+                let intrinsic_value=exercise_rule.exercise_value(remaining_time,spot,payoff.payoff(spot));
+                let continuation_value:f64=row.iter().zip(&coefficients).map(|(x,b)| x*b).sum();
+                if exercise_rule.should_exercise(remaining_time,spot,intrinsic_value,continuation_value){
+                    cashflows[i]=intrinsic_value;
+                }
+            }
+        }
+        let price=cashflows.iter().sum::<f64>()*discount/cashflows.len() as f64;
+        // 估值日当天是否立即行权
+        let intrinsic_value=exercise_rule.exercise_value(t,s0,payoff.payoff(s0));
+        if exercise_rule.can_exercise(t,dt) && exercise_rule.should_exercise(t,s0,intrinsic_value,price){
+            return Ok(intrinsic_value);
+        }
+        Ok(price)
+    }
+
+    /// Price an option written on n correlated assets with the process set by
+    /// `set_multi_asset_process`, or the risk neutral correlated GBM built from the parameters <br>
+    /// 多资产期权定价，优先使用已设置的多资产随机过程，否则由参数生成风险中性相关几何布朗运动
+    pub fn calculate_multi_asset_price(
+        &self,
+        params:&MultiAssetParams,
+        payoff:&dyn MultiAssetPayoff,
+    )->Result<f64>{
+        let process:Box<dyn MultiAssetProcess>=match &self.multi_asset_process{
+            Some(process)=>process.clone_box(),
+            None=>Box::new(CorrelatedGeometricBrownianMotion::from_params(params)?),
+        };
+        if process.num_assets()!=params.num_assets(){
+            return Err(OptionError::InvalidParameter("Number of assets of the process \
+            does not match the parameters".into()));
+        }
+        if payoff.num_assets().is_some_and(|n| n!=params.num_assets()){
+            return Err(OptionError::InvalidParameter("Number of assets of the payoff \
+            does not match the parameters".into()));
+        }
+        let s0=params.spots();
+        let t=params.time_to_maturity();
+
+        let evaluate=|seed:u64|->Result<f64>{
+            let mut process=process.clone();
+            process.init_rng_with_seed(seed);
+            if self.use_antithetic{
+                let (paths,anti)=process.simulate_antithetic_paths(s0,t,self.time_steps)?;
+                Ok(payoff.path_dependent_payoff(&paths)+payoff.path_dependent_payoff(&anti))
+            }else{
+                let paths=process.simulate_paths(s0,t,self.time_steps)?;
+                Ok(payoff.path_dependent_payoff(&paths))
+            }
+        };
+        let average=self.average_over_seeds(evaluate)?;
+        Ok(average*(-params.risk_free_rate()*t).exp())
+    }
+
+    /// Run `evaluate` once per sub-seed (serially or in parallel) and average over the paths;
+    /// with antithetic variates every seed yields two paths <br>
+    /// 对每个子种子执行一次evaluate（串行或并行）并按路径数取平均，对偶模式下每个种子对应两条路径
+    fn average_over_seeds<F>(&self,evaluate:F)->Result<f64>
+    where F:Fn(u64)->Result<f64>+Send+Sync{
+        let mut master_rng=self.create_rng()?;
+        let num_seeds=if self.use_antithetic{(self.num_simulations/2).max(1)}else{self.num_simulations};
+        let seeds:Vec<u64>=(0..num_seeds).map(|_| master_rng.next_u64()).collect();
+        let pb=self.create_progress_bar(num_seeds as u64);
+
+        let run=|seed:u64|->Result<f64>{
+            let val=evaluate(seed)?;
+            pb.inc(1);
+            Ok(val)
+        };
+        let total_payoff:f64=if self.use_parallel{
+            seeds.into_par_iter().map(run).sum::<Result<f64>>()?
+        }else{
+            seeds.into_iter().map(run).sum::<Result<f64>>()?
+        };
+        pb.finish_with_message("Simulation finished");
+
+        let num_paths=if self.use_antithetic{2*num_seeds}else{num_seeds};
+        Ok(total_payoff/num_paths as f64)
+    }
+
     fn create_progress_bar(&self,len:u64)->ProgressBar{
-        let pb=ProgressBar::new(len as u64);
+        let pb=ProgressBar::new(len);
         pb.set_style(ProgressStyle::default_bar()
             .template("{spinner:.green}[{elasped_precies}][{bar:40.cyan/blue}]{pos}/{len}({eta})")
             .unwrap()
@@ -256,20 +405,30 @@ impl PriceEngine for MonteCarloEngine {
         &self,
         params: &CommonParams,
         payoff: &dyn Payoff,
-        _exercise_rule: &dyn ExerciseRule
+        exercise_rule: &dyn ExerciseRule
     ) -> Result<f64> {
 
         if self.process.is_none(){
             return Err(OptionError::NotSet("Process not set".to_string()));
         }
+        require_lognormal_dynamics(params,"Monte Carlo engine")?;
+        // 到期时交付期权的Payoff以当前市场数据估值
+        let bound=payoff.bind_market(params);
+        let payoff=bound.as_deref().unwrap_or(payoff);
+        if !exercise_rule.is_european(){
+            if payoff.requires_path(){
+                return Err(OptionError::InvalidParameter("Payoffs defined on a whole path settle at expiry".into()));
+            }
+            return self.longstaff_schwartz(params,payoff,exercise_rule);
+        }
 
         let s0=params.spot();
         let t=params.time_to_maturity();
 
         let total_payoff=if self.use_parallel{
-            self.calculate_total_payoff_parallel(s0,t,payoff)?
+            self.calculate_total_payoff_parallel(s0,t,payoff,exercise_rule)?
         }else{
-            self.calculate_total_payoff_serial(s0,t,payoff)?
+            self.calculate_total_payoff_serial(s0,t,payoff,exercise_rule)?
         };
 
         let avg_payoff=total_payoff/self.num_simulations as f64;
@@ -282,4 +441,34 @@ impl PriceEngine for MonteCarloEngine {
     }
 }
 
+impl TwoAssetPriceEngine for MonteCarloEngine {
+    /// Price an option written on two correlated assets.
+    /// The risk neutral correlated GBM is built from the parameters,
+    /// so no single-asset process needs to be set <br>
+    /// 两资产期权定价，风险中性相关过程由参数生成，无需设置单资产随机过程
+    fn calculate_two_asset_price(
+        &self,
+        params:&TwoAssetParams,
+        payoff:&dyn TwoAssetPayoff,
+    )->Result<f64>{
+        let process=CorrelatedGeometricBrownianMotion::from_params(&params.to_multi_asset()?)?;
+        let s0=[params.spot1(),params.spot2()];
+        let t=params.time_to_maturity();
+
+        let evaluate=|seed:u64|->Result<f64>{
+            let mut process=process.clone();
+            process.init_rng_with_seed(seed);
+            if self.use_antithetic{
+                let (paths,anti)=process.simulate_antithetic_paths(&s0,t,self.time_steps)?;
+                Ok(payoff.path_dependent_payoff(&paths[0],&paths[1])+payoff.path_dependent_payoff(&anti[0],&anti[1]))
+            }else{
+                let paths=process.simulate_paths(&s0,t,self.time_steps)?;
+                Ok(payoff.path_dependent_payoff(&paths[0],&paths[1]))
+            }
+        };
+        let average=self.average_over_seeds(evaluate)?;
+        Ok(average*(-params.risk_free_rate()*t).exp())
+    }
+}
+
 impl GreeksEngine for MonteCarloEngine {}
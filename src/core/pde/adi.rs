@@ -0,0 +1,673 @@
+//! Two-dimensional alternating direction implicit (ADI) finite difference engine <br>
+//! 二维交替方向隐式（ADI）有限差分引擎
+//!
+//! Solves V_τ = a_xx·V_xx + a_yy·V_yy + a_xy·V_xy + b_x·V_x + b_y·V_y - r·V in time to maturity τ.
+//! The operator is split into the mixed part A₀ (treated explicitly) and the x and y parts
+//! A₁, A₂ (treated implicitly one direction at a time), following In 't Hout & Foulon (2010).
+//! Heston problems use (ln S, v), SABR problems (F, ln α), two-asset problems (ln S₁, ln S₂). <br>
+//! 在到期时间τ上求解上述方程：混合导数部分A₀显式处理，x、y方向部分A₁、A₂逐方向隐式处理
+//! （In 't Hout & Foulon, 2010）；Heston问题使用(ln S, v)坐标，SABR问题使用(F, ln α)坐标，
+//! 两资产问题使用(ln S₁, ln S₂)坐标
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::params::heston::HestonParams;
+use crate::params::sabr::SabrParams;
+use crate::params::two_asset::TwoAssetParams;
+use crate::traits::payoff::{Payoff,TwoAssetPayoff};
+use crate::traits::exercise::ExerciseRule;
+use crate::traits::engine::TwoAssetPriceEngine;
+use crate::utils::linear_algebra::thomas_solver_into;
+use crate::utils::math::quadratic_interpolate;
+use super::grid::GridSpec;
+
+/// ADI splitting scheme ADI分裂格式
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum AdiScheme{
+    /// Douglas scheme, second order only without mixed derivatives and with θ=½ <br>
+    /// Douglas格式，仅在无混合导数且θ=½时二阶
+    Douglas,
+    /// Craig-Sneyd scheme: Douglas plus an explicit correction of the mixed term <br>
+    /// Craig-Sneyd格式：在Douglas格式基础上对混合导数项做显式修正
+    CraigSneyd,
+    /// Hundsdorfer-Verwer scheme: second order for any θ, with a full corrector stage <br>
+    /// Hundsdorfer-Verwer格式：对任意θ二阶，含完整的校正阶段
+    #[default]
+    HundsdorferVerwer,
+}
+
+impl AdiScheme{
+    /// θ recommended by In 't Hout & Foulon: ½ for Douglas and Craig-Sneyd, ½+√3/6 for
+    /// Hundsdorfer-Verwer <br>
+    /// In 't Hout & Foulon推荐的θ
+    pub fn default_theta(&self)->f64{
+        match self{
+            AdiScheme::Douglas|AdiScheme::CraigSneyd=>0.5,
+            AdiScheme::HundsdorferVerwer=>0.5+3.0f64.sqrt()/6.0,
+        }
+    }
+}
+
+/// Two-dimensional ADI engine for Heston, SABR and two-asset problems <br>
+/// 用于Heston、SABR及两资产问题的二维ADI引擎
+#[derive(Debug,Clone)]
+pub struct AdiEngine{
+    x_steps:usize,
+    y_steps:usize,
+    t_steps:usize,
+    scheme:AdiScheme,
+    theta:f64,
+    grid:GridSpec,              // 资产价格方向的网格
+    max_variance:f64,           // Heston方差方向的上界
+    damping_steps:usize,        // 到期后用两个隐式半步替换的时间步数
+}
+
+impl AdiEngine{
+    /// `x_steps` and `y_steps` are the spatial steps of the two directions, `t_steps` the
+    /// number of time steps <br>
+    /// `x_steps`、`y_steps`为两个方向的空间步数，`t_steps`为时间步数
+    pub fn new(x_steps:usize,y_steps:usize,t_steps:usize,scheme:AdiScheme)->Result<Self>{
+        if x_steps<10 || y_steps<10 || t_steps<10{
+            return Err(OptionError::InvalidParameter("ADI grids need at least 10 steps in each direction".into()));
+        }
+        Ok(Self{
+            x_steps,
+            y_steps,
+            t_steps,
+            scheme,
+            theta:scheme.default_theta(),
+            grid:GridSpec::StandardDeviations{num_std:5.0},
+            max_variance:5.0,
+            damping_steps:2,
+        })
+    }
+
+    /// Override the implicitness parameter θ ∈ [0, 1] 设定隐式参数θ
+    pub fn with_theta(mut self,theta:f64)->Result<Self>{
+        if !(0.0..=1.0).contains(&theta){
+            return Err(OptionError::InvalidParameter("θ must lie in [0, 1]".into()));
+        }
+        self.theta=theta;
+        Ok(self)
+    }
+
+    /// Grid of the asset price directions (applied to both assets of two-asset problems) <br>
+    /// 资产价格方向的网格（两资产问题中两个资产共用）
+    pub fn with_grid(mut self,grid:GridSpec)->Self{
+        self.grid=grid;
+        self
+    }
+
+    /// Upper bound of the Heston variance grid, concentrated near v=0 by a sinh map <br>
+    /// Heston方差网格上界，网格经sinh变换在v=0附近加密
+    pub fn with_max_variance(mut self,max_variance:f64)->Result<Self>{
+        if max_variance<=0.0{
+            return Err(OptionError::InvalidParameter("The maximum variance must be greater than zero".into()));
+        }
+        self.max_variance=max_variance;
+        Ok(self)
+    }
+
+    /// Number of time steps after maturity replaced by two fully implicit (θ=1 Douglas)
+    /// half-steps to damp the payoff kink (default 2) <br>
+    /// 到期后以两个全隐式（θ=1 Douglas）半步替换的时间步数，用于平滑收益折点（默认2）
+    pub fn with_damping_steps(mut self,damping_steps:usize)->Self{
+        self.damping_steps=damping_steps;
+        self
+    }
+
+    pub fn scheme(&self)->AdiScheme{self.scheme}
+    pub fn theta(&self)->f64{self.theta}
+
+    /// Price an option on a Heston asset; American and other early exercise rules are
+    /// enforced after every time step <br>
+    /// Heston模型下单资产期权定价，美式等提前行权规则在每个时间步后施加
+    pub fn price_heston(&self,params:&HestonParams,payoff:&dyn Payoff,exercise_rule:&dyn ExerciseRule)->Result<f64>{
+        if params.variance()>=self.max_variance{
+            return Err(OptionError::InvalidParameter("The initial variance must lie below the maximum variance of the grid".into()));
+        }
+        exercise_rule.check_maturity(params.time_to_maturity())?;
+        let xs=self.grid.nodes(&params.to_common()?,self.x_steps,true)?;
+        // v_j = d·sinh(j·Δη)，d = v_max/500
+        let d=self.max_variance/500.0;
+        let step=(self.max_variance/d).asinh()/self.y_steps as f64;
+        let ys:Vec<f64>=(0..=self.y_steps).map(|j| d*(j as f64*step).sinh()).collect();
+
+        let (r,q)=(params.risk_free_rate(),params.dividend_yield());
+        let (kappa,theta,xi,rho)=(params.mean_reversion(),params.long_run_variance(),params.vol_of_vol(),params.correlation());
+        let problem=Problem::new(xs,ys,false,|_,v|{
+            [0.5*v,0.5*xi*xi*v,rho*xi*v,r-q-0.5*v,kappa*(theta-v),r]
+        });
+
+        let t=params.time_to_maturity();
+        let terminal=|i:usize,_:usize|{
+            let s=problem.xs[i].exp();
+            exercise_rule.exercise_value(0.0,s,payoff.payoff(s))
+        };
+        let boundary=|tau:f64,i:usize,_:usize|{
+            let s=problem.xs[i].exp();
+            (-r*tau).exp()*payoff.payoff(s*((r-q)*tau).exp())
+        };
+        let exercise=|tau:f64,i:usize,_:usize,continuation:f64|{
+            let s=problem.xs[i].exp();
+            let value=exercise_rule.exercise_value(tau,s,payoff.payoff(s));
+            if exercise_rule.should_exercise(tau,s,value,continuation){value}else{continuation}
+        };
+        let values=if exercise_rule.is_european(){
+            self.solve(&problem,t,&terminal,&boundary,None)?
+        }else{
+            let dt=t/self.t_steps as f64;
+            let can_exercise=|tau:f64| exercise_rule.can_exercise(tau,dt);
+            self.solve(&problem,t,&terminal,&boundary,Some((&can_exercise,&exercise)))?
+        };
+        problem.interpolate(&values,params.spot().ln(),params.variance())
+    }
+
+    /// Price an option on a SABR forward in (F, ln α): `params` gives the forward as its spot,
+    /// the discount rate and the maturity. The forward grid follows the engine's grid in F
+    /// with the ATM lognormal volatility α·F^(β-1); early exercise rules are enforced after
+    /// every time step <br>
+    /// SABR远期上的期权定价，坐标为(F, ln α)：`params`的现价为远期，并给出贴现利率与期限；
+    /// 远期方向按引擎网格设定以ATM对数正态波动率α·F^(β-1)生成，美式等提前行权规则在每个时间步后施加
+    pub fn price_sabr(&self,sabr:&SabrParams,params:&CommonParams,payoff:&dyn Payoff,exercise_rule:&dyn ExerciseRule)->Result<f64>{
+        exercise_rule.check_maturity(params.time_to_maturity())?;
+        let (forward,r,t)=(params.spot(),params.risk_free_rate(),params.time_to_maturity());
+        let (alpha,beta,rho,nu)=(sabr.alpha(),sabr.beta(),sabr.rho(),sabr.nu());
+        let atm=params.with_volatility(alpha*forward.powf(beta-1.0))?;
+        let xs=self.grid.nodes(&atm,self.x_steps,false)?;
+        // ln α_t = ln α - ½ν²t + νW_t，以ln α为中心覆盖±5个标准差
+        let width=(5.0*nu*t.sqrt()).max(0.1);
+        let ys:Vec<f64>=(0..=self.y_steps).map(|j| alpha.ln()-width+2.0*width*j as f64/self.y_steps as f64).collect();
+
+        let problem=Problem::new(xs,ys,false,|f,y|{
+            let local=y.exp()*f.max(0.0).powf(beta);
+            [0.5*local*local,0.5*nu*nu,rho*nu*local,0.0,-0.5*nu*nu,r]
+        });
+
+        let terminal=|i:usize,_:usize|{
+            let f=problem.xs[i];
+            exercise_rule.exercise_value(0.0,f,payoff.payoff(f))
+        };
+        // 远期为鞅，网格边缘取贴现后的收益
+        let boundary=|tau:f64,i:usize,_:usize| (-r*tau).exp()*payoff.payoff(problem.xs[i]);
+        let exercise=|tau:f64,i:usize,_:usize,continuation:f64|{
+            let f=problem.xs[i];
+            let value=exercise_rule.exercise_value(tau,f,payoff.payoff(f));
+            if exercise_rule.should_exercise(tau,f,value,continuation){value}else{continuation}
+        };
+        let values=if exercise_rule.is_european(){
+            self.solve(&problem,t,&terminal,&boundary,None)?
+        }else{
+            let dt=t/self.t_steps as f64;
+            let can_exercise=|tau:f64| exercise_rule.can_exercise(tau,dt);
+            self.solve(&problem,t,&terminal,&boundary,Some((&can_exercise,&exercise)))?
+        };
+        problem.interpolate(&values,forward,alpha.ln())
+    }
+
+    /// March from τ=0 to τ=T; Dirichlet nodes take the `boundary` values and the optional
+    /// exercise hooks are applied after every step <br>
+    /// 从τ=0推进到τ=T：Dirichlet节点取`boundary`值，每步后施加可选的行权处理
+    fn solve(
+        &self,
+        problem:&Problem,
+        t:f64,
+        terminal:&dyn Fn(usize,usize)->f64,
+        boundary:&dyn Fn(f64,usize,usize)->f64,
+        exercise:Option<ExerciseHooks>,
+    )->Result<Vec<f64>>{
+        let (nx,ny)=(problem.xs.len(),problem.ys.len());
+        let mut values=vec![0.0;nx*ny];
+        for i in 0..nx{
+            for j in 0..ny{
+                values[i*ny+j]=terminal(i,j);
+            }
+        }
+        let dt=t/self.t_steps as f64;
+        let mut next=vec![0.0;nx*ny];
+        let mut workspace=AdiWorkspace::new(nx,ny);
+        for n in 0..self.t_steps{
+            let tau=(n+1) as f64*dt;
+            if n<self.damping_steps{
+                problem.douglas(&values,&mut next,&mut workspace,0.5*dt,1.0,tau-0.5*dt,boundary)?;
+                std::mem::swap(&mut values,&mut next);
+                problem.douglas(&values,&mut next,&mut workspace,0.5*dt,1.0,tau,boundary)?;
+            }else{
+                match self.scheme{
+                    AdiScheme::Douglas=>problem.douglas(&values,&mut next,&mut workspace,dt,self.theta,tau,boundary)?,
+                    AdiScheme::CraigSneyd=>problem.craig_sneyd(&values,&mut next,&mut workspace,dt,self.theta,tau,boundary)?,
+                    AdiScheme::HundsdorferVerwer=>problem.hundsdorfer_verwer(&values,&mut next,&mut workspace,dt,self.theta,tau,boundary)?,
+                }
+            }
+            std::mem::swap(&mut values,&mut next);
+            if let Some((can_exercise,exercise))=exercise && can_exercise(tau){
+                for i in 0..nx{
+                    for j in 0..ny{
+                        let k=i*ny+j;
+                        values[k]=exercise(tau,i,j,values[k]);
+                    }
+                }
+            }
+        }
+        Ok(values)
+    }
+}
+
+impl TwoAssetPriceEngine for AdiEngine{
+    /// Price a European option on two correlated geometric brownian motions <br>
+    /// 两个相关几何布朗运动资产上的欧式期权定价
+    fn calculate_two_asset_price(&self,params:&TwoAssetParams,payoff:&dyn TwoAssetPayoff)->Result<f64>{
+        let xs=self.grid.nodes(&params.first()?,self.x_steps,true)?;
+        let ys=self.grid.nodes(&params.second()?,self.y_steps,true)?;
+        let r=params.risk_free_rate();
+        let (sigma1,sigma2,rho)=(params.volatility1(),params.volatility2(),params.correlation());
+        let (q1,q2)=(params.dividend_yield1(),params.dividend_yield2());
+        let problem=Problem::new(xs,ys,true,|_,_|{
+            [0.5*sigma1*sigma1,0.5*sigma2*sigma2,rho*sigma1*sigma2,r-q1-0.5*sigma1*sigma1,r-q2-0.5*sigma2*sigma2,r]
+        });
+
+        let terminal=|i:usize,j:usize| payoff.payoff(problem.xs[i].exp(),problem.ys[j].exp());
+        let boundary=|tau:f64,i:usize,j:usize|{
+            let forward1=problem.xs[i].exp()*((r-q1)*tau).exp();
+            let forward2=problem.ys[j].exp()*((r-q2)*tau).exp();
+            (-r*tau).exp()*payoff.payoff(forward1,forward2)
+        };
+        let values=self.solve(&problem,params.time_to_maturity(),&terminal,&boundary,None)?;
+        problem.interpolate(&values,params.spot1().ln(),params.spot2().ln())
+    }
+}
+
+/// (can exercise at τ, exercise at (τ, i, j) given the continuation value) <br>
+/// （τ时刻是否可行权，给定继续持有价值时节点(τ, i, j)的行权处理）
+type ExerciseHooks<'a>=(&'a dyn Fn(f64)->bool,&'a dyn Fn(f64,usize,usize,f64)->f64);
+
+/// Discretised two-dimensional operator on a tensor grid, stored row-major in x <br>
+/// 张量网格上离散化的二维算子，按x方向行优先存储
+struct Problem{
+    xs:Vec<f64>,
+    ys:Vec<f64>,
+    y_dirichlet:bool,           // y方向边缘是否为Dirichlet（否则在边缘使用单侧差分求解方程）
+    x_operator:Vec<[f64;3]>,    // A₁的三点系数
+    y_operator:Vec<[f64;3]>,    // A₂的三点系数
+    mixed:Vec<f64>,             // 混合导数系数a_xy（仅内部节点）
+    x_first:Vec<[f64;3]>,       // x方向中心一阶导数权重
+    y_first:Vec<[f64;3]>,       // y方向中心一阶导数权重
+}
+
+/// Tridiagonal system of one grid line (sized for the longer direction) <br>
+/// 单条网格线上的三对角方程组（按较长方向分配）
+struct LineBuffers{
+    lower:Vec<f64>,
+    diagonal:Vec<f64>,
+    upper:Vec<f64>,
+    rhs:Vec<f64>,
+    solution:Vec<f64>,
+    scratch:Vec<f64>,
+}
+
+impl LineBuffers{
+    fn new(n:usize)->Self{
+        Self{
+            lower:vec![0.0;n],
+            diagonal:vec![1.0;n],
+            upper:vec![0.0;n],
+            rhs:vec![0.0;n],
+            solution:vec![0.0;n],
+            scratch:vec![0.0;n],
+        }
+    }
+}
+
+/// Buffers of the two implicit stages 两个隐式阶段的缓冲区
+struct StageBuffers{
+    rhs:Vec<f64>,       // 当前阶段的右端项
+    first:Vec<f64>,     // x方向阶段的解Y₁
+    lines:LineBuffers,
+}
+
+/// Grid layers reused by every time step, so stepping does not allocate <br>
+/// 各时间步复用的网格层，时间推进过程中无需分配内存
+struct AdiWorkspace{
+    predictor:Vec<f64>,     // 显式预测Y₀（及其修正Ỹ₀）
+    stage:Vec<f64>,         // 第一轮隐式阶段的结果Y₂
+    old_operator:Vec<f64>,  // 修正项中作用于U的算子
+    new_operator:Vec<f64>,  // 修正项中作用于Y₂的算子
+    stages:StageBuffers,
+}
+
+impl AdiWorkspace{
+    fn new(nx:usize,ny:usize)->Self{
+        let size=nx*ny;
+        Self{
+            predictor:vec![0.0;size],
+            stage:vec![0.0;size],
+            old_operator:vec![0.0;size],
+            new_operator:vec![0.0;size],
+            stages:StageBuffers{
+                rhs:vec![0.0;size],
+                first:vec![0.0;size],
+                lines:LineBuffers::new(nx.max(ny)),
+            },
+        }
+    }
+}
+
+/// Central (first, second) derivative weights at interior node `i` of a non-uniform grid <br>
+/// 非均匀网格内部节点`i`处的中心一阶、二阶导数权重
+fn derivative_weights(nodes:&[f64],i:usize)->([f64;3],[f64;3]){
+    let (h_minus,h_plus)=(nodes[i]-nodes[i-1],nodes[i+1]-nodes[i]);
+    let sum=h_minus+h_plus;
+    (
+        [-h_plus/(h_minus*sum),(h_plus-h_minus)/(h_minus*h_plus),h_minus/(h_plus*sum)],
+        [2.0/(h_minus*sum),-2.0/(h_minus*h_plus),2.0/(h_plus*sum)],
+    )
+}
+
+impl Problem{
+    /// `coefficients(x, y)` returns [a_xx, a_yy, a_xy, b_x, b_y, r]; the discount term is
+    /// shared equally by A₁ and A₂ <br>
+    /// `coefficients(x, y)`返回[a_xx, a_yy, a_xy, b_x, b_y, r]，贴现项在A₁、A₂间平分
+    fn new(xs:Vec<f64>,ys:Vec<f64>,y_dirichlet:bool,coefficients:impl Fn(f64,f64)->[f64;6])->Self{
+        let (nx,ny)=(xs.len(),ys.len());
+        let mut x_operator=vec![[0.0;3];nx*ny];
+        let mut y_operator=vec![[0.0;3];nx*ny];
+        let mut mixed=vec![0.0;nx*ny];
+        let x_weights:Vec<([f64;3],[f64;3])>=(0..nx).map(|i| if i>0 && i<nx-1{derivative_weights(&xs,i)}else{([0.0;3],[0.0;3])}).collect();
+        let y_weights:Vec<([f64;3],[f64;3])>=(0..ny).map(|j| if j>0 && j<ny-1{derivative_weights(&ys,j)}else{([0.0;3],[0.0;3])}).collect();
+        for i in 1..nx-1{
+            for j in 0..ny{
+                if y_dirichlet && (j==0 || j==ny-1){
+                    continue;
+                }
+                let k=i*ny+j;
+                let [a_xx,a_yy,a_xy,b_x,b_y,r]=coefficients(xs[i],ys[j]);
+                let (first,second)=x_weights[i];
+                x_operator[k]=[0,1,2].map(|m| a_xx*second[m]+b_x*first[m]);
+                x_operator[k][1]-=0.5*r;
+                y_operator[k]=if j==0{
+                    // 退化边缘（如v=0）：扩散项消失，一阶导数用前向差分
+                    let h=ys[1]-ys[0];
+                    [0.0,-b_y/h,b_y/h]
+                }else if j==ny-1{
+                    let h=ys[j]-ys[j-1];
+                    [-b_y/h,b_y/h,0.0]
+                }else{
+                    let (first,second)=y_weights[j];
+                    [0,1,2].map(|m| a_yy*second[m]+b_y*first[m])
+                };
+                y_operator[k][1]-=0.5*r;
+                if j>0 && j<ny-1{
+                    mixed[k]=a_xy;
+                }
+            }
+        }
+        Self{
+            xs,
+            ys,
+            y_dirichlet,
+            x_operator,
+            y_operator,
+            mixed,
+            x_first:x_weights.iter().map(|w| w.0).collect(),
+            y_first:y_weights.iter().map(|w| w.0).collect(),
+        }
+    }
+
+    fn is_dirichlet(&self,i:usize,j:usize)->bool{
+        let (nx,ny)=(self.xs.len(),self.ys.len());
+        i==0 || i==nx-1 || (self.y_dirichlet && (j==0 || j==ny-1))
+    }
+
+    /// Mixed derivative term A₀·U at node (i, j) 节点(i, j)处的混合导数项
+    fn mixed_at(&self,values:&[f64],i:usize,j:usize)->f64{
+        let ny=self.ys.len();
+        let k=i*ny+j;
+        if j==0 || j==ny-1 || self.mixed[k]==0.0{
+            return 0.0;
+        }
+        let mut sum=0.0;
+        for a in 0..3{
+            for b in 0..3{
+                sum+=self.x_first[i][a]*self.y_first[j][b]*values[(i+a-1)*ny+j+b-1];
+            }
+        }
+        self.mixed[k]*sum
+    }
+
+    /// x direction term A₁·U at interior node k 内部节点k处的x方向项
+    fn x_at(&self,values:&[f64],k:usize)->f64{
+        let ny=self.ys.len();
+        let [l,d,u]=self.x_operator[k];
+        l*values[k-ny]+d*values[k]+u*values[k+ny]
+    }
+
+    /// y direction term A₂·U at node (j, k) 节点处的y方向项
+    fn y_at(&self,values:&[f64],j:usize,k:usize)->f64{
+        let ny=self.ys.len();
+        let [l,d,u]=self.y_operator[k];
+        d*values[k]
+            +if j>0{l*values[k-1]}else{0.0}
+            +if j<ny-1{u*values[k+1]}else{0.0}
+    }
+
+    /// Write `term(i, j, k)` on the rows interior in x and zero on the x edges <br>
+    /// 在x方向内部行写入`term(i, j, k)`，x方向边缘置零
+    fn apply_with(&self,result:&mut [f64],term:impl Fn(usize,usize,usize)->f64){
+        let (nx,ny)=(self.xs.len(),self.ys.len());
+        result[..ny].fill(0.0);
+        result[(nx-1)*ny..].fill(0.0);
+        for i in 1..nx-1{
+            for j in 0..ny{
+                let k=i*ny+j;
+                result[k]=term(i,j,k);
+            }
+        }
+    }
+
+    /// A₁·U (x direction) x方向部分
+    fn apply_x(&self,values:&[f64],result:&mut [f64]){
+        self.apply_with(result,|_,_,k| self.x_at(values,k));
+    }
+
+    /// A₂·U (y direction) y方向部分
+    fn apply_y(&self,values:&[f64],result:&mut [f64]){
+        self.apply_with(result,|_,j,k| self.y_at(values,j,k));
+    }
+
+    /// A·U = (A₀ + A₁ + A₂)·U 完整算子
+    fn apply(&self,values:&[f64],result:&mut [f64]){
+        self.apply_with(result,|i,j,k| self.mixed_at(values,i,j)+self.x_at(values,k)+self.y_at(values,j,k));
+    }
+
+    /// A₀·U (mixed derivative part) 混合导数部分
+    fn apply_mixed(&self,values:&[f64],result:&mut [f64]){
+        self.apply_with(result,|i,j,_| self.mixed_at(values,i,j));
+    }
+
+    /// Solve (I - w·A₁)·Y = rhs line by line in x, Dirichlet nodes taking the boundary at τ <br>
+    /// 沿x方向逐行求解(I - w·A₁)·Y = rhs，Dirichlet节点取τ时刻的边界值
+    fn solve_x(
+        &self,
+        rhs:&[f64],
+        result:&mut [f64],
+        lines:&mut LineBuffers,
+        weight:f64,
+        tau:f64,
+        boundary:&dyn Fn(f64,usize,usize)->f64,
+    )->Result<()>{
+        let (nx,ny)=(self.xs.len(),self.ys.len());
+        let LineBuffers{lower:a,diagonal:b,upper:c,rhs:d,solution,scratch}=lines;
+        for j in 0..ny{
+            if self.y_dirichlet && (j==0 || j==ny-1){
+                for i in 0..nx{
+                    result[i*ny+j]=boundary(tau,i,j);
+                }
+                continue;
+            }
+            for i in 0..nx{
+                let k=i*ny+j;
+                if self.is_dirichlet(i,j){
+                    b[i]=1.0;
+                    d[i]=boundary(tau,i,j);
+                    if i>0{a[i-1]=0.0;}
+                    if i<nx-1{c[i]=0.0;}
+                }else{
+                    let [l,diagonal,u]=self.x_operator[k];
+                    a[i-1]=-weight*l;
+                    b[i]=1.0-weight*diagonal;
+                    c[i]=-weight*u;
+                    d[i]=rhs[k];
+                }
+            }
+            thomas_solver_into(&a[..nx-1],&b[..nx],&c[..nx-1],&d[..nx],&mut solution[..nx],scratch)?;
+            for i in 0..nx{
+                result[i*ny+j]=solution[i];
+            }
+        }
+        Ok(())
+    }
+
+    /// Solve (I - w·A₂)·Y = rhs line by line in y 沿y方向逐列求解(I - w·A₂)·Y = rhs
+    fn solve_y(
+        &self,
+        rhs:&[f64],
+        result:&mut [f64],
+        lines:&mut LineBuffers,
+        weight:f64,
+        tau:f64,
+        boundary:&dyn Fn(f64,usize,usize)->f64,
+    )->Result<()>{
+        let (nx,ny)=(self.xs.len(),self.ys.len());
+        let LineBuffers{lower:a,diagonal:b,upper:c,rhs:d,solution:_,scratch}=lines;
+        for i in 0..nx{
+            if i==0 || i==nx-1{
+                for j in 0..ny{
+                    result[i*ny+j]=boundary(tau,i,j);
+                }
+                continue;
+            }
+            for j in 0..ny{
+                let k=i*ny+j;
+                if self.is_dirichlet(i,j){
+                    b[j]=1.0;
+                    d[j]=boundary(tau,i,j);
+                    if j>0{a[j-1]=0.0;}
+                    if j<ny-1{c[j]=0.0;}
+                }else{
+                    let [l,diagonal,u]=self.y_operator[k];
+                    if j>0{a[j-1]=-weight*l;}
+                    b[j]=1.0-weight*diagonal;
+                    if j<ny-1{c[j]=-weight*u;}
+                    d[j]=rhs[k];
+                }
+            }
+            thomas_solver_into(&a[..ny-1],&b[..ny],&c[..ny-1],&d[..ny],&mut result[i*ny..(i+1)*ny],scratch)?;
+        }
+        Ok(())
+    }
+
+    /// Implicit stages Yⱼ = Yⱼ₋₁ + θΔt·(Aⱼ·Yⱼ - Aⱼ·R), j=1,2, from Y₀ = `start` for the
+    /// reference layer R, writing Y₂ into `result` <br>
+    /// 自Y₀ = `start`起对参考层R依次求解两个方向的隐式阶段，Y₂写入`result`
+    #[allow(clippy::too_many_arguments)]
+    fn implicit_stages(
+        &self,
+        start:&[f64],
+        reference:&[f64],
+        result:&mut [f64],
+        buffers:&mut StageBuffers,
+        dt:f64,
+        theta:f64,
+        tau:f64,
+        boundary:&dyn Fn(f64,usize,usize)->f64,
+    )->Result<()>{
+        let weight=theta*dt;
+        let StageBuffers{rhs,first,lines}=buffers;
+        self.apply_x(reference,rhs);
+        rhs.iter_mut().zip(start).for_each(|(f,y)| *f=y-weight**f);
+        self.solve_x(rhs,first,lines,weight,tau,boundary)?;
+        self.apply_y(reference,rhs);
+        rhs.iter_mut().zip(first.iter()).for_each(|(f,y)| *f=y-weight**f);
+        self.solve_y(rhs,result,lines,weight,tau,boundary)
+    }
+
+    /// Explicit predictor Y₀ = U + Δt·A·U 显式预测
+    fn predictor(&self,values:&[f64],dt:f64,result:&mut [f64]){
+        self.apply(values,result);
+        result.iter_mut().zip(values).for_each(|(f,v)| *f=v+dt**f);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn douglas(
+        &self,
+        values:&[f64],
+        result:&mut [f64],
+        workspace:&mut AdiWorkspace,
+        dt:f64,
+        theta:f64,
+        tau:f64,
+        boundary:&dyn Fn(f64,usize,usize)->f64,
+    )->Result<()>{
+        let AdiWorkspace{predictor,stages,..}=workspace;
+        self.predictor(values,dt,predictor);
+        self.implicit_stages(predictor,values,result,stages,dt,theta,tau,boundary)
+    }
+
+    /// Ỹ₀ = Y₀ + ½Δt·(A₀·Y₂ - A₀·U), then the implicit stages again with reference U <br>
+    /// 对混合导数项做显式修正后，以U为参考层再次求解隐式阶段
+    #[allow(clippy::too_many_arguments)]
+    fn craig_sneyd(
+        &self,
+        values:&[f64],
+        result:&mut [f64],
+        workspace:&mut AdiWorkspace,
+        dt:f64,
+        theta:f64,
+        tau:f64,
+        boundary:&dyn Fn(f64,usize,usize)->f64,
+    )->Result<()>{
+        let AdiWorkspace{predictor,stage,old_operator,new_operator,stages}=workspace;
+        self.predictor(values,dt,predictor);
+        self.implicit_stages(predictor,values,stage,stages,dt,theta,tau,boundary)?;
+        self.apply_mixed(values,old_operator);
+        self.apply_mixed(stage,new_operator);
+        for k in 0..values.len(){
+            predictor[k]+=0.5*dt*(new_operator[k]-old_operator[k]);
+        }
+        self.implicit_stages(predictor,values,result,stages,dt,theta,tau,boundary)
+    }
+
+    /// Ỹ₀ = Y₀ + ½Δt·(A·Y₂ - A·U), then the implicit stages with reference Y₂ <br>
+    /// 对完整算子做显式修正后，以Y₂为参考层再次求解隐式阶段
+    #[allow(clippy::too_many_arguments)]
+    fn hundsdorfer_verwer(
+        &self,
+        values:&[f64],
+        result:&mut [f64],
+        workspace:&mut AdiWorkspace,
+        dt:f64,
+        theta:f64,
+        tau:f64,
+        boundary:&dyn Fn(f64,usize,usize)->f64,
+    )->Result<()>{
+        let AdiWorkspace{predictor,stage,old_operator,new_operator,stages}=workspace;
+        self.predictor(values,dt,predictor);
+        self.implicit_stages(predictor,values,stage,stages,dt,theta,tau,boundary)?;
+        self.apply(values,old_operator);
+        self.apply(stage,new_operator);
+        for k in 0..values.len(){
+            predictor[k]+=0.5*dt*(new_operator[k]-old_operator[k]);
+        }
+        self.implicit_stages(predictor,stage,result,stages,dt,theta,tau,boundary)
+    }
+
+    /// Quadratic interpolation in x on each y line, then in y <br>
+    /// 先在各y线上沿x二次插值，再沿y二次插值
+    fn interpolate(&self,values:&[f64],x:f64,y:f64)->Result<f64>{
+        let ny=self.ys.len();
+        let along_y=(0..ny).map(|j|{
+            let line:Vec<f64>=(0..self.xs.len()).map(|i| values[i*ny+j]).collect();
+            quadratic_interpolate(x,&self.xs,&line)
+        }).collect::<Result<Vec<f64>>>()?;
+        quadratic_interpolate(y,&self.ys,&along_y)
+    }
+}
@@ -1,13 +1,17 @@
 //! PDE pricing engine
 
 use std::any::Any;
-use super::methods::{ ExplicitMethod, ImplicitMethod, CrankNicolsonMethod};
+use super::methods::{ ExplicitMethod, ImplicitMethod, CrankNicolsonMethod, Bdf2Method, TrBdf2Method};
+use super::lcp::LcpSolver;
+use super::grid::GridSpec;
+use crate::traits::workspace::{TimeLayers,PdeWorkspace};
 use std::sync::Arc;
-use crate::traits::engine::{PriceEngine, PDEMethod, PDEEngineExt, BoundaryCondition};
+use crate::traits::engine::{PriceEngine, PDEMethod, PDEEngineExt, BoundaryCondition, ExerciseBoundaryEngine, GreeksEngine};
 use crate::params::common::CommonParams;
 use crate::errors::*;
-use crate::traits::{payoff::Payoff,exercise::ExerciseRule};
-use crate::utils::math::linear_interpolate;
+use crate::traits::{payoff::Payoff,exercise::{ExerciseRule,ExerciseBoundary}};
+use crate::utils::math::quadratic_interpolate;
+use crate::utils::statistics::require_lognormal_dynamics;
 
 /// PDE方法类型枚举
 #[derive(Debug,Clone,Copy,PartialEq)]
@@ -15,6 +19,10 @@ pub enum FiniteDifferenceMethod{
     Explicit,
     Implicit,
     CrankNicolson,
+    /// Second-order backward differentiation 二阶后向差分
+    Bdf2,
+    /// Trapezoidal rule followed by BDF2 梯形-BDF2复合格式
+    TrBdf2,
 }
 
 /// PDE引擎配置
@@ -25,6 +33,9 @@ pub struct PDEEngine{
     pub method:FiniteDifferenceMethod,
     pub use_log_space:bool,
     boundary_condition:Arc<dyn BoundaryCondition>,
+    lcp_solver:LcpSolver,
+    grid:GridSpec,
+    rannacher_steps:usize,
     method_instance:Arc<dyn PDEMethod>,
 }
 
@@ -43,46 +54,129 @@ impl PDEEngine{
         if use_log_space && (x_steps<100 || t_steps<100) {
             return Err(OptionError::InvalidParameter("Log space method recommends steps greater than 100".to_string()))
         }
-        let method_instance:Arc<dyn PDEMethod>=match method{
-            FiniteDifferenceMethod::Explicit => Arc::new(ExplicitMethod::new()),
-            FiniteDifferenceMethod::Implicit => Arc::new(ImplicitMethod::new()),
-            FiniteDifferenceMethod::CrankNicolson => Arc::new(CrankNicolsonMethod::new()),
-        };
+        let lcp_solver=LcpSolver::default();
         Ok(Self{
             x_steps,
             t_steps,
             method,
             use_log_space,
             boundary_condition,
-            method_instance,
+            lcp_solver,
+            grid:GridSpec::default(),
+            rannacher_steps:0,
+            method_instance:Self::method_instance(method,lcp_solver),
         })
     }
 
+    fn method_instance(method:FiniteDifferenceMethod,lcp_solver:LcpSolver)->Arc<dyn PDEMethod>{
+        match method{
+            FiniteDifferenceMethod::Explicit => Arc::new(ExplicitMethod::new()),
+            FiniteDifferenceMethod::Implicit => Arc::new(ImplicitMethod::with_solver(lcp_solver)),
+            FiniteDifferenceMethod::CrankNicolson => Arc::new(CrankNicolsonMethod::with_solver(lcp_solver)),
+            FiniteDifferenceMethod::Bdf2 => Arc::new(Bdf2Method::with_solver(lcp_solver)),
+            FiniteDifferenceMethod::TrBdf2 => Arc::new(TrBdf2Method::with_solver(lcp_solver)),
+        }
+    }
+
+    /// Use the given linear complementarity solver for the early exercise constraint
+    /// (the explicit method projects exactly and ignores it) <br>
+    /// 使用指定的线性互补求解器处理提前行权约束（显式法直接投影，不受影响）
+    pub fn with_lcp_solver(mut self,lcp_solver:LcpSolver)->Self{
+        self.lcp_solver=lcp_solver;
+        self.method_instance=Self::method_instance(self.method,lcp_solver);
+        self
+    }
+
+    pub fn lcp_solver(&self)->LcpSolver{
+        self.lcp_solver
+    }
+
+    /// Use the given spatial grid (the default is uniform on [0.1·S₀, 2·S₀]); custom grids
+    /// override `x_steps` <br>
+    /// 使用指定的空间网格（默认在[0.1·S₀, 2·S₀]上均匀），自定义网格的节点数优先于`x_steps`
+    pub fn with_grid(mut self,grid:GridSpec)->Self{
+        self.grid=grid;
+        self
+    }
+
+    pub fn grid(&self)->&GridSpec{
+        &self.grid
+    }
+
+    /// Rannacher smoothing: replace the first `steps` time steps after maturity by two implicit
+    /// half-steps each, damping the oscillations Crank-Nicolson produces on kinked payoffs
+    /// (2 is the usual choice) <br>
+    /// Rannacher平滑：到期后的前`steps`个时间步各替换为两个隐式半步，抑制Crank-Nicolson在收益
+    /// 折点处产生的振荡（通常取2）
+    pub fn with_rannacher_steps(mut self,steps:usize)->Self{
+        self.rannacher_steps=steps;
+        self
+    }
+
+    pub fn rannacher_steps(&self)->usize{
+        self.rannacher_steps
+    }
+
 }
 
-impl PriceEngine for PDEEngine{
-    fn calculate_price(&self, params: &CommonParams, payoff: &dyn Payoff, exercise_rule: &dyn ExerciseRule) -> Result<f64> {
-        let s0=params.spot();
-        let t_total=params.time_to_maturity();
-        let sigma=params.volatility();
+/// Full space-time solution of the PDE engine, kept only on request <br>
+/// PDE引擎的完整时空解，仅按需保留
+#[derive(Debug,Clone)]
+pub struct PdeSolution{
+    /// Underlying prices of the nodes 各节点的标的价格
+    pub spots:Vec<f64>,
+    /// Times from the valuation date, t=0 first 距估值日的时间，t=0在前
+    pub times:Vec<f64>,
+    /// `values[n][i]` is the option value at `times[n]` and `spots[i]` <br>
+    /// `values[n][i]`为`times[n]`时刻、价格`spots[i]`处的期权价值
+    pub values:Vec<Vec<f64>>,
+}
 
-        let (s_min,s_max,s_current,to_price):(f64,f64,f64,fn(f64)->f64)=if self.use_log_space{
+/// Layers kept at the end of a backward solve 反向求解结束时保留的时间层
+struct Solution{
+    nodes:Vec<f64>,
+    values:Vec<f64>,            // t=0
+    first_step:Vec<f64>,        // t=Δt
+    history:Option<Vec<Vec<f64>>>,
+}
 
-            ((0.1*s0).ln(),(2.0*s0).ln(),s0.ln(),|s:f64|s.exp())
-        }else{
+impl PDEEngine{
+    /// Solve the pricing PDE backwards in time on rolling layers, optionally recording the
+    /// exercise boundary and the full history <br>
+    /// 在滚动时间层上反向求解定价PDE，可选记录提前行权边界及完整历史
+    fn solve(
+        &self,
+        params: &CommonParams,
+        payoff: &dyn Payoff,
+        exercise_rule: &dyn ExerciseRule,
+        mut boundary: Option<&mut ExerciseBoundary>,
+        record_history: bool,
+    ) -> Result<Solution> {
+        require_lognormal_dynamics(params,"PDE engine")?;
+        exercise_rule.check_maturity(params.time_to_maturity())?;
+        if payoff.requires_path(){
+            return Err(OptionError::NotImplemented("PDE engine cannot value payoffs defined on a whole path".into()));
+        }
+        if payoff.bind_market(params).is_some(){
+            return Err(OptionError::NotImplemented("PDE engine does not support payoffs that deliver an option at expiry".into()));
+        }
+        let t_total=params.time_to_maturity();
+        let sigma=params.volatility();
 
-            (0.1*s0,2.0*s0,s0,|s:f64| s)
-        };
-        let dx=(s_max-s_min)/self.x_steps as f64;
+        let to_price:fn(f64)->f64=if self.use_log_space{|s:f64|s.exp()}else{|s:f64| s};
+        let nodes=self.grid.nodes(params,self.x_steps,self.use_log_space)?;
+        let size=nodes.len();
+        let last=size-1;
         let dt=t_total/self.t_steps as f64;
 
         // 稳定性检查（仅显式法需要）
-        // 显式有限差分法的稳定性通常由 CFL 条件（Courant-Friedrichs-Lewy Condition） 决定
+        // 显式有限差分法的稳定性通常由 CFL 条件（Courant-Friedrichs-Lewy Condition） 决定，非均匀网格取最小步长
         if matches!(self.method,FiniteDifferenceMethod::Explicit){
+            let dx_min=nodes.windows(2).map(|w| w[1]-w[0]).fold(f64::INFINITY,f64::min);
             let stability_factor=if self.use_log_space{
-                sigma.powi(2)*dt/dx.powi(2)
+                sigma.powi(2)*dt/dx_min.powi(2)
             }else{
-                sigma.powi(2)*(2.0*s0).powi(2)*dt/dx.powi(2)
+                sigma.powi(2)*nodes[last].powi(2)*dt/dx_min.powi(2)
             };
             if stability_factor > 0.5 {
                 return Err(OptionError::Other(format!(
@@ -91,51 +185,207 @@ impl PriceEngine for PDEEngine{
                     1. 增加价格网格步数（当前{}）\n\
                     2. 增加时间网格步数（当前{}）\n\
                     3. 改用隐式法或Crank-Nicolson",
-                    stability_factor, self.x_steps, self.t_steps
+                    stability_factor, last, self.t_steps
                 )));
             }
         }
 
-        // 初始化网格
-        let mut grid=vec![vec![0.0;self.x_steps+1];self.t_steps+1];
+        // 滚动时间层：next为t+Δt层，after_next为t+2Δt层（仅多步格式保留），current为待求解层
+        let keep_after_next=self.method_instance.history_depth()>1;
+        let mut next=vec![0.0;size];
+        let mut current=vec![0.0;size];
+        let mut after_next:Option<Vec<f64>>=None;
+        let mut middle=vec![0.0;if self.rannacher_steps>0{size}else{0}];
+        let mut workspace=PdeWorkspace::new(size);
 
-        // 终值条件
-        for i in 0..=self.x_steps{
-            let s_space=s_min+i as f64 *dx;
-            let s=to_price(s_space);
-            grid[self.t_steps][i]=payoff.payoff(s);
+        // 终值条件：对每个网格单元（相邻节点中点之间）取平均收益，避免不连续收益（如二元期权）引起的振荡
+        for i in 0..=last{
+            let lower=if i==0{1.5*nodes[0]-0.5*nodes[1]}else{0.5*(nodes[i-1]+nodes[i])};
+            let upper=if i==last{1.5*nodes[last]-0.5*nodes[last-1]}else{0.5*(nodes[i]+nodes[i+1])};
+            let s=to_price(nodes[i]);
+            next[i]=exercise_rule.exercise_value(0.0,s,payoff.cell_averaged_payoff(s,to_price(lower).max(0.0),to_price(upper)));
         }
+        let mut history=record_history.then(||{
+            let mut history=Vec::with_capacity(self.t_steps+1);
+            history.push(next.clone());
+            history
+        });
 
         for n in (0..self.t_steps).rev(){
             let current_t=n as f64 * dt;
             let remaining_time=t_total-current_t;
 
             //边界条件
-            grid[n][0]=self.boundary_condition.lower_boundary(remaining_time)?;
-            grid[n][self.x_steps]=self.boundary_condition.upper_boundary(remaining_time)?;
-
-            self.method_instance.step_back(
-                &mut grid,
-                n,
-                s_min,
-                dx,
-                dt,
-                params,
-                payoff,
-                exercise_rule,
-                current_t,
-                self.use_log_space
-            )?;
+            current[0]=self.boundary_condition.lower_boundary_at(remaining_time,to_price(nodes[0]))?;
+            current[last]=self.boundary_condition.upper_boundary_at(remaining_time,to_price(nodes[last]))?;
+
+            if self.t_steps-n<=self.rannacher_steps{
+                self.rannacher_step(&mut current,&next,&mut middle,&mut workspace,&nodes,dt,params,payoff,exercise_rule,current_t)?;
+            }else{
+                self.method_instance.step_back(
+                    TimeLayers{current:&mut current,next:&next,after_next:after_next.as_deref()},
+                    &mut workspace,
+                    &nodes,
+                    dt,
+                    params,
+                    payoff,
+                    exercise_rule,
+                    current_t,
+                    self.use_log_space
+                )?;
+            }
+
+            // 内部节点中取值等于内在价值（且内在价值为正）的节点视为已行权
+            if let Some(boundary)=boundary.as_deref_mut() && !exercise_rule.is_european() && exercise_rule.can_exercise(remaining_time,dt){
+                let (spots,exercised):(Vec<f64>,Vec<bool>)=(1..last).map(|i|{
+                    let s=to_price(nodes[i]);
+                    let intrinsic=exercise_rule.exercise_value(remaining_time,s,payoff.payoff(s));
+                    (s,intrinsic>0.0 && (current[i]-intrinsic).abs()<=1e-10*intrinsic.max(1.0))
+                }).unzip();
+                boundary.push(current_t,ExerciseBoundary::critical_price(&spots,&exercised));
+            }
+
+            // 轮换时间层：current → next → after_next，最旧的一层作为下一步的current复用
+            let solved=std::mem::take(&mut current);
+            let previous=std::mem::replace(&mut next,solved);
+            current=if keep_after_next{
+                after_next.replace(previous).unwrap_or_else(||vec![0.0;size])
+            }else{
+                previous
+            };
+            if let Some(history)=history.as_mut(){
+                history.push(next.clone());
+            }
+        }
+        if let Some(boundary)=boundary{
+            boundary.sort_by_time();
+        }
+        if let Some(history)=history.as_mut(){
+            history.reverse();
         }
 
-        let price=linear_interpolate(s_current,s_min,dx,&grid[0])?.max(0.0);
-        Ok(price)
+        let first_step=if keep_after_next{after_next.unwrap_or_default()}else{current};
+        Ok(Solution{nodes,values:next,first_step,history})
+    }
+
+    /// Two implicit half-steps from `next` to `current` (edges of `current` already set),
+    /// through the preallocated `middle` layer <br>
+    /// 经预分配的中间层`middle`，从`next`到`current`的两个隐式半步（`current`边缘已设定）
+    #[allow(clippy::too_many_arguments)]
+    fn rannacher_step(
+        &self,
+        current:&mut [f64],
+        next:&[f64],
+        middle:&mut [f64],
+        workspace:&mut PdeWorkspace,
+        nodes:&[f64],
+        dt:f64,
+        params:&CommonParams,
+        payoff:&dyn Payoff,
+        exercise_rule:&dyn ExerciseRule,
+        current_t:f64,
+    )->Result<()>{
+        let implicit=ImplicitMethod::with_solver(self.lcp_solver);
+        let to_price:fn(f64)->f64=if self.use_log_space{|s:f64|s.exp()}else{|s:f64| s};
+        let last=nodes.len()-1;
+        let half_remaining=params.time_to_maturity()-current_t-0.5*dt;
+        // 第一个半步：next → 中间层
+        middle[0]=self.boundary_condition.lower_boundary_at(half_remaining,to_price(nodes[0]))?;
+        middle[last]=self.boundary_condition.upper_boundary_at(half_remaining,to_price(nodes[last]))?;
+        implicit.step_back(
+            TimeLayers{current:middle,next,after_next:None},
+            workspace,nodes,0.5*dt,params,payoff,exercise_rule,current_t+0.5*dt,self.use_log_space
+        )?;
+        // 第二个半步：中间层 → current
+        implicit.step_back(
+            TimeLayers{current,next:middle,after_next:None},
+            workspace,nodes,0.5*dt,params,payoff,exercise_rule,current_t,self.use_log_space
+        )
+    }
+
+    /// Solve keeping every time layer (memory O(x_steps·t_steps)), e.g. for plotting <br>
+    /// 保留全部时间层求解（内存O(x_steps·t_steps)），如用于绘图
+    pub fn solve_with_history(&self,params:&CommonParams,payoff:&dyn Payoff,exercise_rule:&dyn ExerciseRule)->Result<PdeSolution>{
+        let solution=self.solve(params,payoff,exercise_rule,None,true)?;
+        let to_price:fn(f64)->f64=if self.use_log_space{|s:f64|s.exp()}else{|s:f64| s};
+        let dt=params.time_to_maturity()/self.t_steps as f64;
+        Ok(PdeSolution{
+            spots:solution.nodes.iter().map(|&x| to_price(x)).collect(),
+            times:(0..=self.t_steps).map(|n| n as f64*dt).collect(),
+            values:solution.history.unwrap_or_default(),
+        })
+    }
+
+    /// Price at the spot by quadratic interpolation of the valuation-date layer <br>
+    /// 对估值日价值层做二次插值得到当前价格
+    fn price_from_layer(&self,params:&CommonParams,nodes:&[f64],values:&[f64])->Result<f64>{
+        let s_current=if self.use_log_space{params.spot().ln()}else{params.spot()};
+        Ok(quadratic_interpolate(s_current,nodes,values)?.max(0.0))
+    }
+
+    /// Delta and gamma from the quadratic through the three nodes nearest the spot <br>
+    /// 由最接近当前价格的三个节点确定的二次多项式求Delta与Gamma
+    fn delta_gamma(&self,params:&CommonParams,payoff:&dyn Payoff,exercise_rule:&dyn ExerciseRule)->Result<(f64,f64)>{
+        let Solution{nodes,values,..}=self.solve(params,payoff,exercise_rule,None,false)?;
+        let s=params.spot();
+        let x=if self.use_log_space{s.ln()}else{s};
+        let last=nodes.len()-1;
+        let nearest=nodes.partition_point(|&node| node<x).clamp(1,last);
+        let nearest=if x-nodes[nearest-1]<nodes[nearest]-x{nearest-1}else{nearest};
+        let i=nearest.clamp(1,last-1);
+        let (h_minus,h_plus)=(nodes[i]-nodes[i-1],nodes[i+1]-nodes[i]);
+        let first_minus=(values[i]-values[i-1])/h_minus;
+        let first_plus=(values[i+1]-values[i])/h_plus;
+        let second=2.0*(first_plus-first_minus)/(h_minus+h_plus);
+        // 二次多项式在x处的一阶导数
+        let first=(first_minus*h_plus+first_plus*h_minus)/(h_minus+h_plus)+second*(x-nodes[i]);
+        Ok(if self.use_log_space{
+            (first/s,(second-first)/(s*s))
+        }else{
+            (first,second)
+        })
+    }
+}
+
+impl PriceEngine for PDEEngine{
+    fn calculate_price(&self, params: &CommonParams, payoff: &dyn Payoff, exercise_rule: &dyn ExerciseRule) -> Result<f64> {
+        let solution=self.solve(params,payoff,exercise_rule,None,false)?;
+        self.price_from_layer(params,&solution.nodes,&solution.values)
     }
     fn as_any(&self) -> &dyn Any {
         self
     }
 }
 
+impl ExerciseBoundaryEngine for PDEEngine{
+    fn calculate_price_with_boundary(&self, params: &CommonParams, payoff: &dyn Payoff, exercise_rule: &dyn ExerciseRule) -> Result<(f64,ExerciseBoundary)> {
+        let mut boundary=ExerciseBoundary::new();
+        let solution=self.solve(params,payoff,exercise_rule,Some(&mut boundary),false)?;
+        Ok((self.price_from_layer(params,&solution.nodes,&solution.values)?,boundary))
+    }
+}
+
+/// Delta, gamma and theta are read off the solved layers; the other Greeks bump and reprice <br>
+/// Delta、Gamma与Theta直接取自求解后的时间层，其余希腊字母采用扰动重定价
+impl GreeksEngine for PDEEngine{
+    fn delta(&self, params: &CommonParams, payoff: &dyn Payoff, exercise_rule: &dyn ExerciseRule) -> Result<f64> {
+        Ok(self.delta_gamma(params,payoff,exercise_rule)?.0)
+    }
+
+    fn gamma(&self, params: &CommonParams, payoff: &dyn Payoff, exercise_rule: &dyn ExerciseRule) -> Result<f64> {
+        Ok(self.delta_gamma(params,payoff,exercise_rule)?.1)
+    }
+
+    /// ∂V/∂t from the layers at t=0 and t=Δt ∂V/∂t，取自t=0与t=Δt两层
+    fn theta(&self, params: &CommonParams, payoff: &dyn Payoff, exercise_rule: &dyn ExerciseRule) -> Result<f64> {
+        let solution=self.solve(params,payoff,exercise_rule,None,false)?;
+        let dt=params.time_to_maturity()/self.t_steps as f64;
+        let now=self.price_from_layer(params,&solution.nodes,&solution.values)?;
+        let later=self.price_from_layer(params,&solution.nodes,&solution.first_step)?;
+        Ok((later-now)/dt)
+    }
+}
+
 impl PDEEngineExt for PDEEngine{
     fn set_grid_size(&mut self, x_steps: usize, t_steps: usize) -> Result<()> {
         if x_steps<50 || t_steps<50{
@@ -0,0 +1,108 @@
+//! Spatial grid generators of the PDE engine <br>
+//! PDE引擎的空间网格生成器
+//!
+//! Nodes are generated in the computational coordinate: ln S in log space, S otherwise. <br>
+//! 网格节点以计算坐标给出：对数空间为ln S，否则为S
+use crate::errors::*;
+use crate::params::common::CommonParams;
+
+/// Spatial grid specification 空间网格设定
+#[derive(Debug,Clone,PartialEq)]
+pub enum GridSpec{
+    /// Uniform grid on [lower·S₀, upper·S₀] 在[lower·S₀, upper·S₀]上的均匀网格
+    Uniform{lower:f64,upper:f64},
+    /// Uniform grid on S₀·exp(±num_std·σ√T) 覆盖S₀·exp(±num_std·σ√T)的均匀网格
+    StandardDeviations{num_std:f64},
+    /// Grid on S₀·exp(±num_std·σ√T) concentrated around `center` (a strike or barrier) by a
+    /// sinh transformation; a smaller `concentration` clusters the nodes more tightly <br>
+    /// 覆盖S₀·exp(±num_std·σ√T)、通过sinh变换在`center`（执行价或障碍）附近加密的网格，
+    /// `concentration`越小节点越集中
+    Sinh{center:f64,concentration:f64,num_std:f64},
+    /// User-supplied price nodes, strictly increasing 用户给定的价格节点，严格递增
+    Custom(Vec<f64>),
+}
+
+impl Default for GridSpec{
+    fn default()->Self{
+        GridSpec::Uniform{lower:0.1,upper:2.0}
+    }
+}
+
+impl GridSpec{
+    /// Nodes in the computational coordinate; generated grids have `x_steps`+1 nodes <br>
+    /// 计算坐标下的网格节点，生成的网格有`x_steps`+1个节点
+    pub fn nodes(&self,params:&CommonParams,x_steps:usize,use_log_space:bool)->Result<Vec<f64>>{
+        let s0=params.spot();
+        let to_space=|s:f64| if use_log_space{s.ln()}else{s};
+        let std_range=|num_std:f64|->Result<(f64,f64)>{
+            if num_std<=0.0{
+                return Err(OptionError::InvalidParameter("The number of standard deviations must be greater than zero".into()));
+            }
+            let width=num_std*params.volatility()*params.time_to_maturity().sqrt();
+            Ok((s0*(-width).exp(),s0*width.exp()))
+        };
+        let uniform=|lower:f64,upper:f64|->Vec<f64>{
+            let (x_min,x_max)=(to_space(lower),to_space(upper));
+            (0..=x_steps).map(|i| x_min+(x_max-x_min)*i as f64/x_steps as f64).collect()
+        };
+        let nodes=match self{
+            GridSpec::Uniform{lower,upper}=>{
+                if *lower<0.0 || lower>=upper || (use_log_space && *lower<=0.0){
+                    return Err(OptionError::InvalidParameter("Uniform grid needs 0 ≤ lower < upper (lower > 0 in log space)".into()));
+                }
+                uniform(lower*s0,upper*s0)
+            },
+            GridSpec::StandardDeviations{num_std}=>{
+                let (lower,upper)=std_range(*num_std)?;
+                uniform(lower,upper)
+            },
+            GridSpec::Sinh{center,concentration,num_std}=>{
+                if *center<=0.0 || *concentration<=0.0{
+                    return Err(OptionError::InvalidParameter("Sinh grid needs a positive center and concentration".into()));
+                }
+                let (lower,upper)=std_range(*num_std)?;
+                let (x_min,x_max)=(to_space(lower),to_space(upper));
+                let center=to_space(center.clamp(lower,upper));
+                let alpha=concentration*(x_max-x_min);
+                let c1=((x_min-center)/alpha).asinh();
+                let c2=((x_max-center)/alpha).asinh();
+                (0..=x_steps).map(|i|{
+                    let u=i as f64/x_steps as f64;
+                    center+alpha*(c1+(c2-c1)*u).sinh()
+                }).collect()
+            },
+            GridSpec::Custom(prices)=>{
+                if prices.len()<3 || prices.windows(2).any(|w| w[0]>=w[1]) || prices[0]<0.0 || (use_log_space && prices[0]<=0.0){
+                    return Err(OptionError::InvalidParameter("Custom grid needs at least 3 strictly increasing non-negative nodes (positive in log space)".into()));
+                }
+                prices.iter().map(|&s| to_space(s)).collect()
+            },
+        };
+        if !(nodes[0]<to_space(s0) && to_space(s0)<nodes[nodes.len()-1]){
+            return Err(OptionError::InvalidParameter("The PDE grid must contain the spot price".into()));
+        }
+        Ok(nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn test_grid_nodes(){
+        let params=CommonParams::new(100.0,0.05,0.2,0.0,1.0).unwrap();
+        let sinh=GridSpec::Sinh{center:110.0,concentration:0.1,num_std:4.0}.nodes(&params,100,true).unwrap();
+        assert_eq!(sinh.len(),101);
+        assert!((sinh[0]-(100.0f64.ln()-0.8)).abs()<1e-12 && (sinh[100]-(100.0f64.ln()+0.8)).abs()<1e-12);
+        // 执行价附近最密
+        let spacing:Vec<f64>=sinh.windows(2).map(|w| w[1]-w[0]).collect();
+        let densest=sinh.partition_point(|&x| x<110.0f64.ln());
+        assert!(spacing[densest]<0.5*spacing[0] && spacing[densest]<0.5*spacing[99]);
+
+        let custom=GridSpec::Custom(vec![50.0,90.0,100.0,110.0,200.0]).nodes(&params,100,false).unwrap();
+        assert_eq!(custom,vec![50.0,90.0,100.0,110.0,200.0]);
+        assert!(GridSpec::Custom(vec![110.0,120.0,130.0]).nodes(&params,100,false).is_err());
+        assert!(GridSpec::Uniform{lower:0.0,upper:2.0}.nodes(&params,100,true).is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! Linear complementarity solvers for the early exercise constraint of implicit PDE schemes <br>
+//! 隐式PDE格式中提前行权约束的线性互补问题求解器
+use crate::errors::*;
+use crate::traits::workspace::SolverScratch;
+use crate::utils::linear_algebra::{
+    thomas_solver_into,projected_sor_into,brennan_schwartz_into,penalty_lcp_into,
+};
+
+/// LCP solver used by `ImplicitMethod` and `CrankNicolsonMethod` <br>
+/// `ImplicitMethod`与`CrankNicolsonMethod`使用的线性互补问题求解器
+#[derive(Debug,Clone,Copy,PartialEq,Default)]
+pub enum LcpSolver{
+    /// Solve the linear system, then project onto the exercise value (first order in time) <br>
+    /// 先解线性方程组再投影到行权价值（时间上一阶精度）
+    #[default]
+    Projection,
+    /// Projected successive over-relaxation 投影逐次超松弛迭代
+    Psor{omega:f64,tolerance:f64,max_iterations:usize},
+    /// Brennan-Schwartz direct algorithm, exact when the exercise region is one-sided <br>
+    /// Brennan-Schwartz直接法，行权区域位于一侧时精确
+    BrennanSchwartz,
+    /// Penalty method solved by policy iteration 策略迭代求解的罚函数法
+    Penalty{penalty:f64,max_iterations:usize},
+}
+
+impl LcpSolver{
+    /// PSOR with ω=1.2, tolerance 1e-10 and at most 10000 sweeps <br>
+    /// 默认参数的PSOR
+    pub fn psor()->Self{
+        LcpSolver::Psor{omega:1.2,tolerance:1e-10,max_iterations:10_000}
+    }
+
+    /// Penalty method with ρ=1e8 and at most 100 policy iterations <br>
+    /// 默认参数的罚函数法
+    pub fn penalty()->Self{
+        LcpSolver::Penalty{penalty:1e8,max_iterations:100}
+    }
+
+    /// Solve A·x=d subject to x ≥ obstacle (no constraint when `obstacle` is None), writing
+    /// into `out` and reusing the buffers of `scratch` (sized to n), so no solver allocates;
+    /// `previous` is the solution of the last time layer, used as the PSOR starting point <br>
+    /// 在x ≥ obstacle约束下求解A·x=d（`obstacle`为None时无约束），结果写入`out`并复用
+    /// `scratch`（长度n）的缓冲区，各求解器均不分配内存；`previous`为上一时间层的解，用作PSOR初值
+    #[allow(clippy::too_many_arguments)]
+    pub fn solve_into(
+        &self,
+        a:&[f64],
+        b:&[f64],
+        c:&[f64],
+        d:&[f64],
+        obstacle:Option<&[f64]>,
+        previous:&[f64],
+        out:&mut [f64],
+        scratch:&mut SolverScratch,
+    )->Result<()>{
+        let SolverScratch{factors,reduced,penalized,active}=scratch;
+        let Some(obstacle)=obstacle else{
+            return thomas_solver_into(a,b,c,d,out,factors);
+        };
+        match *self{
+            LcpSolver::Projection=>thomas_solver_into(a,b,c,d,out,factors),
+            LcpSolver::Psor{omega,tolerance,max_iterations}=>
+                projected_sor_into(a,b,c,d,obstacle,previous,omega,tolerance,max_iterations,out),
+            LcpSolver::BrennanSchwartz=>brennan_schwartz_into(a,b,c,d,obstacle,out,factors,reduced),
+            LcpSolver::Penalty{penalty,max_iterations}=>
+                penalty_lcp_into(a,b,c,d,obstacle,penalty,max_iterations,out,penalized,reduced,factors,active),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! BDF2 二阶后向差分格式
+use crate::traits::engine::PDEMethod;
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::traits::{payoff::Payoff,exercise::ExerciseRule};
+use crate::core::pde::lcp::LcpSolver;
+use crate::traits::workspace::{TimeLayers,PdeWorkspace};
+use super::{exercise_obstacle,implicit_solve};
+
+/// Second-order backward differentiation (BDF2) scheme: L-stable and second order, so the
+/// kink of the payoff does not excite oscillations; the first step is backward Euler <br>
+/// 二阶后向差分（BDF2）格式：L稳定且二阶精度，收益函数折点不会引起振荡；首步使用向后欧拉
+#[derive(Debug,Clone,Default)]
+pub struct Bdf2Method{
+    solver:LcpSolver,   // 提前行权约束的线性互补求解器
+}
+
+impl Bdf2Method{
+    pub fn new()->Self{
+        Self::default()
+    }
+
+    pub fn with_solver(solver:LcpSolver)->Self{
+        Self{solver}
+    }
+}
+
+impl PDEMethod for Bdf2Method{
+    fn step_back(
+        &self,
+        layers: TimeLayers,
+        workspace: &mut PdeWorkspace,
+        nodes: &[f64],
+        dt: f64,
+        params: &CommonParams,
+        payoff: &dyn Payoff,
+        exercise_rule: &dyn ExerciseRule,
+        current_t: f64,
+        use_log_space: bool
+    ) -> Result<()> {
+        let remaining_time=params.time_to_maturity()-current_t;
+        let next=layers.next;
+        // (I - ⅔Δt·L)·Vⁿ = ⁴⁄₃Vⁿ⁺¹ - ⅓Vⁿ⁺²，首步无Vⁿ⁺²时退化为(I - Δt·L)·Vⁿ = Vⁿ⁺¹
+        let weight=match layers.after_next{
+            Some(after)=>{
+                for i in 0..nodes.len(){
+                    workspace.rhs[i]=(4.0*next[i]-after[i])/3.0;
+                }
+                2.0*dt/3.0
+            },
+            None=>{
+                workspace.rhs.copy_from_slice(next);
+                dt
+            },
+        };
+        let constrained=exercise_obstacle(&mut workspace.obstacle,nodes,payoff,exercise_rule,remaining_time,dt,use_log_space);
+        implicit_solve(layers.current,workspace,weight,next,constrained,nodes,&self.solver,params,exercise_rule,remaining_time,use_log_space)
+    }
+
+    fn history_depth(&self)->usize{2}
+}
@@ -3,8 +3,10 @@ use crate::traits::engine::PDEMethod;
 use crate::params::common::CommonParams;
 use crate::traits::{payoff::Payoff,exercise::ExerciseRule};
 use crate::errors::*;
+use crate::traits::workspace::{TimeLayers,PdeWorkspace};
+use super::{apply_operator,exercise_obstacle,apply_exercise};
 
-#[derive(Debug,Clone)]
+#[derive(Debug,Clone,Default)]
 pub struct ExplicitMethod;
 
 impl ExplicitMethod{
@@ -15,12 +17,12 @@ impl ExplicitMethod{
 
 
 impl PDEMethod for ExplicitMethod{
+    #[allow(clippy::needless_range_loop)]
     fn step_back(
         &self,
-        grid: &mut Vec<Vec<f64>>,
-        time_idx:usize,
-        s_min: f64,
-        dx: f64,
+        layers: TimeLayers,
+        workspace: &mut PdeWorkspace,
+        nodes: &[f64],
         dt: f64,
         params: &CommonParams,
         payoff: &dyn Payoff,
@@ -28,44 +30,18 @@ impl PDEMethod for ExplicitMethod{
         current_t: f64,
         use_log_space:bool,
     ) -> Result<()> {
-        let (_,r,sigma,q,t_total)=params.all_params();
-        let remain_time=t_total-current_t;
+        let remain_time=params.time_to_maturity()-current_t;
+        let next=layers.next;
 
-        let to_price:fn(f64)->f64=if use_log_space {|s:f64|s.exp()} else{|s:f64| s};
-
-
-        // 循环内部点
-        for i in 1..grid[time_idx].len()-1{
-            let s_space=s_min+i as f64*dx;
-            let s=to_price(s_space);
-
-            let (drift,diffusion)=if use_log_space {
-                (r-q-0.5*sigma.powi(2),0.5*sigma.powi(2))
-            }else{
-                ((r-q)*s, 0.5*sigma.powi(2)*s.powi(2))
-            };
-
-            let a=diffusion*dt/dx.powi(2)-drift*dt/(2.0*dx);
-            let b=1.0-r*dt-2.0*diffusion*dt/dx.powi(2);
-            let c=diffusion*dt/dx.powi(2)+drift*dt/(2.0*dx);
-
-            let value=a*grid[time_idx+1][i-1]
-                +b*grid[time_idx+1][i]
-                +c*grid[time_idx+1][i+1];
-
-            let intrinsic_value=payoff.payoff(s);
-
-            grid[time_idx][i] = if exercise_rule.should_exercise(remain_time,s,intrinsic_value,value){
-                intrinsic_value
-            }else{
-                value
-            };
+        // 内部点：Vⁿ = Vⁿ⁺¹ + Δt·L·Vⁿ⁺¹
+        apply_operator(nodes,next,params,use_log_space,&mut workspace.operator);
+        for i in 0..nodes.len(){
+            workspace.solution[i]=next[i]+dt*workspace.operator[i];
         }
 
+        let constrained=exercise_obstacle(&mut workspace.obstacle,nodes,payoff,exercise_rule,remain_time,dt,use_log_space);
+        let obstacle=if constrained{Some(workspace.obstacle.as_slice())}else{None};
+        apply_exercise(layers.current,&workspace.solution,obstacle,nodes,exercise_rule,remain_time,use_log_space);
         Ok(())
     }
 }
-
-
-
-
@@ -1,8 +1,176 @@
 pub mod explicit;
 pub mod implicit;
 pub mod crank_nicolson;
+pub mod bdf2;
+pub mod tr_bdf2;
 
 pub use explicit::ExplicitMethod;
 pub use implicit::ImplicitMethod;
 pub use crank_nicolson::CrankNicolsonMethod;
+pub use bdf2::Bdf2Method;
+pub use tr_bdf2::TrBdf2Method;
 
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::traits::{payoff::Payoff,exercise::ExerciseRule};
+use super::lcp::LcpSolver;
+use crate::traits::workspace::PdeWorkspace;
+
+/// Three-point coefficients (l, d, u) of L·V = D·V'' + μ·V' - r·V at interior node `i`
+/// of a possibly non-uniform grid (second order on smooth grids) <br>
+/// 非均匀网格内部节点`i`处算子L·V = D·V'' + μ·V' - r·V的三点差分系数(l, d, u)
+pub(crate) fn operator_coefficients(
+    nodes:&[f64],
+    i:usize,
+    params:&CommonParams,
+    use_log_space:bool,
+)->(f64,f64,f64){
+    let (_,r,sigma,q,_)=params.all_params();
+    let (h_minus,h_plus)=(nodes[i]-nodes[i-1],nodes[i+1]-nodes[i]);
+    let (diffusion,drift)=if use_log_space{
+        (0.5*sigma*sigma,r-q-0.5*sigma*sigma)
+    }else{
+        let s=nodes[i];
+        (0.5*sigma*sigma*s*s,(r-q)*s)
+    };
+    let sum=h_minus+h_plus;
+    let lower=2.0*diffusion/(h_minus*sum)-drift*h_plus/(h_minus*sum);
+    let diagonal=-2.0*diffusion/(h_minus*h_plus)+drift*(h_plus-h_minus)/(h_minus*h_plus)-r;
+    let upper=2.0*diffusion/(h_plus*sum)+drift*h_minus/(h_plus*sum);
+    (lower,diagonal,upper)
+}
+
+/// Write L·V at the interior nodes of one layer into `out` (zero at the edges) <br>
+/// 将一个时间层内部节点处的L·V写入`out`（边缘为零）
+pub(crate) fn apply_operator(
+    nodes:&[f64],
+    values:&[f64],
+    params:&CommonParams,
+    use_log_space:bool,
+    out:&mut [f64],
+){
+    let n=nodes.len();
+    out[0]=0.0;
+    out[n-1]=0.0;
+    for i in 1..n-1{
+        let (lower,diagonal,upper)=operator_coefficients(nodes,i,params,use_log_space);
+        out[i]=lower*values[i-1]+diagonal*values[i]+upper*values[i+1];
+    }
+}
+
+/// Write the exercise values of one layer into `obstacle`; returns false (leaving the buffer
+/// untouched) when exercise is not allowed <br>
+/// 将一个时间层各节点的行权价值写入`obstacle`，不可行权时返回false且不修改缓冲区
+pub(crate) fn exercise_obstacle(
+    obstacle:&mut [f64],
+    nodes:&[f64],
+    payoff:&dyn Payoff,
+    exercise_rule:&dyn ExerciseRule,
+    remaining_time:f64,
+    dt:f64,
+    use_log_space:bool,
+)->bool{
+    if exercise_rule.is_european() || !exercise_rule.can_exercise(remaining_time,dt){
+        return false;
+    }
+    let n=nodes.len();
+    for i in 0..n{
+        obstacle[i]=if i>0 && i<n-1{
+            let s=if use_log_space{nodes[i].exp()}else{nodes[i]};
+            exercise_rule.exercise_value(remaining_time,s,payoff.payoff(s))
+        }else{
+            f64::NEG_INFINITY
+        };
+    }
+    true
+}
+
+/// Write the continuation values of the interior nodes into `layer`, replacing them by the
+/// exercise value wherever the rule exercises <br>
+/// 将内部节点的继续持有价值写入`layer`，行权规则决定行权处替换为行权价值
+pub(crate) fn apply_exercise(
+    layer:&mut [f64],
+    continuation:&[f64],
+    obstacle:Option<&[f64]>,
+    nodes:&[f64],
+    exercise_rule:&dyn ExerciseRule,
+    remaining_time:f64,
+    use_log_space:bool,
+){
+    let n=layer.len();
+    for i in 1..n-1{
+        layer[i]=match obstacle{
+            Some(obstacle) if exercise_rule.should_exercise(
+                remaining_time,
+                if use_log_space{nodes[i].exp()}else{nodes[i]},
+                obstacle[i],
+                continuation[i],
+            )=>obstacle[i],
+            _=>continuation[i],
+        };
+    }
+}
+
+/// Solve (I - w·L)·V = `workspace.rhs` for the interior nodes of `layer`, whose edges hold
+/// the Dirichlet values, subject to the obstacle in `workspace.obstacle` when `constrained`,
+/// then apply the exercise rule <br>
+/// 在`layer`内部节点上求解(I - w·L)·V = `workspace.rhs`（边缘为Dirichlet值），`constrained`时
+/// 带`workspace.obstacle`行权约束，随后应用行权规则
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn implicit_solve(
+    layer:&mut [f64],
+    workspace:&mut PdeWorkspace,
+    weight:f64,
+    guess:&[f64],
+    constrained:bool,
+    nodes:&[f64],
+    solver:&LcpSolver,
+    params:&CommonParams,
+    exercise_rule:&dyn ExerciseRule,
+    remaining_time:f64,
+    use_log_space:bool,
+)->Result<()>{
+    let n=nodes.len();
+    let PdeWorkspace{lower,diagonal,upper,rhs,solution,obstacle,scratch,..}=workspace;
+    rhs[0]=layer[0];
+    rhs[n-1]=layer[n-1];
+    diagonal[0]=1.0;
+    diagonal[n-1]=1.0;
+    upper[0]=0.0;
+    lower[n-2]=0.0;
+    for i in 1..n-1{
+        let (l,d,u)=operator_coefficients(nodes,i,params,use_log_space);
+        lower[i-1]=-weight*l;
+        diagonal[i]=1.0-weight*d;
+        upper[i]=-weight*u;
+    }
+    let obstacle=if constrained{Some(obstacle.as_slice())}else{None};
+    solver.solve_into(lower,diagonal,upper,rhs,obstacle,guess,solution,scratch)?;
+    apply_exercise(layer,solution,obstacle,nodes,exercise_rule,remaining_time,use_log_space);
+    Ok(())
+}
+
+/// One θ-scheme step (I - θ·Δt·L)·Vⁿ = (I + (1-θ)·Δt·L)·Vⁿ⁺¹: θ=1 implicit, θ=½ Crank-Nicolson <br>
+/// θ格式单步：θ=1为隐式，θ=½为Crank-Nicolson
+#[allow(clippy::needless_range_loop,clippy::too_many_arguments)]
+pub(crate) fn theta_step(
+    current:&mut [f64],
+    next:&[f64],
+    workspace:&mut PdeWorkspace,
+    nodes:&[f64],
+    dt:f64,
+    theta:f64,
+    solver:&LcpSolver,
+    params:&CommonParams,
+    payoff:&dyn Payoff,
+    exercise_rule:&dyn ExerciseRule,
+    remaining_time:f64,
+    use_log_space:bool,
+)->Result<()>{
+    apply_operator(nodes,next,params,use_log_space,&mut workspace.operator);
+    for i in 0..nodes.len(){
+        workspace.rhs[i]=next[i]+(1.0-theta)*dt*workspace.operator[i];
+    }
+    let constrained=exercise_obstacle(&mut workspace.obstacle,nodes,payoff,exercise_rule,remaining_time,dt,use_log_space);
+    implicit_solve(current,workspace,theta*dt,next,constrained,nodes,solver,params,exercise_rule,remaining_time,use_log_space)
+}
@@ -0,0 +1,67 @@
+//! TR-BDF2 梯形-二阶后向差分复合格式
+use crate::traits::engine::PDEMethod;
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::traits::{payoff::Payoff,exercise::ExerciseRule};
+use crate::core::pde::lcp::LcpSolver;
+use crate::traits::workspace::{TimeLayers,PdeWorkspace};
+use super::{apply_operator,exercise_obstacle,implicit_solve};
+
+/// TR-BDF2 scheme: a trapezoidal stage over γ·Δt followed by a BDF2 stage over the rest of
+/// the step, with γ = 2 - √2; one-step, L-stable and second order <br>
+/// TR-BDF2格式：先在γ·Δt上做梯形（Crank-Nicolson）步，再以BDF2完成剩余步长，γ = 2 - √2；
+/// 单步、L稳定且二阶精度
+#[derive(Debug,Clone,Default)]
+pub struct TrBdf2Method{
+    solver:LcpSolver,   // 提前行权约束的线性互补求解器
+}
+
+impl TrBdf2Method{
+    pub fn new()->Self{
+        Self::default()
+    }
+
+    pub fn with_solver(solver:LcpSolver)->Self{
+        Self{solver}
+    }
+}
+
+impl PDEMethod for TrBdf2Method{
+    #[allow(clippy::needless_range_loop)]
+    fn step_back(
+        &self,
+        layers: TimeLayers,
+        workspace: &mut PdeWorkspace,
+        nodes: &[f64],
+        dt: f64,
+        params: &CommonParams,
+        payoff: &dyn Payoff,
+        exercise_rule: &dyn ExerciseRule,
+        current_t: f64,
+        use_log_space: bool
+    ) -> Result<()> {
+        let gamma=2.0-2.0f64.sqrt();
+        let remaining_time=params.time_to_maturity()-current_t;
+        let (current,next)=(layers.current,layers.next);
+        let last=nodes.len()-1;
+
+        // 梯形步：(I - ½γΔt·L)·V* = (I + ½γΔt·L)·Vⁿ⁺¹，中间层边缘值线性插值，不做行权
+        apply_operator(nodes,next,params,use_log_space,&mut workspace.operator);
+        for i in 0..=last{
+            workspace.rhs[i]=next[i]+0.5*gamma*dt*workspace.operator[i];
+        }
+        let mut stage=std::mem::take(&mut workspace.stage);
+        stage[0]=(1.0-gamma)*next[0]+gamma*current[0];
+        stage[last]=(1.0-gamma)*next[last]+gamma*current[last];
+        implicit_solve(&mut stage,workspace,0.5*gamma*dt,next,false,nodes,&self.solver,params,exercise_rule,remaining_time,use_log_space)?;
+
+        // BDF2步：(I - (1-γ)/(2-γ)·Δt·L)·Vⁿ = V*/(γ(2-γ)) - (1-γ)²/(γ(2-γ))·Vⁿ⁺¹
+        let scale=gamma*(2.0-gamma);
+        for i in 0..=last{
+            workspace.rhs[i]=(stage[i]-(1.0-gamma).powi(2)*next[i])/scale;
+        }
+        workspace.stage=stage;
+        let constrained=exercise_obstacle(&mut workspace.obstacle,nodes,payoff,exercise_rule,remaining_time,dt,use_log_space);
+        implicit_solve(current,workspace,(1.0-gamma)/(2.0-gamma)*dt,next,constrained,nodes,&self.solver,params,exercise_rule,remaining_time,use_log_space)
+    }
+}
@@ -1,4 +1,11 @@
 pub mod engine;
 pub mod methods;
+pub mod lcp;
+pub mod grid;
+pub mod adi;
 
-pub use engine::PDEEngine;
\ No newline at end of file
+pub use engine::{PDEEngine,PdeSolution};
+pub use lcp::LcpSolver;
+pub use grid::GridSpec;
+pub use adi::{AdiEngine,AdiScheme};
+pub use crate::traits::workspace::{TimeLayers,PdeWorkspace,SolverScratch};
@@ -0,0 +1,185 @@
+//! Vanna-Volga pricing of FX exotics on top of a flat volatility engine <br>
+//! 在平坦波动率引擎之上的外汇奇异期权Vanna-Volga定价
+//!
+//! The exotic is first priced at the ATM vol. Its vega, vanna and volga are then hedged with
+//! three vanilla pivots (25D put, ATM, 25D call), whose weights w solve
+//! Σ wᵢ·(vega, vanna, volga)ᵢ = (vega, vanna, volga)ₓ at the ATM vol. The overhedge cost
+//! Σ wᵢ·(Cᵢ(σᵢ) - Cᵢ(σ_ATM)), i.e. what the smile charges for that hedge, is added to the flat
+//! price. Traders often scale the cost by the no-touch probability of barrier products, since
+//! the hedge is unwound when the barrier is hit. <br>
+//! 先以平值波动率为奇异期权定价，再以三个普通期权支点（25D看跌、平值、25D看涨）对冲其vega、vanna与
+//! volga：权重w满足在平值波动率下Σ wᵢ·(vega, vanna, volga)ᵢ = (vega, vanna, volga)ₓ。将对冲成本
+//! Σ wᵢ·(Cᵢ(σᵢ) - Cᵢ(σ_ATM))（即微笑对该对冲的定价）加到平坦价格上。障碍类产品触碰障碍时对冲即
+//! 平仓，交易员常以无触碰概率缩放该成本
+use std::any::Any;
+use crate::core::analytic::calculators::BinaryCalculator;
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::traits::engine::PriceEngine;
+use crate::traits::exercise::ExerciseRule;
+use crate::traits::payoff::{DownAndOutCallPayoff, NoTouchPayoff, OneTouchPayoff, Payoff};
+use crate::utils::linear_algebra::least_squares;
+use crate::utils::statistics::{black_scholes_price, calculate_d1_d2, norm_pdf};
+use crate::volatility::fx::FxSmile;
+
+/// Vanilla hedge instrument: strike and its smile vol 对冲用普通期权：行权价及其微笑波动率
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct VannaVolgaPivot{
+    pub strike:f64,
+    pub volatility:f64,
+}
+
+/// Scaling of the overhedge cost 对冲成本的缩放方式
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum VannaVolgaWeighting{
+    /// Full cost 全额成本
+    #[default]
+    Full,
+    /// Cost times the risk neutral no-touch probability of the barrier. Only payoffs carrying a
+    /// barrier are accepted: `OneTouchPayoff`, `NoTouchPayoff` and `DownAndOutCallPayoff`; any
+    /// other payoff is rejected with `InvalidParameter` <br>
+    /// 成本乘以障碍的风险中性无触碰概率。仅接受带障碍的Payoff：`OneTouchPayoff`、
+    /// `NoTouchPayoff`与`DownAndOutCallPayoff`，其他Payoff返回`InvalidParameter`错误
+    Survival,
+}
+
+/// Breakdown of a Vanna-Volga price Vanna-Volga价格的分解
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct VannaVolgaAdjustment{
+    /// Price at the ATM vol 平值波动率下的价格
+    pub flat_price:f64,
+    /// Vega, vanna and volga of the exotic at the ATM vol 奇异期权在平值波动率下的vega、vanna与volga
+    pub exotic_greeks:[f64;3],
+    /// Pivot hedge weights 支点对冲权重
+    pub weights:[f64;3],
+    /// Smile minus flat price of each pivot 各支点微笑价格减平坦价格
+    pub pivot_costs:[f64;3],
+    /// Scaling applied to the overhedge cost 对冲成本的缩放系数
+    pub scaling:f64,
+    /// flat_price + scaling·Σ weights·pivot_costs
+    pub price:f64,
+}
+
+/// Vanna-Volga engine wrapping a flat volatility Black-Scholes engine <br>
+/// 包装平坦波动率Black-Scholes引擎的Vanna-Volga引擎
+#[derive(Debug,Clone)]
+pub struct VannaVolgaEngine<E:PriceEngine>{
+    engine:E,
+    pivots:[VannaVolgaPivot;3],
+    weighting:VannaVolgaWeighting,
+}
+
+impl<E:PriceEngine> VannaVolgaEngine<E>{
+    /// Engine over three pivots with increasing strikes; the middle pivot is the ATM <br>
+    /// 以行权价递增的三个支点构建引擎，中间支点为平值
+    pub fn new(engine:E,pivots:[VannaVolgaPivot;3])->Result<Self>{
+        if pivots.iter().any(|p| p.strike<=0.0 || p.volatility<=0.0)
+            || !(pivots[0].strike<pivots[1].strike && pivots[1].strike<pivots[2].strike){
+            return Err(OptionError::InvalidParameter("Vanna-Volga pivots need increasing positive strikes and positive vols".into()));
+        }
+        Ok(Self{engine,pivots,weighting:VannaVolgaWeighting::default()})
+    }
+
+    /// Pivots taken from an FX smile: the ATM and the put/call pillars closest to it <br>
+    /// 从外汇微笑取支点：平值及与其最近的看跌/看涨支点
+    pub fn from_smile(engine:E,smile:&FxSmile)->Result<Self>{
+        let atm=smile.atm_strike();
+        let pillars=smile.pillars();
+        let put=pillars.iter().rfind(|p| p.strike<atm);
+        let call=pillars.iter().find(|p| p.strike>atm);
+        let (Some(put),Some(call))=(put,call) else{
+            return Err(OptionError::InvalidParameter("The smile needs pillars on both sides of the ATM".into()));
+        };
+        Self::new(engine,[
+            VannaVolgaPivot{strike:put.strike,volatility:put.volatility},
+            VannaVolgaPivot{strike:atm,volatility:smile.atm_volatility()},
+            VannaVolgaPivot{strike:call.strike,volatility:call.volatility},
+        ])
+    }
+
+    pub fn with_weighting(mut self,weighting:VannaVolgaWeighting)->Self{
+        self.weighting=weighting;
+        self
+    }
+
+    // Getter method
+    pub fn engine(&self)->&E{&self.engine}
+    pub fn pivots(&self)->&[VannaVolgaPivot;3]{&self.pivots}
+    pub fn weighting(&self)->VannaVolgaWeighting{self.weighting}
+
+    /// Vega, vanna and volga of an instrument by central differences of the wrapped engine <br>
+    /// 以被包装引擎的中心差分计算产品的vega、vanna与volga
+    pub fn exotic_greeks(&self,params:&CommonParams,payoff:&dyn Payoff,exercise_rule:&dyn ExerciseRule)->Result<[f64;3]>{
+        let (s,sigma)=(params.spot(),params.volatility());
+        let (ds,dv)=(1e-4*s,1e-4*sigma);
+        let price=|spot:f64,vol:f64|->Result<f64>{
+            self.engine.calculate_price(&params.with_spot(spot)?.with_volatility(vol)?,payoff,exercise_rule)
+        };
+        let middle=price(s,sigma)?;
+        let (up,down)=(price(s,sigma+dv)?,price(s,sigma-dv)?);
+        let vanna=(price(s+ds,sigma+dv)?-price(s+ds,sigma-dv)?-price(s-ds,sigma+dv)?+price(s-ds,sigma-dv)?)/(4.0*ds*dv);
+        Ok([(up-down)/(2.0*dv),vanna,(up-2.0*middle+down)/(dv*dv)])
+    }
+
+    /// Price with its breakdown; the volatility of `params` is replaced by the ATM pivot vol <br>
+    /// 价格及其分解；`params`中的波动率替换为平值支点波动率
+    pub fn adjustment(&self,params:&CommonParams,payoff:&dyn Payoff,exercise_rule:&dyn ExerciseRule)->Result<VannaVolgaAdjustment>{
+        let (s,r,_,q,t)=params.all_params();
+        if t<=0.0{
+            return Err(OptionError::InvalidParameter("Vanna-Volga needs a positive maturity".into()));
+        }
+        let atm=self.pivots[1].volatility;
+        let flat=params.with_volatility(atm)?;
+        let flat_price=self.engine.calculate_price(&flat,payoff,exercise_rule)?;
+        let exotic_greeks=self.exotic_greeks(&flat,payoff,exercise_rule)?;
+        let mut design=vec![vec![0.0;3];3];
+        let mut pivot_costs=[0.0;3];
+        for (j,pivot) in self.pivots.iter().enumerate(){
+            let (d1,d2)=calculate_d1_d2(s,pivot.strike,r,q,atm,t)?;
+            let vega=s*(-q*t).exp()*norm_pdf(d1)*t.sqrt();
+            design[0][j]=vega;
+            design[1][j]=-(-q*t).exp()*norm_pdf(d1)*d2/atm;
+            design[2][j]=vega*d1*d2/atm;
+            pivot_costs[j]=black_scholes_price(s,pivot.strike,r,q,pivot.volatility,t,true)
+                -black_scholes_price(s,pivot.strike,r,q,atm,t,true);
+        }
+        let solved=least_squares(&design,&exotic_greeks)?;
+        let weights=[solved[0],solved[1],solved[2]];
+        let scaling=match self.weighting{
+            VannaVolgaWeighting::Full=>1.0,
+            VannaVolgaWeighting::Survival=>1.0-touch_probability(&flat,payoff)?,
+        };
+        let cost:f64=(0..3).map(|j| weights[j]*pivot_costs[j]).sum();
+        Ok(VannaVolgaAdjustment{
+            flat_price,
+            exotic_greeks,
+            weights,
+            pivot_costs,
+            scaling,
+            price:flat_price+scaling*cost,
+        })
+    }
+}
+
+// 产品障碍在平值波动率下的风险中性触碰概率
+fn touch_probability(params:&CommonParams,payoff:&dyn Payoff)->Result<f64>{
+    let any=payoff.as_any();
+    if let Some(touch)=any.downcast_ref::<OneTouchPayoff>(){
+        BinaryCalculator::touch_probability(params,touch.barrier,touch.is_up)
+    }else if let Some(touch)=any.downcast_ref::<NoTouchPayoff>(){
+        BinaryCalculator::touch_probability(params,touch.barrier,touch.is_up)
+    }else if let Some(barrier)=any.downcast_ref::<DownAndOutCallPayoff>(){
+        BinaryCalculator::touch_probability(params,barrier.barrier,false)
+    }else{
+        Err(OptionError::InvalidParameter("Survival weighting needs a one-touch, no-touch or down-and-out call payoff".into()))
+    }
+}
+
+impl<E:PriceEngine+'static> PriceEngine for VannaVolgaEngine<E>{
+    fn calculate_price(&self,params:&CommonParams,payoff:&dyn Payoff,exercise_rule:&dyn ExerciseRule)->Result<f64>{
+        Ok(self.adjustment(params,payoff,exercise_rule)?.price)
+    }
+    fn as_any(&self)->&dyn Any{
+        self
+    }
+}
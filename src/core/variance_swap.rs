@@ -0,0 +1,181 @@
+//! Variance and volatility swaps <br>
+//! 方差互换与波动率互换
+//!
+//! The fair variance strike is replicated by a static strip of out-of-the-money options
+//! (Carr-Madan, Demeterfi-Derman-Kamal-Zou 1999), discretized as in the CBOE VIX method:
+//! K_var = (2/T)·e^{rT}·Σ ΔKᵢ/Kᵢ²·Q(Kᵢ) - (1/T)·(F/K₀ - 1)², where K₀ is the highest strike at
+//! or below the forward and Q the put below K₀, the call above it and their average at K₀.
+//! A volatility swap is not replicable; its strike is the convexity adjusted
+//! √E[V] - Var[V]/(8·E[V]^{3/2}) for a model of the realized variance V, or is simulated with
+//! `RealizedVolatilityPayoff` (e.g. under `HestonProcess`). <br>
+//! 公允方差行权价由一组虚值期权静态复制（Carr-Madan、Demeterfi-Derman-Kamal-Zou 1999），
+//! 离散方式同CBOE VIX：K_var = (2/T)·e^{rT}·Σ ΔKᵢ/Kᵢ²·Q(Kᵢ) - (1/T)·(F/K₀ - 1)²，其中K₀为
+//! 不高于远期的最高行权价，Q在K₀以下取看跌、以上取看涨、在K₀处取两者平均。波动率互换不可静态复制，
+//! 其行权价由已实现方差V的模型做凸性修正√E[V] - Var[V]/(8·E[V]^{3/2})，或以
+//! `RealizedVolatilityPayoff`模拟（如在`HestonProcess`下）
+use std::any::Any;
+use crate::core::analytic::calculators::VanillaCalculator;
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::params::heston::HestonParams;
+use crate::traits::engine::{AnalyticCalculator,PriceEngine};
+use crate::traits::exercise::ExerciseRule;
+use crate::traits::payoff::{CallPayoff,Payoff,PutPayoff,RealizedVariancePayoff,RealizedVolatilityPayoff};
+use crate::utils::math::gauss_legendre;
+use crate::volatility::surface::VolSurface;
+
+/// Variance swap engine replicating the fair variance with a strike strip; the listed options
+/// are priced by `VanillaCalculator` at the flat volatility of the parameters, or at the
+/// volatility of a surface <br>
+/// 以行权价序列复制公允方差的方差互换引擎；挂牌期权由`VanillaCalculator`按参数的平坦波动率或
+/// 曲面波动率定价
+#[derive(Debug,Clone)]
+pub struct VarianceSwapEngine{
+    strikes:Vec<f64>,
+    surface:Option<VolSurface>,
+}
+
+impl VarianceSwapEngine{
+    /// Engine over increasing positive strikes 以递增的正行权价构建引擎
+    pub fn new(strikes:Vec<f64>)->Result<Self>{
+        if strikes.len()<3{
+            return Err(OptionError::InvalidParameter("The replication strip needs at least 3 strikes".into()));
+        }
+        if strikes[0]<=0.0 || strikes.windows(2).any(|w| w[1]<=w[0]){
+            return Err(OptionError::InvalidParameter("Replication strikes must be positive and increasing".into()));
+        }
+        Ok(Self{strikes,surface:None})
+    }
+
+    /// `count` equally spaced strikes on [lower, upper] [lower, upper]上`count`个等距行权价
+    pub fn uniform(lower:f64,upper:f64,count:usize)->Result<Self>{
+        if count<3{
+            return Err(OptionError::InvalidParameter("The replication strip needs at least 3 strikes".into()));
+        }
+        let step=(upper-lower)/(count-1) as f64;
+        Self::new((0..count).map(|i| lower+step*i as f64).collect())
+    }
+
+    /// Price the strip off a volatility surface 以波动率曲面为复制期权定价
+    pub fn with_surface(mut self,surface:VolSurface)->Self{
+        self.surface=Some(surface);
+        self
+    }
+
+    // Getter method
+    pub fn strikes(&self)->&[f64]{&self.strikes}
+    pub fn surface(&self)->Option<&VolSurface>{self.surface.as_ref()}
+
+    /// Fair variance from any option prices; `price(strike, is_call)` returns the present value
+    /// of the call or put. The strip must bracket the forward <br>
+    /// 由任意期权价格得到公允方差；`price(strike, is_call)`返回看涨或看跌期权现值，行权价序列须包含远期
+    pub fn fair_variance_with<F>(&self,forward:f64,risk_free_rate:f64,time_to_maturity:f64,price:F)->Result<f64>
+    where F:Fn(f64,bool)->Result<f64>{
+        if time_to_maturity<=0.0{
+            return Err(OptionError::InvalidParameter("Variance replication needs a positive maturity".into()));
+        }
+        let ks=&self.strikes;
+        let n=ks.len();
+        if forward<ks[0] || forward>=ks[n-1]{
+            return Err(OptionError::InvalidParameter(format!(
+                "The replication strip [{}, {}] must bracket the forward {}",ks[0],ks[n-1],forward
+            )));
+        }
+        let atm=ks.partition_point(|&k| k<=forward)-1;
+        let mut sum=0.0;
+        for i in 0..n{
+            let dk=match i{
+                0=>ks[1]-ks[0],
+                _ if i==n-1=>ks[n-1]-ks[n-2],
+                _=>0.5*(ks[i+1]-ks[i-1]),
+            };
+            let quote=if i<atm{
+                price(ks[i],false)?
+            }else if i>atm{
+                price(ks[i],true)?
+            }else{
+                0.5*(price(ks[i],false)?+price(ks[i],true)?)
+            };
+            sum+=dk/(ks[i]*ks[i])*quote;
+        }
+        let t=time_to_maturity;
+        Ok(2.0/t*(risk_free_rate*t).exp()*sum-(forward/ks[atm]-1.0).powi(2)/t)
+    }
+
+    /// Fair variance for the market data of `params` 给定`params`市场数据的公允方差
+    pub fn fair_variance(&self,params:&CommonParams)->Result<f64>{
+        let (s,r,_,q,t)=params.all_params();
+        self.fair_variance_with(s*((r-q)*t).exp(),r,t,|strike,is_call|{
+            let quote=match &self.surface{
+                Some(surface)=>surface.apply(params,strike)?,
+                None=>*params,
+            };
+            if is_call{
+                VanillaCalculator.calculate(&quote,&CallPayoff::new(strike))
+            }else{
+                VanillaCalculator.calculate(&quote,&PutPayoff::new(strike))
+            }
+        })
+    }
+}
+
+impl PriceEngine for VarianceSwapEngine{
+    fn calculate_price(&self,params:&CommonParams,payoff:&dyn Payoff,exercise_rule:&dyn ExerciseRule)->Result<f64>{
+        if !exercise_rule.is_european(){
+            return Err(OptionError::InvalidParameter("Variance swaps settle at expiry".into()));
+        }
+        if payoff.as_any().is::<RealizedVolatilityPayoff>(){
+            return Err(OptionError::NotImplemented(
+                "Volatility swaps are not statically replicable; use a convexity adjustment or Monte Carlo".into()
+            ));
+        }
+        let swap=payoff.as_any().downcast_ref::<RealizedVariancePayoff>()
+            .ok_or_else(|| OptionError::NotImplemented("The variance swap engine only prices variance swaps".into()))?;
+        if (swap.maturity-params.time_to_maturity()).abs()>1e-12{
+            return Err(OptionError::InvalidParameter(format!(
+                "Variance swap maturity {} does not match the pricing maturity {}",swap.maturity,params.time_to_maturity()
+            )));
+        }
+        let discount=(-params.risk_free_rate()*params.time_to_maturity()).exp();
+        Ok(discount*swap.notional*(self.fair_variance(params)?-swap.strike))
+    }
+
+    fn as_any(&self)->&dyn Any{
+        self
+    }
+}
+
+/// Mean and variance of the annualized realized variance V = (1/T)∫₀ᵀ v_t dt under Heston.
+/// E[V] = θ + (v₀ - θ)(1 - e^{-κT})/(κT), and Var[∫v] = (2/κ)∫₀ᵀ Var[v_s](1 - e^{-κ(T-s)})ds is
+/// integrated by Gauss-Legendre quadrature <br>
+/// Heston模型下年化已实现方差V = (1/T)∫₀ᵀ v_t dt的均值与方差。
+/// E[V] = θ + (v₀ - θ)(1 - e^{-κT})/(κT)，Var[∫v] = (2/κ)∫₀ᵀ Var[v_s](1 - e^{-κ(T-s)})ds以Gauss-Legendre求积
+pub fn heston_realized_variance_moments(params:&HestonParams)->Result<(f64,f64)>{
+    let t=params.time_to_maturity();
+    if t<=0.0{
+        return Err(OptionError::InvalidParameter("Realized variance needs a positive maturity".into()));
+    }
+    let (v0,kappa,theta,xi)=(params.variance(),params.mean_reversion(),params.long_run_variance(),params.vol_of_vol());
+    let mean=theta+(v0-theta)*(1.0-(-kappa*t).exp())/(kappa*t);
+    // CIR方差过程的瞬时方差
+    let instantaneous=|s:f64|{
+        let decay=(-kappa*s).exp();
+        v0*xi*xi/kappa*(decay-decay*decay)+theta*xi*xi/(2.0*kappa)*(1.0-decay).powi(2)
+    };
+    let (nodes,weights)=gauss_legendre(64);
+    let integral:f64=nodes.iter().zip(&weights).map(|(x,w)|{
+        let s=0.5*t*(x+1.0);
+        w*instantaneous(s)*(1.0-(-kappa*(t-s)).exp())
+    }).sum::<f64>()*0.5*t;
+    Ok((mean,2.0/kappa*integral/(t*t)))
+}
+
+/// Convexity adjusted volatility swap strike √m - v/(8·m^{3/2}) for a realized variance with
+/// mean m and variance v <br>
+/// 已实现方差均值为m、方差为v时经凸性修正的波动率互换行权价√m - v/(8·m^{3/2})
+pub fn volatility_swap_strike(mean_variance:f64,variance_of_variance:f64)->Result<f64>{
+    if mean_variance<=0.0 || variance_of_variance<0.0{
+        return Err(OptionError::InvalidParameter("The realized variance needs a positive mean and a non-negative variance".into()));
+    }
+    Ok(mean_variance.sqrt()-variance_of_variance/(8.0*mean_variance.powf(1.5)))
+}
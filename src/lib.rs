@@ -9,16 +9,18 @@ pub mod traits;
 pub mod simulation;
 pub mod errors;
 pub mod utils;
+pub mod volatility;
+pub mod calibration;
 
 pub mod prelude {
     pub use std::sync::Arc;
     pub use crate::traits::engine::PriceEngine;
     pub use crate::core::engine_config::EngineConfig;
-    pub use crate::params::common::CommonParams;
+    pub use crate::params::common::{CommonParams,Dynamics};
     pub use crate::core::analytic::engine::AnalyticEngine;
     pub use crate::errors::*;
-    pub use crate::traits::engine::PricingTrait;
+    pub use crate::traits::engine::{PricingTrait,TwoAssetPriceEngine,TwoAssetPricingTrait};
     pub use crate::simulation::brownian::GeometricBrownianMotion;
     pub use crate::core::pde::engine::FiniteDifferenceMethod;
-    pub use crate::traits::exercise::{EuropeanExercise,ExerciseRule,AmericanExercise};
+    pub use crate::traits::exercise::{EuropeanExercise,ExerciseRule,AmericanExercise,BermudanExercise};
 }
\ No newline at end of file
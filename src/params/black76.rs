@@ -0,0 +1,73 @@
+//! Parameters of the Black-76 model for options on forwards and futures <br>
+//! 远期/期货期权的Black-76模型参数
+//!
+//! The underlying is the forward (futures) price F with dF = σ·F·dW; there is no carry and the
+//! premium is only discounted at the risk free rate. On the existing engines this is a
+//! Black-Scholes underlying with spot F and dividend yield equal to the risk free rate. <br>
+//! 标的为远期（期货）价格F，dF = σ·F·dW；无持有成本，期权费仅按无风险利率贴现。
+//! 在现有引擎中等价于现价为F、股息率等于无风险利率的Black-Scholes标的
+use crate::errors::*;
+use crate::params::common::CommonParams;
+
+/// Market parameters of an option on a forward or futures contract <br>
+/// 远期/期货期权的市场参数
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct Black76Params{
+    forward:f64,
+    risk_free_rate:f64,
+    volatility:f64,
+    time_to_maturity:f64,
+}
+
+impl Black76Params{
+    /// Create Black-76 parameters, including parameter validation <br>
+    /// 创建Black-76参数，包含参数验证
+    pub fn new(forward:f64,risk_free_rate:f64,volatility:f64,time_to_maturity:f64)->Result<Self>{
+        if forward<=0.0{
+            return Err(OptionError::InvalidParameter("Forward must be greater than zero.".into()));
+        }
+        if volatility<=0.0{
+            return Err(OptionError::InvalidParameter("Volatility must be greater than zero.".into()));
+        }
+        if time_to_maturity<0.0{
+            return Err(OptionError::InvalidParameter("Time to maturity cannot be negative.".into()));
+        }
+        Ok(Self{forward,risk_free_rate,volatility,time_to_maturity})
+    }
+
+    // Getter method
+    pub fn forward(&self)->f64{self.forward}
+    pub fn risk_free_rate(&self)->f64{self.risk_free_rate}
+    pub fn volatility(&self)->f64{self.volatility}
+    pub fn time_to_maturity(&self)->f64{self.time_to_maturity}
+
+    /// Discount factor e^{-rT} 贴现因子e^{-rT}
+    pub fn discount_factor(&self)->f64{
+        (-self.risk_free_rate*self.time_to_maturity).exp()
+    }
+
+    /// Equivalent parameters for the generic engines: spot F and dividend yield r <br>
+    /// 供通用引擎使用的等价参数：现价F，股息率r
+    pub fn to_common(&self)->Result<CommonParams>{
+        CommonParams::new(self.forward,self.risk_free_rate,self.volatility,self.risk_free_rate,self.time_to_maturity)
+    }
+
+    /// Read `CommonParams` as an option on the forward F = S·e^{(r-q)T}; inverse of `to_common` <br>
+    /// 将`CommonParams`视为远期F = S·e^{(r-q)T}上的期权；为`to_common`的逆
+    pub fn from_common(params:&CommonParams)->Result<Self>{
+        let (s,r,sigma,q,t)=params.all_params();
+        Self::new(s*((r-q)*t).exp(),r,sigma,t)
+    }
+
+    /// Create a parameter copy with a new forward(for calculating Greek letters)<br>
+    /// 创建新远期价格的参数副本（用于计算希腊字母）
+    pub fn with_forward(&self,forward:f64)->Result<Self>{
+        Self::new(forward,self.risk_free_rate,self.volatility,self.time_to_maturity)
+    }
+
+    /// Create a parameter copy with a new volatility <br>
+    /// 创建新波动率的参数副本
+    pub fn with_volatility(&self,volatility:f64)->Result<Self>{
+        Self::new(self.forward,self.risk_free_rate,volatility,self.time_to_maturity)
+    }
+}
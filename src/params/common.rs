@@ -1,5 +1,27 @@
 //! Common parameters for all type of options 所有期权通用的参数
 use crate::errors::*;
+
+/// Dynamics of the forward F = S·e^{(r-q)t} assumed by the analytic calculators <br>
+/// 解析解计算器假设的远期F = S·e^{(r-q)t}的动态
+#[derive(Debug,Clone,Copy,PartialEq,Default)]
+pub enum Dynamics{
+    /// dF = σ·F·dW (Black-Scholes); the spot must be positive 现价须为正
+    #[default]
+    Lognormal,
+    /// dF = σ·dW (Bachelier), `volatility` is the absolute normal volatility; any spot allowed <br>
+    /// `volatility`为绝对正态波动率；现价可为任意值
+    Normal,
+    /// d(F+shift) = σ·(F+shift)·dW (shifted lognormal); requires F + shift > 0 <br>
+    /// 平移对数正态，要求F + shift > 0
+    DisplacedDiffusion{shift:f64},
+}
+
+impl Dynamics{
+    pub fn is_lognormal(&self)->bool{
+        matches!(self,Dynamics::Lognormal)
+    }
+}
+
 #[derive(Debug,Clone,Copy)]
 pub struct CommonParams{
     spot:f64,
@@ -7,6 +29,7 @@ pub struct CommonParams{
     volatility:f64,
     dividend_yield:f64,
     time_to_maturity:f64,
+    dynamics:Dynamics,
 }
 
 impl CommonParams{
@@ -18,6 +41,19 @@ impl CommonParams{
         volatility:f64,
         dividend_yield:f64,
         time_to_maturity:f64,
+    )->Result<Self>{
+        Self::new_with_dynamics(spot,risk_free_rate,volatility,dividend_yield,time_to_maturity,Dynamics::Lognormal)
+    }
+
+    /// Create parameters for the given forward dynamics, including parameter validation <br>
+    /// 创建指定远期动态的参数，包含参数验证
+    pub fn new_with_dynamics(
+        spot:f64,
+        risk_free_rate:f64,
+        volatility:f64,
+        dividend_yield:f64,
+        time_to_maturity:f64,
+        dynamics:Dynamics,
     )->Result<Self>{
         let params=Self{
             spot,
@@ -25,6 +61,7 @@ impl CommonParams{
             volatility,
             dividend_yield,
             time_to_maturity,
+            dynamics,
         };
         crate::utils::statistics::validate_common_params(&params)?;
         Ok(params)
@@ -36,45 +73,67 @@ impl CommonParams{
     pub fn volatility(&self) -> f64{self.volatility}
     pub fn dividend_yield(&self) -> f64{self.dividend_yield}
     pub fn time_to_maturity(&self) -> f64{self.time_to_maturity}
+    pub fn dynamics(&self) -> Dynamics{self.dynamics}
     /// spot, risk_free_rate, volatility, dividend_yield, time_to_maturity
     pub fn all_params(&self)->(f64,f64,f64,f64,f64){
         (self.spot,self.risk_free_rate,self.volatility,self.dividend_yield,self.time_to_maturity)
     }
 
+    /// Forward price F = S·e^{(r-q)t} 远期价格
+    pub fn forward(&self)->f64{
+        self.spot*((self.risk_free_rate-self.dividend_yield)*self.time_to_maturity).exp()
+    }
+
     /// Create a parameter copy of minor pertubations(for calculating Greek letters)<br>
     /// 创建微小扰动的参数副本（用于计算希腊字母）
     pub fn with_spot(&self, new_spot:f64)->Result<Self>{
-        Self::new(
+        Self::new_with_dynamics(
             new_spot,
             self.risk_free_rate,
             self.volatility,
             self.dividend_yield,
             self.time_to_maturity,
+            self.dynamics,
         )
     }
 
     /// Create a parameter copy of minor pertubations(for calculating Greek letters)<br>
     /// 创建微小扰动的参数副本（用于计算希腊字母）
     pub fn with_volatility(&self, new_volatility:f64)->Result<Self>{
-        Self::new(
+        Self::new_with_dynamics(
             self.spot,
             self.risk_free_rate,
             new_volatility,
             self.dividend_yield,
             self.time_to_maturity,
+            self.dynamics,
         )
     }
 
     /// Create a parameter copy of minor pertubations(for calculating Greek letters)<br>
     /// 创建微小扰动的参数副本（用于计算希腊字母）
     pub fn with_time(&self, new_maturity:f64)->Result<Self>{
-        Self::new(
+        Self::new_with_dynamics(
             self.spot,
             self.risk_free_rate,
             self.volatility,
             self.dividend_yield,
             new_maturity,
+            self.dynamics,
+        )
+    }
+
+    /// Create a parameter copy with other forward dynamics <br>
+    /// 创建其他远期动态的参数副本
+    pub fn with_dynamics(&self, dynamics:Dynamics)->Result<Self>{
+        Self::new_with_dynamics(
+            self.spot,
+            self.risk_free_rate,
+            self.volatility,
+            self.dividend_yield,
+            self.time_to_maturity,
+            dynamics,
         )
     }
 
-}
\ No newline at end of file
+}
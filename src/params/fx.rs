@@ -0,0 +1,183 @@
+//! Parameters and quoting conventions of FX options (Garman-Kohlhagen) <br>
+//! 外汇期权参数与报价约定（Garman-Kohlhagen）
+//!
+//! The spot S is the price of one unit of the foreign currency in the domestic currency (FOR/DOM,
+//! e.g. EUR/USD = 1.10 means 1.10 USD per EUR). Under the domestic risk neutral measure
+//! dS = (r_d - r_f)·S·dt + σ·S·dW, i.e. Black-Scholes with the foreign rate as dividend yield. <br>
+//! 现价S为一单位外币的本币价格（FOR/DOM，如EUR/USD = 1.10表示1欧元兑1.10美元）；在本币风险中性
+//! 测度下dS = (r_d - r_f)·S·dt + σ·S·dW，即以外币利率为股息率的Black-Scholes模型
+use crate::errors::*;
+use crate::params::common::CommonParams;
+
+/// Market parameters of an FX option <br>
+/// 外汇期权的市场参数
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct GarmanKohlhagenParams{
+    spot:f64,
+    domestic_rate:f64,      // 本币利率r_d
+    foreign_rate:f64,       // 外币利率r_f
+    volatility:f64,
+    time_to_maturity:f64,
+}
+
+impl GarmanKohlhagenParams{
+    /// Create Garman-Kohlhagen parameters, including parameter validation <br>
+    /// 创建Garman-Kohlhagen参数，包含参数验证
+    pub fn new(
+        spot:f64,
+        domestic_rate:f64,
+        foreign_rate:f64,
+        volatility:f64,
+        time_to_maturity:f64,
+    )->Result<Self>{
+        if spot<=0.0{
+            return Err(OptionError::InvalidParameter("Spot must be greater than zero.".into()));
+        }
+        if volatility<=0.0{
+            return Err(OptionError::InvalidParameter("Volatility must be greater than zero.".into()));
+        }
+        if time_to_maturity<0.0{
+            return Err(OptionError::InvalidParameter("Time to maturity cannot be negative.".into()));
+        }
+        Ok(Self{spot,domestic_rate,foreign_rate,volatility,time_to_maturity})
+    }
+
+    // Getter method
+    pub fn spot(&self)->f64{self.spot}
+    pub fn domestic_rate(&self)->f64{self.domestic_rate}
+    pub fn foreign_rate(&self)->f64{self.foreign_rate}
+    pub fn volatility(&self)->f64{self.volatility}
+    pub fn time_to_maturity(&self)->f64{self.time_to_maturity}
+
+    /// Outright forward F = S·e^{(r_d-r_f)T} 远期汇率
+    pub fn forward(&self)->f64{
+        self.spot*((self.domestic_rate-self.foreign_rate)*self.time_to_maturity).exp()
+    }
+
+    /// Domestic discount factor e^{-r_d·T} 本币贴现因子
+    pub fn domestic_discount(&self)->f64{
+        (-self.domestic_rate*self.time_to_maturity).exp()
+    }
+
+    /// Foreign discount factor e^{-r_f·T} 外币贴现因子
+    pub fn foreign_discount(&self)->f64{
+        (-self.foreign_rate*self.time_to_maturity).exp()
+    }
+
+    /// Equivalent parameters for the generic engines: dividend yield r_f, prices in domestic
+    /// currency per unit of foreign notional <br>
+    /// 供通用引擎使用的等价参数：股息率取r_f，价格为每单位外币名义本金的本币金额
+    pub fn to_common(&self)->Result<CommonParams>{
+        CommonParams::new(self.spot,self.domestic_rate,self.volatility,self.foreign_rate,self.time_to_maturity)
+    }
+
+    /// Read `CommonParams` with r_d = r and r_f = q; inverse of `to_common` <br>
+    /// 以r_d = r、r_f = q读取`CommonParams`；为`to_common`的逆
+    pub fn from_common(params:&CommonParams)->Result<Self>{
+        let (s,r,sigma,q,t)=params.all_params();
+        Self::new(s,r,q,sigma,t)
+    }
+
+    /// Create a parameter copy with a new spot(for calculating Greek letters)<br>
+    /// 创建新现价的参数副本（用于计算希腊字母）
+    pub fn with_spot(&self,spot:f64)->Result<Self>{
+        Self::new(spot,self.domestic_rate,self.foreign_rate,self.volatility,self.time_to_maturity)
+    }
+
+    /// Create a parameter copy with a new volatility <br>
+    /// 创建新波动率的参数副本
+    pub fn with_volatility(&self,volatility:f64)->Result<Self>{
+        Self::new(self.spot,self.domestic_rate,self.foreign_rate,volatility,self.time_to_maturity)
+    }
+}
+
+/// Currency and unit in which an FX option premium is quoted. All values are per unit of
+/// notional; multiply pips by the pip factor of the pair (e.g. 10⁴) and percent by 100 for display <br>
+/// 外汇期权期权费的报价币种与单位。均按单位名义本金计；展示时点数乘以货币对的点值因子（如10⁴），
+/// 百分比乘以100
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum FxPremium{
+    /// Domestic currency per unit of foreign notional (the Garman-Kohlhagen value) <br>
+    /// 每单位外币名义本金的本币金额（即Garman-Kohlhagen价值）
+    #[default]
+    DomesticPips,
+    /// Foreign currency per unit of foreign notional: V/S <br>
+    /// 外币名义本金的外币百分比：V/S
+    ForeignPercent,
+    /// Domestic currency per unit of domestic notional K: V/K <br>
+    /// 本币名义本金（K）的本币百分比：V/K
+    DomesticPercent,
+    /// Foreign currency per unit of domestic notional: V/(S·K) <br>
+    /// 每单位本币名义本金的外币金额：V/(S·K)
+    ForeignPips,
+}
+
+impl FxPremium{
+    /// Convert a value in domestic pips to this convention <br>
+    /// 将本币点数表示的价值转换为该约定
+    pub fn from_domestic_pips(&self,value:f64,spot:f64,strike:f64)->f64{
+        match self{
+            FxPremium::DomesticPips=>value,
+            FxPremium::ForeignPercent=>value/spot,
+            FxPremium::DomesticPercent=>value/strike,
+            FxPremium::ForeignPips=>value/(spot*strike),
+        }
+    }
+
+    /// Convert a premium quoted in this convention back to domestic pips <br>
+    /// 将该约定下的期权费换回本币点数
+    pub fn to_domestic_pips(&self,premium:f64,spot:f64,strike:f64)->f64{
+        match self{
+            FxPremium::DomesticPips=>premium,
+            FxPremium::ForeignPercent=>premium*spot,
+            FxPremium::DomesticPercent=>premium*strike,
+            FxPremium::ForeignPips=>premium*spot*strike,
+        }
+    }
+
+    /// Whether the premium is paid in the foreign currency, which makes the hedge delta
+    /// premium-adjusted <br>
+    /// 期权费是否以外币支付（此时对冲delta需做期权费调整）
+    pub fn is_foreign(&self)->bool{
+        matches!(self,FxPremium::ForeignPercent|FxPremium::ForeignPips)
+    }
+}
+
+/// Delta quoting convention of FX options <br>
+/// 外汇期权的delta报价约定
+///
+/// Spot deltas carry the foreign discount factor e^{-r_f·T}, forward deltas do not. Premium
+/// adjusted deltas subtract the premium paid in foreign currency, V/S, which gives
+/// (K/F)·N(d₂) for a call instead of N(d₁); they are the market standard when the premium is
+/// paid in the foreign currency (e.g. USD/JPY) <br>
+/// 即期delta含外币贴现因子e^{-r_f·T}，远期delta不含；期权费调整delta扣除以外币支付的期权费V/S，
+/// 看涨期权由N(d₁)变为(K/F)·N(d₂)，是期权费以外币支付时（如USD/JPY）的市场惯例
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum FxDeltaConvention{
+    #[default]
+    Spot,
+    Forward,
+    PremiumAdjustedSpot,
+    PremiumAdjustedForward,
+}
+
+impl FxDeltaConvention{
+    pub fn is_premium_adjusted(&self)->bool{
+        matches!(self,FxDeltaConvention::PremiumAdjustedSpot|FxDeltaConvention::PremiumAdjustedForward)
+    }
+
+    pub fn is_spot(&self)->bool{
+        matches!(self,FxDeltaConvention::Spot|FxDeltaConvention::PremiumAdjustedSpot)
+    }
+}
+
+/// Definition of the at-the-money strike of FX options <br>
+/// 外汇期权平值行权价的定义
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum FxAtmConvention{
+    /// Delta neutral straddle: call and put deltas cancel 跨式组合delta中性
+    #[default]
+    DeltaNeutral,
+    /// Strike at the outright forward 行权价等于远期汇率
+    Forward,
+}
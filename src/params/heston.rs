@@ -0,0 +1,131 @@
+//! Parameters of the Heston stochastic volatility model Heston随机波动率模型参数
+//!
+//! dS = (r-q)·S·dt + √v·S·dW₁, dv = κ(θ-v)·dt + ξ·√v·dW₂, d⟨W₁,W₂⟩ = ρ·dt
+use crate::errors::*;
+use crate::params::common::CommonParams;
+
+/// Model parameters (v₀, κ, θ, ξ, ρ) of the Heston model, without market data <br>
+/// Heston模型的模型参数(v₀, κ, θ, ξ, ρ)，不含市场数据
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct HestonModelParams{
+    variance:f64,               // 初始方差v₀
+    mean_reversion:f64,         // 均值回复速度κ
+    long_run_variance:f64,      // 长期方差θ
+    vol_of_vol:f64,             // 波动率的波动率ξ
+    correlation:f64,            // 价格与方差的相关系数ρ
+}
+
+impl HestonModelParams{
+    /// Create Heston model parameters, including parameter validation <br>
+    /// 创建Heston模型参数，包含参数验证
+    pub fn new(variance:f64,mean_reversion:f64,long_run_variance:f64,vol_of_vol:f64,correlation:f64)->Result<Self>{
+        if variance<0.0 || long_run_variance<=0.0{
+            return Err(OptionError::InvalidParameter("Variance cannot be negative and the long run variance must be greater than zero.".into()));
+        }
+        if mean_reversion<=0.0 || vol_of_vol<=0.0{
+            return Err(OptionError::InvalidParameter("Mean reversion and vol of vol must be greater than zero.".into()));
+        }
+        if !(-1.0..=1.0).contains(&correlation){
+            return Err(OptionError::InvalidParameter("Correlation must be between -1 and 1.".into()));
+        }
+        Ok(Self{variance,mean_reversion,long_run_variance,vol_of_vol,correlation})
+    }
+
+    // Getter method
+    pub fn variance(&self)->f64{self.variance}
+    pub fn mean_reversion(&self)->f64{self.mean_reversion}
+    pub fn long_run_variance(&self)->f64{self.long_run_variance}
+    pub fn vol_of_vol(&self)->f64{self.vol_of_vol}
+    pub fn correlation(&self)->f64{self.correlation}
+
+    /// Whether the Feller condition 2κθ ≥ ξ² holds (the variance stays positive) <br>
+    /// 是否满足Feller条件2κθ ≥ ξ²（方差保持为正）
+    pub fn feller_satisfied(&self)->bool{
+        2.0*self.mean_reversion*self.long_run_variance>=self.vol_of_vol*self.vol_of_vol
+    }
+}
+
+/// Market data and model parameters of the Heston model <br>
+/// Heston模型的市场数据与模型参数
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct HestonParams{
+    spot:f64,
+    model:HestonModelParams,
+    risk_free_rate:f64,
+    dividend_yield:f64,
+    time_to_maturity:f64,
+}
+
+impl HestonParams{
+    /// Create Heston parameters, including parameter validation <br>
+    /// 创建Heston参数，包含参数验证
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        spot:f64,
+        variance:f64,
+        mean_reversion:f64,
+        long_run_variance:f64,
+        vol_of_vol:f64,
+        correlation:f64,
+        risk_free_rate:f64,
+        dividend_yield:f64,
+        time_to_maturity:f64,
+    )->Result<Self>{
+        let model=HestonModelParams::new(variance,mean_reversion,long_run_variance,vol_of_vol,correlation)?;
+        Self::from_model(spot,model,risk_free_rate,dividend_yield,time_to_maturity)
+    }
+
+    /// Combine model parameters with market data <br>
+    /// 由模型参数与市场数据组合
+    pub fn from_model(spot:f64,model:HestonModelParams,risk_free_rate:f64,dividend_yield:f64,time_to_maturity:f64)->Result<Self>{
+        if spot<=0.0{
+            return Err(OptionError::InvalidParameter("Spot must be greater than zero.".into()));
+        }
+        if time_to_maturity<0.0{
+            return Err(OptionError::InvalidParameter("Time to maturity cannot be negative.".into()));
+        }
+        Ok(Self{spot,model,risk_free_rate,dividend_yield,time_to_maturity})
+    }
+
+    // Getter method
+    pub fn spot(&self)->f64{self.spot}
+    pub fn model(&self)->&HestonModelParams{&self.model}
+    pub fn variance(&self)->f64{self.model.variance}
+    pub fn mean_reversion(&self)->f64{self.model.mean_reversion}
+    pub fn long_run_variance(&self)->f64{self.model.long_run_variance}
+    pub fn vol_of_vol(&self)->f64{self.model.vol_of_vol}
+    pub fn correlation(&self)->f64{self.model.correlation}
+    pub fn risk_free_rate(&self)->f64{self.risk_free_rate}
+    pub fn dividend_yield(&self)->f64{self.dividend_yield}
+    pub fn time_to_maturity(&self)->f64{self.time_to_maturity}
+
+    /// Whether the Feller condition 2κθ ≥ ξ² holds (the variance stays positive) <br>
+    /// 是否满足Feller条件2κθ ≥ ξ²（方差保持为正）
+    pub fn feller_satisfied(&self)->bool{
+        self.model.feller_satisfied()
+    }
+
+    /// Black-Scholes parameters with the volatility √max(v₀, θ) <br>
+    /// 波动率取√max(v₀, θ)的Black-Scholes参数
+    pub fn to_common(&self)->Result<CommonParams>{
+        CommonParams::new(
+            self.spot,
+            self.risk_free_rate,
+            self.variance().max(self.long_run_variance()).sqrt(),
+            self.dividend_yield,
+            self.time_to_maturity,
+        )
+    }
+
+    /// Create a parameter copy with a new spot(for calculating Greek letters)<br>
+    /// 创建新现价的参数副本（用于计算希腊字母）
+    pub fn with_spot(&self,spot:f64)->Result<Self>{
+        Self::from_model(spot,self.model,self.risk_free_rate,self.dividend_yield,self.time_to_maturity)
+    }
+
+    /// Create a parameter copy with a new maturity <br>
+    /// 创建新期限的参数副本
+    pub fn with_time(&self,time_to_maturity:f64)->Result<Self>{
+        Self::from_model(self.spot,self.model,self.risk_free_rate,self.dividend_yield,time_to_maturity)
+    }
+}
@@ -1 +1,7 @@
-pub mod common;
\ No newline at end of file
+pub mod common;
+pub mod two_asset;
+pub mod multi_asset;
+pub mod heston;
+pub mod sabr;
+pub mod black76;
+pub mod fx;
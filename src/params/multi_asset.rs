@@ -0,0 +1,100 @@
+//! Parameters of options written on several correlated underlyings 多资产期权参数
+use crate::errors::*;
+use crate::utils::linear_algebra::cholesky_decomposition;
+
+/// Market parameters of n assets following correlated geometric brownian motions <br>
+/// n个相关几何布朗运动资产的市场参数
+#[derive(Debug,Clone)]
+pub struct MultiAssetParams{
+    spots:Vec<f64>,
+    volatilities:Vec<f64>,
+    dividend_yields:Vec<f64>,
+    correlation:Vec<Vec<f64>>,
+    risk_free_rate:f64,
+    time_to_maturity:f64,
+}
+
+impl MultiAssetParams{
+    /// Create multi-asset parameters, including parameter validation
+    /// (the correlation matrix must be a valid positive semi-definite correlation matrix) <br>
+    /// 创建多资产参数，包含参数验证（相关系数矩阵须对称、对角为1且半正定）
+    pub fn new(
+        spots:Vec<f64>,
+        volatilities:Vec<f64>,
+        dividend_yields:Vec<f64>,
+        correlation:Vec<Vec<f64>>,
+        risk_free_rate:f64,
+        time_to_maturity:f64,
+    )->Result<Self>{
+        let n=spots.len();
+        if n==0{
+            return Err(OptionError::EmptyData);
+        }
+        if volatilities.len()!=n || dividend_yields.len()!=n || correlation.len()!=n{
+            return Err(OptionError::InvalidParameter("Spots, volatilities, dividend yields \
+            and correlation matrix must have the same dimension".into()));
+        }
+        if spots.iter().any(|&s| s<=0.0){
+            return Err(OptionError::InvalidParameter("Spot must be greater than zero.".into()));
+        }
+        if volatilities.iter().any(|&v| v<=0.0){
+            return Err(OptionError::InvalidParameter("Volatility must be greater than zero.".into()));
+        }
+        if time_to_maturity<0.0{
+            return Err(OptionError::InvalidParameter("Time to maturity cannot be negative.".into()));
+        }
+        validate_correlation_matrix(&correlation)?;
+        Ok(Self{
+            spots,
+            volatilities,
+            dividend_yields,
+            correlation,
+            risk_free_rate,
+            time_to_maturity,
+        })
+    }
+
+    // Getter method
+    pub fn num_assets(&self)->usize{self.spots.len()}
+    pub fn spots(&self)->&[f64]{&self.spots}
+    pub fn volatilities(&self)->&[f64]{&self.volatilities}
+    pub fn dividend_yields(&self)->&[f64]{&self.dividend_yields}
+    pub fn correlation(&self)->&[Vec<f64>]{&self.correlation}
+    pub fn risk_free_rate(&self)->f64{self.risk_free_rate}
+    pub fn time_to_maturity(&self)->f64{self.time_to_maturity}
+
+    /// Create a parameter copy with new spots(for calculating Greek letters)<br>
+    /// 创建新现价的参数副本（用于计算希腊字母）
+    pub fn with_spots(&self,spots:Vec<f64>)->Result<Self>{
+        Self::new(
+            spots,
+            self.volatilities.clone(),
+            self.dividend_yields.clone(),
+            self.correlation.clone(),
+            self.risk_free_rate,
+            self.time_to_maturity,
+        )
+    }
+}
+
+/// Check that a matrix is a valid correlation matrix: square, symmetric,
+/// unit diagonal, entries in [-1,1] and positive semi-definite <br>
+/// 检查相关系数矩阵的合法性
+pub fn validate_correlation_matrix(correlation:&[Vec<f64>])->Result<()>{
+    let n=correlation.len();
+    for (i,row) in correlation.iter().enumerate(){
+        if row.len()!=n{
+            return Err(OptionError::InvalidParameter("Correlation matrix must be square".into()));
+        }
+        if (row[i]-1.0).abs()>1e-12{
+            return Err(OptionError::InvalidParameter("Diagonal of correlation matrix must be 1".into()));
+        }
+        if row.iter().any(|rho| !(-1.0..=1.0).contains(rho)){
+            return Err(OptionError::InvalidParameter("Correlation must be between -1 and 1.".into()));
+        }
+    }
+    cholesky_decomposition(correlation).map_err(|e| OptionError::InvalidParameter(
+        format!("Invalid correlation matrix: {}",e)
+    ))?;
+    Ok(())
+}
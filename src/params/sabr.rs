@@ -0,0 +1,40 @@
+//! Parameters of the SABR stochastic volatility model (Hagan et al., 2002) SABR随机波动率模型参数
+//!
+//! dF = α·F^β·dW₁, dα = ν·α·dW₂, d⟨W₁,W₂⟩ = ρ·dt, where F is the forward of the expiry <br>
+//! F为对应到期日的远期价格
+use crate::errors::*;
+
+/// SABR parameters of one expiry 单个到期日的SABR参数
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct SabrParams{
+    alpha:f64,  // 初始波动率α
+    beta:f64,   // CEV指数β ∈ [0,1]
+    rho:f64,    // 远期与波动率的相关系数ρ
+    nu:f64,     // 波动率的波动率ν
+}
+
+impl SabrParams{
+    /// Create SABR parameters, including parameter validation <br>
+    /// 创建SABR参数，包含参数验证
+    pub fn new(alpha:f64,beta:f64,rho:f64,nu:f64)->Result<Self>{
+        if alpha<=0.0{
+            return Err(OptionError::InvalidParameter("Alpha must be greater than zero.".into()));
+        }
+        if !(0.0..=1.0).contains(&beta){
+            return Err(OptionError::InvalidParameter("Beta must be between 0 and 1.".into()));
+        }
+        if rho<= -1.0 || rho>=1.0{
+            return Err(OptionError::InvalidParameter("Correlation must be strictly between -1 and 1.".into()));
+        }
+        if nu<0.0{
+            return Err(OptionError::InvalidParameter("Vol of vol cannot be negative.".into()));
+        }
+        Ok(Self{alpha,beta,rho,nu})
+    }
+
+    // Getter method
+    pub fn alpha(&self)->f64{self.alpha}
+    pub fn beta(&self)->f64{self.beta}
+    pub fn rho(&self)->f64{self.rho}
+    pub fn nu(&self)->f64{self.nu}
+}
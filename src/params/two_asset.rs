@@ -0,0 +1,128 @@
+//! Parameters of options written on two correlated underlyings 两资产期权参数
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::params::multi_asset::MultiAssetParams;
+
+/// Market parameters of two correlated assets following geometric brownian motions <br>
+/// 两个相关几何布朗运动资产的市场参数
+#[derive(Debug,Clone,Copy)]
+pub struct TwoAssetParams{
+    spot1:f64,
+    spot2:f64,
+    volatility1:f64,
+    volatility2:f64,
+    dividend_yield1:f64,
+    dividend_yield2:f64,
+    correlation:f64,
+    risk_free_rate:f64,
+    time_to_maturity:f64,
+}
+
+impl TwoAssetParams{
+    /// Create two-asset parameters, including parameter validation <br>
+    /// 创建两资产参数，包含参数验证
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        spot1:f64,
+        spot2:f64,
+        volatility1:f64,
+        volatility2:f64,
+        dividend_yield1:f64,
+        dividend_yield2:f64,
+        correlation:f64,
+        risk_free_rate:f64,
+        time_to_maturity:f64,
+    )->Result<Self>{
+        if spot1<=0.0 || spot2<=0.0{
+            return Err(OptionError::InvalidParameter("Spot must be greater than zero.".into()));
+        }
+        if volatility1<=0.0 || volatility2<=0.0{
+            return Err(OptionError::InvalidParameter("Volatility must be greater than zero.".into()));
+        }
+        if !(-1.0..=1.0).contains(&correlation){
+            return Err(OptionError::InvalidParameter("Correlation must be between -1 and 1.".into()));
+        }
+        if time_to_maturity<0.0{
+            return Err(OptionError::InvalidParameter("Time to maturity cannot be negative.".into()));
+        }
+        Ok(Self{
+            spot1,
+            spot2,
+            volatility1,
+            volatility2,
+            dividend_yield1,
+            dividend_yield2,
+            correlation,
+            risk_free_rate,
+            time_to_maturity,
+        })
+    }
+
+    // Getter method
+    pub fn spot1(&self)->f64{self.spot1}
+    pub fn spot2(&self)->f64{self.spot2}
+    pub fn volatility1(&self)->f64{self.volatility1}
+    pub fn volatility2(&self)->f64{self.volatility2}
+    pub fn dividend_yield1(&self)->f64{self.dividend_yield1}
+    pub fn dividend_yield2(&self)->f64{self.dividend_yield2}
+    pub fn correlation(&self)->f64{self.correlation}
+    pub fn risk_free_rate(&self)->f64{self.risk_free_rate}
+    pub fn time_to_maturity(&self)->f64{self.time_to_maturity}
+
+    /// Single-asset parameters of the first asset <br>
+    /// 第一个资产的单资产参数
+    pub fn first(&self)->Result<CommonParams>{
+        CommonParams::new(self.spot1,self.risk_free_rate,self.volatility1,self.dividend_yield1,self.time_to_maturity)
+    }
+
+    /// Single-asset parameters of the second asset <br>
+    /// 第二个资产的单资产参数
+    pub fn second(&self)->Result<CommonParams>{
+        CommonParams::new(self.spot2,self.risk_free_rate,self.volatility2,self.dividend_yield2,self.time_to_maturity)
+    }
+
+    /// Convert into the general n-asset parameters <br>
+    /// 转换为通用的多资产参数
+    pub fn to_multi_asset(&self)->Result<MultiAssetParams>{
+        MultiAssetParams::new(
+            vec![self.spot1,self.spot2],
+            vec![self.volatility1,self.volatility2],
+            vec![self.dividend_yield1,self.dividend_yield2],
+            vec![vec![1.0,self.correlation],vec![self.correlation,1.0]],
+            self.risk_free_rate,
+            self.time_to_maturity,
+        )
+    }
+
+    /// Create a parameter copy with new spots(for calculating Greek letters)<br>
+    /// 创建新现价的参数副本（用于计算希腊字母）
+    pub fn with_spots(&self,spot1:f64,spot2:f64)->Result<Self>{
+        Self::new(
+            spot1,
+            spot2,
+            self.volatility1,
+            self.volatility2,
+            self.dividend_yield1,
+            self.dividend_yield2,
+            self.correlation,
+            self.risk_free_rate,
+            self.time_to_maturity,
+        )
+    }
+
+    /// Create a parameter copy with a new correlation <br>
+    /// 创建新相关系数的参数副本
+    pub fn with_correlation(&self,correlation:f64)->Result<Self>{
+        Self::new(
+            self.spot1,
+            self.spot2,
+            self.volatility1,
+            self.volatility2,
+            self.dividend_yield1,
+            self.dividend_yield2,
+            correlation,
+            self.risk_free_rate,
+            self.time_to_maturity,
+        )
+    }
+}
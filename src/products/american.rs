@@ -0,0 +1,146 @@
+//! American options 美式期权
+//!
+//! Priced by the binomial and PDE engines, which can also report the early exercise
+//! boundary S*(t) through `ExerciseBoundaryEngine`. <br>
+//! 由二叉树和PDE引擎定价，二者亦可通过`ExerciseBoundaryEngine`给出提前行权边界S*(t)
+use std::sync::Arc;
+use crate::params::common::CommonParams;
+use crate::errors::*;
+use crate::utils::statistics::validate_common_params;
+use crate::traits::payoff::{CallPayoff,PutPayoff,Payoff};
+use crate::traits::exercise::{AmericanExercise,ExerciseRule};
+use crate::traits::engine::{BoundaryCondition,PricingTrait};
+
+/// American call option 美式看涨期权
+#[derive(Clone)]
+pub struct AmericanCall{
+    common:CommonParams,
+    strike:f64,
+    payoff:CallPayoff,
+    exercise_type:Arc<dyn ExerciseRule>,
+    boundary_condition:Arc<dyn BoundaryCondition>,
+}
+
+impl AmericanCall{
+    pub fn new(
+        spot:f64,
+        strike:f64,
+        risk_free_rate:f64,
+        volatility:f64,
+        dividend_yield:f64,
+        time_to_maturity:f64,
+    )->Result<Self>{
+        let common=CommonParams::new(spot,risk_free_rate,volatility,dividend_yield,time_to_maturity)?;
+        validate_common_params(&common)?;
+        let boundary_condition=AmericanBoundaryCondition::new(strike,risk_free_rate,dividend_yield,true)?;
+        Ok(Self{
+            common,
+            strike,
+            payoff:CallPayoff::new(strike),
+            exercise_type:Arc::new(AmericanExercise),
+            boundary_condition:Arc::new(boundary_condition),
+        })
+    }
+
+    pub fn strike(&self)->f64{self.strike}
+}
+
+impl PricingTrait for AmericanCall{
+    fn common(&self) -> &CommonParams { &self.common }
+    fn payoff(&self)->&dyn Payoff{&self.payoff}
+    fn exercise_type(&self)->&dyn ExerciseRule{self.exercise_type.as_ref()}
+    fn boundary_condition(&self)->&Arc<dyn BoundaryCondition>{&self.boundary_condition}
+}
+
+/// American put option 美式看跌期权
+#[derive(Clone)]
+pub struct AmericanPut{
+    common:CommonParams,
+    strike:f64,
+    payoff:PutPayoff,
+    exercise_type:Arc<dyn ExerciseRule>,
+    boundary_condition:Arc<dyn BoundaryCondition>,
+}
+
+impl AmericanPut{
+    pub fn new(
+        spot:f64,
+        strike:f64,
+        risk_free_rate:f64,
+        volatility:f64,
+        dividend_yield:f64,
+        time_to_maturity:f64,
+    )->Result<Self>{
+        let common=CommonParams::new(spot,risk_free_rate,volatility,dividend_yield,time_to_maturity)?;
+        validate_common_params(&common)?;
+        let boundary_condition=AmericanBoundaryCondition::new(strike,risk_free_rate,dividend_yield,false)?;
+        Ok(Self{
+            common,
+            strike,
+            payoff:PutPayoff::new(strike),
+            exercise_type:Arc::new(AmericanExercise),
+            boundary_condition:Arc::new(boundary_condition),
+        })
+    }
+
+    pub fn strike(&self)->f64{self.strike}
+}
+
+impl PricingTrait for AmericanPut{
+    fn common(&self) -> &CommonParams { &self.common }
+    fn payoff(&self)->&dyn Payoff{&self.payoff}
+    fn exercise_type(&self)->&dyn ExerciseRule{self.exercise_type.as_ref()}
+    fn boundary_condition(&self)->&Arc<dyn BoundaryCondition>{&self.boundary_condition}
+}
+
+/// PDE boundary condition of American vanilla options: deep in the money the option
+/// is worth the larger of its intrinsic value and the discounted forward intrinsic value <br>
+/// 美式普通期权的PDE边界条件：深度实值时价值取内在价值与远期内在价值贴现值中的较大者
+#[derive(Debug,Clone)]
+pub struct AmericanBoundaryCondition{
+    strike:f64,
+    risk_free_rate:f64,
+    dividend_yield:f64,
+    is_call:bool,
+}
+
+impl AmericanBoundaryCondition{
+    pub fn new(
+        strike:f64,
+        risk_free_rate:f64,
+        dividend_yield:f64,
+        is_call:bool,
+    )->Result<Self>{
+        if strike<=0.0{
+            return Err(OptionError::InvalidParameter("Strike must be greater than zero.".to_string()));
+        }
+        Ok(Self{strike,risk_free_rate,dividend_yield,is_call})
+    }
+
+    /// max(φ(S-K), φ(S·e^{-qt} - K·e^{-rt}))
+    fn in_the_money_value(&self,t:f64,spot:f64)->f64{
+        let sign=if self.is_call{1.0}else{-1.0};
+        let immediate=sign*(spot-self.strike);
+        let forward=sign*(spot*(-self.dividend_yield*t).exp()-self.strike*(-self.risk_free_rate*t).exp());
+        immediate.max(forward).max(0.0)
+    }
+}
+
+impl BoundaryCondition for AmericanBoundaryCondition{
+    fn lower_boundary_at(&self,t:f64,spot:f64)->Result<f64>{
+        Ok(if self.is_call{0.0}else{self.in_the_money_value(t,spot)})
+    }
+
+    fn upper_boundary_at(&self,t:f64,spot:f64)->Result<f64>{
+        Ok(if self.is_call{self.in_the_money_value(t,spot)}else{0.0})
+    }
+
+    fn final_condition(&self,spot:f64)->Result<f64>{
+        let sign=if self.is_call{1.0}else{-1.0};
+        Ok((sign*(spot-self.strike)).max(0.0))
+    }
+
+    fn clone_box(&self) -> Box<dyn BoundaryCondition> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,163 @@
+//! Bermudan options 百慕大期权
+//!
+//! Exercisable on a schedule of dates only; priced by the binomial and PDE engines
+//! (each date mapped to the nearest node) and by Longstaff-Schwartz Monte Carlo. <br>
+//! 仅可在给定日期行权；由二叉树、PDE引擎（日期映射到最近节点）及Longstaff-Schwartz蒙特卡洛定价
+use std::sync::Arc;
+use crate::params::common::CommonParams;
+use crate::errors::*;
+use crate::utils::statistics::validate_common_params;
+use crate::traits::payoff::{CallPayoff,PutPayoff,Payoff};
+use crate::traits::exercise::{BermudanExercise,ExerciseRule};
+use crate::traits::engine::{BoundaryCondition,PricingTrait};
+
+/// Bermudan call option 百慕大看涨期权
+#[derive(Clone)]
+pub struct BermudanCall{
+    common:CommonParams,
+    strike:f64,
+    payoff:CallPayoff,
+    exercise_type:Arc<dyn ExerciseRule>,
+    boundary_condition:Arc<dyn BoundaryCondition>,
+}
+
+impl BermudanCall{
+    /// `exercise_times` are measured in years from the valuation date <br>
+    /// `exercise_times`为距估值日的时间（年）
+    pub fn new(
+        spot:f64,
+        strike:f64,
+        risk_free_rate:f64,
+        volatility:f64,
+        dividend_yield:f64,
+        time_to_maturity:f64,
+        exercise_times:Vec<f64>,
+    )->Result<Self>{
+        let common=CommonParams::new(spot,risk_free_rate,volatility,dividend_yield,time_to_maturity)?;
+        validate_common_params(&common)?;
+        let exercise=BermudanExercise::new(exercise_times,time_to_maturity)?;
+        let boundary_condition=BermudanBoundaryCondition::new(strike,risk_free_rate,dividend_yield,true,exercise.clone())?;
+        Ok(Self{
+            common,
+            strike,
+            payoff:CallPayoff::new(strike),
+            exercise_type:Arc::new(exercise),
+            boundary_condition:Arc::new(boundary_condition),
+        })
+    }
+
+    pub fn strike(&self)->f64{self.strike}
+}
+
+impl PricingTrait for BermudanCall{
+    fn common(&self) -> &CommonParams { &self.common }
+    fn payoff(&self)->&dyn Payoff{&self.payoff}
+    fn exercise_type(&self)->&dyn ExerciseRule{self.exercise_type.as_ref()}
+    fn boundary_condition(&self)->&Arc<dyn BoundaryCondition>{&self.boundary_condition}
+}
+
+/// Bermudan put option 百慕大看跌期权
+#[derive(Clone)]
+pub struct BermudanPut{
+    common:CommonParams,
+    strike:f64,
+    payoff:PutPayoff,
+    exercise_type:Arc<dyn ExerciseRule>,
+    boundary_condition:Arc<dyn BoundaryCondition>,
+}
+
+impl BermudanPut{
+    /// `exercise_times` are measured in years from the valuation date <br>
+    /// `exercise_times`为距估值日的时间（年）
+    pub fn new(
+        spot:f64,
+        strike:f64,
+        risk_free_rate:f64,
+        volatility:f64,
+        dividend_yield:f64,
+        time_to_maturity:f64,
+        exercise_times:Vec<f64>,
+    )->Result<Self>{
+        let common=CommonParams::new(spot,risk_free_rate,volatility,dividend_yield,time_to_maturity)?;
+        validate_common_params(&common)?;
+        let exercise=BermudanExercise::new(exercise_times,time_to_maturity)?;
+        let boundary_condition=BermudanBoundaryCondition::new(strike,risk_free_rate,dividend_yield,false,exercise.clone())?;
+        Ok(Self{
+            common,
+            strike,
+            payoff:PutPayoff::new(strike),
+            exercise_type:Arc::new(exercise),
+            boundary_condition:Arc::new(boundary_condition),
+        })
+    }
+
+    pub fn strike(&self)->f64{self.strike}
+}
+
+impl PricingTrait for BermudanPut{
+    fn common(&self) -> &CommonParams { &self.common }
+    fn payoff(&self)->&dyn Payoff{&self.payoff}
+    fn exercise_type(&self)->&dyn ExerciseRule{self.exercise_type.as_ref()}
+    fn boundary_condition(&self)->&Arc<dyn BoundaryCondition>{&self.boundary_condition}
+}
+
+/// PDE boundary condition of Bermudan vanilla options: deep in the money the option is
+/// exercised on the best remaining exercise date (or at maturity) <br>
+/// 百慕大普通期权的PDE边界条件：深度实值时在剩余行权日（或到期日）中最优的日期行权
+#[derive(Debug,Clone)]
+pub struct BermudanBoundaryCondition{
+    strike:f64,
+    risk_free_rate:f64,
+    dividend_yield:f64,
+    is_call:bool,
+    exercise:BermudanExercise,
+}
+
+impl BermudanBoundaryCondition{
+    pub fn new(
+        strike:f64,
+        risk_free_rate:f64,
+        dividend_yield:f64,
+        is_call:bool,
+        exercise:BermudanExercise,
+    )->Result<Self>{
+        if strike<=0.0{
+            return Err(OptionError::InvalidParameter("Strike must be greater than zero.".to_string()));
+        }
+        Ok(Self{strike,risk_free_rate,dividend_yield,is_call,exercise})
+    }
+
+    /// max over remaining dates d of φ(S·e^{-q(d-s)} - K·e^{-r(d-s)}), s = elapsed time <br>
+    /// 对剩余行权日d取φ(S·e^{-q(d-s)} - K·e^{-r(d-s)})的最大值，s为已经过时间
+    fn in_the_money_value(&self,t:f64,spot:f64)->f64{
+        let sign=if self.is_call{1.0}else{-1.0};
+        let elapsed=self.exercise.maturity()-t;
+        self.exercise.exercise_times().iter()
+            .chain(std::iter::once(&self.exercise.maturity()))
+            .filter(|&&d| d>=elapsed-1e-12)
+            .map(|d|{
+                let wait=(d-elapsed).max(0.0);
+                sign*(spot*(-self.dividend_yield*wait).exp()-self.strike*(-self.risk_free_rate*wait).exp())
+            })
+            .fold(0.0,f64::max)
+    }
+}
+
+impl BoundaryCondition for BermudanBoundaryCondition{
+    fn lower_boundary_at(&self,t:f64,spot:f64)->Result<f64>{
+        Ok(if self.is_call{0.0}else{self.in_the_money_value(t,spot)})
+    }
+
+    fn upper_boundary_at(&self,t:f64,spot:f64)->Result<f64>{
+        Ok(if self.is_call{self.in_the_money_value(t,spot)}else{0.0})
+    }
+
+    fn final_condition(&self,spot:f64)->Result<f64>{
+        let sign=if self.is_call{1.0}else{-1.0};
+        Ok((sign*(spot-self.strike)).max(0.0))
+    }
+
+    fn clone_box(&self) -> Box<dyn BoundaryCondition> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,285 @@
+//! Binary (digital) options 二元期权
+//!
+//! European binaries are priced by every engine: the PDE engine averages the discontinuous
+//! payoff over the terminal grid cells, and a smoothing width can replace the jump by a
+//! linear ramp for the binomial and Monte Carlo engines. Touch options are priced by the
+//! analytic engine, by the Monte Carlo engine with a Brownian bridge correction and by the
+//! binomial engine, which monitors the barrier at every node. <br>
+//! 欧式二元期权可由所有引擎定价：PDE引擎对终值网格单元取平均收益，
+//! 设置平滑宽度后二叉树和蒙特卡洛引擎以线性斜坡代替跳跃。
+//! 触碰期权由解析解引擎、带布朗桥修正的蒙特卡洛引擎以及在每个节点监测障碍的二叉树引擎定价
+use std::sync::Arc;
+use crate::params::common::CommonParams;
+use crate::errors::*;
+use crate::utils::statistics::validate_common_params;
+use crate::traits::payoff::{
+    Payoff, DigitalPayoff,
+    CashOrNothingCallPayoff, CashOrNothingPutPayoff,
+    AssetOrNothingCallPayoff, AssetOrNothingPutPayoff,
+    GapCallPayoff, GapPutPayoff,
+    OneTouchPayoff, NoTouchPayoff, BrownianBridge,
+};
+use crate::traits::exercise::{EuropeanExercise,ExerciseRule};
+use crate::traits::engine::{BoundaryCondition,NoBoundaryCondition,PricingTrait};
+
+/// Kind of a European binary option 欧式二元期权类型
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum BinaryKind{
+    /// Q·1{S>K}
+    CashOrNothingCall{payout:f64},
+    /// Q·1{S<K}
+    CashOrNothingPut{payout:f64},
+    /// S·1{S>K}
+    AssetOrNothingCall,
+    /// S·1{S<K}
+    AssetOrNothingPut,
+    /// (S-K)·1{S>trigger}
+    GapCall{trigger:f64},
+    /// (K-S)·1{S<trigger}
+    GapPut{trigger:f64},
+}
+
+impl BinaryKind{
+    fn payoff(&self,strike:f64,smoothing:f64)->Arc<dyn Payoff>{
+        match *self{
+            BinaryKind::CashOrNothingCall{payout}=>smoothed(CashOrNothingCallPayoff::new(strike,payout),smoothing),
+            BinaryKind::CashOrNothingPut{payout}=>smoothed(CashOrNothingPutPayoff::new(strike,payout),smoothing),
+            BinaryKind::AssetOrNothingCall=>smoothed(AssetOrNothingCallPayoff::new(strike),smoothing),
+            BinaryKind::AssetOrNothingPut=>smoothed(AssetOrNothingPutPayoff::new(strike),smoothing),
+            BinaryKind::GapCall{trigger}=>smoothed(GapCallPayoff::new(strike,trigger),smoothing),
+            BinaryKind::GapPut{trigger}=>smoothed(GapPutPayoff::new(strike,trigger),smoothing),
+        }
+    }
+}
+
+fn smoothed<P:DigitalPayoff+'static>(payoff:P,width:f64)->Arc<dyn Payoff>{
+    if width>0.0{
+        Arc::new(payoff.with_smoothing(width))
+    }else{
+        Arc::new(payoff)
+    }
+}
+
+/// European binary option 欧式二元期权
+#[derive(Clone)]
+pub struct BinaryOption{
+    common:CommonParams,
+    strike:f64,
+    kind:BinaryKind,
+    payoff:Arc<dyn Payoff>,
+    exercise_type:Arc<dyn ExerciseRule>,
+    boundary_condition:Arc<dyn BoundaryCondition>,
+}
+
+impl BinaryOption{
+    pub fn new(
+        spot:f64,
+        strike:f64,
+        risk_free_rate:f64,
+        volatility:f64,
+        dividend_yield:f64,
+        time_to_maturity:f64,
+        kind:BinaryKind,
+    )->Result<Self>{
+        let common=CommonParams::new(spot,risk_free_rate,volatility,dividend_yield,time_to_maturity)?;
+        validate_common_params(&common)?;
+        if strike<=0.0{
+            return Err(OptionError::InvalidParameter("Strike must be greater than zero.".to_string()));
+        }
+        match kind{
+            BinaryKind::CashOrNothingCall{payout} | BinaryKind::CashOrNothingPut{payout} if payout<0.0=>{
+                return Err(OptionError::InvalidParameter("The payout of binary option cannot be negative".to_string()));
+            },
+            BinaryKind::GapCall{trigger} | BinaryKind::GapPut{trigger} if trigger<=0.0=>{
+                return Err(OptionError::InvalidParameter("Trigger must be greater than zero.".to_string()));
+            },
+            _=>{},
+        }
+        Ok(Self{
+            common,
+            strike,
+            kind,
+            payoff:kind.payoff(strike,0.0),
+            exercise_type:Arc::new(EuropeanExercise),
+            boundary_condition:Arc::new(BinaryBoundaryCondition::new(kind,strike,risk_free_rate,dividend_yield)),
+        })
+    }
+
+    pub fn cash_or_nothing_call(spot:f64,strike:f64,payout:f64,risk_free_rate:f64,volatility:f64,
+                                dividend_yield:f64,time_to_maturity:f64)->Result<Self>{
+        Self::new(spot,strike,risk_free_rate,volatility,dividend_yield,time_to_maturity,BinaryKind::CashOrNothingCall{payout})
+    }
+
+    pub fn cash_or_nothing_put(spot:f64,strike:f64,payout:f64,risk_free_rate:f64,volatility:f64,
+                               dividend_yield:f64,time_to_maturity:f64)->Result<Self>{
+        Self::new(spot,strike,risk_free_rate,volatility,dividend_yield,time_to_maturity,BinaryKind::CashOrNothingPut{payout})
+    }
+
+    pub fn asset_or_nothing_call(spot:f64,strike:f64,risk_free_rate:f64,volatility:f64,
+                                 dividend_yield:f64,time_to_maturity:f64)->Result<Self>{
+        Self::new(spot,strike,risk_free_rate,volatility,dividend_yield,time_to_maturity,BinaryKind::AssetOrNothingCall)
+    }
+
+    pub fn asset_or_nothing_put(spot:f64,strike:f64,risk_free_rate:f64,volatility:f64,
+                                dividend_yield:f64,time_to_maturity:f64)->Result<Self>{
+        Self::new(spot,strike,risk_free_rate,volatility,dividend_yield,time_to_maturity,BinaryKind::AssetOrNothingPut)
+    }
+
+    /// Gap call paying S - strike when S ends above the trigger <br>
+    /// 缺口看涨期权：S高于触发价时支付S - strike
+    pub fn gap_call(spot:f64,strike:f64,trigger:f64,risk_free_rate:f64,volatility:f64,
+                    dividend_yield:f64,time_to_maturity:f64)->Result<Self>{
+        Self::new(spot,strike,risk_free_rate,volatility,dividend_yield,time_to_maturity,BinaryKind::GapCall{trigger})
+    }
+
+    /// Gap put paying strike - S when S ends below the trigger <br>
+    /// 缺口看跌期权：S低于触发价时支付strike - S
+    pub fn gap_put(spot:f64,strike:f64,trigger:f64,risk_free_rate:f64,volatility:f64,
+                   dividend_yield:f64,time_to_maturity:f64)->Result<Self>{
+        Self::new(spot,strike,risk_free_rate,volatility,dividend_yield,time_to_maturity,BinaryKind::GapPut{trigger})
+    }
+
+    /// Replace the jump of the payoff by a linear ramp of the given width
+    /// (binomial and Monte Carlo engines; the analytic engine is unaffected) <br>
+    /// 以给定宽度的线性斜坡代替收益的跳跃（作用于二叉树和蒙特卡洛引擎，不影响解析解）
+    pub fn with_smoothing(mut self,width:f64)->Result<Self>{
+        if width<0.0{
+            return Err(OptionError::InvalidParameter("Smoothing width cannot be negative".to_string()));
+        }
+        self.payoff=self.kind.payoff(self.strike,width);
+        Ok(self)
+    }
+
+    pub fn kind(&self)->BinaryKind{self.kind}
+    pub fn strike(&self)->f64{self.strike}
+}
+
+impl PricingTrait for BinaryOption{
+    fn common(&self) -> &CommonParams { &self.common }
+    fn payoff(&self)->&dyn Payoff{self.payoff.as_ref()}
+    fn exercise_type(&self)->&dyn ExerciseRule{self.exercise_type.as_ref()}
+    fn boundary_condition(&self)->&Arc<dyn BoundaryCondition>{&self.boundary_condition}
+}
+
+/// PDE boundary condition of European binary options <br>
+/// 欧式二元期权的PDE边界条件
+#[derive(Debug,Clone)]
+pub struct BinaryBoundaryCondition{
+    kind:BinaryKind,
+    strike:f64,
+    risk_free_rate:f64,
+    dividend_yield:f64,
+}
+
+impl BinaryBoundaryCondition{
+    pub fn new(kind:BinaryKind,strike:f64,risk_free_rate:f64,dividend_yield:f64)->Self{
+        Self{kind,strike,risk_free_rate,dividend_yield}
+    }
+
+    /// (discounted cash, discounted asset) at the grid edge 网格边缘处的现金与资产贴现值
+    fn discounted(&self,t:f64,spot:f64)->(f64,f64){
+        ((-self.risk_free_rate*t).exp(),spot*(-self.dividend_yield*t).exp())
+    }
+}
+
+impl BoundaryCondition for BinaryBoundaryCondition{
+    /// S→0：看涨类价值为0，看跌类必然支付
+    fn lower_boundary_at(&self,t:f64,spot:f64)->Result<f64>{
+        let (disc,asset)=self.discounted(t,spot);
+        Ok(match self.kind{
+            BinaryKind::CashOrNothingPut{payout}=>payout*disc,
+            BinaryKind::AssetOrNothingPut=>asset,
+            BinaryKind::GapPut{..}=>self.strike*disc-asset,
+            _=>0.0,
+        })
+    }
+
+    /// S→∞：看跌类价值为0，看涨类必然支付
+    fn upper_boundary_at(&self,t:f64,spot:f64)->Result<f64>{
+        let (disc,asset)=self.discounted(t,spot);
+        Ok(match self.kind{
+            BinaryKind::CashOrNothingCall{payout}=>payout*disc,
+            BinaryKind::AssetOrNothingCall=>asset,
+            BinaryKind::GapCall{..}=>asset-self.strike*disc,
+            _=>0.0,
+        })
+    }
+
+    fn final_condition(&self,spot:f64)->Result<f64>{
+        Ok(self.kind.payoff(self.strike,0.0).payoff(spot))
+    }
+
+    fn clone_box(&self) -> Box<dyn BoundaryCondition> {
+        Box::new(self.clone())
+    }
+}
+
+/// One-touch / no-touch option paid at expiry (American digital) <br>
+/// 到期支付的一触即付/无触碰期权（美式二元期权）
+#[derive(Clone)]
+pub struct TouchOption{
+    common:CommonParams,
+    barrier:f64,
+    payoff:Arc<dyn Payoff>,
+    exercise_type:Arc<dyn ExerciseRule>,
+    boundary_condition:Arc<dyn BoundaryCondition>,
+}
+
+impl TouchOption{
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        spot:f64,
+        barrier:f64,
+        payout:f64,
+        risk_free_rate:f64,
+        volatility:f64,
+        dividend_yield:f64,
+        time_to_maturity:f64,
+        one_touch:bool,
+    )->Result<Self>{
+        let common=CommonParams::new(spot,risk_free_rate,volatility,dividend_yield,time_to_maturity)?;
+        validate_common_params(&common)?;
+        if barrier<=0.0 || barrier==spot{
+            return Err(OptionError::InvalidParameter("Barrier must be positive and different from the spot".to_string()));
+        }
+        if payout<0.0{
+            return Err(OptionError::InvalidParameter("The payout of binary option cannot be negative".to_string()));
+        }
+        // 障碍方向由障碍与现价的相对位置决定；模拟路径默认按连续监测进行布朗桥修正
+        let is_up=barrier>spot;
+        let bridge=Some(BrownianBridge::new(volatility,time_to_maturity));
+        let payoff:Arc<dyn Payoff>=if one_touch{
+            Arc::new(OneTouchPayoff{barrier,payout,is_up,bridge})
+        }else{
+            Arc::new(NoTouchPayoff{barrier,payout,is_up,bridge})
+        };
+        Ok(Self{
+            common,
+            barrier,
+            payoff,
+            exercise_type:Arc::new(EuropeanExercise),
+            boundary_condition:Arc::new(NoBoundaryCondition),
+        })
+    }
+
+    /// Pays `payout` at expiry if the barrier is touched 触碰障碍则到期支付payout
+    pub fn one_touch(spot:f64,barrier:f64,payout:f64,risk_free_rate:f64,volatility:f64,
+                     dividend_yield:f64,time_to_maturity:f64)->Result<Self>{
+        Self::new(spot,barrier,payout,risk_free_rate,volatility,dividend_yield,time_to_maturity,true)
+    }
+
+    /// Pays `payout` at expiry if the barrier is never touched 从未触碰障碍则到期支付payout
+    pub fn no_touch(spot:f64,barrier:f64,payout:f64,risk_free_rate:f64,volatility:f64,
+                    dividend_yield:f64,time_to_maturity:f64)->Result<Self>{
+        Self::new(spot,barrier,payout,risk_free_rate,volatility,dividend_yield,time_to_maturity,false)
+    }
+
+    pub fn barrier(&self)->f64{self.barrier}
+}
+
+impl PricingTrait for TouchOption{
+    fn common(&self) -> &CommonParams { &self.common }
+    fn payoff(&self)->&dyn Payoff{self.payoff.as_ref()}
+    fn exercise_type(&self)->&dyn ExerciseRule{self.exercise_type.as_ref()}
+    fn boundary_condition(&self)->&Arc<dyn BoundaryCondition>{&self.boundary_condition}
+}
@@ -35,9 +35,10 @@ impl EuropeanCall{
             time_to_maturity,
         )?;
         validate_common_params(&common)?;
-        let payoff=CallPayoff{strike:strike};
+        let payoff=CallPayoff{strike};
         let european_exercise=EuropeanExercise;
-        let boundary_condition=CallBoundaryCondition::new(strike,risk_free_rate,volatility)?;
+        let boundary_condition=CallBoundaryCondition::new(strike,risk_free_rate,volatility)?
+            .with_dividend_yield(dividend_yield);
 
         Ok(Self{
             common,
@@ -59,6 +60,7 @@ impl EuropeanCall{
             CallPayoff::new(self.strike),
             EuropeanExercise::new(),
             CallBoundaryCondition::new(self.strike,self.common.risk_free_rate(),self.common.volatility())?
+                .with_dividend_yield(self.common.dividend_yield())
         ))
     }
 }
@@ -66,7 +68,7 @@ impl EuropeanCall{
 impl PricingTrait for EuropeanCall{
     fn common(&self) -> &CommonParams { &self.common }
     fn payoff(&self)->&dyn Payoff{&self.payoff}
-    fn exercise_type(&self)->&dyn ExerciseRule{(&self.exercise_type).as_ref()}
+    fn exercise_type(&self)->&dyn ExerciseRule{self.exercise_type.as_ref()}
     fn boundary_condition(&self)->&Arc<dyn BoundaryCondition>{&self.boundary_condition}
 }
 /// Boundary Condition config
@@ -75,6 +77,7 @@ pub struct CallBoundaryCondition{
     strike:f64,
     risk_free_rate:f64,
     volatility:f64,
+    dividend_yield:f64,
 }
 
 impl CallBoundaryCondition{
@@ -86,7 +89,14 @@ impl CallBoundaryCondition{
         if strike<0.0{
             return Err(OptionError::InvalidParameter("Strike cannot be negative".to_string()));
         }
-        Ok(Self{strike, risk_free_rate, volatility})
+        Ok(Self{strike, risk_free_rate, volatility, dividend_yield:0.0})
+    }
+
+    /// Dividend yield discounting the underlying at the upper edge of the grid <br>
+    /// 网格上边缘处用于折现标的资产的股息率
+    pub fn with_dividend_yield(mut self,dividend_yield:f64)->Self{
+        self.dividend_yield=dividend_yield;
+        self
     }
 }
 
@@ -109,6 +119,11 @@ impl BoundaryCondition for CallBoundaryCondition{
         Ok(s_max-self.strike*discount_factor)
     }
 
+    /// 网格上边缘S_max处：C ≈ S_max*e^(-qt) - K*e^(-rt)
+    fn upper_boundary_at(&self, t: f64, spot: f64) -> Result<f64> {
+        Ok(spot*(-self.dividend_yield*t).exp()-self.strike*(-self.risk_free_rate*t).exp())
+    }
+
     fn final_condition(&self, spot: f64) -> Result<f64> {
         Ok((spot-self.strike).max(0.0))
     }
@@ -0,0 +1,260 @@
+//! Exotic options: compound, chooser and forward start options <br>
+//! 其他奇异期权：复合期权、选择期权、远期生效期权
+//!
+//! All maturities are measured from the valuation date. The time to maturity of the
+//! common parameters is the first decision date (outer expiry, choice date or start date),
+//! at which the payoff is the Black-Scholes value of the delivered option under the market
+//! data of the common parameters, so these products can also be priced by the binomial and
+//! Monte Carlo engines. <br>
+//! 所有期限均从估值日起算。通用参数中的到期时间为第一个决策日（外层到期日、选择日或生效日），
+//! 该日的Payoff为所交付期权在通用参数市场数据下的BS价值，因此也可用二叉树和蒙特卡洛引擎定价
+use std::sync::Arc;
+use crate::params::common::CommonParams;
+use crate::errors::*;
+use crate::traits::payoff::{
+    Payoff,
+    CompoundOptionPayoff, SimpleChooserPayoff, ComplexChooserPayoff, ForwardStartPayoff,
+};
+use crate::traits::exercise::{EuropeanExercise,ExerciseRule};
+use crate::traits::engine::{BoundaryCondition,NoBoundaryCondition,PricingTrait};
+
+fn validate_positive(value:f64,name:&str)->Result<()>{
+    if value<=0.0{
+        return Err(OptionError::InvalidParameter(format!("{} must be greater than zero.",name)));
+    }
+    Ok(())
+}
+
+/// Check that the later maturity does not come before the decision date
+fn validate_later(decision_time:f64,maturity:f64)->Result<()>{
+    if maturity<decision_time{
+        return Err(OptionError::InvalidParameter("The delivered option cannot expire \
+        before the decision date".to_string()));
+    }
+    Ok(())
+}
+
+/// Compound option: an option on a vanilla option <br>
+/// 复合期权：以普通期权为标的的期权
+#[derive(Clone)]
+pub struct CompoundOption{
+    common:CommonParams,
+    payoff:Arc<dyn Payoff>,
+    exercise_type:Arc<dyn ExerciseRule>,
+    boundary_condition:Arc<dyn BoundaryCondition>,
+}
+
+impl CompoundOption{
+    /// ### parameter
+    /// - outer_strike: price paid for the inner option at the outer expiry 外层执行价
+    /// - inner_strike: strike of the inner option 内层执行价
+    /// - outer_maturity: T₁ 外层到期时间
+    /// - inner_maturity: T₂ > T₁ 内层到期时间
+    /// - outer_is_call / inner_is_call: 外层/内层是否为看涨
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        spot:f64,
+        outer_strike:f64,
+        inner_strike:f64,
+        risk_free_rate:f64,
+        volatility:f64,
+        dividend_yield:f64,
+        outer_maturity:f64,
+        inner_maturity:f64,
+        outer_is_call:bool,
+        inner_is_call:bool,
+    )->Result<Self>{
+        let common=CommonParams::new(spot,risk_free_rate,volatility,dividend_yield,outer_maturity)?;
+        validate_positive(outer_strike,"Outer strike")?;
+        validate_positive(inner_strike,"Inner strike")?;
+        if inner_maturity<=outer_maturity{
+            return Err(OptionError::InvalidParameter("Inner option must expire after the outer option".to_string()));
+        }
+        Ok(Self{
+            common,
+            payoff:Arc::new(CompoundOptionPayoff{
+                outer_is_call,
+                inner_is_call,
+                outer_strike,
+                inner_strike,
+                inner_time:inner_maturity-outer_maturity,
+            }),
+            exercise_type:Arc::new(EuropeanExercise),
+            boundary_condition:Arc::new(NoBoundaryCondition),
+        })
+    }
+
+    /// Call on a call 看涨期权的看涨期权
+    #[allow(clippy::too_many_arguments)]
+    pub fn call_on_call(spot:f64,outer_strike:f64,inner_strike:f64,risk_free_rate:f64,volatility:f64,
+                        dividend_yield:f64,outer_maturity:f64,inner_maturity:f64)->Result<Self>{
+        Self::new(spot,outer_strike,inner_strike,risk_free_rate,volatility,dividend_yield,outer_maturity,inner_maturity,true,true)
+    }
+
+    /// Call on a put 看跌期权的看涨期权
+    #[allow(clippy::too_many_arguments)]
+    pub fn call_on_put(spot:f64,outer_strike:f64,inner_strike:f64,risk_free_rate:f64,volatility:f64,
+                       dividend_yield:f64,outer_maturity:f64,inner_maturity:f64)->Result<Self>{
+        Self::new(spot,outer_strike,inner_strike,risk_free_rate,volatility,dividend_yield,outer_maturity,inner_maturity,true,false)
+    }
+
+    /// Put on a call 看涨期权的看跌期权
+    #[allow(clippy::too_many_arguments)]
+    pub fn put_on_call(spot:f64,outer_strike:f64,inner_strike:f64,risk_free_rate:f64,volatility:f64,
+                       dividend_yield:f64,outer_maturity:f64,inner_maturity:f64)->Result<Self>{
+        Self::new(spot,outer_strike,inner_strike,risk_free_rate,volatility,dividend_yield,outer_maturity,inner_maturity,false,true)
+    }
+
+    /// Put on a put 看跌期权的看跌期权
+    #[allow(clippy::too_many_arguments)]
+    pub fn put_on_put(spot:f64,outer_strike:f64,inner_strike:f64,risk_free_rate:f64,volatility:f64,
+                      dividend_yield:f64,outer_maturity:f64,inner_maturity:f64)->Result<Self>{
+        Self::new(spot,outer_strike,inner_strike,risk_free_rate,volatility,dividend_yield,outer_maturity,inner_maturity,false,false)
+    }
+}
+
+impl PricingTrait for CompoundOption{
+    fn common(&self) -> &CommonParams { &self.common }
+    fn payoff(&self)->&dyn Payoff{self.payoff.as_ref()}
+    fn exercise_type(&self)->&dyn ExerciseRule{self.exercise_type.as_ref()}
+    fn boundary_condition(&self)->&Arc<dyn BoundaryCondition>{&self.boundary_condition}
+}
+
+/// Chooser option: at the choice date the holder decides whether the option is a call or a put <br>
+/// 选择期权：持有人在选择日决定期权为看涨或看跌
+#[derive(Clone)]
+pub struct ChooserOption{
+    common:CommonParams,
+    payoff:Arc<dyn Payoff>,
+    exercise_type:Arc<dyn ExerciseRule>,
+    boundary_condition:Arc<dyn BoundaryCondition>,
+}
+
+impl ChooserOption{
+    /// Simple chooser: call and put share the strike and the maturity <br>
+    /// 简单选择期权：看涨与看跌期权执行价和到期日相同
+    pub fn simple(
+        spot:f64,
+        strike:f64,
+        risk_free_rate:f64,
+        volatility:f64,
+        dividend_yield:f64,
+        choice_time:f64,
+        maturity:f64,
+    )->Result<Self>{
+        let common=CommonParams::new(spot,risk_free_rate,volatility,dividend_yield,choice_time)?;
+        validate_positive(strike,"Strike")?;
+        validate_later(choice_time,maturity)?;
+        Ok(Self{
+            common,
+            payoff:Arc::new(SimpleChooserPayoff{
+                strike,
+                remaining_time:maturity-choice_time,
+            }),
+            exercise_type:Arc::new(EuropeanExercise),
+            boundary_condition:Arc::new(NoBoundaryCondition),
+        })
+    }
+
+    /// Complex chooser: call and put may have different strikes and maturities <br>
+    /// 复杂选择期权：看涨与看跌期权执行价和到期日可以不同
+    #[allow(clippy::too_many_arguments)]
+    pub fn complex(
+        spot:f64,
+        call_strike:f64,
+        put_strike:f64,
+        risk_free_rate:f64,
+        volatility:f64,
+        dividend_yield:f64,
+        choice_time:f64,
+        call_maturity:f64,
+        put_maturity:f64,
+    )->Result<Self>{
+        let common=CommonParams::new(spot,risk_free_rate,volatility,dividend_yield,choice_time)?;
+        validate_positive(call_strike,"Call strike")?;
+        validate_positive(put_strike,"Put strike")?;
+        if call_maturity<=choice_time || put_maturity<=choice_time{
+            return Err(OptionError::InvalidParameter("Options of a complex chooser must \
+            expire after the choice date".to_string()));
+        }
+        Ok(Self{
+            common,
+            payoff:Arc::new(ComplexChooserPayoff{
+                call_strike,
+                put_strike,
+                call_remaining_time:call_maturity-choice_time,
+                put_remaining_time:put_maturity-choice_time,
+            }),
+            exercise_type:Arc::new(EuropeanExercise),
+            boundary_condition:Arc::new(NoBoundaryCondition),
+        })
+    }
+}
+
+impl PricingTrait for ChooserOption{
+    fn common(&self) -> &CommonParams { &self.common }
+    fn payoff(&self)->&dyn Payoff{self.payoff.as_ref()}
+    fn exercise_type(&self)->&dyn ExerciseRule{self.exercise_type.as_ref()}
+    fn boundary_condition(&self)->&Arc<dyn BoundaryCondition>{&self.boundary_condition}
+}
+
+/// Forward start option: starts at t₁ with strike α·S(t₁) and expires at T <br>
+/// 远期生效期权：t₁生效，执行价为α·S(t₁)，T到期
+#[derive(Clone)]
+pub struct ForwardStartOption{
+    common:CommonParams,
+    payoff:Arc<dyn Payoff>,
+    exercise_type:Arc<dyn ExerciseRule>,
+    boundary_condition:Arc<dyn BoundaryCondition>,
+}
+
+impl ForwardStartOption{
+    /// ### parameter
+    /// - strike_ratio: α, e.g. 1.0 for at-the-money 执行价相对生效日价格的比例
+    /// - start_time: t₁ 生效时间
+    /// - maturity: T 到期时间
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        spot:f64,
+        strike_ratio:f64,
+        risk_free_rate:f64,
+        volatility:f64,
+        dividend_yield:f64,
+        start_time:f64,
+        maturity:f64,
+        is_call:bool,
+    )->Result<Self>{
+        let common=CommonParams::new(spot,risk_free_rate,volatility,dividend_yield,start_time)?;
+        validate_positive(strike_ratio,"Strike ratio")?;
+        validate_later(start_time,maturity)?;
+        Ok(Self{
+            common,
+            payoff:Arc::new(ForwardStartPayoff{
+                is_call,
+                strike_ratio,
+                remaining_time:maturity-start_time,
+            }),
+            exercise_type:Arc::new(EuropeanExercise),
+            boundary_condition:Arc::new(NoBoundaryCondition),
+        })
+    }
+
+    /// Forward start call 远期生效看涨期权
+    pub fn call(spot:f64,strike_ratio:f64,risk_free_rate:f64,volatility:f64,
+                dividend_yield:f64,start_time:f64,maturity:f64)->Result<Self>{
+        Self::new(spot,strike_ratio,risk_free_rate,volatility,dividend_yield,start_time,maturity,true)
+    }
+
+    /// Forward start put 远期生效看跌期权
+    pub fn put(spot:f64,strike_ratio:f64,risk_free_rate:f64,volatility:f64,
+               dividend_yield:f64,start_time:f64,maturity:f64)->Result<Self>{
+        Self::new(spot,strike_ratio,risk_free_rate,volatility,dividend_yield,start_time,maturity,false)
+    }
+}
+
+impl PricingTrait for ForwardStartOption{
+    fn common(&self) -> &CommonParams { &self.common }
+    fn payoff(&self)->&dyn Payoff{self.payoff.as_ref()}
+    fn exercise_type(&self)->&dyn ExerciseRule{self.exercise_type.as_ref()}
+    fn boundary_condition(&self)->&Arc<dyn BoundaryCondition>{&self.boundary_condition}
+}
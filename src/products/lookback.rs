@@ -0,0 +1,176 @@
+//! Lookback options 回望期权
+//!
+//! Priced by the analytic engine (continuous monitoring) and the Monte Carlo engine
+//! (discrete monitoring on the simulated path). Seasoned contracts carry the running
+//! minimum/maximum observed before the valuation date. <br>
+//! 可由解析解引擎（连续监测）和蒙特卡洛引擎（按模拟路径离散监测）定价，
+//! 存续期中的合约需传入估值日前已观测到的最低/最高价
+use std::sync::Arc;
+use crate::params::common::CommonParams;
+use crate::errors::*;
+use crate::traits::payoff::{
+    Payoff,
+    FloatingStrikeLookbackCallPayoff, FloatingStrikeLookbackPutPayoff,
+    FixedStrikeLookbackCallPayoff, FixedStrikeLookbackPutPayoff,
+};
+use crate::traits::exercise::{EuropeanExercise,ExerciseRule};
+use crate::traits::engine::{BoundaryCondition,NoBoundaryCondition,PricingTrait};
+
+/// Check that the running minimum does not exceed the current spot
+fn validate_observed_min(spot:f64,observed_min:Option<f64>)->Result<()>{
+    if let Some(m)=observed_min && (m<=0.0 || m>spot){
+        return Err(OptionError::InvalidParameter("Observed minimum must be positive \
+        and not greater than the current spot".to_string()));
+    }
+    Ok(())
+}
+
+/// Check that the running maximum is not below the current spot
+fn validate_observed_max(spot:f64,observed_max:Option<f64>)->Result<()>{
+    if let Some(m)=observed_max && m<spot{
+        return Err(OptionError::InvalidParameter("Observed maximum cannot be \
+        less than the current spot".to_string()));
+    }
+    Ok(())
+}
+
+/// Floating strike lookback option <br>
+/// 浮动执行价回望期权
+#[derive(Clone)]
+pub struct FloatingStrikeLookback{
+    common:CommonParams,
+    payoff:Arc<dyn Payoff>,
+    exercise_type:Arc<dyn ExerciseRule>,
+    boundary_condition:Arc<dyn BoundaryCondition>,
+}
+
+impl FloatingStrikeLookback{
+    /// Floating strike lookback call paying S_T - min(S_t) <br>
+    /// 浮动执行价回望看涨期权
+    /// ### parameter
+    /// - observed_min: minimum observed so far (None for a new contract) 已观测最低价（新合约为None）
+    pub fn call(
+        spot:f64,
+        risk_free_rate:f64,
+        volatility:f64,
+        dividend_yield:f64,
+        time_to_maturity:f64,
+        observed_min:Option<f64>,
+    )->Result<Self>{
+        let common=CommonParams::new(spot,risk_free_rate,volatility,dividend_yield,time_to_maturity)?;
+        validate_observed_min(spot,observed_min)?;
+        Ok(Self{
+            common,
+            payoff:Arc::new(FloatingStrikeLookbackCallPayoff::new(observed_min)),
+            exercise_type:Arc::new(EuropeanExercise),
+            boundary_condition:Arc::new(NoBoundaryCondition),
+        })
+    }
+
+    /// Floating strike lookback put paying max(S_t) - S_T <br>
+    /// 浮动执行价回望看跌期权
+    /// ### parameter
+    /// - observed_max: maximum observed so far (None for a new contract) 已观测最高价（新合约为None）
+    pub fn put(
+        spot:f64,
+        risk_free_rate:f64,
+        volatility:f64,
+        dividend_yield:f64,
+        time_to_maturity:f64,
+        observed_max:Option<f64>,
+    )->Result<Self>{
+        let common=CommonParams::new(spot,risk_free_rate,volatility,dividend_yield,time_to_maturity)?;
+        validate_observed_max(spot,observed_max)?;
+        Ok(Self{
+            common,
+            payoff:Arc::new(FloatingStrikeLookbackPutPayoff::new(observed_max)),
+            exercise_type:Arc::new(EuropeanExercise),
+            boundary_condition:Arc::new(NoBoundaryCondition),
+        })
+    }
+}
+
+impl PricingTrait for FloatingStrikeLookback{
+    fn common(&self) -> &CommonParams { &self.common }
+    fn payoff(&self)->&dyn Payoff{self.payoff.as_ref()}
+    fn exercise_type(&self)->&dyn ExerciseRule{self.exercise_type.as_ref()}
+    fn boundary_condition(&self)->&Arc<dyn BoundaryCondition>{&self.boundary_condition}
+}
+
+/// Fixed strike lookback option <br>
+/// 固定执行价回望期权
+#[derive(Clone)]
+pub struct FixedStrikeLookback{
+    common:CommonParams,
+    strike:f64,
+    payoff:Arc<dyn Payoff>,
+    exercise_type:Arc<dyn ExerciseRule>,
+    boundary_condition:Arc<dyn BoundaryCondition>,
+}
+
+impl FixedStrikeLookback{
+    /// Fixed strike lookback call paying max(max(S_t) - K, 0) <br>
+    /// 固定执行价回望看涨期权
+    /// ### parameter
+    /// - observed_max: maximum observed so far (None for a new contract) 已观测最高价（新合约为None）
+    pub fn call(
+        spot:f64,
+        strike:f64,
+        risk_free_rate:f64,
+        volatility:f64,
+        dividend_yield:f64,
+        time_to_maturity:f64,
+        observed_max:Option<f64>,
+    )->Result<Self>{
+        let common=CommonParams::new(spot,risk_free_rate,volatility,dividend_yield,time_to_maturity)?;
+        if strike<=0.0{
+            return Err(OptionError::InvalidParameter("Strike must be greater than zero".to_string()));
+        }
+        validate_observed_max(spot,observed_max)?;
+        Ok(Self{
+            common,
+            strike,
+            payoff:Arc::new(FixedStrikeLookbackCallPayoff::new(strike,observed_max)),
+            exercise_type:Arc::new(EuropeanExercise),
+            boundary_condition:Arc::new(NoBoundaryCondition),
+        })
+    }
+
+    /// Fixed strike lookback put paying max(K - min(S_t), 0) <br>
+    /// 固定执行价回望看跌期权
+    /// ### parameter
+    /// - observed_min: minimum observed so far (None for a new contract) 已观测最低价（新合约为None）
+    pub fn put(
+        spot:f64,
+        strike:f64,
+        risk_free_rate:f64,
+        volatility:f64,
+        dividend_yield:f64,
+        time_to_maturity:f64,
+        observed_min:Option<f64>,
+    )->Result<Self>{
+        let common=CommonParams::new(spot,risk_free_rate,volatility,dividend_yield,time_to_maturity)?;
+        if strike<=0.0{
+            return Err(OptionError::InvalidParameter("Strike must be greater than zero".to_string()));
+        }
+        validate_observed_min(spot,observed_min)?;
+        Ok(Self{
+            common,
+            strike,
+            payoff:Arc::new(FixedStrikeLookbackPutPayoff::new(strike,observed_min)),
+            exercise_type:Arc::new(EuropeanExercise),
+            boundary_condition:Arc::new(NoBoundaryCondition),
+        })
+    }
+
+    pub fn strike(&self)->f64{
+        self.strike
+    }
+}
+
+impl PricingTrait for FixedStrikeLookback{
+    fn common(&self) -> &CommonParams { &self.common }
+    fn payoff(&self)->&dyn Payoff{self.payoff.as_ref()}
+    fn exercise_type(&self)->&dyn ExerciseRule{self.exercise_type.as_ref()}
+    fn boundary_condition(&self)->&Arc<dyn BoundaryCondition>{&self.boundary_condition}
+}
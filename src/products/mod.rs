@@ -1,6 +1,8 @@
 pub mod european_call;
 pub mod american;
+pub mod bermudan;
 mod barrier;
-mod lookback;
-mod spread;
-mod exotic;
\ No newline at end of file
+pub mod lookback;
+pub mod spread;
+pub mod binary;
+pub mod exotic;
\ No newline at end of file
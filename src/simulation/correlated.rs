@@ -0,0 +1,143 @@
+//! Correlated geometric brownian motions for multi-asset options
+//! 多资产期权使用的相关几何布朗运动
+//! - ε = L·z，其中 LLᵀ = ρ 为相关系数矩阵的Cholesky分解（两资产时ε₂ = ρ·z₁ + √(1-ρ²)·z₂）
+
+use rand::{Rng, SeedableRng,rngs::StdRng};
+use rand_distr::StandardNormal;
+use crate::params::multi_asset::{MultiAssetParams, validate_correlation_matrix};
+use crate::traits::process::{MultiAssetProcess, AssetPaths};
+use crate::utils::linear_algebra::cholesky_decomposition;
+use crate::errors::*;
+
+/// n correlated geometric brownian motions <br>
+/// n个相关的几何布朗运动
+/// dSᵢ = μᵢSᵢdt + σᵢSᵢdWᵢ, dWᵢdWⱼ = ρᵢⱼdt
+#[derive(Debug,Clone)]
+pub struct CorrelatedGeometricBrownianMotion{
+    drifts:Vec<f64>,
+    volatilities:Vec<f64>,
+    /// Cholesky因子L，LLᵀ=ρ
+    cholesky:Vec<Vec<f64>>,
+    rng:StdRng,
+}
+
+impl CorrelatedGeometricBrownianMotion{
+    pub fn new(
+        drifts:Vec<f64>,
+        volatilities:Vec<f64>,
+        correlation:&[Vec<f64>],
+    )->Result<Self>{
+        let n=drifts.len();
+        if n==0{
+            return Err(OptionError::EmptyData);
+        }
+        if volatilities.len()!=n || correlation.len()!=n{
+            return Err(OptionError::InvalidParameter("Drifts, volatilities and correlation matrix \
+            must have the same dimension".to_string()));
+        }
+        if volatilities.iter().any(|&v| v<0.0){
+            return Err(OptionError::InvalidParameter("Volatility must be 0 or positive".to_string()));
+        }
+        validate_correlation_matrix(correlation)?;
+        let cholesky=cholesky_decomposition(correlation)?;
+        Ok(Self{
+            drifts,
+            volatilities,
+            cholesky,
+            rng:StdRng::from_os_rng(),
+        })
+    }
+
+    /// Risk neutral dynamics from the multi-asset market parameters <br>
+    /// 由多资产市场参数生成风险中性过程（漂移为r-qᵢ）
+    pub fn from_params(params:&MultiAssetParams)->Result<Self>{
+        let r=params.risk_free_rate();
+        Self::new(
+            params.dividend_yields().iter().map(|q| r-q).collect(),
+            params.volatilities().to_vec(),
+            params.correlation(),
+        )
+    }
+
+    fn simulate(
+        &mut self,
+        initial_prices:&[f64],
+        time_horizon:f64,
+        steps:usize,
+        antithetic:bool,
+    )->Result<(AssetPaths,Option<AssetPaths>)>{
+        let n=self.drifts.len();
+        if initial_prices.len()!=n{
+            return Err(OptionError::InvalidParameter("Number of initial prices must match the number of assets".to_string()));
+        }
+        if initial_prices.iter().any(|&s| s<=0.0){
+            return Err(OptionError::InvalidParameter("Initial price must be positive".to_string()));
+        }
+        if time_horizon<0.0{
+            return Err(OptionError::InvalidParameter("Time horizon must be 0 or positive".to_string()));
+        }
+        if steps==0{
+            return Err(OptionError::InvalidParameter("Steps must be positive".to_string()));
+        }
+        let dt=time_horizon/steps as f64;
+        let sqrt_dt=dt.sqrt();
+        let drift_terms:Vec<f64>=self.drifts.iter().zip(&self.volatilities)
+            .map(|(mu,sigma)| (mu-0.5*sigma*sigma)*dt)
+            .collect();
+
+        let new_paths=||->AssetPaths{
+            initial_prices.iter().map(|&s|{
+                let mut path=Vec::with_capacity(steps+1);
+                path.push(s);
+                path
+            }).collect()
+        };
+        let mut paths=new_paths();
+        let mut anti=if antithetic{Some(new_paths())}else{None};
+        let mut log_s:Vec<f64>=initial_prices.iter().map(|s| s.ln()).collect();
+        let mut anti_log_s=log_s.clone();
+        let mut z=vec![0.0;n];
+
+        for _ in 1..=steps{
+            for zi in z.iter_mut(){
+                *zi=self.rng.sample(StandardNormal);
+            }
+            for i in 0..n{
+                // εᵢ = Σⱼ Lᵢⱼ zⱼ（L为下三角）
+                let epsilon:f64=(0..=i).map(|j| self.cholesky[i][j]*z[j]).sum();
+                let shock=self.volatilities[i]*sqrt_dt*epsilon;
+                log_s[i]+=drift_terms[i]+shock;
+                paths[i].push(log_s[i].exp());
+                if let Some(anti)=anti.as_mut(){
+                    anti_log_s[i]+=drift_terms[i]-shock;
+                    anti[i].push(anti_log_s[i].exp());
+                }
+            }
+        }
+        Ok((paths,anti))
+    }
+}
+
+impl MultiAssetProcess for CorrelatedGeometricBrownianMotion{
+    fn clone_box(&self) -> Box<dyn MultiAssetProcess> {
+        Box::new(self.clone())
+    }
+
+    fn init_rng_with_seed(&mut self, seed: u64) {
+        self.rng=StdRng::seed_from_u64(seed);
+    }
+
+    fn num_assets(&self) -> usize {
+        self.drifts.len()
+    }
+
+    fn simulate_paths(&mut self, initial_prices: &[f64], time_horizon: f64, steps: usize) -> Result<AssetPaths> {
+        let (paths,_)=self.simulate(initial_prices,time_horizon,steps,false)?;
+        Ok(paths)
+    }
+
+    fn simulate_antithetic_paths(&mut self, initial_prices: &[f64], time_horizon: f64, steps: usize) -> Result<(AssetPaths, AssetPaths)> {
+        let (paths,anti)=self.simulate(initial_prices,time_horizon,steps,true)?;
+        Ok((paths,anti.unwrap_or_default()))
+    }
+}
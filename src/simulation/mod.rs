@@ -1,3 +1,4 @@
 pub mod brownian;
 pub mod time_series;
-pub mod stochastic_volatility;
\ No newline at end of file
+pub mod stochastic_volatility;
+pub mod correlated;
\ No newline at end of file
@@ -0,0 +1,253 @@
+//! 随机波动率模型
+//! - SABR：dF = α·F^β·dW₁，dα = ν·α·dW₂，d⟨W₁,W₂⟩ = ρ·dt（远期测度下无漂移）
+//! - Heston：dS = (r-q)·S·dt + √v·S·dW₁，dv = κ(θ-v)·dt + ξ·√v·dW₂，d⟨W₁,W₂⟩ = ρ·dt（风险中性测度）
+
+use rand::{Rng,SeedableRng,rngs::StdRng};
+use rand_distr::StandardNormal;
+use crate::params::heston::HestonModelParams;
+use crate::params::sabr::SabrParams;
+use crate::traits::process::StochasticProcess;
+use crate::errors::*;
+
+/// SABR process of a forward price. The simulated "price" is the forward itself, so the
+/// `CommonParams` spot passed to `MonteCarloEngine` must be the forward of the expiry and the
+/// engine discounts at the risk free rate (Black-76 convention) <br>
+/// 远期价格的SABR过程。模拟的"价格"即远期本身，因此传给`MonteCarloEngine`的`CommonParams`
+/// 现价应为对应到期日的远期，引擎按无风险利率贴现（Black-76约定）
+///
+/// The volatility is simulated exactly (lognormal), the forward by an Euler step absorbed
+/// at zero when β > 0 <br>
+/// 波动率精确模拟（对数正态），远期采用Euler格式，β > 0时在0处吸收
+#[derive(Debug,Clone)]
+pub struct SabrProcess{
+    params:SabrParams,
+    volatility:f64,     // 当前波动率状态α_t
+    rng:StdRng,
+}
+
+impl SabrProcess{
+    pub fn new(params:SabrParams)->Self{
+        Self{
+            params,
+            volatility:params.alpha(),
+            rng:StdRng::from_os_rng(),
+        }
+    }
+
+    // Getter method
+    pub fn params(&self)->&SabrParams{&self.params}
+    pub fn volatility(&self)->f64{self.volatility}
+
+    /// Reset the volatility state to α 将波动率状态重置为α
+    pub fn reset(&mut self){
+        self.volatility=self.params.alpha();
+    }
+
+    // 给定两个独立标准正态数的单步演化，返回新的远期与波动率
+    fn evolve(&self,forward:f64,volatility:f64,dt:f64,e1:f64,e2:f64)->(f64,f64){
+        let (beta,rho,nu)=(self.params.beta(),self.params.rho(),self.params.nu());
+        let sqrt_dt=dt.sqrt();
+        let w1=e1;
+        let w2=rho*e1+(1.0-rho*rho).sqrt()*e2;
+        let next_forward=if beta==0.0{
+            forward+volatility*sqrt_dt*w1
+        }else if forward<=0.0{
+            0.0
+        }else{
+            (forward+volatility*forward.powf(beta)*sqrt_dt*w1).max(0.0)
+        };
+        let next_volatility=volatility*(nu*sqrt_dt*w2-0.5*nu*nu*dt).exp();
+        (next_forward,next_volatility)
+    }
+
+    fn check_path_inputs(&self,initial_price:f64,time_horizon:f64,steps:usize)->Result<()>{
+        if self.params.beta()>0.0 && initial_price<=0.0{
+            return Err(OptionError::InvalidParameter("Initial forward must be positive when β > 0".to_string()));
+        }
+        if time_horizon<0.0{
+            return Err(OptionError::InvalidParameter("Time horizon must be 0 or positive".to_string()));
+        }
+        if steps==0{
+            return Err(OptionError::InvalidParameter("Steps must be positive".to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl StochasticProcess for SabrProcess{
+    fn clone_box(&self)->Box<dyn StochasticProcess>{
+        Box::new(self.clone())
+    }
+
+    fn init_rng_with_seed(&mut self,seed:u64){
+        self.rng=StdRng::seed_from_u64(seed);
+    }
+
+    /// Advance the forward and the internal volatility state by one step <br>
+    /// 远期与内部波动率状态前进一步
+    fn next_step(&mut self,current_price:f64,time_step:f64)->Result<f64>{
+        if time_step<0.0{
+            return Err(OptionError::InvalidParameter("Time step must be non-negative".into()));
+        }
+        let e1:f64=self.rng.sample(StandardNormal);
+        let e2:f64=self.rng.sample(StandardNormal);
+        let (forward,volatility)=self.evolve(current_price,self.volatility,time_step,e1,e2);
+        self.volatility=volatility;
+        Ok(forward)
+    }
+
+    fn simulate_path(&mut self,initial_price:f64,time_horizon:f64,steps:usize)->Result<Vec<f64>>{
+        self.check_path_inputs(initial_price,time_horizon,steps)?;
+        self.reset();
+        let dt=time_horizon/steps as f64;
+        let mut path=Vec::with_capacity(steps+1);
+        path.push(initial_price);
+        let mut forward=initial_price;
+        for _ in 0..steps{
+            forward=self.next_step(forward,dt)?;
+            path.push(forward);
+        }
+        Ok(path)
+    }
+
+    fn simulate_antithetic_path(&mut self,initial_price:f64,time_horizon:f64,steps:usize)->Result<(Vec<f64>,Vec<f64>)>{
+        self.check_path_inputs(initial_price,time_horizon,steps)?;
+        let dt=time_horizon/steps as f64;
+        let mut path1=Vec::with_capacity(steps+1);
+        let mut path2=Vec::with_capacity(steps+1);
+        path1.push(initial_price);
+        path2.push(initial_price);
+        let (mut f1,mut f2)=(initial_price,initial_price);
+        let (mut v1,mut v2)=(self.params.alpha(),self.params.alpha());
+        for _ in 0..steps{
+            let e1:f64=self.rng.sample(StandardNormal);
+            let e2:f64=self.rng.sample(StandardNormal);
+            (f1,v1)=self.evolve(f1,v1,dt,e1,e2);
+            (f2,v2)=self.evolve(f2,v2,dt,-e1,-e2);
+            path1.push(f1);
+            path2.push(f2);
+        }
+        Ok((path1,path2))
+    }
+}
+
+/// Risk neutral Heston process of the spot price, built from the model parameters (v₀, κ, θ, ξ, ρ)
+/// and the drift rates; the path starts at the given price <br>
+/// 现价的风险中性Heston过程，由模型参数(v₀, κ, θ, ξ, ρ)与漂移利率构造，路径从给定价格出发
+///
+/// The variance follows a full truncation Euler scheme (Lord et al., 2010), the spot a log
+/// Euler step using the truncated variance <br>
+/// 方差采用完全截断Euler格式（Lord等, 2010），现价以截断后的方差做对数Euler步进
+#[derive(Debug,Clone)]
+pub struct HestonProcess{
+    model:HestonModelParams,
+    risk_free_rate:f64,
+    dividend_yield:f64,
+    variance:f64,       // 当前方差状态v_t（可能为负，使用时截断为0）
+    rng:StdRng,
+}
+
+impl HestonProcess{
+    pub fn new(model:HestonModelParams,risk_free_rate:f64,dividend_yield:f64)->Self{
+        Self{
+            model,
+            risk_free_rate,
+            dividend_yield,
+            variance:model.variance(),
+            rng:StdRng::from_os_rng(),
+        }
+    }
+
+    // Getter method
+    pub fn model(&self)->&HestonModelParams{&self.model}
+    pub fn risk_free_rate(&self)->f64{self.risk_free_rate}
+    pub fn dividend_yield(&self)->f64{self.dividend_yield}
+    pub fn variance(&self)->f64{self.variance}
+
+    /// Reset the variance state to v₀ 将方差状态重置为v₀
+    pub fn reset(&mut self){
+        self.variance=self.model.variance();
+    }
+
+    // 给定两个独立标准正态数的单步演化，返回新的现价与方差
+    fn evolve(&self,spot:f64,variance:f64,dt:f64,e1:f64,e2:f64)->(f64,f64){
+        let m=&self.model;
+        let (kappa,theta,xi,rho)=(m.mean_reversion(),m.long_run_variance(),m.vol_of_vol(),m.correlation());
+        let positive=variance.max(0.0);
+        let diffusion=(positive*dt).sqrt();
+        let w2=rho*e1+(1.0-rho*rho).sqrt()*e2;
+        let next_spot=spot*((self.risk_free_rate-self.dividend_yield-0.5*positive)*dt+diffusion*e1).exp();
+        let next_variance=variance+kappa*(theta-positive)*dt+xi*diffusion*w2;
+        (next_spot,next_variance)
+    }
+
+    fn check_path_inputs(&self,initial_price:f64,time_horizon:f64,steps:usize)->Result<()>{
+        if initial_price<=0.0{
+            return Err(OptionError::InvalidParameter("Initial price must be greater than zero".to_string()));
+        }
+        if time_horizon<0.0{
+            return Err(OptionError::InvalidParameter("Time horizon must be 0 or positive".to_string()));
+        }
+        if steps==0{
+            return Err(OptionError::InvalidParameter("Steps must be positive".to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl StochasticProcess for HestonProcess{
+    fn clone_box(&self)->Box<dyn StochasticProcess>{
+        Box::new(self.clone())
+    }
+
+    fn init_rng_with_seed(&mut self,seed:u64){
+        self.rng=StdRng::seed_from_u64(seed);
+    }
+
+    /// Advance the spot and the internal variance state by one step <br>
+    /// 现价与内部方差状态前进一步
+    fn next_step(&mut self,current_price:f64,time_step:f64)->Result<f64>{
+        if time_step<0.0{
+            return Err(OptionError::InvalidParameter("Time step must be non-negative".into()));
+        }
+        let e1:f64=self.rng.sample(StandardNormal);
+        let e2:f64=self.rng.sample(StandardNormal);
+        let (spot,variance)=self.evolve(current_price,self.variance,time_step,e1,e2);
+        self.variance=variance;
+        Ok(spot)
+    }
+
+    fn simulate_path(&mut self,initial_price:f64,time_horizon:f64,steps:usize)->Result<Vec<f64>>{
+        self.check_path_inputs(initial_price,time_horizon,steps)?;
+        self.reset();
+        let dt=time_horizon/steps as f64;
+        let mut path=Vec::with_capacity(steps+1);
+        path.push(initial_price);
+        let mut spot=initial_price;
+        for _ in 0..steps{
+            spot=self.next_step(spot,dt)?;
+            path.push(spot);
+        }
+        Ok(path)
+    }
+
+    fn simulate_antithetic_path(&mut self,initial_price:f64,time_horizon:f64,steps:usize)->Result<(Vec<f64>,Vec<f64>)>{
+        self.check_path_inputs(initial_price,time_horizon,steps)?;
+        let dt=time_horizon/steps as f64;
+        let mut path1=Vec::with_capacity(steps+1);
+        let mut path2=Vec::with_capacity(steps+1);
+        path1.push(initial_price);
+        path2.push(initial_price);
+        let (mut s1,mut s2)=(initial_price,initial_price);
+        let (mut v1,mut v2)=(self.model.variance(),self.model.variance());
+        for _ in 0..steps{
+            let e1:f64=self.rng.sample(StandardNormal);
+            let e2:f64=self.rng.sample(StandardNormal);
+            (s1,v1)=self.evolve(s1,v1,dt,e1,e2);
+            (s2,v2)=self.evolve(s2,v2,dt,-e1,-e2);
+            path1.push(s1);
+            path2.push(s2);
+        }
+        Ok((path1,path2))
+    }
+}
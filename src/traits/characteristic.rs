@@ -0,0 +1,33 @@
+use std::fmt::Debug;
+use num_complex::Complex64;
+
+/// Characteristic function interface of Fourier pricing models. The model describes the
+/// martingale part X_t = ln(S_t/S₀) - (r-q)·t, so that E[e^{X_t}] = 1; rates and the spot are
+/// supplied by the pricing parameters <br>
+/// Fourier定价模型的特征函数接口。模型描述鞅部分X_t = ln(S_t/S₀) - (r-q)·t，满足E[e^{X_t}] = 1；
+/// 利率与现价由定价参数给出
+pub trait CharacteristicFunction:Debug+Send+Sync{
+    /// φ(u, t) = E[e^{iu·X_t}], complex `u` allowed 特征函数（允许复数`u`）
+    fn characteristic_function(&self,u:Complex64,t:f64)->Complex64;
+
+    /// Whether X has stationary independent increments (Lévy process), so that
+    /// φ(u, t+s) = φ(u, t)·φ(u, s); required by the Bermudan COS recursion <br>
+    /// X是否具有平稳独立增量（Lévy过程），即φ(u, t+s) = φ(u, t)·φ(u, s)；百慕大COS递推需要该性质
+    fn is_levy(&self)->bool{
+        false
+    }
+
+    /// Cumulants (c₁, c₂, c₄) of X_t, used for the COS truncation range. The default takes
+    /// central differences of the cumulant generating function ln E[e^{θX_t}] = ln φ(-iθ, t) <br>
+    /// X_t的累积量(c₁, c₂, c₄)，用于COS截断区间；默认对累积量生成函数ln E[e^{θX_t}] = ln φ(-iθ, t)做中心差分
+    fn cumulants(&self,t:f64)->(f64,f64,f64){
+        let h=1e-2;
+        let k=|theta:f64| self.characteristic_function(Complex64::new(0.0,-theta),t).ln().re;
+        let (m2,m1,p1,p2)=(k(-2.0*h),k(-h),k(h),k(2.0*h));
+        (
+            (p1-m1)/(2.0*h),
+            (p1+m1)/(h*h),
+            (p2-4.0*p1-4.0*m1+m2)/h.powi(4),
+        )
+    }
+}
@@ -1,8 +1,10 @@
 use crate::params::common::CommonParams;
+use crate::params::two_asset::TwoAssetParams;
 use crate::traits::{payoff,process};
 use crate::errors::*;
-use crate::traits::exercise::ExerciseRule;
-use crate::traits::payoff::Payoff;
+use crate::traits::exercise::{ExerciseRule,ExerciseBoundary};
+use crate::traits::payoff::{Payoff,TwoAssetPayoff};
+use crate::traits::workspace::{TimeLayers,PdeWorkspace};
 use std::any::Any;
 use std::fmt::Debug;
 use std::sync::Arc;
@@ -30,6 +32,39 @@ pub trait PriceEngine:Send+Sync{
     fn as_any(&self) -> &dyn Any;
 }
 
+/// The interface for engines pricing options on two correlated assets <br>
+/// 两资产期权定价引擎接口
+pub trait TwoAssetPriceEngine:Send+Sync{
+    /// calculate the price of an option on two assets <br>
+    /// 计算两资产期权价格
+    fn calculate_two_asset_price(
+        &self,
+        params:&TwoAssetParams,
+        payoff:&dyn TwoAssetPayoff,
+    )->Result<f64>;
+
+    fn price_two_asset(&self,product:&impl TwoAssetPricingTrait)->Result<f64>{
+        self.calculate_two_asset_price(product.params(),product.payoff())
+    }
+}
+
+/// Engines that can report the early exercise boundary S*(t) together with the price <br>
+/// 可在定价的同时给出提前行权边界S*(t)的引擎
+pub trait ExerciseBoundaryEngine:PriceEngine{
+    /// calculate option price and the early exercise boundary <br>
+    /// 计算期权价格及提前行权边界
+    fn calculate_price_with_boundary(
+        &self,
+        params:&CommonParams,
+        payoff:&dyn Payoff,
+        exercise_rule:&dyn ExerciseRule,
+    )->Result<(f64,ExerciseBoundary)>;
+
+    fn price_with_boundary(&self,product:&impl PricingTrait)->Result<(f64,ExerciseBoundary)>{
+        self.calculate_price_with_boundary(product.common(),product.payoff(),product.exercise_type())
+    }
+}
+
 /// 解析解计算器插件Trait(核心：插件化的核心契约）
 pub trait AnalyticCalculator:Debug+Send+Sync{
     fn supported_types(&self)->Vec<payoff::AnalyticPayoffType>;
@@ -71,7 +106,7 @@ pub trait GreeksEngine:PriceEngine{
         let h=0.01*params.spot();
         let params_up=params.with_spot(params.spot()+h)?;
         let params_down=params.with_spot(params.spot()-h)?;
-        let params_middle=params.clone();
+        let params_middle=*params;
 
         let price_up=self.calculate_price(&params_up,payoff,exercise_rule)?;
         let price_down=self.calculate_price(&params_down,payoff,exercise_rule)?;
@@ -153,22 +188,21 @@ pub trait PDEMethod:Debug+Send+Sync{
     /// 执行单步反向迭代
     ///
     /// # parameter
-    /// - `grid`: 价值网络
-    /// - `time_idx': 当前时间层索引
-    /// - `s_min`: 价格下界
-    /// - `dx`: 价格步长
+    /// - `layers`: 滚动时间层（待求解层及其后各层）
+    /// - `workspace`: 预分配的三对角求解工作区
+    /// - `nodes`: 空间网格节点（对数空间为ln S，可非均匀）
     /// - `dt`: 时间步长
     /// - `params`: 市场参数
     /// - `payoff`: payoff函数
     /// - `exercise_rule`: 行权规则
     /// - `current_t`: 当前时点
     /// - `use_log_space`: 是否使用对数价格价格
+    #[allow(clippy::too_many_arguments)]
     fn step_back(
         &self,
-        grid:&mut Vec<Vec<f64>>,
-        time_idx:usize,
-        s_min: f64,
-        dx: f64,
+        layers:TimeLayers,
+        workspace:&mut PdeWorkspace,
+        nodes: &[f64],
         dt: f64,
         params: &CommonParams,
         payoff: &dyn Payoff,
@@ -176,16 +210,28 @@ pub trait PDEMethod:Debug+Send+Sync{
         current_t: f64,
         use_log_space: bool
     )->Result<()>;
+
+    /// Number of later layers the scheme reads (1 for one-step schemes, 2 for BDF2) <br>
+    /// 格式需要读取的后续时间层数（单步格式为1，BDF2为2）
+    fn history_depth(&self)->usize{1}
 }
 /// PDE boundary condition interface
 /// PDE边界条件接口
 pub trait BoundaryCondition:Debug+Send+Sync{
     /// 价格下界（S→0）的期权价值
-    fn upper_boundary(&self,_t:f64)->Result<f64>{return Err(OptionError::NotImplemented("BoundaryConditon:upper_boundary".to_string()));}
+    fn upper_boundary(&self,_t:f64)->Result<f64>{Err(OptionError::NotImplemented("BoundaryConditon:upper_boundary".to_string()))}
     /// 价格上界（S→∞）的期权价值
-    fn lower_boundary(&self,_t:f64)->Result<f64>{return Err(OptionError::NotImplemented("BoundaryConditon:lower_boundary".to_string()));}
+    fn lower_boundary(&self,_t:f64)->Result<f64>{Err(OptionError::NotImplemented("BoundaryConditon:lower_boundary".to_string()))}
+    /// Option value at the lower edge `spot` of the grid with `t` years remaining;
+    /// defaults to `lower_boundary` for conditions that do not depend on the grid edge <br>
+    /// 网格下边缘价格`spot`处、剩余期限`t`时的期权价值，默认调用`lower_boundary`
+    fn lower_boundary_at(&self,t:f64,_spot:f64)->Result<f64>{self.lower_boundary(t)}
+    /// Option value at the upper edge `spot` of the grid with `t` years remaining;
+    /// defaults to `upper_boundary` for conditions that do not depend on the grid edge <br>
+    /// 网格上边缘价格`spot`处、剩余期限`t`时的期权价值，默认调用`upper_boundary`
+    fn upper_boundary_at(&self,t:f64,_spot:f64)->Result<f64>{self.upper_boundary(t)}
     /// 终值条件（到期时T的期权价值）
-    fn final_condition(&self,_spot:f64)->Result<f64>{return Err(OptionError::NotImplemented("BoundaryConditon:final_condition".to_string()));}
+    fn final_condition(&self,_spot:f64)->Result<f64>{Err(OptionError::NotImplemented("BoundaryConditon:final_condition".to_string()))}
     fn clone_box(&self) -> Box<dyn BoundaryCondition>;
 }
 
@@ -195,9 +241,28 @@ impl Clone for Box<dyn BoundaryCondition> {
     }
 }
 
+/// Boundary condition for products that cannot be priced on the one-dimensional PDE grid
+/// (path dependent or multi-asset payoffs); every boundary returns `NotImplemented` <br>
+/// 无法在一维PDE网格上定价的产品（路径依赖或多资产）使用的边界条件，所有边界均返回`NotImplemented`
+#[derive(Debug,Clone,Copy,Default)]
+pub struct NoBoundaryCondition;
+
+impl BoundaryCondition for NoBoundaryCondition{
+    fn clone_box(&self) -> Box<dyn BoundaryCondition> {
+        Box::new(*self)
+    }
+}
+
 pub trait PricingTrait{
     fn common(&self)->&CommonParams;
     fn payoff(&self)->&dyn Payoff;
     fn exercise_type(&self)->&dyn ExerciseRule;
     fn boundary_condition(&self)->&Arc<dyn BoundaryCondition>;
+}
+
+/// Products written on two correlated assets, priced by a [`TwoAssetPriceEngine`] <br>
+/// 两资产期权产品接口，由两资产定价引擎定价
+pub trait TwoAssetPricingTrait{
+    fn params(&self)->&TwoAssetParams;
+    fn payoff(&self)->&dyn TwoAssetPayoff;
 }
\ No newline at end of file
@@ -1,4 +1,6 @@
 use std::any::Any;
+use std::sync::Arc;
+use crate::errors::*;
 /// define the interface for exercise rules
 /// 定义行权规则接口
 pub trait ExerciseRule:Send+Sync{
@@ -19,6 +21,30 @@ pub trait ExerciseRule:Send+Sync{
         false
     }
 
+    /// Whether exercise is allowed on the lattice/grid node `remaining_time` before expiry,
+    /// with nodes `time_step` apart; engines only call `should_exercise` where this is true <br>
+    /// 距到期`remaining_time`、节点间隔`time_step`的树/网格节点上是否允许行权；
+    /// 引擎仅在该方法返回true时调用`should_exercise`
+    fn can_exercise(&self,_remaining_time:f64,_time_step:f64)->bool{
+        true
+    }
+
+    /// Value received when exercising at `remaining_time` before expiry given the payoff's
+    /// `intrinsic_value`; rules with time-dependent strikes or exercise penalties override it.
+    /// Engines also settle the payoff at expiry through it with `remaining_time` = 0 <br>
+    /// 距到期`remaining_time`时行权所得价值（`intrinsic_value`为payoff的内在价值），
+    /// 含时变执行价或行权罚金的规则需重写；引擎在到期时也以`remaining_time`=0经此结算payoff
+    fn exercise_value(&self,_remaining_time:f64,_spot:f64,intrinsic_value:f64)->f64{
+        intrinsic_value
+    }
+
+    /// Check that a rule whose schedule is measured from its own maturity agrees with the time
+    /// to maturity of the pricing parameters; engines call it before pricing <br>
+    /// 检查按自身到期时间度量行权安排的规则与定价参数的到期时间一致；引擎在定价前调用
+    fn check_maturity(&self,_time_to_maturity:f64)->Result<()>{
+        Ok(())
+    }
+
     fn is_european(&self)->bool;
 
     fn as_any(&self)->&dyn Any;
@@ -27,7 +53,7 @@ pub trait ExerciseRule:Send+Sync{
 
 /// European exercise rule <br>
 /// 欧式行权规则
-#[derive(Debug,Clone,Copy)]
+#[derive(Debug,Clone,Copy,Default)]
 pub struct EuropeanExercise;
 
 impl EuropeanExercise{
@@ -58,8 +84,15 @@ impl ExerciseRule for EuropeanExercise{
 
 /// American exercise rule <br>
 /// 美式行权规则
+#[derive(Debug,Clone,Copy,Default)]
 pub struct AmericanExercise;
 
+impl AmericanExercise{
+    pub fn new()->Self{
+        Self
+    }
+}
+
 impl ExerciseRule for AmericanExercise{
     fn should_exercise(
         &self,
@@ -80,3 +113,454 @@ impl ExerciseRule for AmericanExercise{
     }
 
 }
+
+
+/// Bermudan exercise rule: exercise is allowed on a schedule of dates only.
+/// On a lattice each date is mapped to the nearest node <br>
+/// 百慕大行权规则：仅允许在给定日期行权，在树/网格上每个日期映射到最近的节点
+#[derive(Debug,Clone,PartialEq)]
+pub struct BermudanExercise{
+    /// exercise times from the valuation date (year), ascending 距估值日的行权时间（年），升序
+    exercise_times:Vec<f64>,
+    maturity:f64,
+}
+
+impl BermudanExercise{
+    pub fn new(mut exercise_times:Vec<f64>,maturity:f64)->Result<Self>{
+        validate_maturity(maturity)?;
+        if exercise_times.is_empty(){
+            return Err(OptionError::EmptyData);
+        }
+        if exercise_times.iter().any(|t| !(0.0..=maturity).contains(t)){
+            return Err(OptionError::InvalidParameter("Bermudan exercise times must lie between 0 and the maturity".into()));
+        }
+        exercise_times.sort_by(|a,b| a.total_cmp(b));
+        exercise_times.dedup();
+        Ok(Self{exercise_times,maturity})
+    }
+
+    /// `periods` equally spaced exercise dates maturity·k/periods, k=1..=periods <br>
+    /// 等间隔的`periods`个行权日期
+    pub fn periodic(maturity:f64,periods:usize)->Result<Self>{
+        if periods==0{
+            return Err(OptionError::InvalidParameter("Bermudan exercise needs at least one period".into()));
+        }
+        Self::new((1..=periods).map(|k| maturity*k as f64/periods as f64).collect(),maturity)
+    }
+
+    pub fn exercise_times(&self)->&[f64]{&self.exercise_times}
+    pub fn maturity(&self)->f64{self.maturity}
+}
+
+impl ExerciseRule for BermudanExercise{
+    /// Exercise when the intrinsic value exceeds the continuation value;
+    /// the schedule is enforced through `can_exercise` <br>
+    /// 内在价值高于继续持有价值时行权，行权日期由`can_exercise`控制
+    fn should_exercise(
+        &self,
+        _time: f64,
+        _spot: f64,
+        intrinsic_value: f64,
+        continuation_value: f64
+    ) -> bool {
+        intrinsic_value>continuation_value
+    }
+
+    /// A node owns the dates within (-Δt/2, Δt/2] of it, so each date maps to exactly one node <br>
+    /// 节点对应距其(-Δt/2, Δt/2]内的日期，保证每个日期只映射到一个节点
+    fn can_exercise(&self,remaining_time:f64,time_step:f64)->bool{
+        let half=0.5*time_step.max(1e-12);
+        self.exercise_times.iter().any(|t|{
+            let offset=(self.maturity-t)-remaining_time;
+            offset> -half && offset<=half
+        })
+    }
+
+    fn check_maturity(&self,time_to_maturity:f64)->Result<()>{
+        match_maturity(self.maturity,time_to_maturity)
+    }
+
+    fn is_european(&self) -> bool {
+        false
+    }
+
+    fn as_any(&self)->&dyn Any{
+        self
+    }
+}
+
+fn validate_maturity(maturity:f64)->Result<()>{
+    if maturity<=0.0 || !maturity.is_finite(){
+        return Err(OptionError::InvalidParameter("Maturity must be greater than zero.".into()));
+    }
+    Ok(())
+}
+
+/// The schedule of a rule is measured from `maturity`, which must equal the priced maturity
+fn match_maturity(maturity:f64,time_to_maturity:f64)->Result<()>{
+    if (maturity-time_to_maturity).abs()>1e-10*maturity.max(1.0){
+        return Err(OptionError::InvalidParameter(format!(
+            "Exercise rule maturity {} differs from the time to maturity {}",maturity,time_to_maturity
+        )));
+    }
+    Ok(())
+}
+
+/// Vesting lockout: no exercise before `lockout_end` (years from the valuation date),
+/// afterwards the wrapped rule applies <br>
+/// 锁定期（归属期）：`lockout_end`（距估值日的年数）之前不可行权，之后按内层规则行权
+#[derive(Clone)]
+pub struct LockoutExercise{
+    inner:Arc<dyn ExerciseRule>,
+    lockout_end:f64,
+    maturity:f64,
+}
+
+impl LockoutExercise{
+    pub fn new(inner:Arc<dyn ExerciseRule>,lockout_end:f64,maturity:f64)->Result<Self>{
+        validate_maturity(maturity)?;
+        if !(0.0..=maturity).contains(&lockout_end){
+            return Err(OptionError::InvalidParameter("The lockout must end between 0 and the maturity".into()));
+        }
+        Ok(Self{inner,lockout_end,maturity})
+    }
+
+    pub fn lockout_end(&self)->f64{self.lockout_end}
+}
+
+impl ExerciseRule for LockoutExercise{
+    fn should_exercise(&self,time:f64,spot:f64,intrinsic_value:f64,continuation_value:f64)->bool{
+        self.inner.should_exercise(time,spot,intrinsic_value,continuation_value)
+    }
+
+    /// The first node at or after the end of the lockout may exercise <br>
+    /// 锁定期结束时或之后的节点可行权
+    fn can_exercise(&self,remaining_time:f64,time_step:f64)->bool{
+        let elapsed=self.maturity-remaining_time;
+        elapsed>=self.lockout_end-1e-12*self.maturity && self.inner.can_exercise(remaining_time,time_step)
+    }
+
+    fn exercise_value(&self,remaining_time:f64,spot:f64,intrinsic_value:f64)->f64{
+        self.inner.exercise_value(remaining_time,spot,intrinsic_value)
+    }
+
+    fn check_maturity(&self,time_to_maturity:f64)->Result<()>{
+        match_maturity(self.maturity,time_to_maturity)?;
+        self.inner.check_maturity(time_to_maturity)
+    }
+
+    fn is_european(&self)->bool{
+        self.inner.is_european()
+    }
+
+    fn as_any(&self)->&dyn Any{
+        self
+    }
+}
+
+/// Exercise windows: exercise is allowed only inside the given [start, end] intervals
+/// (years from the valuation date). A node is inside a window when its cell
+/// [t-Δt/2, t+Δt/2] overlaps it, so windows narrower than a step still map to a node <br>
+/// 行权窗口：仅在给定区间[start, end]（距估值日的年数）内可行权；
+/// 节点单元[t-Δt/2, t+Δt/2]与窗口相交即视为在窗口内，窄于步长的窗口也能映射到节点
+#[derive(Clone)]
+pub struct WindowExercise{
+    inner:Arc<dyn ExerciseRule>,
+    windows:Vec<(f64,f64)>,
+    maturity:f64,
+}
+
+impl WindowExercise{
+    pub fn new(inner:Arc<dyn ExerciseRule>,windows:Vec<(f64,f64)>,maturity:f64)->Result<Self>{
+        validate_maturity(maturity)?;
+        if windows.is_empty(){
+            return Err(OptionError::EmptyData);
+        }
+        if windows.iter().any(|&(start,end)| start>end || start<0.0 || end>maturity){
+            return Err(OptionError::InvalidParameter("Exercise windows must satisfy 0 ≤ start ≤ end ≤ maturity".into()));
+        }
+        Ok(Self{inner,windows,maturity})
+    }
+
+    pub fn windows(&self)->&[(f64,f64)]{&self.windows}
+}
+
+impl ExerciseRule for WindowExercise{
+    fn should_exercise(&self,time:f64,spot:f64,intrinsic_value:f64,continuation_value:f64)->bool{
+        self.inner.should_exercise(time,spot,intrinsic_value,continuation_value)
+    }
+
+    fn can_exercise(&self,remaining_time:f64,time_step:f64)->bool{
+        let elapsed=self.maturity-remaining_time;
+        let half=0.5*time_step;
+        self.windows.iter().any(|&(start,end)| elapsed+half>start && elapsed-half<=end)
+            && self.inner.can_exercise(remaining_time,time_step)
+    }
+
+    fn exercise_value(&self,remaining_time:f64,spot:f64,intrinsic_value:f64)->f64{
+        self.inner.exercise_value(remaining_time,spot,intrinsic_value)
+    }
+
+    fn check_maturity(&self,time_to_maturity:f64)->Result<()>{
+        match_maturity(self.maturity,time_to_maturity)?;
+        self.inner.check_maturity(time_to_maturity)
+    }
+
+    fn is_european(&self)->bool{
+        self.inner.is_european()
+    }
+
+    fn as_any(&self)->&dyn Any{
+        self
+    }
+}
+
+/// Piecewise constant strike schedule: strike `strikes[i]` applies from `times[i]`
+/// (years from the valuation date) until the next time; the exercise value is
+/// max(φ(S-K(t)),0) and replaces the payoff's intrinsic value, at expiry too, where
+/// the last strike applies whatever the strike of the payoff <br>
+/// 分段常数执行价：`strikes[i]`自`times[i]`（距估值日的年数）起生效直至下一时点；
+/// 行权价值max(φ(S-K(t)),0)替代payoff的内在价值，到期时同样适用，
+/// 此时无论payoff的执行价为何均按最后一个执行价结算
+#[derive(Clone)]
+pub struct StrikeScheduleExercise{
+    inner:Arc<dyn ExerciseRule>,
+    times:Vec<f64>,
+    strikes:Vec<f64>,
+    is_call:bool,
+    maturity:f64,
+}
+
+impl StrikeScheduleExercise{
+    pub fn new(
+        inner:Arc<dyn ExerciseRule>,
+        times:Vec<f64>,
+        strikes:Vec<f64>,
+        is_call:bool,
+        maturity:f64,
+    )->Result<Self>{
+        validate_maturity(maturity)?;
+        if times.is_empty(){
+            return Err(OptionError::EmptyData);
+        }
+        if times.len()!=strikes.len(){
+            return Err(OptionError::InvalidParameter("Strike schedule needs one strike per time".into()));
+        }
+        if times[0]!=0.0 || times.windows(2).any(|w| w[0]>=w[1]){
+            return Err(OptionError::InvalidParameter("Strike schedule times must start at 0 and be strictly increasing".into()));
+        }
+        if strikes.iter().any(|&k| k<=0.0){
+            return Err(OptionError::InvalidParameter("Strike must be greater than zero.".into()));
+        }
+        Ok(Self{inner,times,strikes,is_call,maturity})
+    }
+
+    /// Strike in force `remaining_time` before expiry 距到期`remaining_time`时生效的执行价
+    pub fn strike_at(&self,remaining_time:f64)->f64{
+        let elapsed=self.maturity-remaining_time;
+        let index=self.times.partition_point(|&t| t<=elapsed+1e-12).max(1)-1;
+        self.strikes[index]
+    }
+}
+
+impl ExerciseRule for StrikeScheduleExercise{
+    fn should_exercise(&self,time:f64,spot:f64,intrinsic_value:f64,continuation_value:f64)->bool{
+        self.inner.should_exercise(time,spot,intrinsic_value,continuation_value)
+    }
+
+    fn can_exercise(&self,remaining_time:f64,time_step:f64)->bool{
+        self.inner.can_exercise(remaining_time,time_step)
+    }
+
+    fn exercise_value(&self,remaining_time:f64,spot:f64,_intrinsic_value:f64)->f64{
+        let sign=if self.is_call{1.0}else{-1.0};
+        let value=(sign*(spot-self.strike_at(remaining_time))).max(0.0);
+        self.inner.exercise_value(remaining_time,spot,value)
+    }
+
+    fn check_maturity(&self,time_to_maturity:f64)->Result<()>{
+        match_maturity(self.maturity,time_to_maturity)?;
+        self.inner.check_maturity(time_to_maturity)
+    }
+
+    fn is_european(&self)->bool{
+        self.inner.is_european()
+    }
+
+    fn as_any(&self)->&dyn Any{
+        self
+    }
+}
+
+/// Early exercise penalty: exercising before `penalty_end` (years from the valuation date,
+/// the whole life by default) pays value·(1-proportional) - fixed; the value at expiry
+/// is never charged <br>
+/// 提前行权罚金：在`penalty_end`（距估值日的年数，默认整个存续期）之前行权
+/// 所得为 价值·(1-proportional) - fixed；到期时的价值不收取罚金
+#[derive(Clone)]
+pub struct PenaltyExercise{
+    inner:Arc<dyn ExerciseRule>,
+    fixed:f64,
+    proportional:f64,
+    penalty_end:Option<(f64,f64)>,
+}
+
+impl PenaltyExercise{
+    pub fn new(inner:Arc<dyn ExerciseRule>,fixed:f64,proportional:f64)->Result<Self>{
+        if fixed<0.0 || !(0.0..=1.0).contains(&proportional){
+            return Err(OptionError::InvalidParameter("Penalty must be non-negative and the proportional part at most 100%".into()));
+        }
+        Ok(Self{inner,fixed,proportional,penalty_end:None})
+    }
+
+    /// Only charge the penalty before `penalty_end` 仅在`penalty_end`之前收取罚金
+    pub fn until(mut self,penalty_end:f64,maturity:f64)->Result<Self>{
+        validate_maturity(maturity)?;
+        if !(0.0..=maturity).contains(&penalty_end){
+            return Err(OptionError::InvalidParameter("The penalty must end between 0 and the maturity".into()));
+        }
+        self.penalty_end=Some((penalty_end,maturity));
+        Ok(self)
+    }
+}
+
+impl ExerciseRule for PenaltyExercise{
+    fn should_exercise(&self,time:f64,spot:f64,intrinsic_value:f64,continuation_value:f64)->bool{
+        self.inner.should_exercise(time,spot,intrinsic_value,continuation_value)
+    }
+
+    fn can_exercise(&self,remaining_time:f64,time_step:f64)->bool{
+        self.inner.can_exercise(remaining_time,time_step)
+    }
+
+    fn exercise_value(&self,remaining_time:f64,spot:f64,intrinsic_value:f64)->f64{
+        let value=self.inner.exercise_value(remaining_time,spot,intrinsic_value);
+        let charged=match self.penalty_end{
+            Some((end,maturity))=>maturity-remaining_time<end,
+            None=>remaining_time>0.0,
+        };
+        if charged && value>0.0{
+            value*(1.0-self.proportional)-self.fixed
+        }else{
+            value
+        }
+    }
+
+    fn check_maturity(&self,time_to_maturity:f64)->Result<()>{
+        if let Some((_,maturity))=self.penalty_end{
+            match_maturity(maturity,time_to_maturity)?;
+        }
+        self.inner.check_maturity(time_to_maturity)
+    }
+
+    fn is_european(&self)->bool{
+        self.inner.is_european()
+    }
+
+    fn as_any(&self)->&dyn Any{
+        self
+    }
+}
+
+/// Early exercise boundary S*(t) computed by a lattice or grid engine <br>
+/// 由树或网格引擎计算得到的提前行权边界S*(t)
+#[derive(Debug,Clone,Default,PartialEq)]
+pub struct ExerciseBoundary{
+    /// time from the valuation date (year), ascending 距估值日的时间（年），升序
+    pub times:Vec<f64>,
+    /// critical spot at each time (None when no node is exercised) <br>
+    /// 各时点的临界价格（该时点无行权节点时为None）
+    pub critical_prices:Vec<Option<f64>>,
+}
+
+impl ExerciseBoundary{
+    pub fn new()->Self{
+        Self::default()
+    }
+
+    pub fn len(&self)->usize{
+        self.times.len()
+    }
+
+    pub fn is_empty(&self)->bool{
+        self.times.is_empty()
+    }
+
+    /// Record the critical price of one time layer <br>
+    /// 记录一个时间层的临界价格
+    pub fn push(&mut self,time:f64,critical_price:Option<f64>){
+        self.times.push(time);
+        self.critical_prices.push(critical_price);
+    }
+
+    /// Sort the records by time (engines fill the boundary backwards in time) <br>
+    /// 按时间排序（引擎按时间倒序填充边界）
+    pub fn sort_by_time(&mut self){
+        let mut pairs:Vec<(f64,Option<f64>)>=self.times.iter().copied().zip(self.critical_prices.iter().copied()).collect();
+        pairs.sort_by(|a,b| a.0.total_cmp(&b.0));
+        (self.times,self.critical_prices)=pairs.into_iter().unzip();
+    }
+
+    /// Edge of the exercise region among the nodes of one time layer (spots ascending).
+    /// A region touching the lowest node is put-like and its highest node is returned,
+    /// otherwise the region is call-like and its lowest node is returned <br>
+    /// 一个时间层内（价格升序）行权区域的边缘：行权区域包含最低节点时为看跌型，返回其最高节点；
+    /// 否则为看涨型，返回其最低节点
+    pub fn critical_price(spots:&[f64],exercised:&[bool])->Option<f64>{
+        let first=exercised.iter().position(|&e| e)?;
+        if first==0{
+            let run=exercised.iter().take_while(|&&e| e).count();
+            Some(spots[run-1])
+        }else{
+            Some(spots[first])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn test_bermudan_nearest_node()->Result<()>{
+        // 到期T=1，行权日0.3与1.0；节点间隔0.25
+        let rule=BermudanExercise::new(vec![1.0,0.3],1.0)?;
+        assert_eq!(rule.exercise_times(),&[0.3,1.0]);
+        let exercisable:Vec<bool>=(0..=4).map(|j| rule.can_exercise(1.0-0.25*j as f64,0.25)).collect();
+        // 0.3映射到最近的节点0.25，1.0映射到到期节点
+        assert_eq!(exercisable,vec![false,true,false,false,true]);
+        assert!(BermudanExercise::new(vec![1.5],1.0).is_err());
+        assert_eq!(BermudanExercise::periodic(1.0,4)?.exercise_times(),&[0.25,0.5,0.75,1.0]);
+        // 行权安排按自身到期时间度量，须与定价参数一致
+        assert!(rule.check_maturity(1.0).is_ok());
+        assert!(rule.check_maturity(2.0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_composed_time_varying_rules()->Result<()>{
+        let american:Arc<dyn ExerciseRule>=Arc::new(AmericanExercise);
+        // 一年锁定期后只能在每年的第二季度行权，T=4
+        let lockout:Arc<dyn ExerciseRule>=Arc::new(LockoutExercise::new(american.clone(),1.0,4.0)?);
+        let windows=WindowExercise::new(lockout,vec![(0.25,0.5),(1.25,1.5),(2.25,2.5),(3.25,3.5)],4.0)?;
+        assert!(!windows.can_exercise(4.0-0.4,0.01));
+        assert!(windows.can_exercise(4.0-1.4,0.01));
+        assert!(!windows.can_exercise(4.0-1.7,0.01));
+
+        // 执行价逐年上调，前两年行权扣除10%
+        let schedule:Arc<dyn ExerciseRule>=Arc::new(StrikeScheduleExercise::new(american,vec![0.0,1.0,2.0],vec![100.0,105.0,110.0],true,3.0)?);
+        assert_eq!(schedule.exercise_value(2.5,120.0,0.0),20.0);
+        assert_eq!(schedule.exercise_value(1.5,120.0,0.0),15.0);
+        assert_eq!(schedule.exercise_value(0.5,120.0,0.0),10.0);
+        // 到期时按最后一个执行价结算
+        assert_eq!(schedule.exercise_value(0.0,120.0,0.0),10.0);
+        let penalty=PenaltyExercise::new(schedule.clone(),0.0,0.1)?.until(2.0,3.0)?;
+        assert!((penalty.exercise_value(2.5,120.0,0.0)-18.0).abs()<1e-12);
+        assert_eq!(penalty.exercise_value(0.5,120.0,0.0),10.0);
+        // 罚金仅针对提前行权
+        let penalty=PenaltyExercise::new(schedule,1.0,0.1)?;
+        assert!((penalty.exercise_value(0.5,120.0,0.0)-8.0).abs()<1e-12);
+        assert_eq!(penalty.exercise_value(0.0,120.0,0.0),10.0);
+        Ok(())
+    }
+}
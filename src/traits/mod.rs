@@ -2,4 +2,6 @@ pub mod payoff;
 pub mod exercise;
 pub mod process;
 pub mod engine;
+pub mod characteristic;
+pub mod workspace;
 
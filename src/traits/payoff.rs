@@ -1,4 +1,6 @@
 use std::any::Any;
+use crate::params::common::CommonParams;
+use crate::utils::statistics::black_scholes_price;
 
 /// 解析解期权类型枚举（标识不同期权类型)
 #[derive(Debug,Clone,Copy,PartialEq,Hash,Eq)]
@@ -12,11 +14,36 @@ pub enum AnalyticPayoffType{
     CashOrNothingPut,
     AssertOrNothingCall,
     AssertOrNothingPut,
+    GapCall,
+    GapPut,
+
+    // American digital 美式二元（触碰）期权
+    OneTouch,
+    NoTouch,
 
     // barrier option 障碍期权
     DownAndOutCall,
     UpAndOutCall,
 
+    // lookback option 回望期权
+    FloatingStrikeLookbackCall,
+    FloatingStrikeLookbackPut,
+    FixedStrikeLookbackCall,
+    FixedStrikeLookbackPut,
+
+    // compound option 复合期权
+    CompoundCallOnCall,
+    CompoundCallOnPut,
+    CompoundPutOnCall,
+    CompoundPutOnPut,
+
+    // chooser option 选择期权
+    SimpleChooser,
+    ComplexChooser,
+
+    // forward start option 远期生效期权
+    ForwardStartCall,
+    ForwardStartPut,
 }
 
 
@@ -40,6 +67,15 @@ pub trait Payoff:Send+Sync{
         self.payoff(path.last().copied().unwrap_or(0.0))
     }
 
+    /// Average payoff over the grid cell [lower, upper] around `spot`, used as the terminal
+    /// condition of the PDE engine (exact averaging removes the oscillations caused by
+    /// discontinuous payoffs; the default evaluates the payoff at `spot`) <br>
+    /// 网格单元[lower, upper]上的平均收益，用作PDE引擎的终值条件
+    /// （对不连续收益精确平均可消除数值振荡；默认直接取spot处的收益）
+    fn cell_averaged_payoff(&self,spot:f64,_lower:f64,_upper:f64)->f64{
+        self.payoff(spot)
+    }
+
     /// 向下转型为Any（用于类型识别）
     fn as_any(&self)->&dyn Any;
 
@@ -49,6 +85,21 @@ pub trait Payoff:Send+Sync{
     fn analytic_type(&self)->Option<AnalyticPayoffType>{
         None
     }
+
+    /// Payoff valued under the market data of the pricing parameters, for payoffs that deliver
+    /// an option at expiry (compound, chooser and forward start); None for all other payoffs <br>
+    /// 以定价参数的市场数据估值的Payoff，用于到期时交付期权的Payoff（复合、选择、远期生效期权）；
+    /// 其他Payoff返回None
+    fn bind_market(&self,_params:&CommonParams)->Option<Box<dyn Payoff>>{
+        None
+    }
+
+    /// Whether the payoff is only defined on a whole path (e.g. realized variance), so engines
+    /// valuing a terminal spot reject it <br>
+    /// Payoff是否仅对整条路径有定义（如已实现方差），按终值估值的引擎拒绝此类Payoff
+    fn requires_path(&self)->bool{
+        false
+    }
 }
 
 /// Vanilla call option<br>
@@ -80,6 +131,11 @@ impl Payoff for CallPayoff{
 pub struct PutPayoff{
     pub strike:f64,
 }
+impl PutPayoff {
+    pub fn new(strike:f64)->Self{
+        Self{strike}
+    }
+}
 
 impl Payoff for PutPayoff{
     fn payoff(&self,spot:f64)->f64{
@@ -93,17 +149,102 @@ impl Payoff for PutPayoff{
     }
 }
 
-/// Cash or nothing call option payoff <br>
-/// 现金或无看涨二元期权Payoff
+/// Weight of a digital payoff at `spot`: 1{S>K} (or 1{S<K}), replaced by a linear ramp
+/// of the given width around K when smoothing is enabled <br>
+/// 二元收益在spot处的权重1{S>K}（或1{S<K}），启用平滑时在K附近以给定宽度的线性斜坡代替
+fn digital_weight(spot:f64,strike:f64,smoothing:f64,above:bool)->f64{
+    let weight=if smoothing>0.0{
+        ((spot-strike)/smoothing+0.5).clamp(0.0,1.0)
+    }else if spot>=strike{
+        1.0
+    }else{
+        0.0
+    };
+    if above{weight}else{1.0-weight}
+}
+
+/// Digital payoffs whose jump at the strike (or trigger) can be replaced by a linear ramp <br>
+/// 可在执行价（或触发价）处以线性斜坡代替跳跃的二元收益
+pub trait DigitalPayoff:Payoff{
+    /// Payoff with the jump replaced by a linear ramp of the given width (0 for none) <br>
+    /// 以给定宽度的线性斜坡代替跳跃后的收益（0表示不平滑）
+    fn smoothed_payoff(&self,spot:f64,width:f64)->f64;
+
+    /// Wrap the payoff so the binomial and Monte Carlo engines see the smoothed jump <br>
+    /// 包装为平滑收益，供二叉树和蒙特卡洛引擎使用
+    fn with_smoothing(self,width:f64)->SmoothedPayoff<Self>
+    where Self:Sized{
+        SmoothedPayoff{payoff:self,width}
+    }
+}
+
+/// Digital payoff with its jump replaced by a linear ramp. Type identification, analytic
+/// pricing and PDE cell averaging use the wrapped payoff <br>
+/// 以线性斜坡代替跳跃的二元收益；类型识别、解析解定价与PDE单元平均均使用被包装的收益
+#[derive(Debug,Clone,Copy)]
+pub struct SmoothedPayoff<P>{
+    pub payoff:P,
+    /// width of the linear ramp 线性斜坡宽度
+    pub width:f64,
+}
+
+impl<P:DigitalPayoff+'static> Payoff for SmoothedPayoff<P>{
+    fn payoff(&self,spot:f64)->f64{
+        self.payoff.smoothed_payoff(spot,self.width)
+    }
+    fn cell_averaged_payoff(&self,spot:f64,lower:f64,upper:f64)->f64{
+        self.payoff.cell_averaged_payoff(spot,lower,upper)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self.payoff.as_any()
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        self.payoff.analytic_type()
+    }
+}
+
+/// Exact average of 1{S>K}·(slope·S+intercept) (or 1{S<K}·...) over the cell [lower, upper] <br>
+/// 单元[lower, upper]上 1{S>K}·(slope·S+intercept)（或1{S<K}·...）的精确平均值
+fn digital_cell_average(lower:f64,upper:f64,strike:f64,slope:f64,intercept:f64,above:bool)->f64{
+    if upper<=lower{
+        let spot=0.5*(lower+upper);
+        let inside=if above{spot>=strike}else{spot<strike};
+        return if inside{slope*spot+intercept}else{0.0};
+    }
+    let (from,to)=if above{
+        (strike.clamp(lower,upper),upper)
+    }else{
+        (lower,strike.clamp(lower,upper))
+    };
+    (0.5*slope*(to*to-from*from)+intercept*(to-from))/(upper-lower)
+}
+
+/// Cash or nothing call option payoff: Q·1{S>K} <br>
+/// 现金或无看涨二元期权Payoff：Q·1{S>K}
 #[derive(Debug,Clone,Copy)]
 pub struct CashOrNothingCallPayoff{
     pub strike:f64,
     pub payout:f64, // 赔付额（二元期权专属）
 }
 
+impl CashOrNothingCallPayoff{
+    pub fn new(strike:f64,payout:f64)->Self{
+        Self{strike,payout}
+    }
+}
+
+impl DigitalPayoff for CashOrNothingCallPayoff{
+    fn smoothed_payoff(&self,spot:f64,width:f64)->f64{
+        self.payout*digital_weight(spot,self.strike,width,true)
+    }
+}
+
 impl Payoff for CashOrNothingCallPayoff{
     fn payoff(&self,spot:f64)->f64{
-        if spot>=self.strike{self.payout} else {0.0}
+        self.smoothed_payoff(spot,0.0)
+    }
+    fn cell_averaged_payoff(&self,_spot:f64,lower:f64,upper:f64)->f64{
+        digital_cell_average(lower,upper,self.strike,0.0,self.payout,true)
     }
     fn as_any(&self) -> &dyn Any {
         self
@@ -113,7 +254,296 @@ impl Payoff for CashOrNothingCallPayoff{
     }
 }
 
-/// 现金或无看跌期权
+/// Cash or nothing put option payoff: Q·1{S<K} <br>
+/// 现金或无看跌二元期权Payoff：Q·1{S<K}
+#[derive(Debug,Clone,Copy)]
+pub struct CashOrNothingPutPayoff{
+    pub strike:f64,
+    pub payout:f64,
+}
+
+impl CashOrNothingPutPayoff{
+    pub fn new(strike:f64,payout:f64)->Self{
+        Self{strike,payout}
+    }
+}
+
+impl DigitalPayoff for CashOrNothingPutPayoff{
+    fn smoothed_payoff(&self,spot:f64,width:f64)->f64{
+        self.payout*digital_weight(spot,self.strike,width,false)
+    }
+}
+
+impl Payoff for CashOrNothingPutPayoff{
+    fn payoff(&self,spot:f64)->f64{
+        self.smoothed_payoff(spot,0.0)
+    }
+    fn cell_averaged_payoff(&self,_spot:f64,lower:f64,upper:f64)->f64{
+        digital_cell_average(lower,upper,self.strike,0.0,self.payout,false)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        Some(AnalyticPayoffType::CashOrNothingPut)
+    }
+}
+
+/// Asset or nothing call option payoff: S·1{S>K} <br>
+/// 资产或无看涨二元期权Payoff：S·1{S>K}
+#[derive(Debug,Clone,Copy)]
+pub struct AssetOrNothingCallPayoff{
+    pub strike:f64,
+}
+
+impl AssetOrNothingCallPayoff{
+    pub fn new(strike:f64)->Self{
+        Self{strike}
+    }
+}
+
+impl DigitalPayoff for AssetOrNothingCallPayoff{
+    fn smoothed_payoff(&self,spot:f64,width:f64)->f64{
+        spot*digital_weight(spot,self.strike,width,true)
+    }
+}
+
+impl Payoff for AssetOrNothingCallPayoff{
+    fn payoff(&self,spot:f64)->f64{
+        self.smoothed_payoff(spot,0.0)
+    }
+    fn cell_averaged_payoff(&self,_spot:f64,lower:f64,upper:f64)->f64{
+        digital_cell_average(lower,upper,self.strike,1.0,0.0,true)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        Some(AnalyticPayoffType::AssertOrNothingCall)
+    }
+}
+
+/// Asset or nothing put option payoff: S·1{S<K} <br>
+/// 资产或无看跌二元期权Payoff：S·1{S<K}
+#[derive(Debug,Clone,Copy)]
+pub struct AssetOrNothingPutPayoff{
+    pub strike:f64,
+}
+
+impl AssetOrNothingPutPayoff{
+    pub fn new(strike:f64)->Self{
+        Self{strike}
+    }
+}
+
+impl DigitalPayoff for AssetOrNothingPutPayoff{
+    fn smoothed_payoff(&self,spot:f64,width:f64)->f64{
+        spot*digital_weight(spot,self.strike,width,false)
+    }
+}
+
+impl Payoff for AssetOrNothingPutPayoff{
+    fn payoff(&self,spot:f64)->f64{
+        self.smoothed_payoff(spot,0.0)
+    }
+    fn cell_averaged_payoff(&self,_spot:f64,lower:f64,upper:f64)->f64{
+        digital_cell_average(lower,upper,self.strike,1.0,0.0,false)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        Some(AnalyticPayoffType::AssertOrNothingPut)
+    }
+}
+
+/// Gap call option payoff: (S - K₁)·1{S>K₂}, K₁ is the payment strike and K₂ the trigger <br>
+/// 缺口看涨期权Payoff：(S - K₁)·1{S>K₂}，K₁为支付执行价，K₂为触发价
+#[derive(Debug,Clone,Copy)]
+pub struct GapCallPayoff{
+    pub strike:f64,
+    pub trigger:f64,
+}
+
+impl GapCallPayoff{
+    pub fn new(strike:f64,trigger:f64)->Self{
+        Self{strike,trigger}
+    }
+}
+
+impl DigitalPayoff for GapCallPayoff{
+    fn smoothed_payoff(&self,spot:f64,width:f64)->f64{
+        (spot-self.strike)*digital_weight(spot,self.trigger,width,true)
+    }
+}
+
+impl Payoff for GapCallPayoff{
+    fn payoff(&self,spot:f64)->f64{
+        self.smoothed_payoff(spot,0.0)
+    }
+    fn cell_averaged_payoff(&self,_spot:f64,lower:f64,upper:f64)->f64{
+        digital_cell_average(lower,upper,self.trigger,1.0,-self.strike,true)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        Some(AnalyticPayoffType::GapCall)
+    }
+}
+
+/// Gap put option payoff: (K₁ - S)·1{S<K₂} <br>
+/// 缺口看跌期权Payoff：(K₁ - S)·1{S<K₂}
+#[derive(Debug,Clone,Copy)]
+pub struct GapPutPayoff{
+    pub strike:f64,
+    pub trigger:f64,
+}
+
+impl GapPutPayoff{
+    pub fn new(strike:f64,trigger:f64)->Self{
+        Self{strike,trigger}
+    }
+}
+
+impl DigitalPayoff for GapPutPayoff{
+    fn smoothed_payoff(&self,spot:f64,width:f64)->f64{
+        (self.strike-spot)*digital_weight(spot,self.trigger,width,false)
+    }
+}
+
+impl Payoff for GapPutPayoff{
+    fn payoff(&self,spot:f64)->f64{
+        self.smoothed_payoff(spot,0.0)
+    }
+    fn cell_averaged_payoff(&self,_spot:f64,lower:f64,upper:f64)->f64{
+        digital_cell_average(lower,upper,self.trigger,-1.0,self.strike,false)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        Some(AnalyticPayoffType::GapPut)
+    }
+}
+
+/// Brownian bridge correction for barrier monitoring on a discrete path: the probability
+/// that a geometric brownian motion touched the barrier between two simulated nodes
+/// is exp(-2·ln(B/Sᵢ)·ln(B/Sᵢ₊₁)/(σ²Δt)) <br>
+/// 离散路径障碍监测的布朗桥修正：相邻两个模拟节点之间触碰障碍的概率为
+/// exp(-2·ln(B/Sᵢ)·ln(B/Sᵢ₊₁)/(σ²Δt))
+#[derive(Debug,Clone,Copy)]
+pub struct BrownianBridge{
+    pub volatility:f64,
+    /// time spanned by the simulated path 模拟路径覆盖的时间长度
+    pub maturity:f64,
+}
+
+impl BrownianBridge{
+    pub fn new(volatility:f64,maturity:f64)->Self{
+        Self{volatility,maturity}
+    }
+
+    /// Probability that the path touched the barrier somewhere on [0, T] <br>
+    /// 路径在[0, T]上触碰障碍的概率
+    pub fn touch_probability(&self,path:&[f64],barrier:f64,is_up:bool)->f64{
+        let crossed=|s:f64| if is_up{s>=barrier}else{s<=barrier};
+        if path.iter().any(|&s| crossed(s)){
+            return 1.0;
+        }
+        if path.len()<2 || self.volatility<=0.0 || self.maturity<=0.0{
+            return 0.0;
+        }
+        let dt=self.maturity/(path.len()-1) as f64;
+        let variance=self.volatility*self.volatility*dt;
+        let survival:f64=path.windows(2).map(|w|{
+            let (a,b)=((barrier/w[0]).ln(),(barrier/w[1]).ln());
+            1.0-(-2.0*a*b/variance).exp()
+        }).product();
+        1.0-survival
+    }
+}
+
+/// Touch indicator of a path: 1 if any node is beyond the barrier, or the continuous
+/// monitoring probability when a Brownian bridge correction is given <br>
+/// 路径的触碰指示：任一节点越过障碍则为1；给定布朗桥修正时为连续监测下的触碰概率
+fn touch_indicator(path:&[f64],barrier:f64,is_up:bool,bridge:Option<&BrownianBridge>)->f64{
+    match bridge{
+        Some(bridge)=>bridge.touch_probability(path,barrier,is_up),
+        None=>{
+            let touched=path.iter().any(|&s| if is_up{s>=barrier}else{s<=barrier});
+            if touched{1.0}else{0.0}
+        }
+    }
+}
+
+/// One-touch option payoff: pays Q at expiry if the barrier is touched before expiry <br>
+/// 一触即付期权Payoff：到期前触碰障碍则于到期日支付Q
+#[derive(Debug,Clone,Copy)]
+pub struct OneTouchPayoff{
+    pub barrier:f64,
+    pub payout:f64,
+    /// barrier above the spot (up) or below (down) 障碍在现价之上（向上）或之下（向下）
+    pub is_up:bool,
+    /// continuous monitoring correction for simulated paths (None for discrete monitoring) <br>
+    /// 模拟路径的连续监测修正（None表示离散监测）
+    pub bridge:Option<BrownianBridge>,
+}
+
+impl OneTouchPayoff{
+    pub fn new(barrier:f64,payout:f64,is_up:bool)->Self{
+        Self{barrier,payout,is_up,bridge:None}
+    }
+}
+
+impl Payoff for OneTouchPayoff{
+    fn payoff(&self,spot:f64)->f64{
+        self.payout*touch_indicator(&[spot],self.barrier,self.is_up,None)
+    }
+    fn path_dependent_payoff(&self,path:&[f64])->f64{
+        self.payout*touch_indicator(path,self.barrier,self.is_up,self.bridge.as_ref())
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        Some(AnalyticPayoffType::OneTouch)
+    }
+}
+
+/// No-touch option payoff: pays Q at expiry if the barrier is never touched <br>
+/// 无触碰期权Payoff：到期前从未触碰障碍则于到期日支付Q
+#[derive(Debug,Clone,Copy)]
+pub struct NoTouchPayoff{
+    pub barrier:f64,
+    pub payout:f64,
+    /// barrier above the spot (up) or below (down) 障碍在现价之上（向上）或之下（向下）
+    pub is_up:bool,
+    /// continuous monitoring correction for simulated paths (None for discrete monitoring) <br>
+    /// 模拟路径的连续监测修正（None表示离散监测）
+    pub bridge:Option<BrownianBridge>,
+}
+
+impl NoTouchPayoff{
+    pub fn new(barrier:f64,payout:f64,is_up:bool)->Self{
+        Self{barrier,payout,is_up,bridge:None}
+    }
+}
+
+impl Payoff for NoTouchPayoff{
+    fn payoff(&self,spot:f64)->f64{
+        self.payout*(1.0-touch_indicator(&[spot],self.barrier,self.is_up,None))
+    }
+    fn path_dependent_payoff(&self,path:&[f64])->f64{
+        self.payout*(1.0-touch_indicator(path,self.barrier,self.is_up,self.bridge.as_ref()))
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        Some(AnalyticPayoffType::NoTouch)
+    }
+}
 
 /// Knock down the call barrier option payoff
 /// 向下敲出看涨障碍期权Payoff
@@ -133,4 +563,742 @@ impl Payoff for DownAndOutCallPayoff{
     fn analytic_type(&self)->Option<AnalyticPayoffType>{
         Some(AnalyticPayoffType::DownAndOutCall)
     }
-}
\ No newline at end of file
+}
+
+/// Floating strike lookback call payoff: S_T - min(S_t) <br>
+/// 浮动执行价回望看涨期权Payoff：S_T - min(S_t)
+#[derive(Debug,Clone,Copy)]
+pub struct FloatingStrikeLookbackCallPayoff{
+    /// minimum already observed before the valuation date (None for a new contract) <br>
+    /// 估值日前已观测到的最低价（新合约为None）
+    pub observed_min:Option<f64>,
+}
+
+impl FloatingStrikeLookbackCallPayoff{
+    pub fn new(observed_min:Option<f64>)->Self{
+        Self{observed_min}
+    }
+}
+
+impl Payoff for FloatingStrikeLookbackCallPayoff{
+    fn payoff(&self,spot:f64)->f64{
+        let running_min=self.observed_min.unwrap_or(spot).min(spot);
+        (spot-running_min).max(0.0)
+    }
+    fn path_dependent_payoff(&self,path:&[f64])->f64{
+        let last=path.last().copied().unwrap_or(0.0);
+        let running_min=path.iter().copied().fold(self.observed_min.unwrap_or(last),f64::min);
+        (last-running_min).max(0.0)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        Some(AnalyticPayoffType::FloatingStrikeLookbackCall)
+    }
+}
+
+/// Floating strike lookback put payoff: max(S_t) - S_T <br>
+/// 浮动执行价回望看跌期权Payoff：max(S_t) - S_T
+#[derive(Debug,Clone,Copy)]
+pub struct FloatingStrikeLookbackPutPayoff{
+    /// maximum already observed before the valuation date (None for a new contract) <br>
+    /// 估值日前已观测到的最高价（新合约为None）
+    pub observed_max:Option<f64>,
+}
+
+impl FloatingStrikeLookbackPutPayoff{
+    pub fn new(observed_max:Option<f64>)->Self{
+        Self{observed_max}
+    }
+}
+
+impl Payoff for FloatingStrikeLookbackPutPayoff{
+    fn payoff(&self,spot:f64)->f64{
+        let running_max=self.observed_max.unwrap_or(spot).max(spot);
+        (running_max-spot).max(0.0)
+    }
+    fn path_dependent_payoff(&self,path:&[f64])->f64{
+        let last=path.last().copied().unwrap_or(0.0);
+        let running_max=path.iter().copied().fold(self.observed_max.unwrap_or(last),f64::max);
+        (running_max-last).max(0.0)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        Some(AnalyticPayoffType::FloatingStrikeLookbackPut)
+    }
+}
+
+/// Fixed strike lookback call payoff: max(max(S_t) - K, 0) <br>
+/// 固定执行价回望看涨期权Payoff：max(max(S_t) - K, 0)
+#[derive(Debug,Clone,Copy)]
+pub struct FixedStrikeLookbackCallPayoff{
+    pub strike:f64,
+    /// maximum already observed before the valuation date (None for a new contract) <br>
+    /// 估值日前已观测到的最高价（新合约为None）
+    pub observed_max:Option<f64>,
+}
+
+impl FixedStrikeLookbackCallPayoff{
+    pub fn new(strike:f64,observed_max:Option<f64>)->Self{
+        Self{strike,observed_max}
+    }
+}
+
+impl Payoff for FixedStrikeLookbackCallPayoff{
+    fn payoff(&self,spot:f64)->f64{
+        let running_max=self.observed_max.unwrap_or(spot).max(spot);
+        (running_max-self.strike).max(0.0)
+    }
+    fn path_dependent_payoff(&self,path:&[f64])->f64{
+        let last=path.last().copied().unwrap_or(0.0);
+        let running_max=path.iter().copied().fold(self.observed_max.unwrap_or(last),f64::max);
+        (running_max-self.strike).max(0.0)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        Some(AnalyticPayoffType::FixedStrikeLookbackCall)
+    }
+}
+
+/// Fixed strike lookback put payoff: max(K - min(S_t), 0) <br>
+/// 固定执行价回望看跌期权Payoff：max(K - min(S_t), 0)
+#[derive(Debug,Clone,Copy)]
+pub struct FixedStrikeLookbackPutPayoff{
+    pub strike:f64,
+    /// minimum already observed before the valuation date (None for a new contract) <br>
+    /// 估值日前已观测到的最低价（新合约为None）
+    pub observed_min:Option<f64>,
+}
+
+impl FixedStrikeLookbackPutPayoff{
+    pub fn new(strike:f64,observed_min:Option<f64>)->Self{
+        Self{strike,observed_min}
+    }
+}
+
+impl Payoff for FixedStrikeLookbackPutPayoff{
+    fn payoff(&self,spot:f64)->f64{
+        let running_min=self.observed_min.unwrap_or(spot).min(spot);
+        (self.strike-running_min).max(0.0)
+    }
+    fn path_dependent_payoff(&self,path:&[f64])->f64{
+        let last=path.last().copied().unwrap_or(0.0);
+        let running_min=path.iter().copied().fold(self.observed_min.unwrap_or(last),f64::min);
+        (self.strike-running_min).max(0.0)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        Some(AnalyticPayoffType::FixedStrikeLookbackPut)
+    }
+}
+
+/// Market data (r, q, σ) under which a delivered option is valued by Black-Scholes <br>
+/// 以BS公式估值所交付期权的市场数据(r, q, σ)
+#[derive(Debug,Clone,Copy)]
+struct Market{
+    risk_free_rate:f64,
+    dividend_yield:f64,
+    volatility:f64,
+}
+
+impl Market{
+    /// Zero rates and volatility: the delivered option is worth its intrinsic value
+    const INTRINSIC:Market=Market{risk_free_rate:0.0,dividend_yield:0.0,volatility:0.0};
+
+    fn of(params:&CommonParams)->Self{
+        Self{
+            risk_free_rate:params.risk_free_rate(),
+            dividend_yield:params.dividend_yield(),
+            volatility:params.volatility(),
+        }
+    }
+
+    fn black_scholes(&self,spot:f64,strike:f64,time_to_maturity:f64,is_call:bool)->f64{
+        black_scholes_price(spot,strike,self.risk_free_rate,self.dividend_yield,self.volatility,time_to_maturity,is_call)
+    }
+}
+
+/// Payoff delivering an option whose value depends on the market data
+trait DeliversOption:Payoff+Copy+'static{
+    fn value_in(&self,market:Market,spot:f64)->f64;
+}
+
+/// Delivering payoff bound to the market data of the pricing parameters; type queries are
+/// forwarded to the wrapped payoff <br>
+/// 绑定定价参数市场数据的交付期权Payoff；类型查询转发至被包装的Payoff
+struct MarketBound<P>{
+    payoff:P,
+    market:Market,
+}
+
+impl<P:DeliversOption> MarketBound<P>{
+    fn new(payoff:P,params:&CommonParams)->Self{
+        Self{payoff,market:Market::of(params)}
+    }
+}
+
+impl<P:DeliversOption> Payoff for MarketBound<P>{
+    fn payoff(&self,spot:f64)->f64{
+        self.payoff.value_in(self.market,spot)
+    }
+    fn as_any(&self)->&dyn Any{
+        self.payoff.as_any()
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        self.payoff.analytic_type()
+    }
+}
+
+/// Compound option payoff: an option expiring at T₁ on a vanilla option expiring at T₂ <br>
+/// 复合期权Payoff：T₁到期、标的为T₂到期普通期权的期权
+///
+/// The payoff is evaluated at the outer expiry T₁ (the time to maturity of the
+/// common parameters), where the inner option is worth its Black-Scholes value under the
+/// market data bound by `bind_market`; unbound, the inner option is worth its intrinsic value <br>
+/// Payoff在外层到期日T₁（即通用参数中的到期时间）计算，此时内层期权价值为`bind_market`所绑定
+/// 市场数据下的BS价格；未绑定时取内在价值
+#[derive(Debug,Clone,Copy)]
+pub struct CompoundOptionPayoff{
+    /// outer option is a call 外层期权是否为看涨
+    pub outer_is_call:bool,
+    /// inner option is a call 内层期权是否为看涨
+    pub inner_is_call:bool,
+    /// strike of the outer option (premium paid for the inner option) 外层执行价
+    pub outer_strike:f64,
+    /// strike of the inner option 内层执行价
+    pub inner_strike:f64,
+    /// T₂ - T₁, life of the inner option after the outer expiry 外层到期后内层期权的剩余期限
+    pub inner_time:f64,
+}
+
+impl CompoundOptionPayoff{
+    /// Value of the inner option at the outer expiry under the market data of `params` <br>
+    /// 以`params`的市场数据计算外层到期日时内层期权的价值
+    pub fn inner_value(&self,params:&CommonParams,spot:f64)->f64{
+        self.inner_value_in(Market::of(params),spot)
+    }
+
+    fn inner_value_in(&self,market:Market,spot:f64)->f64{
+        market.black_scholes(spot,self.inner_strike,self.inner_time,self.inner_is_call)
+    }
+}
+
+impl DeliversOption for CompoundOptionPayoff{
+    fn value_in(&self,market:Market,spot:f64)->f64{
+        let inner=self.inner_value_in(market,spot);
+        if self.outer_is_call{
+            (inner-self.outer_strike).max(0.0)
+        }else{
+            (self.outer_strike-inner).max(0.0)
+        }
+    }
+}
+
+impl Payoff for CompoundOptionPayoff{
+    fn payoff(&self,spot:f64)->f64{
+        self.value_in(Market::INTRINSIC,spot)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn bind_market(&self,params:&CommonParams)->Option<Box<dyn Payoff>>{
+        Some(Box::new(MarketBound::new(*self,params)))
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        Some(match (self.outer_is_call,self.inner_is_call){
+            (true,true)=>AnalyticPayoffType::CompoundCallOnCall,
+            (true,false)=>AnalyticPayoffType::CompoundCallOnPut,
+            (false,true)=>AnalyticPayoffType::CompoundPutOnCall,
+            (false,false)=>AnalyticPayoffType::CompoundPutOnPut,
+        })
+    }
+}
+
+/// Simple chooser payoff: at the choice date the holder picks the more valuable of
+/// a call and a put with the same strike and maturity <br>
+/// 简单选择期权Payoff：选择日持有人在同执行价、同到期日的看涨与看跌期权中择优
+///
+/// Evaluated at the choice date (the time to maturity of the common parameters) under the
+/// market data bound by `bind_market` <br>
+/// 在选择日（即通用参数中的到期时间）以`bind_market`所绑定的市场数据计算
+#[derive(Debug,Clone,Copy)]
+pub struct SimpleChooserPayoff{
+    pub strike:f64,
+    /// T - t, life of the chosen option after the choice date 选择日后所选期权的剩余期限
+    pub remaining_time:f64,
+}
+
+impl DeliversOption for SimpleChooserPayoff{
+    fn value_in(&self,market:Market,spot:f64)->f64{
+        let value=|is_call:bool| market.black_scholes(spot,self.strike,self.remaining_time,is_call);
+        value(true).max(value(false))
+    }
+}
+
+impl Payoff for SimpleChooserPayoff{
+    fn payoff(&self,spot:f64)->f64{
+        self.value_in(Market::INTRINSIC,spot)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn bind_market(&self,params:&CommonParams)->Option<Box<dyn Payoff>>{
+        Some(Box::new(MarketBound::new(*self,params)))
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        Some(AnalyticPayoffType::SimpleChooser)
+    }
+}
+
+/// Complex chooser payoff: the call and the put may have different strikes and maturities <br>
+/// 复杂选择期权Payoff：看涨与看跌期权的执行价和到期日可以不同
+#[derive(Debug,Clone,Copy)]
+pub struct ComplexChooserPayoff{
+    pub call_strike:f64,
+    pub put_strike:f64,
+    /// T_c - t 选择日后看涨期权的剩余期限
+    pub call_remaining_time:f64,
+    /// T_p - t 选择日后看跌期权的剩余期限
+    pub put_remaining_time:f64,
+}
+
+impl ComplexChooserPayoff{
+    /// Value of the call minus the put at the choice date under the market data of `params` <br>
+    /// 以`params`的市场数据计算选择日看涨与看跌期权的价值之差
+    pub fn call_minus_put(&self,params:&CommonParams,spot:f64)->f64{
+        let (call,put)=self.call_and_put(Market::of(params),spot);
+        call-put
+    }
+
+    fn call_and_put(&self,market:Market,spot:f64)->(f64,f64){
+        (
+            market.black_scholes(spot,self.call_strike,self.call_remaining_time,true),
+            market.black_scholes(spot,self.put_strike,self.put_remaining_time,false),
+        )
+    }
+}
+
+impl DeliversOption for ComplexChooserPayoff{
+    fn value_in(&self,market:Market,spot:f64)->f64{
+        let (call,put)=self.call_and_put(market,spot);
+        call.max(put)
+    }
+}
+
+impl Payoff for ComplexChooserPayoff{
+    fn payoff(&self,spot:f64)->f64{
+        self.value_in(Market::INTRINSIC,spot)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn bind_market(&self,params:&CommonParams)->Option<Box<dyn Payoff>>{
+        Some(Box::new(MarketBound::new(*self,params)))
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        Some(AnalyticPayoffType::ComplexChooser)
+    }
+}
+
+/// Forward start payoff: at the start date t₁ the holder receives an at-the-money-forward
+/// option with strike α·S(t₁) expiring at T <br>
+/// 远期生效期权Payoff：生效日t₁获得执行价为α·S(t₁)、T到期的期权
+///
+/// Evaluated at the start date (the time to maturity of the common parameters) under the
+/// market data bound by `bind_market` <br>
+/// 在生效日（即通用参数中的到期时间）以`bind_market`所绑定的市场数据计算
+#[derive(Debug,Clone,Copy)]
+pub struct ForwardStartPayoff{
+    pub is_call:bool,
+    /// α, strike as a fraction of the spot at the start date 执行价相对生效日价格的比例
+    pub strike_ratio:f64,
+    /// T - t₁, life of the option after it starts 生效后期权的剩余期限
+    pub remaining_time:f64,
+}
+
+impl DeliversOption for ForwardStartPayoff{
+    fn value_in(&self,market:Market,spot:f64)->f64{
+        market.black_scholes(spot,self.strike_ratio*spot,self.remaining_time,self.is_call)
+    }
+}
+
+impl Payoff for ForwardStartPayoff{
+    fn payoff(&self,spot:f64)->f64{
+        self.value_in(Market::INTRINSIC,spot)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn bind_market(&self,params:&CommonParams)->Option<Box<dyn Payoff>>{
+        Some(Box::new(MarketBound::new(*self,params)))
+    }
+    fn analytic_type(&self)->Option<AnalyticPayoffType>{
+        Some(if self.is_call{AnalyticPayoffType::ForwardStartCall}else{AnalyticPayoffType::ForwardStartPut})
+    }
+}
+
+/// Annualized realized variance of a path with zero-mean log returns: Σ ln²(Sᵢ₊₁/Sᵢ) / T <br>
+/// 路径的年化已实现方差（零均值对数收益）：Σ ln²(Sᵢ₊₁/Sᵢ) / T
+/// - maturity: time spanned by the path 路径覆盖的时间长度
+pub fn realized_variance(path:&[f64],maturity:f64)->f64{
+    if path.len()<2 || maturity<=0.0{
+        return 0.0;
+    }
+    path.windows(2).map(|w| (w[1]/w[0]).ln().powi(2)).sum::<f64>()/maturity
+}
+
+/// Variance swap payoff: N·(σ²_realized - K_var), paid at expiry <br>
+/// 方差互换Payoff：到期支付N·(σ²_realized - K_var)
+///
+/// The path is annualized over the swap's own maturity. A terminal spot carries no realized
+/// variance, so `payoff` settles on zero variance and terminal value engines reject the swap
+/// through `requires_path` <br>
+/// 路径按互换自身的期限年化。单个终值不含已实现方差，`payoff`按零方差结算，
+/// 按终值估值的引擎经`requires_path`拒绝
+#[derive(Debug,Clone,Copy)]
+pub struct RealizedVariancePayoff{
+    /// K_var, variance strike (σ_K²) 方差行权价
+    pub strike:f64,
+    /// variance notional 方差名义本金
+    pub notional:f64,
+    /// time spanned by the observed path, over which it is annualized 观察路径覆盖的时间长度（年化区间）
+    pub maturity:f64,
+}
+
+impl RealizedVariancePayoff{
+    pub fn new(strike:f64,notional:f64,maturity:f64)->Self{
+        Self{strike,notional,maturity}
+    }
+
+    /// Market quotation by a volatility strike σ_K and a vega notional: K_var = σ_K², and the
+    /// variance notional is N_vega / (2σ_K) <br>
+    /// 按波动率行权价σ_K与vega名义本金报价：K_var = σ_K²，方差名义本金为N_vega / (2σ_K)
+    pub fn from_volatility_strike(volatility_strike:f64,vega_notional:f64,maturity:f64)->Self{
+        Self::new(volatility_strike*volatility_strike,vega_notional/(2.0*volatility_strike),maturity)
+    }
+
+    fn settle(&self,variance:f64)->f64{
+        self.notional*(variance-self.strike)
+    }
+}
+
+impl Payoff for RealizedVariancePayoff{
+    fn payoff(&self,_spot:f64)->f64{
+        self.settle(0.0)
+    }
+    fn path_dependent_payoff(&self,path:&[f64])->f64{
+        self.settle(realized_variance(path,self.maturity))
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn requires_path(&self)->bool{
+        true
+    }
+}
+
+/// Volatility swap payoff: N·(σ_realized - K_vol), paid at expiry; annualized like
+/// `RealizedVariancePayoff` <br>
+/// 波动率互换Payoff：到期支付N·(σ_realized - K_vol)；年化方式同`RealizedVariancePayoff`
+#[derive(Debug,Clone,Copy)]
+pub struct RealizedVolatilityPayoff{
+    /// K_vol, volatility strike 波动率行权价
+    pub strike:f64,
+    /// vega notional vega名义本金
+    pub notional:f64,
+    /// time spanned by the observed path, over which it is annualized 观察路径覆盖的时间长度（年化区间）
+    pub maturity:f64,
+}
+
+impl RealizedVolatilityPayoff{
+    pub fn new(strike:f64,notional:f64,maturity:f64)->Self{
+        Self{strike,notional,maturity}
+    }
+
+    fn settle(&self,variance:f64)->f64{
+        self.notional*(variance.sqrt()-self.strike)
+    }
+}
+
+impl Payoff for RealizedVolatilityPayoff{
+    fn payoff(&self,_spot:f64)->f64{
+        self.settle(0.0)
+    }
+    fn path_dependent_payoff(&self,path:&[f64])->f64{
+        self.settle(realized_variance(path,self.maturity))
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn requires_path(&self)->bool{
+        true
+    }
+}
+
+/// Define the interface for options written on two underlying assets <br>
+/// 定义两资产期权收益计算接口
+pub trait TwoAssetPayoff:Send+Sync{
+    /// calculate option returns at the given prices of the two assets <br>
+    /// 计算给定两资产价格下的期权收益
+    fn payoff(&self,spot1:f64,spot2:f64)->f64;
+
+    /// Calculate the returns of path dependent options
+    /// (implemented as non path dependent by default) <br>
+    /// 计算路径依赖期权的收益（默认实现为非路径依赖）
+    fn path_dependent_payoff(&self,path1:&[f64],path2:&[f64])->f64{
+        self.payoff(
+            path1.last().copied().unwrap_or(0.0),
+            path2.last().copied().unwrap_or(0.0)
+        )
+    }
+
+    /// 向下转型为Any（用于类型识别）
+    fn as_any(&self)->&dyn Any;
+}
+
+/// Spread call option payoff: max(S1 - S2 - K, 0) <br>
+/// 价差看涨期权Payoff：max(S1 - S2 - K, 0)
+///
+/// With K=0 it is the option to exchange asset 2 for asset 1 <br>
+/// K=0时即为以资产2交换资产1的交换期权
+#[derive(Debug,Clone,Copy)]
+pub struct SpreadCallPayoff{
+    pub strike:f64,
+}
+
+impl SpreadCallPayoff{
+    pub fn new(strike:f64)->Self{
+        Self{strike}
+    }
+}
+
+impl TwoAssetPayoff for SpreadCallPayoff{
+    fn payoff(&self,spot1:f64,spot2:f64)->f64{
+        (spot1-spot2-self.strike).max(0.0)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Spread put option payoff: max(K - (S1 - S2), 0) <br>
+/// 价差看跌期权Payoff：max(K - (S1 - S2), 0)
+#[derive(Debug,Clone,Copy)]
+pub struct SpreadPutPayoff{
+    pub strike:f64,
+}
+
+impl SpreadPutPayoff{
+    pub fn new(strike:f64)->Self{
+        Self{strike}
+    }
+}
+
+impl TwoAssetPayoff for SpreadPutPayoff{
+    fn payoff(&self,spot1:f64,spot2:f64)->f64{
+        (self.strike-(spot1-spot2)).max(0.0)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Payoff interface of options written on n assets <br>
+/// n资产期权的Payoff接口
+pub trait MultiAssetPayoff:Send+Sync{
+    /// calculate option returns at the given prices of the assets <br>
+    /// 计算给定各资产价格下的期权收益
+    fn payoff(&self,spots:&[f64])->f64;
+
+    /// Calculate the returns of path dependent options, one path per asset
+    /// (implemented as non path dependent by default) <br>
+    /// 计算路径依赖期权的收益，每个资产一条路径（默认实现为非路径依赖）
+    fn path_dependent_payoff(&self,paths:&[Vec<f64>])->f64{
+        let spots:Vec<f64>=paths.iter().map(|p| p.last().copied().unwrap_or(0.0)).collect();
+        self.payoff(&spots)
+    }
+
+    /// Number of assets the payoff is written on, if fixed by the contract
+    /// (None when any number is accepted) <br>
+    /// 合约确定的标的资产个数（可接受任意个数时为None）
+    fn num_assets(&self)->Option<usize>{
+        None
+    }
+
+    /// 向下转型为Any（用于类型识别）
+    fn as_any(&self)->&dyn Any;
+}
+
+/// Weighted sum Σwᵢ·Sᵢ of the asset prices 资产价格的加权和
+fn basket_value(weights:&[f64],spots:&[f64])->f64{
+    weights.iter().zip(spots).map(|(w,s)| w*s).sum()
+}
+
+/// Basket call option payoff: max(Σwᵢ·Sᵢ - K, 0) <br>
+/// 一篮子看涨期权Payoff：max(Σwᵢ·Sᵢ - K, 0)
+#[derive(Debug,Clone)]
+pub struct BasketCallPayoff{
+    pub weights:Vec<f64>,
+    pub strike:f64,
+}
+
+impl BasketCallPayoff{
+    pub fn new(weights:Vec<f64>,strike:f64)->Self{
+        Self{weights,strike}
+    }
+}
+
+impl MultiAssetPayoff for BasketCallPayoff{
+    fn payoff(&self,spots:&[f64])->f64{
+        (basket_value(&self.weights,spots)-self.strike).max(0.0)
+    }
+    fn num_assets(&self)->Option<usize>{
+        Some(self.weights.len())
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Basket put option payoff: max(K - Σwᵢ·Sᵢ, 0) <br>
+/// 一篮子看跌期权Payoff：max(K - Σwᵢ·Sᵢ, 0)
+#[derive(Debug,Clone)]
+pub struct BasketPutPayoff{
+    pub weights:Vec<f64>,
+    pub strike:f64,
+}
+
+impl BasketPutPayoff{
+    pub fn new(weights:Vec<f64>,strike:f64)->Self{
+        Self{weights,strike}
+    }
+}
+
+impl MultiAssetPayoff for BasketPutPayoff{
+    fn payoff(&self,spots:&[f64])->f64{
+        (self.strike-basket_value(&self.weights,spots)).max(0.0)
+    }
+    fn num_assets(&self)->Option<usize>{
+        Some(self.weights.len())
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Call on the best of the assets: max(max(Sᵢ) - K, 0) <br>
+/// 最优资产看涨期权Payoff：max(max(Sᵢ) - K, 0)
+#[derive(Debug,Clone,Copy)]
+pub struct BestOfCallPayoff{
+    pub strike:f64,
+}
+
+impl BestOfCallPayoff{
+    pub fn new(strike:f64)->Self{
+        Self{strike}
+    }
+}
+
+impl MultiAssetPayoff for BestOfCallPayoff{
+    fn payoff(&self,spots:&[f64])->f64{
+        (spots.iter().copied().fold(f64::NEG_INFINITY,f64::max)-self.strike).max(0.0)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Put on the best of the assets: max(K - max(Sᵢ), 0) <br>
+/// 最优资产看跌期权Payoff：max(K - max(Sᵢ), 0)
+#[derive(Debug,Clone,Copy)]
+pub struct BestOfPutPayoff{
+    pub strike:f64,
+}
+
+impl BestOfPutPayoff{
+    pub fn new(strike:f64)->Self{
+        Self{strike}
+    }
+}
+
+impl MultiAssetPayoff for BestOfPutPayoff{
+    fn payoff(&self,spots:&[f64])->f64{
+        (self.strike-spots.iter().copied().fold(f64::NEG_INFINITY,f64::max)).max(0.0)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Call on the worst of the assets: max(min(Sᵢ) - K, 0) <br>
+/// 最差资产看涨期权Payoff：max(min(Sᵢ) - K, 0)
+#[derive(Debug,Clone,Copy)]
+pub struct WorstOfCallPayoff{
+    pub strike:f64,
+}
+
+impl WorstOfCallPayoff{
+    pub fn new(strike:f64)->Self{
+        Self{strike}
+    }
+}
+
+impl MultiAssetPayoff for WorstOfCallPayoff{
+    fn payoff(&self,spots:&[f64])->f64{
+        (spots.iter().copied().fold(f64::INFINITY,f64::min)-self.strike).max(0.0)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Put on the worst of the assets: max(K - min(Sᵢ), 0) <br>
+/// 最差资产看跌期权Payoff：max(K - min(Sᵢ), 0)
+#[derive(Debug,Clone,Copy)]
+pub struct WorstOfPutPayoff{
+    pub strike:f64,
+}
+
+impl WorstOfPutPayoff{
+    pub fn new(strike:f64)->Self{
+        Self{strike}
+    }
+}
+
+impl MultiAssetPayoff for WorstOfPutPayoff{
+    fn payoff(&self,spots:&[f64])->f64{
+        (self.strike-spots.iter().copied().fold(f64::INFINITY,f64::min)).max(0.0)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Rainbow option paying the best of the assets or cash: max(S₁, S₂, ..., Sₙ, K) <br>
+/// 彩虹期权：获得各资产与现金K中的最大值 max(S₁, S₂, ..., Sₙ, K)
+#[derive(Debug,Clone,Copy)]
+pub struct BestOfAssetsOrCashPayoff{
+    pub cash:f64,
+}
+
+impl BestOfAssetsOrCashPayoff{
+    pub fn new(cash:f64)->Self{
+        Self{cash}
+    }
+}
+
+impl MultiAssetPayoff for BestOfAssetsOrCashPayoff{
+    fn payoff(&self,spots:&[f64])->f64{
+        spots.iter().copied().fold(self.cash,f64::max)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -44,4 +44,52 @@ impl Clone for Box<dyn StochasticProcess> {
     fn clone(&self) -> Box<dyn StochasticProcess> {
         self.clone_box()
     }
-}
\ No newline at end of file
+}
+
+/// Joint paths of several assets, one path per asset 多资产联合路径（每个资产一条路径）
+pub type AssetPaths=Vec<Vec<f64>>;
+
+/// Multi-asset random process interface <br>
+/// 多资产随机过程接口
+pub trait MultiAssetProcess:Debug+Send+Sync{
+    ///To solve dyn Clone problem
+    fn clone_box(&self) -> Box<dyn MultiAssetProcess>;
+
+    /// Initialize the random generator
+    /// 初始化随机生成器
+    fn init_rng_with_seed(&mut self,seed:u64);
+
+    /// Number of simulated underlyings
+    /// 标的资产个数
+    fn num_assets(&self)->usize;
+
+    /// Simulate the joint paths, one path per asset
+    /// 模拟联合路径，每个资产一条路径
+    /// ## parameters
+    /// + initial_prices: 各资产初始价格
+    /// + time_horizon: total time(year) 总时间（年）
+    /// + steps: 步数
+    fn simulate_paths(
+        &mut self,
+        initial_prices:&[f64],
+        time_horizon:f64,
+        steps:usize,
+    )->Result<AssetPaths>;
+
+    /// Simulate the joint paths and their antithetic counterparts
+    /// 模拟联合路径及其对偶路径
+    fn simulate_antithetic_paths(
+        &mut self,
+        _initial_prices:&[f64],
+        _time_horizon:f64,
+        _steps:usize,
+    )->Result<(AssetPaths,AssetPaths)>{
+        Err(OptionError::NotImplemented("Simulate antithetic paths function not implemented".into()))
+    }
+}
+
+impl Clone for Box<dyn MultiAssetProcess> {
+    fn clone(&self) -> Box<dyn MultiAssetProcess> {
+        self.clone_box()
+    }
+}
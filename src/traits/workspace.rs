@@ -0,0 +1,98 @@
+//! Rolling time layers and preallocated buffers passed to `PDEMethod` steps <br>
+//! 传递给`PDEMethod`单步迭代的滚动时间层与预分配缓冲区
+//!
+//! The engine keeps only the layers a scheme needs (two for one-step schemes, three for BDF2)
+//! and reuses one workspace for every tridiagonal and complementarity solve, so memory is
+//! O(x_steps) whatever the number of time steps. <br>
+//! 引擎只保留格式所需的时间层（单步格式两层，BDF2三层），所有三对角及线性互补求解共用同一工作区，
+//! 内存占用为O(x_steps)，与时间步数无关
+
+/// Time layers seen by one backward step: `current` (whose edges already hold the boundary
+/// values) is solved from the later layers <br>
+/// 单步反向迭代所见的时间层：由其后各层求解`current`（边缘已写入边界值）
+#[derive(Debug)]
+pub struct TimeLayers<'a>{
+    /// Layer being solved, at time t 待求解层（t时刻）
+    pub current:&'a mut [f64],
+    /// Layer at t+Δt t+Δt时刻的层
+    pub next:&'a [f64],
+    /// Layer at t+2Δt, kept only for multi-step schemes and absent on the first step <br>
+    /// t+2Δt时刻的层，仅多步格式保留，首步为None
+    pub after_next:Option<&'a [f64]>,
+}
+
+/// Buffers reused by every step of the PDE methods, sized to the number of nodes <br>
+/// PDE方法各步复用的缓冲区，长度为节点数
+#[derive(Debug,Clone,Default)]
+pub struct PdeWorkspace{
+    /// Sub-diagonal (n-1) 下对角线
+    pub lower:Vec<f64>,
+    /// Main diagonal (n) 主对角线
+    pub diagonal:Vec<f64>,
+    /// Super-diagonal (n-1) 上对角线
+    pub upper:Vec<f64>,
+    /// Right-hand side (n) 右端项
+    pub rhs:Vec<f64>,
+    /// L·V of a layer (n) 一个时间层的L·V
+    pub operator:Vec<f64>,
+    /// Continuation values solved by the scheme (n) 格式求得的继续持有价值
+    pub solution:Vec<f64>,
+    /// Exercise values of the layer (n) 该层的行权价值
+    pub obstacle:Vec<f64>,
+    /// Intermediate stage of multi-stage schemes (n) 多阶段格式的中间阶段
+    pub stage:Vec<f64>,
+    /// Scratch space of the tridiagonal and complementarity solvers 三对角及线性互补求解器的临时空间
+    pub scratch:SolverScratch,
+}
+
+impl PdeWorkspace{
+    pub fn new(nodes:usize)->Self{
+        let edge=nodes.saturating_sub(1);
+        Self{
+            lower:vec![0.0;edge],
+            diagonal:vec![0.0;nodes],
+            upper:vec![0.0;edge],
+            rhs:vec![0.0;nodes],
+            operator:vec![0.0;nodes],
+            solution:vec![0.0;nodes],
+            obstacle:vec![0.0;nodes],
+            stage:vec![0.0;nodes],
+            scratch:SolverScratch::new(nodes),
+        }
+    }
+
+    /// Number of nodes the workspace is sized for 工作区对应的节点数
+    pub fn len(&self)->usize{
+        self.diagonal.len()
+    }
+
+    pub fn is_empty(&self)->bool{
+        self.diagonal.is_empty()
+    }
+}
+
+/// Scratch space of the tridiagonal and complementarity solvers, sized to the number of nodes <br>
+/// 三对角及线性互补求解器的临时空间，长度为节点数
+#[derive(Debug,Clone,Default)]
+pub struct SolverScratch{
+    /// Elimination factors of the Thomas and Brennan-Schwartz algorithms (n) <br>
+    /// Thomas与Brennan-Schwartz算法的消元系数
+    pub factors:Vec<f64>,
+    /// Eliminated or penalized right-hand side (n) 消元后或加罚后的右端项
+    pub reduced:Vec<f64>,
+    /// Penalized main diagonal of the penalty method (n) 罚函数法加罚后的主对角线
+    pub penalized:Vec<f64>,
+    /// Penalized rows of the penalty method (n) 罚函数法中被惩罚的行
+    pub active:Vec<bool>,
+}
+
+impl SolverScratch{
+    pub fn new(nodes:usize)->Self{
+        Self{
+            factors:vec![0.0;nodes],
+            reduced:vec![0.0;nodes],
+            penalized:vec![0.0;nodes],
+            active:vec![false;nodes],
+        }
+    }
+}
@@ -7,29 +7,43 @@ pub fn thomas_solver(
     c:&[f64],   // 上对角线(长度n-1)
     d:&[f64],   // 右端项(长度n)
 )->Result<Vec<f64>>{
+    let n=d.len();
+    let mut x=vec![0.0;n];
+    let mut scratch=vec![0.0;n];
+    thomas_solver_into(a,b,c,d,&mut x,&mut scratch)?;
+    Ok(x)
+}
+
+/// Thomas algorithm writing the solution into `x`, with `scratch` (length n) as workspace,
+/// so repeated solves do not allocate <br>
+/// 将解写入`x`的Thomas算法，`scratch`（长度n）为工作区，重复求解时无需分配内存
+pub fn thomas_solver_into(
+    a:&[f64],
+    b:&[f64],
+    c:&[f64],
+    d:&[f64],
+    x:&mut [f64],
+    scratch:&mut [f64],
+)->Result<()>{
     let n=d.len();
     if n==0{
-        return Ok(vec![]);
+        return Ok(());
     }
-    if b.len()!=n || a.len()!=n-1 ||c.len()!=n-1 {
+    if b.len()!=n || a.len()!=n-1 ||c.len()!=n-1 || x.len()!=n || scratch.len()<n{
         return Err(OptionError::InvalidParameter("Thamos algorithm: \n \
         the input dim of array not match".to_string()));
     }
-    if n==1{
-        if b[0].abs()<1e-12{
-            return Err(OptionError::CalculationError("Matrix is singular".to_string()));
-        }
-        return Ok(vec![d[0]/b[0]]);
-    }
-    // 前向消元
-    let mut c_prime=vec![0.0;n];
-    let mut d_prime=vec![0.0;n];
-
     if b[0].abs()<1e-12{
         return Err(OptionError::CalculationError("Matrix is singular".to_string()));
     }
+    if n==1{
+        x[0]=d[0]/b[0];
+        return Ok(());
+    }
+    // 前向消元：scratch存放c'，x暂存d'
+    let c_prime=scratch;
     c_prime[0]=c[0]/b[0];
-    d_prime[0]=d[0]/b[0];
+    x[0]=d[0]/b[0];
 
     for i in 1..n{
         let denominator=b[i]-a[i-1]*c_prime[i-1];
@@ -38,13 +52,354 @@ pub fn thomas_solver(
             Principal element is zero in line {}",i)));
         }
         c_prime[i]=if i<n-1{c[i]/denominator}else{0.0};
-        d_prime[i]=(d[i]-a[i-1]*d_prime[i-1])/denominator;
+        x[i]=(d[i]-a[i-1]*x[i-1])/denominator;
     }
-    let mut x=vec![0.0;n];
-    x[n-1]=d_prime[n-1];
+    // 回代
     for i in (0..n-1).rev(){
-        x[i]=d_prime[i]-c_prime[i]*x[i+1];
+        x[i]-=c_prime[i]*x[i+1];
     }
-    Ok(x)
+    Ok(())
+}
+
+fn validate_tridiagonal(a:&[f64],b:&[f64],c:&[f64],d:&[f64],obstacle:&[f64])->Result<usize>{
+    let n=d.len();
+    if n==0 || b.len()!=n || a.len()!=n-1 || c.len()!=n-1 || obstacle.len()!=n{
+        return Err(OptionError::InvalidParameter("LCP solver: \n \
+        the input dim of array not match".to_string()));
+    }
+    Ok(n)
+}
+
+/// Projected SOR for the tridiagonal LCP A·x ≥ d, x ≥ g, (A·x-d)ᵀ(x-g)=0, writing the solution
+/// into `x` (length n) so repeated solves do not allocate <br>
+/// 投影SOR（PSOR）求解三对角线性互补问题，解写入`x`（长度n），重复求解时无需分配内存
+///
+/// `obstacle`为下界g（无约束处取`f64::NEG_INFINITY`），`initial`为迭代初值
+#[allow(clippy::too_many_arguments)]
+pub fn projected_sor_into(
+    a:&[f64],
+    b:&[f64],
+    c:&[f64],
+    d:&[f64],
+    obstacle:&[f64],
+    initial:&[f64],
+    omega:f64,
+    tolerance:f64,
+    max_iterations:usize,
+    x:&mut [f64],
+)->Result<()>{
+    let n=validate_tridiagonal(a,b,c,d,obstacle)?;
+    if initial.len()!=n || x.len()!=n{
+        return Err(OptionError::InvalidParameter("PSOR: the initial guess dim not match".to_string()));
+    }
+    if !(0.0..2.0).contains(&omega) || omega==0.0{
+        return Err(OptionError::InvalidParameter("PSOR: relaxation factor must lie in (0,2)".to_string()));
+    }
+    for ((x,initial),g) in x.iter_mut().zip(initial).zip(obstacle){
+        *x=initial.max(*g);
+    }
+    for _ in 0..max_iterations{
+        let mut error:f64=0.0;
+        for i in 0..n{
+            let mut residual=d[i];
+            if i>0{residual-=a[i-1]*x[i-1];}
+            if i<n-1{residual-=c[i]*x[i+1];}
+            let gauss_seidel=residual/b[i];
+            let updated=(x[i]+omega*(gauss_seidel-x[i])).max(obstacle[i]);
+            error=error.max((updated-x[i]).abs());
+            x[i]=updated;
+        }
+        if error<tolerance{
+            return Ok(());
+        }
+    }
+    Err(OptionError::ConvergenceError(format!("PSOR did not converge in {} iterations",max_iterations)))
+}
+
+/// Brennan-Schwartz writing the solution into `x`, with `factors` and `reduced` (length n)
+/// holding the eliminated coefficients and right-hand side, so repeated solves do not allocate <br>
+/// 将解写入`x`的Brennan-Schwartz算法，`factors`与`reduced`（长度n）存放消元后的系数与右端项，
+/// 重复求解时无需分配内存
+///
+/// 要求行权区域位于网格一端：下界在低端较大时（看跌型）自上而下消元、自下而上代入并投影，
+/// 否则（看涨型）按Thomas算法消元后自上而下代入并投影
+#[allow(clippy::too_many_arguments)]
+pub fn brennan_schwartz_into(
+    a:&[f64],
+    b:&[f64],
+    c:&[f64],
+    d:&[f64],
+    obstacle:&[f64],
+    x:&mut [f64],
+    factors:&mut [f64],
+    reduced:&mut [f64],
+)->Result<()>{
+    let n=validate_tridiagonal(a,b,c,d,obstacle)?;
+    if x.len()!=n || factors.len()<n || reduced.len()<n{
+        return Err(OptionError::InvalidParameter("LCP solver: \n \
+        the input dim of array not match".to_string()));
+    }
+    let finite=|g:f64| if g.is_finite(){g}else{0.0};
+    let put_like=n>2 && finite(obstacle[1])>finite(obstacle[n-2]);
+    if put_like{
+        // 自第n-1行向第0行消元：x_i = d'_i - a'_i·x_{i-1}
+        let (a_prime,d_prime)=(factors,reduced);
+        let mut pivot=b[n-1];
+        if pivot.abs()<1e-12{
+            return Err(OptionError::CalculationError("Matrix is singular".to_string()));
+        }
+        a_prime[n-1]=a[n-2]/pivot;
+        d_prime[n-1]=d[n-1]/pivot;
+        for i in (0..n-1).rev(){
+            pivot=b[i]-c[i]*a_prime[i+1];
+            if pivot.abs()<1e-12{
+                return Err(OptionError::CalculationError(format!("Brennan-Schwartz: \n \
+                Principal element is zero in line {}",i)));
+            }
+            a_prime[i]=if i>0{a[i-1]/pivot}else{0.0};
+            d_prime[i]=(d[i]-c[i]*d_prime[i+1])/pivot;
+        }
+        x[0]=d_prime[0].max(obstacle[0]);
+        for i in 1..n{
+            x[i]=(d_prime[i]-a_prime[i]*x[i-1]).max(obstacle[i]);
+        }
+    }else{
+        let (c_prime,d_prime)=(factors,reduced);
+        let mut pivot=b[0];
+        if pivot.abs()<1e-12{
+            return Err(OptionError::CalculationError("Matrix is singular".to_string()));
+        }
+        c_prime[0]=if n>1{c[0]/pivot}else{0.0};
+        d_prime[0]=d[0]/pivot;
+        for i in 1..n{
+            pivot=b[i]-a[i-1]*c_prime[i-1];
+            if pivot.abs()<1e-12{
+                return Err(OptionError::CalculationError(format!("Brennan-Schwartz: \n \
+                Principal element is zero in line {}",i)));
+            }
+            c_prime[i]=if i<n-1{c[i]/pivot}else{0.0};
+            d_prime[i]=(d[i]-a[i-1]*d_prime[i-1])/pivot;
+        }
+        x[n-1]=d_prime[n-1].max(obstacle[n-1]);
+        for i in (0..n-1).rev(){
+            x[i]=(d_prime[i]-c_prime[i]*x[i+1]).max(obstacle[i]);
+        }
+    }
+    Ok(())
+}
+
+/// Penalty method writing the solution into `x`; `penalized`, `rhs` and `factors` (length n)
+/// hold the penalized system and the Thomas factors and `active` the penalized rows, so
+/// repeated solves do not allocate <br>
+/// 将解写入`x`的罚函数法，`penalized`、`rhs`与`factors`（长度n）存放加罚后的方程组及Thomas消元系数，
+/// `active`记录被惩罚的行，重复求解时无需分配内存
+///
+/// 迭代求解 (A+P)·x = d+P·g，其中约束被违反（x_i<g_i）的行P_ii=ρ，直到约束集合不再变化；
+/// 收敛后对违反量为O(1/ρ)的节点投影到下界
+#[allow(clippy::too_many_arguments)]
+pub fn penalty_lcp_into(
+    a:&[f64],
+    b:&[f64],
+    c:&[f64],
+    d:&[f64],
+    obstacle:&[f64],
+    penalty:f64,
+    max_iterations:usize,
+    x:&mut [f64],
+    penalized:&mut [f64],
+    rhs:&mut [f64],
+    factors:&mut [f64],
+    active:&mut [bool],
+)->Result<()>{
+    let n=validate_tridiagonal(a,b,c,d,obstacle)?;
+    if penalty<=0.0{
+        return Err(OptionError::InvalidParameter("Penalty factor must be greater than zero".to_string()));
+    }
+    if penalized.len()<n || rhs.len()<n || active.len()<n{
+        return Err(OptionError::InvalidParameter("LCP solver: \n \
+        the input dim of array not match".to_string()));
+    }
+    let (penalized,rhs,active)=(&mut penalized[..n],&mut rhs[..n],&mut active[..n]);
+    thomas_solver_into(a,b,c,d,x,factors)?;
+    for i in 0..n{
+        active[i]=x[i]<obstacle[i];
+    }
+    for _ in 0..max_iterations{
+        for i in 0..n{
+            penalized[i]=if active[i]{b[i]+penalty}else{b[i]};
+            rhs[i]=if active[i]{d[i]+penalty*obstacle[i]}else{d[i]};
+        }
+        thomas_solver_into(a,penalized,c,rhs,x,factors)?;
+        // x_i≤g_i的节点保持惩罚，避免策略在边界附近来回切换
+        let mut changed=false;
+        for i in 0..n{
+            let updated=x[i]<=obstacle[i];
+            changed|=updated!=active[i];
+            active[i]=updated;
+        }
+        if !changed{
+            for (x,g) in x.iter_mut().zip(obstacle){
+                *x=x.max(*g);
+            }
+            return Ok(());
+        }
+    }
+    Err(OptionError::ConvergenceError(format!("Penalty iteration did not converge in {} iterations",max_iterations)))
+}
 
-}
\ No newline at end of file
+/// Cholesky分解：将对称正定矩阵分解为 A = L·Lᵀ，返回下三角矩阵L
+///
+/// 半正定矩阵（如完全相关的相关系数矩阵）对应的零主元按0处理
+pub fn cholesky_decomposition(matrix:&[Vec<f64>])->Result<Vec<Vec<f64>>>{
+    let n=matrix.len();
+    if n==0{
+        return Err(OptionError::EmptyData);
+    }
+    if matrix.iter().any(|row| row.len()!=n){
+        return Err(OptionError::InvalidParameter("Cholesky decomposition: \n \
+        the matrix must be square".to_string()));
+    }
+    let mut lower=vec![vec![0.0;n];n];
+    for i in 0..n{
+        for j in 0..=i{
+            if (matrix[i][j]-matrix[j][i]).abs()>1e-12{
+                return Err(OptionError::InvalidParameter("Cholesky decomposition: \n \
+                the matrix must be symmetric".to_string()));
+            }
+            let sum:f64=(0..j).map(|k| lower[i][k]*lower[j][k]).sum();
+            if i==j{
+                let pivot=matrix[i][i]-sum;
+                if pivot< -1e-10{
+                    return Err(OptionError::CalculationError(format!("Cholesky decomposition: \n \
+                    matrix is not positive semi-definite (pivot {} in line {})",pivot,i)));
+                }
+                lower[i][j]=pivot.max(0.0).sqrt();
+            }else if lower[j][j]>1e-14{
+                lower[i][j]=(matrix[i][j]-sum)/lower[j][j];
+            }else{
+                // 零主元：该列已被前面的因子完全解释
+                if (matrix[i][j]-sum).abs()>1e-8{
+                    return Err(OptionError::CalculationError(format!("Cholesky decomposition: \n \
+                    matrix is not positive semi-definite (line {})",i)));
+                }
+                lower[i][j]=0.0;
+            }
+        }
+    }
+    Ok(lower)
+}
+
+/// 最小二乘回归：求解 min‖X·β - y‖²，`design`的每一行为一个样本的基函数取值
+///
+/// 通过正规方程 XᵀX·β = Xᵀy 与Cholesky分解求解，零主元对应的系数取0
+#[allow(clippy::needless_range_loop)]
+pub fn least_squares(design:&[Vec<f64>],y:&[f64])->Result<Vec<f64>>{
+    if design.is_empty(){
+        return Err(OptionError::EmptyData);
+    }
+    let k=design[0].len();
+    if design.len()!=y.len() || design.iter().any(|row| row.len()!=k){
+        return Err(OptionError::InvalidParameter("Least squares: \n \
+        the dim of design matrix and observations not match".to_string()));
+    }
+    let mut normal=vec![vec![0.0;k];k];
+    let mut rhs=vec![0.0;k];
+    for (row,yi) in design.iter().zip(y){
+        for i in 0..k{
+            rhs[i]+=row[i]*yi;
+            for j in 0..=i{
+                normal[i][j]+=row[i]*row[j];
+            }
+        }
+    }
+    for i in 0..k{
+        for j in i+1..k{
+            normal[i][j]=normal[j][i];
+        }
+    }
+    let lower=cholesky_decomposition(&normal)?;
+    // 前代 L·z = Xᵀy，回代 Lᵀ·β = z
+    let mut z=vec![0.0;k];
+    for i in 0..k{
+        let sum:f64=(0..i).map(|j| lower[i][j]*z[j]).sum();
+        z[i]=if lower[i][i]>1e-14{(rhs[i]-sum)/lower[i][i]}else{0.0};
+    }
+    let mut beta=vec![0.0;k];
+    for i in (0..k).rev(){
+        let sum:f64=(i+1..k).map(|j| lower[j][i]*beta[j]).sum();
+        beta[i]=if lower[i][i]>1e-14{(z[i]-sum)/lower[i][i]}else{0.0};
+    }
+    Ok(beta)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    #[allow(clippy::needless_range_loop)]
+    fn test_cholesky_decomposition()->Result<()>{
+        let matrix=vec![
+            vec![1.0,0.5,0.2],
+            vec![0.5,1.0,0.3],
+            vec![0.2,0.3,1.0],
+        ];
+        let lower=cholesky_decomposition(&matrix)?;
+        for i in 0..3{
+            for j in 0..3{
+                let product:f64=(0..3).map(|k| lower[i][k]*lower[j][k]).sum();
+                assert_approx_eq!(product,matrix[i][j],1e-12);
+            }
+            for j in i+1..3{
+                assert_eq!(lower[i][j],0.0);
+            }
+        }
+        // 非正定矩阵应报错
+        let invalid=vec![vec![1.0,0.9,-0.9],vec![0.9,1.0,0.9],vec![-0.9,0.9,1.0]];
+        assert!(cholesky_decomposition(&invalid).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_least_squares()->Result<()>{
+        // y = 1 + 2x - 0.5x² 精确拟合
+        let xs=[0.0,0.5,1.0,1.5,2.0,3.0];
+        let design:Vec<Vec<f64>>=xs.iter().map(|x| vec![1.0,*x,x*x]).collect();
+        let y:Vec<f64>=xs.iter().map(|x| 1.0+2.0*x-0.5*x*x).collect();
+        let beta=least_squares(&design,&y)?;
+        for (b,expected) in beta.iter().zip([1.0,2.0,-0.5]){
+            assert_approx_eq!(*b,expected,1e-10);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_lcp_solvers_agree()->Result<()>{
+        // 看跌型下界：低端节点被约束
+        let n=21;
+        let a=vec![-1.0;n-1];
+        let b=vec![2.2;n];
+        let c=vec![-1.0;n-1];
+        let d=vec![0.1;n];
+        let obstacle:Vec<f64>=(0..n).map(|i| (1.0-0.1*i as f64).max(0.0)).collect();
+        let (mut psor,mut direct,mut penalty)=(vec![0.0;n],vec![0.0;n],vec![0.0;n]);
+        let (mut factors,mut reduced,mut penalized,mut active)=(vec![0.0;n],vec![0.0;n],vec![0.0;n],vec![false;n]);
+        projected_sor_into(&a,&b,&c,&d,&obstacle,&obstacle,1.5,1e-14,10_000,&mut psor)?;
+        brennan_schwartz_into(&a,&b,&c,&d,&obstacle,&mut direct,&mut factors,&mut reduced)?;
+        penalty_lcp_into(&a,&b,&c,&d,&obstacle,1e10,100,&mut penalty,&mut penalized,&mut reduced,&mut factors,&mut active)?;
+        for i in 0..n{
+            assert_approx_eq!(psor[i],direct[i],1e-10);
+            assert_approx_eq!(psor[i],penalty[i],1e-8);
+            assert!(psor[i]>=obstacle[i]);
+        }
+        // 看涨型下界同样一致
+        let reversed:Vec<f64>=obstacle.iter().rev().copied().collect();
+        projected_sor_into(&a,&b,&c,&d,&reversed,&reversed,1.5,1e-14,10_000,&mut psor)?;
+        brennan_schwartz_into(&a,&b,&c,&d,&reversed,&mut direct,&mut factors,&mut reduced)?;
+        for i in 0..n{
+            assert_approx_eq!(psor[i],direct[i],1e-10);
+        }
+        Ok(())
+    }
+}
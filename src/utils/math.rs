@@ -1,5 +1,6 @@
 //! 通用数学工具函数
 
+use num_complex::Complex64;
 use crate::errors::*;
 
 /// 计算百分比值（用于风险价值等计算）
@@ -7,7 +8,7 @@ pub fn calc_percentage(data: &mut [f64], pct: f64) -> Result<f64> {
     if data.is_empty() {
         return Err(OptionError::InvalidParameter("Data is empty".to_string()));
     }
-    if pct < 0.0 || pct > 1.0 {
+    if !(0.0..=1.0).contains(&pct) {
         return Err(OptionError::InvalidParameter("Percentage must between 0 and 1.0".to_string()));
     }
 
@@ -72,12 +73,217 @@ pub fn linear_interpolate(x: f64, x_min: f64, dx: f64,grid: &[f64])->Result<f64>
     Ok(grid[i_floor]*(1.0-weight)+grid[i_ceil]*weight)
 }
 
+/// Quadratic (three-point Lagrange) interpolation on a possibly non-uniform grid;
+/// values outside [nodes[0], nodes[n-1]] are clamped to the end values <br>
+/// 非均匀网格上的二次（三点Lagrange）插值，超出网格范围时取端点值
+///
+/// # example:
+/// ```rust
+/// use assert_approx_eq::assert_approx_eq;
+/// use optionrs::utils::math::quadratic_interpolate;
+///
+/// let nodes=vec![0.0,1.0,3.0];
+/// let values:Vec<f64>=nodes.iter().map(|x| x*x).collect();
+/// assert_approx_eq!(quadratic_interpolate(2.0,&nodes,&values).unwrap(),4.0);
+/// ```
+pub fn quadratic_interpolate(x:f64,nodes:&[f64],values:&[f64])->Result<f64>{
+    let n=nodes.len();
+    if n<3 || values.len()!=n{
+        return Err(OptionError::InvalidParameter("Quadratic interpolation needs at least 3 nodes with matching values".to_string()));
+    }
+    if x<=nodes[0]{
+        return Ok(values[0]);
+    }
+    if x>=nodes[n-1]{
+        return Ok(values[n-1]);
+    }
+    // 取最接近x的三个节点
+    let upper=nodes.partition_point(|&node| node<x);
+    let mid=if upper<n-1 && x-nodes[upper-1]>nodes[upper]-x{upper}else{upper-1};
+    let start=mid.clamp(1,n-2)-1;
+    let (x0,x1,x2)=(nodes[start],nodes[start+1],nodes[start+2]);
+    Ok(values[start]*(x-x1)*(x-x2)/((x0-x1)*(x0-x2))
+        +values[start+1]*(x-x0)*(x-x2)/((x1-x0)*(x1-x2))
+        +values[start+2]*(x-x0)*(x-x1)/((x2-x0)*(x2-x1)))
+}
 
+/// Brent's method for a root of `f` in the bracket [a,b] (f(a) and f(b) must have opposite signs) <br>
+/// Brent法求f在区间[a,b]内的根（要求f(a)与f(b)异号）
+///
+/// # example:
+/// ```rust
+/// use assert_approx_eq::assert_approx_eq;
+/// use optionrs::utils::math::brent_root;
+///
+/// let root=brent_root(|x| x*x-2.0,0.0,2.0,1e-12,100).unwrap();
+/// assert_approx_eq!(root,2f64.sqrt(),1e-10);
+/// ```
+pub fn brent_root<F:Fn(f64)->f64>(f:F,a:f64,b:f64,tol:f64,max_iter:usize)->Result<f64>{
+    let (mut a,mut b)=(a,b);
+    let (mut fa,mut fb)=(f(a),f(b));
+    if fa==0.0{
+        return Ok(a);
+    }
+    if fb==0.0{
+        return Ok(b);
+    }
+    if fa*fb>0.0{
+        return Err(OptionError::InvalidParameter("Root is not bracketed by the interval".to_string()));
+    }
+    let (mut c,mut fc)=(a,fa);
+    let mut d=b-a;
+    let mut e=d;
+    for _ in 0..max_iter{
+        if fb*fc>0.0{
+            c=a;
+            fc=fa;
+            d=b-a;
+            e=d;
+        }
+        if fc.abs()<fb.abs(){
+            a=b;
+            b=c;
+            c=a;
+            fa=fb;
+            fb=fc;
+            fc=fa;
+        }
+        let tol1=2.0*f64::EPSILON*b.abs()+0.5*tol;
+        let m=0.5*(c-b);
+        if m.abs()<=tol1 || fb==0.0{
+            return Ok(b);
+        }
+        if e.abs()>=tol1 && fa.abs()>fb.abs(){
+            // 逆二次插值/割线步
+            let s=fb/fa;
+            let (mut p,mut q)=if a==c{
+                (2.0*m*s,1.0-s)
+            }else{
+                let q=fa/fc;
+                let r=fb/fc;
+                (s*(2.0*m*q*(q-r)-(b-a)*(r-1.0)),(q-1.0)*(r-1.0)*(s-1.0))
+            };
+            if p>0.0{
+                q= -q;
+            }else{
+                p= -p;
+            }
+            if 2.0*p<(3.0*m*q-(tol1*q).abs()).min((e*q).abs()){
+                e=d;
+                d=p/q;
+            }else{
+                d=m;
+                e=m;
+            }
+        }else{
+            // 二分步
+            d=m;
+            e=m;
+        }
+        a=b;
+        fa=fb;
+        b+=if d.abs()>tol1{d}else{tol1.copysign(m)};
+        fb=f(b);
+    }
+    Err(OptionError::ConvergenceError(format!("Brent's method did not converge in {} iterations",max_iter)))
+}
+
+/// Gauss-Legendre quadrature nodes and weights on [-1, 1] <br>
+/// [-1, 1]上的Gauss-Legendre求积节点与权重
+///
+/// # example:
+/// ```rust
+/// use assert_approx_eq::assert_approx_eq;
+/// use optionrs::utils::math::gauss_legendre;
+///
+/// let (nodes,weights)=gauss_legendre(8);
+/// let integral:f64=nodes.iter().zip(&weights).map(|(x,w)| w*x.powi(6)).sum();
+/// assert_approx_eq!(integral,2.0/7.0,1e-14);
+/// ```
+pub fn gauss_legendre(n:usize)->(Vec<f64>,Vec<f64>){
+    let mut nodes=vec![0.0;n];
+    let mut weights=vec![0.0;n];
+    for i in 0..n.div_ceil(2){
+        // 以Chebyshev近似为初值，对Legendre多项式做Newton迭代
+        let mut x=(std::f64::consts::PI*(i as f64+0.75)/(n as f64+0.5)).cos();
+        let mut derivative=0.0;
+        for _ in 0..100{
+            let (mut p0,mut p1)=(1.0,x);
+            for k in 2..=n{
+                let p2=((2*k-1) as f64*x*p1-(k-1) as f64*p0)/k as f64;
+                p0=p1;
+                p1=p2;
+            }
+            let p=if n==0{1.0}else if n==1{x}else{p1};
+            derivative=n as f64*(x*p-if n==1{1.0}else{p0})/(x*x-1.0);
+            let dx=p/derivative;
+            x-=dx;
+            if dx.abs()<1e-15{
+                break;
+            }
+        }
+        nodes[i]= -x;
+        nodes[n-1-i]=x;
+        weights[i]=2.0/((1.0-x*x)*derivative*derivative);
+        weights[n-1-i]=weights[i];
+    }
+    (nodes,weights)
+}
+
+/// In-place radix-2 fast Fourier transform X_k = Σⱼ xⱼ·e^{-2πijk/N}; N must be a power of two <br>
+/// 原地基2快速傅里叶变换X_k = Σⱼ xⱼ·e^{-2πijk/N}，N须为2的幂
+///
+/// # example:
+/// ```rust
+/// use assert_approx_eq::assert_approx_eq;
+/// use num_complex::Complex64;
+/// use optionrs::utils::math::fft;
+///
+/// let mut values=vec![Complex64::new(1.0,0.0),Complex64::new(2.0,0.0),Complex64::new(3.0,0.0),Complex64::new(4.0,0.0)];
+/// fft(&mut values).unwrap();
+/// assert_approx_eq!(values[0].re,10.0);
+/// assert_approx_eq!(values[1].re,-2.0);
+/// assert_approx_eq!(values[1].im,2.0);
+/// ```
+pub fn fft(values:&mut [Complex64])->Result<()>{
+    let n=values.len();
+    if !n.is_power_of_two(){
+        return Err(OptionError::InvalidParameter(format!("FFT length {} is not a power of two",n)));
+    }
+    // 位反转重排
+    let mut j=0;
+    for i in 1..n{
+        let mut bit=n>>1;
+        while j&bit!=0{
+            j^=bit;
+            bit>>=1;
+        }
+        j|=bit;
+        if i<j{
+            values.swap(i,j);
+        }
+    }
+    // 逐层蝶形运算
+    let mut len=2;
+    while len<=n{
+        let root=Complex64::from_polar(1.0,-2.0*std::f64::consts::PI/len as f64);
+        for start in (0..n).step_by(len){
+            let mut twiddle=Complex64::new(1.0,0.0);
+            for k in 0..len/2{
+                let (a,b)=(values[start+k],values[start+k+len/2]*twiddle);
+                values[start+k]=a+b;
+                values[start+k+len/2]=a-b;
+                twiddle*=root;
+            }
+        }
+        len<<=1;
+    }
+    Ok(())
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::errors::*;
     use assert_approx_eq::assert_approx_eq;
     #[test]
     fn test_linear_interpolate_inside() ->Result<()> {
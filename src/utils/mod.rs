@@ -1,3 +1,4 @@
 pub mod statistics;
 pub mod math;
-pub mod linear_algebra;
\ No newline at end of file
+pub mod linear_algebra;
+pub mod optimization;
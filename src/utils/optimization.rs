@@ -0,0 +1,224 @@
+//! Derivative-free minimisation 无导数优化
+use crate::errors::*;
+
+/// Result of a minimisation 最小化结果
+#[derive(Debug,Clone,PartialEq)]
+pub struct Minimum{
+    /// Best point found 找到的最优点
+    pub point:Vec<f64>,
+    /// Objective value at `point` `point`处的目标函数值
+    pub value:f64,
+    /// Iterations used 迭代次数
+    pub iterations:usize,
+    /// Whether the tolerance was reached before the iteration limit 是否在迭代上限前达到容差
+    pub converged:bool,
+}
+
+/// Nelder-Mead simplex minimisation of `f`, starting from the simplex spanned by `initial`
+/// and `initial + step[i]·eᵢ`; stops when the spread of the simplex values falls below
+/// `tolerance`. Non-finite objective values are treated as +∞ <br>
+/// Nelder-Mead单纯形法最小化`f`：初始单纯形由`initial`与`initial + step[i]·eᵢ`张成，单纯形上
+/// 函数值的极差小于`tolerance`时停止；非有限的目标值视为+∞
+///
+/// # example:
+/// ```rust
+/// use assert_approx_eq::assert_approx_eq;
+/// use optionrs::utils::optimization::nelder_mead;
+///
+/// let rosenbrock=|x:&[f64]| (1.0-x[0]).powi(2)+100.0*(x[1]-x[0]*x[0]).powi(2);
+/// let minimum=nelder_mead(rosenbrock,&[-1.2,1.0],&[0.5,0.5],1e-16,5000).unwrap();
+/// assert_approx_eq!(minimum.point[0],1.0,1e-5);
+/// assert_approx_eq!(minimum.point[1],1.0,1e-5);
+/// ```
+#[allow(clippy::needless_range_loop)]
+pub fn nelder_mead<F:Fn(&[f64])->f64>(
+    f:F,
+    initial:&[f64],
+    step:&[f64],
+    tolerance:f64,
+    max_iterations:usize,
+)->Result<Minimum>{
+    let n=initial.len();
+    if n==0 || step.len()!=n{
+        return Err(OptionError::InvalidParameter("Nelder-Mead needs a non-empty start point and one step per dimension".to_string()));
+    }
+    let objective=|x:&[f64]|{
+        let value=f(x);
+        if value.is_finite(){value}else{f64::INFINITY}
+    };
+    let mut simplex:Vec<Vec<f64>>=(0..=n).map(|i|{
+        let mut vertex=initial.to_vec();
+        if i>0{
+            vertex[i-1]+=step[i-1];
+        }
+        vertex
+    }).collect();
+    let mut values:Vec<f64>=simplex.iter().map(|v| objective(v)).collect();
+
+    let (alpha,gamma,rho,sigma)=(1.0,2.0,0.5,0.5);
+    let mut iterations=0;
+    let mut converged=false;
+    while iterations<max_iterations{
+        // 按函数值排序
+        let mut order:Vec<usize>=(0..=n).collect();
+        order.sort_by(|&i,&j| values[i].total_cmp(&values[j]));
+        simplex=order.iter().map(|&i| simplex[i].clone()).collect();
+        values=order.iter().map(|&i| values[i]).collect();
+        if (values[n]-values[0]).abs()<=tolerance{
+            converged=true;
+            break;
+        }
+        iterations+=1;
+
+        let centroid:Vec<f64>=(0..n).map(|d| simplex[..n].iter().map(|v| v[d]).sum::<f64>()/n as f64).collect();
+        let towards=|coefficient:f64|->Vec<f64>{
+            (0..n).map(|d| centroid[d]+coefficient*(simplex[n][d]-centroid[d])).collect()
+        };
+        let reflected=towards(-alpha);
+        let reflected_value=objective(&reflected);
+        if reflected_value<values[0]{
+            let expanded=towards(-gamma);
+            let expanded_value=objective(&expanded);
+            if expanded_value<reflected_value{
+                simplex[n]=expanded;
+                values[n]=expanded_value;
+            }else{
+                simplex[n]=reflected;
+                values[n]=reflected_value;
+            }
+            continue;
+        }
+        if reflected_value<values[n-1]{
+            simplex[n]=reflected;
+            values[n]=reflected_value;
+            continue;
+        }
+        // 收缩：反射点优于最差点时外收缩，否则内收缩
+        let (contracted,bound)=if reflected_value<values[n]{
+            (towards(-rho),reflected_value)
+        }else{
+            (towards(rho),values[n])
+        };
+        let contracted_value=objective(&contracted);
+        if contracted_value<bound{
+            simplex[n]=contracted;
+            values[n]=contracted_value;
+            continue;
+        }
+        // 向最优点压缩
+        for i in 1..=n{
+            for d in 0..n{
+                simplex[i][d]=simplex[0][d]+sigma*(simplex[i][d]-simplex[0][d]);
+            }
+            values[i]=objective(&simplex[i]);
+        }
+    }
+    let best=(0..=n).min_by(|&i,&j| values[i].total_cmp(&values[j])).unwrap_or(0);
+    Ok(Minimum{
+        point:simplex[best].clone(),
+        value:values[best],
+        iterations,
+        converged,
+    })
+}
+
+/// Levenberg-Marquardt minimisation of ‖r(x)‖² within the box [lower, upper]; the Jacobian
+/// is taken by forward differences and steps are projected onto the box. Stops when the
+/// relative decrease of the cost or the relative step falls below `tolerance` <br>
+/// 在区间[lower, upper]内用Levenberg-Marquardt法最小化‖r(x)‖²：Jacobian由前向差分得到，
+/// 步长投影回区间；目标函数的相对下降或相对步长小于`tolerance`时停止
+///
+/// # example:
+/// ```rust
+/// use assert_approx_eq::assert_approx_eq;
+/// use optionrs::utils::optimization::levenberg_marquardt;
+///
+/// // 拟合 y = a·e^{-b·x}
+/// let xs:Vec<f64>=(0..10).map(|i| i as f64*0.5).collect();
+/// let ys:Vec<f64>=xs.iter().map(|x| 2.0*(-0.7*x).exp()).collect();
+/// let residuals=|p:&[f64]| xs.iter().zip(&ys).map(|(x,y)| p[0]*(-p[1]*x).exp()-y).collect::<Vec<f64>>();
+/// let minimum=levenberg_marquardt(residuals,&[1.0,0.1],&[0.0,0.0],&[10.0,10.0],1e-14,200).unwrap();
+/// assert_approx_eq!(minimum.point[0],2.0,1e-6);
+/// assert_approx_eq!(minimum.point[1],0.7,1e-6);
+/// ```
+pub fn levenberg_marquardt<F:Fn(&[f64])->Vec<f64>>(
+    residuals:F,
+    initial:&[f64],
+    lower:&[f64],
+    upper:&[f64],
+    tolerance:f64,
+    max_iterations:usize,
+)->Result<Minimum>{
+    let n=initial.len();
+    if n==0 || lower.len()!=n || upper.len()!=n || (0..n).any(|i| lower[i]>upper[i]){
+        return Err(OptionError::InvalidParameter("Levenberg-Marquardt needs a non-empty start point and consistent bounds".to_string()));
+    }
+    let project=|x:&mut [f64]| for i in 0..n{x[i]=x[i].clamp(lower[i],upper[i]);};
+    let cost=|r:&[f64]|{
+        let value:f64=r.iter().map(|e| e*e).sum();
+        if value.is_finite(){value}else{f64::INFINITY}
+    };
+    let mut x=initial.to_vec();
+    project(&mut x);
+    let mut r=residuals(&x);
+    let mut value=cost(&r);
+    if !value.is_finite(){
+        return Err(OptionError::InvalidParameter("Residuals are not finite at the initial point".to_string()));
+    }
+    let m=r.len();
+    let mut lambda=1e-3;
+    let mut iterations=0;
+    let mut converged=false;
+    while iterations<max_iterations && !converged{
+        iterations+=1;
+        // 前向差分Jacobian，靠近上界时改为后向差分
+        let mut jacobian=vec![vec![0.0;n];m];
+        for j in 0..n{
+            let mut h=1e-7*x[j].abs().max(1e-3);
+            if x[j]+h>upper[j]{
+                h= -h;
+            }
+            let mut shifted=x.clone();
+            shifted[j]+=h;
+            let r_shifted=residuals(&shifted);
+            for i in 0..m{
+                jacobian[i][j]=(r_shifted[i]-r[i])/h;
+            }
+        }
+        let scale:Vec<f64>=(0..n).map(|j| (0..m).map(|i| jacobian[i][j]*jacobian[i][j]).sum::<f64>().max(1e-12)).collect();
+        // 增大阻尼直到目标函数下降：求解 min‖J·δ + r‖² + λ·Σ scaleⱼ·δⱼ²
+        loop{
+            let mut design=jacobian.clone();
+            let mut target:Vec<f64>=r.iter().map(|e| -e).collect();
+            for j in 0..n{
+                let mut row=vec![0.0;n];
+                row[j]=(lambda*scale[j]).sqrt();
+                design.push(row);
+                target.push(0.0);
+            }
+            let step=crate::utils::linear_algebra::least_squares(&design,&target)?;
+            let mut candidate:Vec<f64>=(0..n).map(|j| x[j]+step[j]).collect();
+            project(&mut candidate);
+            let r_candidate=residuals(&candidate);
+            let candidate_value=cost(&r_candidate);
+            let step_norm=(0..n).map(|j| (candidate[j]-x[j]).powi(2)).sum::<f64>().sqrt();
+            let x_norm=x.iter().map(|v| v*v).sum::<f64>().sqrt();
+            if candidate_value<value{
+                let decrease=(value-candidate_value)/value.max(f64::MIN_POSITIVE);
+                converged=decrease<tolerance || step_norm<=tolerance*(x_norm+tolerance);
+                x=candidate;
+                r=r_candidate;
+                value=candidate_value;
+                lambda=(lambda/3.0).max(1e-12);
+                break;
+            }
+            lambda*=4.0;
+            // 阻尼过大或步长已可忽略：已到达局部极小
+            if lambda>1e12 || step_norm<=tolerance*(x_norm+tolerance){
+                converged=true;
+                break;
+            }
+        }
+    }
+    Ok(Minimum{point:x,value,iterations,converged})
+}
@@ -9,6 +9,18 @@ pub fn norm_cdf(x:f64)->f64{
     normal.cdf(x)
 }
 
+/// Inverse of the standard normal CDF (quantile function) <br>
+/// 标准正态分布CDF的反函数（分位数函数）
+pub fn norm_inv(p:f64)->f64{
+    let normal=Normal::new(0.0, 1.0).expect("Failed to create normal distribution");
+    let x=normal.inverse_cdf(p);
+    if !x.is_finite(){
+        return x;
+    }
+    // 一步Newton修正statrs反函数的截断误差
+    x-(normal.cdf(x)-p)/normal.pdf(x)
+}
+
 /// Standard normal distribution pdf <br>
 /// 标准正态分布的PDF（概率密度函数)
 pub fn norm_pdf(x:f64)->f64{
@@ -26,28 +38,141 @@ pub fn calculate_d1_d2(
     time_to_maturity: f64,
 )->Result<(f64,f64)>{
     if spot<=0.0{
-        return Err(OptionError::InvalidParameter("Spot must be greater than zero.".to_owned()).into());
+        return Err(OptionError::InvalidParameter("Spot must be greater than zero.".to_owned()));
     }
     if strike<=0.0{
-        return Err(OptionError::InvalidParameter("Strike must be greater than zero.".to_owned()).into());
+        return Err(OptionError::InvalidParameter("Strike must be greater than zero.".to_owned()));
     }
     if volatility<=0.0{
-        return Err(OptionError::InvalidParameter("Volatility must be greater than zero.".to_owned()).into());
+        return Err(OptionError::InvalidParameter("Volatility must be greater than zero.".to_owned()));
     }
     if time_to_maturity<0.0{
-        return Err(OptionError::InvalidParameter("Time to maturity cannot be negative.".to_owned()).into());
+        return Err(OptionError::InvalidParameter("Time to maturity cannot be negative.".to_owned()));
     }
     if time_to_maturity==0.0{
         return Err(OptionError::InvalidParameter("When the expiration time is 0,there is \
-        no analytic solution (return intrinsic value directly)".to_owned()).into());
+        no analytic solution (return intrinsic value directly)".to_owned()));
     }
     let ln_sk=(spot/strike).ln();
     let sigma_sqrt_t=volatility*time_to_maturity.sqrt();
-    let d1=(ln_sk+(risk_free_rate-dividend_yield+0.5*volatility.powi(2)*time_to_maturity))/sigma_sqrt_t;
+    let d1=(ln_sk+(risk_free_rate-dividend_yield+0.5*volatility.powi(2))*time_to_maturity)/sigma_sqrt_t;
     let d2=d1-sigma_sqrt_t;
     Ok((d1,d2))
 }
 
+/// Black-Scholes-Merton price of a vanilla call/put.
+/// Falls back to the intrinsic value of the forward when t=0 or σ=0,
+/// so it can be evaluated inside payoffs without error handling <br>
+/// 普通看涨/看跌期权的BSM价格，t=0或σ=0时退化为远期的内在价值，便于在Payoff内部直接调用
+pub fn black_scholes_price(
+    spot:f64,
+    strike:f64,
+    risk_free_rate:f64,
+    dividend_yield:f64,
+    volatility:f64,
+    time_to_maturity:f64,
+    is_call:bool,
+)->f64{
+    let forward_pv=spot*(-dividend_yield*time_to_maturity).exp();
+    let strike_pv=strike*(-risk_free_rate*time_to_maturity).exp();
+    let sign=if is_call{1.0}else{-1.0};
+    if time_to_maturity<=0.0 || volatility<=0.0 || spot<=0.0 || strike<=0.0{
+        return (sign*(forward_pv-strike_pv)).max(0.0);
+    }
+    let sigma_sqrt_t=volatility*time_to_maturity.sqrt();
+    let d1=((forward_pv/strike_pv).ln()+0.5*sigma_sqrt_t*sigma_sqrt_t)/sigma_sqrt_t;
+    let d2=d1-sigma_sqrt_t;
+    sign*(forward_pv*norm_cdf(sign*d1)-strike_pv*norm_cdf(sign*d2))
+}
+
+/// Black-Scholes-Merton implied volatility of a vanilla call/put price, found by Brent's
+/// method on [1e-6, 10]; prices outside the no-arbitrage bounds are rejected <br>
+/// 由普通看涨/看跌期权价格反推BSM隐含波动率（在[1e-6, 10]上用Brent法求根），超出无套利界的价格报错
+pub fn black_scholes_implied_volatility(
+    price:f64,
+    spot:f64,
+    strike:f64,
+    risk_free_rate:f64,
+    dividend_yield:f64,
+    time_to_maturity:f64,
+    is_call:bool,
+)->Result<f64>{
+    if spot<=0.0 || strike<=0.0 || time_to_maturity<=0.0{
+        return Err(OptionError::InvalidParameter("Implied volatility needs positive spot, strike and maturity".to_string()));
+    }
+    let price_at=|volatility:f64| black_scholes_price(spot,strike,risk_free_rate,dividend_yield,volatility,time_to_maturity,is_call);
+    let (lower,upper)=(1e-6,10.0);
+    if price<price_at(lower)-1e-12 || price>price_at(upper){
+        return Err(OptionError::ArbitrationViolation(format!("Price {} lies outside the no-arbitrage bounds of the option",price)));
+    }
+    crate::utils::math::brent_root(|volatility| price_at(volatility)-price,lower,upper,1e-12,200)
+}
+
+/// Bachelier (normal model) price of a vanilla call/put: the forward F = S·e^{(r-q)T} follows
+/// dF = σ·dW, so the spot and strike may be zero or negative. Falls back to the discounted
+/// intrinsic value of the forward when t=0 or σ=0 <br>
+/// 普通看涨/看跌期权的Bachelier（正态模型）价格：远期F = S·e^{(r-q)T}服从dF = σ·dW，现价与
+/// 行权价可以为零或负；t=0或σ=0时退化为远期的贴现内在价值
+pub fn bachelier_price(
+    spot:f64,
+    strike:f64,
+    risk_free_rate:f64,
+    dividend_yield:f64,
+    volatility:f64,
+    time_to_maturity:f64,
+    is_call:bool,
+)->f64{
+    let forward=spot*((risk_free_rate-dividend_yield)*time_to_maturity).exp();
+    let discount=(-risk_free_rate*time_to_maturity).exp();
+    let sign=if is_call{1.0}else{-1.0};
+    if time_to_maturity<=0.0 || volatility<=0.0{
+        return discount*(sign*(forward-strike)).max(0.0);
+    }
+    let sigma_sqrt_t=volatility*time_to_maturity.sqrt();
+    let d=(forward-strike)/sigma_sqrt_t;
+    discount*(sign*(forward-strike)*norm_cdf(sign*d)+sigma_sqrt_t*norm_pdf(d))
+}
+
+/// Normal (Bachelier) implied volatility of a vanilla call/put price, found by Brent's method;
+/// prices outside the no-arbitrage bounds are rejected <br>
+/// 由普通看涨/看跌期权价格反推正态（Bachelier）隐含波动率（Brent法），超出无套利界的价格报错
+pub fn bachelier_implied_volatility(
+    price:f64,
+    spot:f64,
+    strike:f64,
+    risk_free_rate:f64,
+    dividend_yield:f64,
+    time_to_maturity:f64,
+    is_call:bool,
+)->Result<f64>{
+    if time_to_maturity<=0.0{
+        return Err(OptionError::InvalidParameter("Implied volatility needs a positive maturity".to_string()));
+    }
+    let price_at=|volatility:f64| bachelier_price(spot,strike,risk_free_rate,dividend_yield,volatility,time_to_maturity,is_call);
+    let forward=spot*((risk_free_rate-dividend_yield)*time_to_maturity).exp();
+    let undiscounted=price*(risk_free_rate*time_to_maturity).exp();
+    // σ√T = 10·(|F-K| + 未贴现价格)时模型价格必高于市场价格
+    let upper=10.0*((forward-strike).abs()+undiscounted)/time_to_maturity.sqrt()+1e-12;
+    if price<price_at(0.0)-1e-12 || !price.is_finite(){
+        return Err(OptionError::ArbitrationViolation(format!("Price {} lies below the intrinsic value of the option",price)));
+    }
+    if price<=price_at(0.0){
+        return Ok(0.0);
+    }
+    crate::utils::math::brent_root(|volatility| price_at(volatility)-price,0.0,upper,1e-14*upper.max(1.0),200)
+}
+
+/// Reject parameters whose dynamics is not lognormal, for engines built on geometric Brownian motion <br>
+/// 基于几何布朗运动的引擎拒绝非对数正态动态的参数
+pub fn require_lognormal_dynamics(params:&crate::params::common::CommonParams,engine:&str)->Result<()>{
+    if !params.dynamics().is_lognormal(){
+        return Err(OptionError::NotImplemented(format!(
+            "{} only supports lognormal dynamics, got {:?}",engine,params.dynamics()
+        )));
+    }
+    Ok(())
+}
+
 /// CDF of binary normal distribution <br>
 /// 二元正态分布的CDF
 pub fn bivariate_norm_cdf(a: f64, b: f64, rho: f64) -> f64 {
@@ -61,10 +186,19 @@ pub fn bivariate_norm_cdf(a: f64, b: f64, rho: f64) -> f64 {
     phi_a + phi_b - 1.0 + p_gt_a_gt_b
 }
 
-/// 通用参数校验
+/// 通用参数校验：对数正态动态要求现价为正，正态动态允许任意现价，平移对数正态要求F + shift > 0
 pub fn validate_common_params(params:&crate::params::common::CommonParams)->Result<()>{
-    if params.spot()<=0.0{
-        return Err(OptionError::InvalidParameter("Spot must be greater than zero.".into()));
+    use crate::params::common::Dynamics;
+    match params.dynamics(){
+        Dynamics::Lognormal=>if params.spot()<=0.0{
+            return Err(OptionError::InvalidParameter("Spot must be greater than zero.".into()));
+        },
+        Dynamics::Normal=>if !params.spot().is_finite(){
+            return Err(OptionError::InvalidParameter("Spot must be finite.".into()));
+        },
+        Dynamics::DisplacedDiffusion{shift}=>if params.forward()+shift<=0.0{
+            return Err(OptionError::InvalidParameter("The shifted forward F + shift must be greater than zero.".into()));
+        },
     }
     if params.volatility()<=0.0{
         return Err(OptionError::InvalidParameter("Volatility must be greater than zero.".into()));
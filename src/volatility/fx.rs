@@ -0,0 +1,317 @@
+//! FX volatility smile from at-the-money, risk reversal and butterfly quotes <br>
+//! 由平值、风险逆转与蝶式报价构建外汇波动率微笑
+//!
+//! FX vols are quoted by delta: an ATM vol (usually delta neutral straddle) and, per delta pillar
+//! (25D, 10D), a risk reversal RR = σ_call - σ_put and a butterfly. With smile butterflies the
+//! pillar vols are σ_call = σ_ATM + BF + RR/2 and σ_put = σ_ATM + BF - RR/2; broker (market
+//! strangle) butterflies instead fix the premium of the strangle priced at the single vol
+//! σ_ATM + BF, and the smile butterfly is solved so that the smile reprices that strangle.
+//! Strikes follow from the delta convention, and the pivots are joined by the Vanna-Volga
+//! approximation of Castagna-Mercurio (2007) or a SABR fit. <br>
+//! 外汇波动率按delta报价：平值波动率（通常为delta中性跨式）以及各delta支点（25D、10D）的风险逆转
+//! RR = σ_call - σ_put与蝶式价差。采用微笑蝶式时支点波动率为σ_call = σ_ATM + BF + RR/2、
+//! σ_put = σ_ATM + BF - RR/2；经纪商（市场宽跨式）蝶式则约定以单一波动率σ_ATM + BF定价的宽跨式
+//! 期权费，需反解微笑蝶式使微笑重现该期权费。行权价由delta约定确定，支点之间以Castagna-Mercurio
+//! (2007) 的Vanna-Volga近似或SABR拟合连接
+use crate::core::analytic::calculators::GarmanKohlhagenCalculator;
+use crate::errors::*;
+use crate::params::fx::{FxAtmConvention, FxDeltaConvention, FxPremium, GarmanKohlhagenParams};
+use crate::traits::payoff::{CallPayoff, PutPayoff};
+use crate::utils::math::brent_root;
+use crate::volatility::sabr::{SabrExpansion, SabrSmile};
+
+/// Risk reversal and butterfly quoted at one delta pillar, e.g. 0.25 for 25D <br>
+/// 单个delta支点（如25D取0.25）的风险逆转与蝶式报价
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct FxDeltaQuote{
+    pub delta:f64,
+    pub risk_reversal:f64,
+    pub butterfly:f64,
+}
+
+impl FxDeltaQuote{
+    pub fn new(delta:f64,risk_reversal:f64,butterfly:f64)->Self{
+        Self{delta,risk_reversal,butterfly}
+    }
+}
+
+/// Meaning of the quoted butterfly 蝶式报价的含义
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum FxButterfly{
+    /// σ_call + σ_put = 2·(σ_ATM + BF) on the smile 微笑上σ_call + σ_put = 2·(σ_ATM + BF)
+    #[default]
+    Smile,
+    /// Premium of the strangle priced at the single vol σ_ATM + BF (market strangle) <br>
+    /// 以单一波动率σ_ATM + BF定价的宽跨式期权费（市场宽跨式）
+    Broker,
+}
+
+/// Market conventions of the quotes 报价的市场约定
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub struct FxSmileConventions{
+    pub delta:FxDeltaConvention,
+    pub atm:FxAtmConvention,
+    pub butterfly:FxButterfly,
+}
+
+/// Interpolation of the smile between the pivots 支点之间的微笑插值
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum FxSmileModel{
+    /// Vanna-Volga through the ATM and a single delta pillar 通过平值与单个delta支点的Vanna-Volga
+    VannaVolga,
+    /// SABR with fixed β fitted to every pivot 固定β拟合全部支点的SABR
+    Sabr{beta:f64},
+}
+
+/// Strike and smile vol of a delta pillar; the delta is positive for calls and negative for puts <br>
+/// delta支点的行权价与微笑波动率；看涨delta为正，看跌为负
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct FxSmilePillar{
+    pub delta:f64,
+    pub strike:f64,
+    pub volatility:f64,
+}
+
+#[derive(Debug,Clone)]
+enum Interpolation{
+    VannaVolga([(f64,f64);3]),
+    Sabr(SabrSmile),
+}
+
+/// FX smile of one expiry 单个到期日的外汇微笑
+#[derive(Debug,Clone)]
+pub struct FxSmile{
+    market:GarmanKohlhagenParams,
+    conventions:FxSmileConventions,
+    atm_strike:f64,
+    pillars:Vec<FxSmilePillar>,
+    smile_butterflies:Vec<f64>,
+    interpolation:Interpolation,
+}
+
+impl FxSmile{
+    /// Build the smile of `expiry` from the ATM vol and the delta pillars <br>
+    /// 由平值波动率与delta支点构建到期日`expiry`的微笑
+    #[allow(clippy::too_many_arguments)]
+    pub fn build(
+        spot:f64,
+        domestic_rate:f64,
+        foreign_rate:f64,
+        expiry:f64,
+        atm_volatility:f64,
+        quotes:&[FxDeltaQuote],
+        conventions:FxSmileConventions,
+        model:FxSmileModel,
+    )->Result<Self>{
+        if expiry<=0.0{
+            return Err(OptionError::InvalidParameter("Expiry must be greater than zero.".into()));
+        }
+        if quotes.is_empty() || quotes.iter().any(|q| !(q.delta>0.0 && q.delta<0.5)){
+            return Err(OptionError::InvalidParameter("The smile needs delta pillars strictly between 0 and 0.5".into()));
+        }
+        if model==FxSmileModel::VannaVolga && quotes.len()!=1{
+            return Err(OptionError::InvalidParameter("Vanna-Volga interpolates three pivots: quote a single delta pillar or use SABR".into()));
+        }
+        let market=GarmanKohlhagenParams::new(spot,domestic_rate,foreign_rate,atm_volatility,expiry)?;
+        let mut quotes=quotes.to_vec();
+        quotes.sort_by(|a,b| b.delta.total_cmp(&a.delta));
+        if quotes.windows(2).any(|w| w[0].delta==w[1].delta){
+            return Err(OptionError::InvalidParameter("Delta pillars must be distinct".into()));
+        }
+        let atm_strike=GarmanKohlhagenCalculator::atm_strike(&market,conventions.atm,conventions.delta);
+        let quoted:Vec<f64>=quotes.iter().map(|q| q.butterfly).collect();
+        let smile_butterflies=match conventions.butterfly{
+            FxButterfly::Smile=>quoted,
+            FxButterfly::Broker=>Self::solve_broker_butterflies(&market,atm_strike,&quotes,conventions,model)?,
+        };
+        Self::assemble(market,atm_strike,&quotes,&smile_butterflies,conventions,model)
+    }
+
+    fn assemble(
+        market:GarmanKohlhagenParams,
+        atm_strike:f64,
+        quotes:&[FxDeltaQuote],
+        smile_butterflies:&[f64],
+        conventions:FxSmileConventions,
+        model:FxSmileModel,
+    )->Result<Self>{
+        let atm=market.volatility();
+        let mut pillars=Vec::with_capacity(2*quotes.len());
+        for (quote,&butterfly) in quotes.iter().zip(smile_butterflies){
+            let call_vol=atm+butterfly+0.5*quote.risk_reversal;
+            let put_vol=atm+butterfly-0.5*quote.risk_reversal;
+            if call_vol<=0.0 || put_vol<=0.0{
+                return Err(OptionError::InvalidParameter(format!("The {} delta quotes give a non-positive vol",quote.delta)));
+            }
+            let call=GarmanKohlhagenCalculator::strike_from_delta(&market.with_volatility(call_vol)?,quote.delta,true,conventions.delta)?;
+            let put=GarmanKohlhagenCalculator::strike_from_delta(&market.with_volatility(put_vol)?,-quote.delta,false,conventions.delta)?;
+            pillars.push(FxSmilePillar{delta:quote.delta,strike:call,volatility:call_vol});
+            pillars.push(FxSmilePillar{delta:-quote.delta,strike:put,volatility:put_vol});
+        }
+        pillars.sort_by(|a,b| a.strike.total_cmp(&b.strike));
+        if pillars.iter().any(|p| (p.delta<0.0)!=(p.strike<atm_strike)) || pillars.windows(2).any(|w| w[0].strike>=w[1].strike){
+            return Err(OptionError::ArbitrationViolation("Pillar strikes are not ordered around the ATM strike".into()));
+        }
+        let interpolation=match model{
+            FxSmileModel::VannaVolga=>{
+                let (put,call)=(pillars[0],pillars[1]);
+                Interpolation::VannaVolga([(put.strike,put.volatility),(atm_strike,atm),(call.strike,call.volatility)])
+            }
+            FxSmileModel::Sabr{beta}=>{
+                let mut strikes:Vec<f64>=pillars.iter().map(|p| p.strike).collect();
+                let mut vols:Vec<f64>=pillars.iter().map(|p| p.volatility).collect();
+                strikes.push(atm_strike);
+                vols.push(atm);
+                let fit=SabrSmile::calibrate(market.forward(),market.time_to_maturity(),beta,&strikes,&vols,SabrExpansion::Hagan)?;
+                Interpolation::Sabr(fit.smile)
+            }
+        };
+        Ok(Self{
+            market,
+            conventions,
+            atm_strike,
+            pillars,
+            smile_butterflies:smile_butterflies.to_vec(),
+            interpolation,
+        })
+    }
+
+    // 经纪商蝶式：逐支点反解微笑蝶式，使微笑在市场宽跨式行权价上重现单一波动率下的宽跨式期权费
+    fn solve_broker_butterflies(
+        market:&GarmanKohlhagenParams,
+        atm_strike:f64,
+        quotes:&[FxDeltaQuote],
+        conventions:FxSmileConventions,
+        model:FxSmileModel,
+    )->Result<Vec<f64>>{
+        let atm=market.volatility();
+        let mut strangles=Vec::with_capacity(quotes.len());
+        for quote in quotes{
+            let one_vol=market.with_volatility(atm+quote.butterfly)?;
+            let call=GarmanKohlhagenCalculator::strike_from_delta(&one_vol,quote.delta,true,conventions.delta)?;
+            let put=GarmanKohlhagenCalculator::strike_from_delta(&one_vol,-quote.delta,false,conventions.delta)?;
+            let premium=strangle_premium(&one_vol,&one_vol,call,put)?;
+            strangles.push((call,put,premium));
+        }
+        let mut butterflies:Vec<f64>=quotes.iter().map(|q| q.butterfly).collect();
+        for _ in 0..50{
+            let mut change:f64=0.0;
+            for i in 0..quotes.len(){
+                let (call,put,premium)=strangles[i];
+                let mismatch=|butterfly:f64|{
+                    let mut trial=butterflies.clone();
+                    trial[i]=butterfly;
+                    Self::assemble(*market,atm_strike,quotes,&trial,conventions,model)
+                        .and_then(|smile| strangle_premium(&smile.params(call)?,&smile.params(put)?,call,put))
+                        .map_or(f64::NAN,|value| value-premium)
+                };
+                let (lower,upper)=bracket_butterfly(&mismatch,quotes[i].butterfly,0.01*atm)?;
+                let solved=brent_root(mismatch,lower,upper,1e-14,200)?;
+                change=change.max((solved-butterflies[i]).abs());
+                butterflies[i]=solved;
+            }
+            // SABR拟合本身有约1e-10的噪声
+            if change<1e-8{
+                return Ok(butterflies);
+            }
+        }
+        Err(OptionError::ConvergenceError("Broker butterflies did not converge".into()))
+    }
+
+    // Getter method
+    pub fn spot(&self)->f64{self.market.spot()}
+    pub fn forward(&self)->f64{self.market.forward()}
+    pub fn expiry(&self)->f64{self.market.time_to_maturity()}
+    pub fn conventions(&self)->FxSmileConventions{self.conventions}
+    pub fn atm_strike(&self)->f64{self.atm_strike}
+    pub fn atm_volatility(&self)->f64{self.market.volatility()}
+    /// Pillars sorted by strike 按行权价排序的支点
+    pub fn pillars(&self)->&[FxSmilePillar]{&self.pillars}
+    /// Smile butterflies of the quotes, by decreasing delta; equal to the quotes with smile
+    /// butterflies, solved with broker butterflies <br>
+    /// 各报价（按delta降序）的微笑蝶式；微笑蝶式约定下即报价本身，经纪商约定下为反解结果
+    pub fn smile_butterflies(&self)->&[f64]{&self.smile_butterflies}
+
+    /// Implied volatility at `strike` 行权价处的隐含波动率
+    pub fn volatility(&self,strike:f64)->Result<f64>{
+        if strike<=0.0{
+            return Err(OptionError::InvalidParameter("Strike must be greater than zero.".into()));
+        }
+        match &self.interpolation{
+            Interpolation::VannaVolga(pivots)=>vanna_volga_volatility(pivots,self.forward(),self.expiry(),strike),
+            Interpolation::Sabr(smile)=>smile.volatility(strike),
+        }
+    }
+
+    /// Garman-Kohlhagen parameters carrying the smile vol of `strike` <br>
+    /// 带有`strike`处微笑波动率的Garman-Kohlhagen参数
+    pub fn params(&self,strike:f64)->Result<GarmanKohlhagenParams>{
+        self.market.with_volatility(self.volatility(strike)?)
+    }
+}
+
+// 从报价蝶式出发按倍增步长寻找变号区间；微笑无效（NaN）时缩小步长
+fn bracket_butterfly(mismatch:&dyn Fn(f64)->f64,quoted:f64,step:f64)->Result<(f64,f64)>{
+    let start=mismatch(quoted);
+    if !start.is_finite(){
+        return Err(OptionError::CalculationError("The quoted butterfly does not give a valid smile".into()));
+    }
+    if start==0.0{
+        return Ok((quoted,quoted));
+    }
+    // 期权费随蝶式增加：价格偏高则向下搜索
+    let direction=if start>0.0{-1.0}else{1.0};
+    let (mut inner,mut step)=(quoted,step);
+    for _ in 0..100{
+        let trial=inner+direction*step;
+        let value=mismatch(trial);
+        if value.is_nan(){
+            step*=0.5;
+            continue;
+        }
+        if value*start<=0.0{
+            return Ok(if direction<0.0{(trial,inner)}else{(inner,trial)});
+        }
+        inner=trial;
+        step*=2.0;
+    }
+    Err(OptionError::ConvergenceError("Cannot bracket the smile butterfly".into()))
+}
+
+// 宽跨式期权费：看涨与看跌分别以各自参数定价（本币点数）
+fn strangle_premium(call_params:&GarmanKohlhagenParams,put_params:&GarmanKohlhagenParams,call:f64,put:f64)->Result<f64>{
+    Ok(GarmanKohlhagenCalculator::price(call_params,&CallPayoff::new(call),FxPremium::DomesticPips)?
+        +GarmanKohlhagenCalculator::price(put_params,&PutPayoff::new(put),FxPremium::DomesticPips)?)
+}
+
+/// Second order Vanna-Volga smile of Castagna-Mercurio (2007) through three (strike, vol)
+/// pivots, the middle one being the ATM; it reproduces the pivots exactly <br>
+/// 通过三个(行权价, 波动率)支点（中间为平值）的Castagna-Mercurio (2007) 二阶Vanna-Volga微笑，
+/// 精确重现支点
+pub fn vanna_volga_volatility(pivots:&[(f64,f64);3],forward:f64,expiry:f64,strike:f64)->Result<f64>{
+    let [(k1,s1),(k2,s2),(k3,s3)]=*pivots;
+    if !(0.0<k1 && k1<k2 && k2<k3) || expiry<=0.0{
+        return Err(OptionError::InvalidParameter("Vanna-Volga needs increasing positive pivot strikes and a positive expiry".into()));
+    }
+    let ln=|a:f64,b:f64| (a/b).ln();
+    let y1=ln(k2,strike)*ln(k3,strike)/(ln(k2,k1)*ln(k3,k1));
+    let y2=ln(strike,k1)*ln(k3,strike)/(ln(k2,k1)*ln(k3,k2));
+    let y3=ln(strike,k1)*ln(strike,k2)/(ln(k3,k1)*ln(k3,k2));
+    let sqrt_t=expiry.sqrt();
+    let d1d2=|k:f64|{
+        let d1=(ln(forward,k)+0.5*s2*s2*expiry)/(s2*sqrt_t);
+        d1*(d1-s2*sqrt_t)
+    };
+    let first=y1*s1+y2*s2+y3*s3-s2;
+    let second=y1*d1d2(k1)*(s1-s2).powi(2)+y3*d1d2(k3)*(s3-s2).powi(2);
+    let x=d1d2(strike);
+    let discriminant=s2*s2+x*(2.0*s2*first+second);
+    if discriminant<0.0{
+        // 二阶近似无实根时退回一阶近似
+        return Ok(s2+first);
+    }
+    if x.abs()<1e-12{
+        return Ok(s2+first+0.5*second/s2);
+    }
+    Ok(s2+(discriminant.sqrt()-s2)/x)
+}
@@ -0,0 +1,12 @@
+//! Implied volatility parameterisations and surfaces 隐含波动率参数化与曲面
+pub mod svi;
+pub mod ssvi;
+pub mod surface;
+pub mod sabr;
+pub mod fx;
+
+pub use svi::SviSlice;
+pub use ssvi::SsviParams;
+pub use surface::{VolQuote,VolSurface,SurfaceModel};
+pub use sabr::{SabrExpansion,SabrSmile,SabrCalibration};
+pub use fx::{FxButterfly,FxDeltaQuote,FxSmile,FxSmileConventions,FxSmileModel,FxSmilePillar};
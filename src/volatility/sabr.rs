@@ -0,0 +1,205 @@
+//! SABR implied volatility expansions and smile calibration <br>
+//! SABR隐含波动率展开式与微笑校准
+//!
+//! Hagan et al. (2002) give the Black (lognormal) and Bachelier (normal) implied volatility of
+//! the SABR model to first order in T. Obłój (2008) replaces the leading term by
+//! ν·ln(F/K)/x(z) with z = ν·(F^{1-β} - K^{1-β})/(α·(1-β)), which is exact as T → 0 and
+//! better behaved far from the money. <br>
+//! Hagan等 (2002) 给出SABR模型对T一阶的Black（对数正态）与Bachelier（正态）隐含波动率；
+//! Obłój (2008) 将首项替换为ν·ln(F/K)/x(z)，在T → 0时精确且在深度虚值处表现更好
+//!
+//! Options under SABR dynamics are priced by Monte Carlo (`SabrProcess`), on a two-factor
+//! (F, ln α) grid by `AdiEngine::price_sabr`, or by Black/Bachelier with these volatilities. <br>
+//! SABR动态下的期权由蒙特卡洛（`SabrProcess`）、`AdiEngine::price_sabr`在(F, ln α)双因子网格上，
+//! 或以上述波动率代入Black/Bachelier定价
+use std::sync::Arc;
+use crate::calibration::{Calibrator,LevenbergMarquardtOptimizer,NelderMeadOptimizer,Optimizer};
+use crate::errors::*;
+use crate::params::sabr::SabrParams;
+
+/// Choice of the leading term of the expansion 展开式首项的选择
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum SabrExpansion{
+    /// Hagan et al. (2002)
+    #[default]
+    Hagan,
+    /// Obłój (2008) correction Obłój (2008) 修正
+    Obloj,
+}
+
+// x(z) = ln((√(1-2ρz+z²) + z - ρ)/(1-ρ))，返回z/x(z)（z → 0时取级数）
+fn z_over_x(z:f64,rho:f64)->f64{
+    if z.abs()<1e-7{
+        return 1.0-0.5*rho*z;
+    }
+    let x=(((1.0-2.0*rho*z+z*z).sqrt()+z-rho)/(1.0-rho)).ln();
+    z/x
+}
+
+// (F-K)·(1-β)/(F^{1-β} - K^{1-β})，β = 1时为(F-K)/ln(F/K)，F = K时为F^β
+fn cev_average(forward:f64,strike:f64,beta:f64)->f64{
+    if (forward-strike).abs()<=1e-12*forward.abs().max(1e-12){
+        return forward.abs().powf(beta);
+    }
+    if beta==1.0{
+        return (forward-strike)/(forward/strike).ln();
+    }
+    let one=1.0-beta;
+    (forward-strike)*one/(forward.powf(one)-strike.powf(one))
+}
+
+// Obłój的z = ν·(F^{1-β} - K^{1-β})/(α·(1-β)) = ν·(F-K)/(α·cev_average)
+fn obloj_z(params:&SabrParams,forward:f64,strike:f64)->f64{
+    params.nu()*(forward-strike)/(params.alpha()*cev_average(forward,strike,params.beta()))
+}
+
+fn check_inputs(params:&SabrParams,forward:f64,strike:f64,expiry:f64)->Result<()>{
+    if expiry<0.0{
+        return Err(OptionError::InvalidParameter("Expiry cannot be negative.".into()));
+    }
+    if params.beta()>0.0 && (forward<=0.0 || strike<=0.0){
+        return Err(OptionError::InvalidParameter("SABR with β > 0 needs positive forward and strike".into()));
+    }
+    Ok(())
+}
+
+/// Black (lognormal) implied volatility of the SABR model <br>
+/// SABR模型的Black（对数正态）隐含波动率
+///
+/// # example:
+/// ```rust
+/// use optionrs::params::sabr::SabrParams;
+/// use optionrs::volatility::sabr::{sabr_lognormal_volatility,SabrExpansion};
+///
+/// // ν = 0且β = 1时为常数波动率α
+/// let params=SabrParams::new(0.2,1.0,0.0,0.0).unwrap();
+/// let vol=sabr_lognormal_volatility(&params,100.0,120.0,1.0,SabrExpansion::Hagan).unwrap();
+/// assert!((vol-0.2).abs()<1e-12);
+/// ```
+pub fn sabr_lognormal_volatility(params:&SabrParams,forward:f64,strike:f64,expiry:f64,expansion:SabrExpansion)->Result<f64>{
+    if forward<=0.0 || strike<=0.0{
+        return Err(OptionError::InvalidParameter("Lognormal volatility needs positive forward and strike".into()));
+    }
+    check_inputs(params,forward,strike,expiry)?;
+    let (alpha,beta,rho,nu)=(params.alpha(),params.beta(),params.rho(),params.nu());
+    let one=1.0-beta;
+    let log_moneyness=(forward/strike).ln();
+    let fk=(forward*strike).powf(0.5*one);
+    let correction=1.0+(one*one*alpha*alpha/(24.0*fk*fk)+0.25*rho*beta*nu*alpha/fk+(2.0-3.0*rho*rho)*nu*nu/24.0)*expiry;
+    let leading=match expansion{
+        SabrExpansion::Hagan=>{
+            let z=nu/alpha*fk*log_moneyness;
+            let l2=log_moneyness*log_moneyness;
+            alpha/(fk*(1.0+one*one/24.0*l2+one.powi(4)/1920.0*l2*l2))*z_over_x(z,rho)
+        }
+        SabrExpansion::Obloj=>{
+            // ν·ln(F/K)/x(z) = α·ln(F/K)/(F-K)·cev_average·z/x(z)
+            let ratio=if log_moneyness.abs()<1e-12{1.0/forward}else{log_moneyness/(forward-strike)};
+            alpha*ratio*cev_average(forward,strike,beta)*z_over_x(obloj_z(params,forward,strike),rho)
+        }
+    };
+    Ok(leading*correction)
+}
+
+/// Bachelier (normal) implied volatility of the SABR model; with β = 0 the forward and strike
+/// may be zero or negative <br>
+/// SABR模型的Bachelier（正态）隐含波动率；β = 0时远期与行权价可以为零或负
+pub fn sabr_normal_volatility(params:&SabrParams,forward:f64,strike:f64,expiry:f64,expansion:SabrExpansion)->Result<f64>{
+    check_inputs(params,forward,strike,expiry)?;
+    let (alpha,beta,rho,nu)=(params.alpha(),params.beta(),params.rho(),params.nu());
+    let average=if beta==0.0{1.0}else{(forward*strike).sqrt()};
+    let correction=1.0+(-beta*(2.0-beta)*alpha*alpha/(24.0*average.powf(2.0-2.0*beta))
+        +0.25*rho*alpha*nu*beta/average.powf(1.0-beta)
+        +(2.0-3.0*rho*rho)*nu*nu/24.0)*expiry;
+    let z=match expansion{
+        SabrExpansion::Hagan=>nu*(forward-strike)/(alpha*average.powf(beta)),
+        SabrExpansion::Obloj=>obloj_z(params,forward,strike),
+    };
+    Ok(alpha*cev_average(forward,strike,beta)*z_over_x(z,rho)*correction)
+}
+
+/// SABR smile of one expiry SABR单个到期日的微笑
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct SabrSmile{
+    pub params:SabrParams,
+    pub forward:f64,
+    pub expiry:f64,
+    pub expansion:SabrExpansion,
+}
+
+/// Calibrated smile with the fit report 校准的微笑与拟合报告
+#[derive(Debug,Clone,PartialEq)]
+pub struct SabrCalibration{
+    pub smile:SabrSmile,
+    /// Model minus market volatility per quote 每条报价的模型减市场波动率
+    pub residuals:Vec<f64>,
+    pub rmse:f64,
+    pub optimizer:Optimizer,
+    pub converged:bool,
+}
+
+impl SabrSmile{
+    pub fn new(params:SabrParams,forward:f64,expiry:f64,expansion:SabrExpansion)->Result<Self>{
+        if expiry<=0.0{
+            return Err(OptionError::InvalidParameter("Expiry must be greater than zero.".into()));
+        }
+        if params.beta()>0.0 && forward<=0.0{
+            return Err(OptionError::InvalidParameter("SABR with β > 0 needs a positive forward".into()));
+        }
+        Ok(Self{params,forward,expiry,expansion})
+    }
+
+    /// Black implied volatility at `strike` 行权价处的Black隐含波动率
+    pub fn volatility(&self,strike:f64)->Result<f64>{
+        sabr_lognormal_volatility(&self.params,self.forward,strike,self.expiry,self.expansion)
+    }
+
+    /// Bachelier implied volatility at `strike` 行权价处的Bachelier隐含波动率
+    pub fn normal_volatility(&self,strike:f64)->Result<f64>{
+        sabr_normal_volatility(&self.params,self.forward,strike,self.expiry,self.expansion)
+    }
+
+    /// Fit α, ρ and ν with β fixed to Black implied volatilities of one expiry, by
+    /// Levenberg-Marquardt with a Nelder-Mead fallback <br>
+    /// 固定β，将α、ρ、ν拟合到单个到期日的Black隐含波动率（Levenberg-Marquardt，失败时回退Nelder-Mead）
+    pub fn calibrate(
+        forward:f64,
+        expiry:f64,
+        beta:f64,
+        strikes:&[f64],
+        volatilities:&[f64],
+        expansion:SabrExpansion,
+    )->Result<SabrCalibration>{
+        if strikes.len()<3 || strikes.len()!=volatilities.len(){
+            return Err(OptionError::InvalidParameter("SABR calibration needs at least 3 strikes with matching volatilities".into()));
+        }
+        if forward<=0.0 || expiry<=0.0 || volatilities.iter().any(|&v| v<=0.0){
+            return Err(OptionError::InvalidParameter("SABR calibration needs positive forward, expiry and volatilities".into()));
+        }
+        SabrParams::new(1.0,beta,0.0,0.0)?;
+        let residuals=|x:&[f64]|->Vec<f64>{
+            let smile=SabrParams::new(x[0],beta,x[1],x[2]).and_then(|params| Self::new(params,forward,expiry,expansion));
+            strikes.iter().zip(volatilities).map(|(&strike,&vol)|{
+                smile.as_ref().ok().and_then(|smile| smile.volatility(strike).ok()).map_or(f64::NAN,|model| model-vol)
+            }).collect()
+        };
+        // α初值取ATM附近报价：σ_ATM ≈ α/F^{1-β}
+        let nearest=(0..strikes.len())
+            .min_by(|&i,&j| (strikes[i]-forward).abs().total_cmp(&(strikes[j]-forward).abs()))
+            .unwrap_or(0);
+        let alpha0=volatilities[nearest]*forward.powf(1.0-beta);
+        let initial=[alpha0,-0.2,0.5];
+        let lower=[1e-6*alpha0,-0.9999,0.0];
+        let upper=[10.0*alpha0.max(1e-6),0.9999,10.0];
+        let (minimum,optimizer)=Calibrator::new()
+            .with_optimizers(vec![
+                Arc::new(LevenbergMarquardtOptimizer{tolerance:1e-14,max_iterations:200}),
+                Arc::new(NelderMeadOptimizer{tolerance:1e-16,max_iterations:10000}),
+            ])?
+            .minimize_residuals(&residuals,&initial,&lower,&upper)?;
+        let smile=Self::new(SabrParams::new(minimum.point[0],beta,minimum.point[1],minimum.point[2])?,forward,expiry,expansion)?;
+        let residuals=residuals(&minimum.point);
+        let rmse=(residuals.iter().map(|e| e*e).sum::<f64>()/residuals.len() as f64).sqrt();
+        Ok(SabrCalibration{smile,residuals,rmse,optimizer,converged:minimum.converged})
+    }
+}
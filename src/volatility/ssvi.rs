@@ -0,0 +1,111 @@
+//! Surface SVI with power-law curvature (Gatheral & Jacquier, 2014) <br>
+//! 幂律曲率的SSVI曲面（Gatheral & Jacquier, 2014）
+//!
+//! w(k, θ) = θ/2·(1 + ρ·φ(θ)·k + √((φ(θ)·k + ρ)² + 1 - ρ²)), φ(θ) = η/(θ^γ·(1+θ)^(1-γ)),
+//! where θ_t is the ATM total variance of expiry t. <br>
+//! θ_t为到期日t的ATM总方差
+use crate::errors::*;
+use crate::utils::optimization::nelder_mead;
+
+/// SSVI surface parameters shared by all expiries <br>
+/// 所有到期日共享的SSVI曲面参数
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct SsviParams{
+    rho:f64,    // 偏斜
+    eta:f64,    // 曲率水平
+    gamma:f64,  // 曲率随θ衰减的指数
+}
+
+impl SsviParams{
+    /// Create parameters; requires |ρ| < 1, η > 0 and γ ∈ (0, 1] <br>
+    /// 创建参数，要求|ρ| < 1、η > 0且γ ∈ (0, 1]
+    pub fn new(rho:f64,eta:f64,gamma:f64)->Result<Self>{
+        if rho.abs()>=1.0 || eta<=0.0 || gamma<=0.0 || gamma>1.0{
+            return Err(OptionError::InvalidParameter("SSVI needs |ρ| < 1, η > 0 and 0 < γ ≤ 1".into()));
+        }
+        Ok(Self{rho,eta,gamma})
+    }
+
+    // Getter method
+    pub fn rho(&self)->f64{self.rho}
+    pub fn eta(&self)->f64{self.eta}
+    pub fn gamma(&self)->f64{self.gamma}
+
+    /// Curvature function φ(θ) 曲率函数
+    pub fn phi(&self,theta:f64)->f64{
+        self.eta/(theta.powf(self.gamma)*(1.0+theta).powf(1.0-self.gamma))
+    }
+
+    /// Total implied variance at log-moneyness `k` for ATM total variance `theta` <br>
+    /// ATM总方差为`theta`时对数价值度`k`处的总隐含方差
+    pub fn total_variance(&self,k:f64,theta:f64)->f64{
+        let phi_k=self.phi(theta)*k;
+        0.5*theta*(1.0+self.rho*phi_k+((phi_k+self.rho).powi(2)+1.0-self.rho*self.rho).sqrt())
+    }
+
+    /// Sufficient condition for no butterfly arbitrage of the power-law SSVI: η·(1+|ρ|) ≤ 2 <br>
+    /// 幂律SSVI无蝶式套利的充分条件
+    pub fn check_butterfly(&self)->Result<()>{
+        if self.eta*(1.0+self.rho.abs())>2.0+1e-12{
+            return Err(OptionError::ArbitrationViolation(format!(
+                "SSVI violates η·(1+|ρ|) ≤ 2 (η={:.4}, ρ={:.4})",self.eta,self.rho
+            )));
+        }
+        Ok(())
+    }
+
+    /// Fit (ρ, η, γ) by Nelder-Mead to quotes `(k, θ, w)` of log-moneyness, ATM total
+    /// variance and total variance; the fit is restricted to the butterfly-free region <br>
+    /// 用Nelder-Mead将(ρ, η, γ)拟合到报价`(k, θ, w)`（对数价值度、ATM总方差、总方差），
+    /// 搜索限制在无蝶式套利区域内
+    pub fn calibrate(quotes:&[(f64,f64,f64)])->Result<Self>{
+        if quotes.len()<3{
+            return Err(OptionError::InvalidParameter("SSVI calibration needs at least 3 quotes".into()));
+        }
+        if quotes.iter().any(|&(_,theta,w)| theta<=0.0 || w<=0.0){
+            return Err(OptionError::InvalidParameter("Total variances must be positive".into()));
+        }
+        // 无约束变量：ρ = tanh(x₀)，γ = 1/(1+e^{-x₂})，η = 2/(1+|ρ|)·1/(1+e^{-x₁})
+        let unpack=|x:&[f64]|->Option<Self>{
+            let rho=x[0].tanh();
+            let eta=2.0/(1.0+rho.abs())/(1.0+(-x[1]).exp());
+            let gamma=1.0/(1.0+(-x[2]).exp());
+            Self::new(rho,eta,gamma).ok()
+        };
+        let objective=|x:&[f64]| unpack(x).map_or(f64::INFINITY,|params|{
+            quotes.iter().map(|&(k,theta,w)| (params.total_variance(k,theta)-w).powi(2)).sum()
+        });
+        let mut best:Option<(f64,Vec<f64>)>=None;
+        for rho0 in [-0.5,0.0,0.5]{
+            let minimum=nelder_mead(objective,&[rho0,0.0,0.0],&[0.5,1.0,1.0],1e-18,3000)?;
+            if best.as_ref().is_none_or(|(value,_)| minimum.value<*value){
+                best=Some((minimum.value,minimum.point));
+            }
+        }
+        best.and_then(|(_,point)| unpack(&point))
+            .ok_or_else(|| OptionError::ConvergenceError("SSVI calibration failed".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn test_ssvi_calibration_recovers_surface(){
+        let params=SsviParams::new(-0.3,1.0,0.4).unwrap();
+        let quotes:Vec<(f64,f64,f64)>=[0.02,0.05,0.1]
+            .iter()
+            .flat_map(|&theta| (0..9).map(move |i| (-0.4+0.1*i as f64,theta)))
+            .map(|(k,theta)| (k,theta,params.total_variance(k,theta)))
+            .collect();
+        let fitted=SsviParams::calibrate(&quotes).unwrap();
+        assert!((fitted.rho()-params.rho()).abs()<1e-4);
+        assert!((fitted.eta()-params.eta()).abs()<1e-4);
+        assert!((fitted.gamma()-params.gamma()).abs()<1e-4);
+        for &(k,theta,w) in &quotes{
+            assert!((fitted.total_variance(k,theta)-w).abs()<1e-8);
+        }
+        assert!(fitted.check_butterfly().is_ok());
+    }
+}
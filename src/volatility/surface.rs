@@ -0,0 +1,323 @@
+//! Implied volatility surface fitted to a strike × expiry grid of quotes <br>
+//! 由行权价×到期日报价网格拟合的隐含波动率曲面
+//!
+//! Each expiry is fitted in log forward moneyness k = ln(K/F(t)), F(t) = S·e^{(r-q)t}, either
+//! slice by slice with raw SVI or jointly with SSVI. The fitted surface is checked for static
+//! arbitrage (butterfly within slices, calendar across slices) before it is returned. <br>
+//! 每个到期日以远期对数价值度k拟合：逐切片的原始SVI或整体的SSVI。拟合后的曲面在返回前
+//! 检查静态套利（切片内蝶式套利、切片间日历套利）
+use crate::errors::*;
+use crate::params::common::CommonParams;
+use crate::utils::math::quadratic_interpolate;
+use crate::utils::statistics::black_scholes_implied_volatility;
+use crate::volatility::ssvi::SsviParams;
+use crate::volatility::svi::SviSlice;
+
+/// Number of log-moneyness points used by the arbitrage checks 套利检查使用的对数价值度点数
+const CHECK_POINTS:usize=201;
+
+/// One market quote of Black-Scholes implied volatility 一条Black-Scholes隐含波动率报价
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct VolQuote{
+    pub strike:f64,
+    pub expiry:f64,
+    pub volatility:f64,
+}
+
+impl VolQuote{
+    pub fn new(strike:f64,expiry:f64,volatility:f64)->Result<Self>{
+        if strike<=0.0 || expiry<=0.0 || volatility<=0.0{
+            return Err(OptionError::InvalidParameter("Quotes need positive strike, expiry and volatility".into()));
+        }
+        Ok(Self{strike,expiry,volatility})
+    }
+
+    /// Quote from an option price, converted by Black-Scholes implied volatility <br>
+    /// 由期权价格经Black-Scholes隐含波动率转换得到的报价
+    pub fn from_price(
+        price:f64,
+        strike:f64,
+        expiry:f64,
+        is_call:bool,
+        spot:f64,
+        risk_free_rate:f64,
+        dividend_yield:f64,
+    )->Result<Self>{
+        let volatility=black_scholes_implied_volatility(price,spot,strike,risk_free_rate,dividend_yield,expiry,is_call)?;
+        Self::new(strike,expiry,volatility)
+    }
+}
+
+/// Parameterisation used to fit the surface 曲面拟合使用的参数化
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum SurfaceModel{
+    /// Raw SVI per expiry, total variance interpolated linearly in time at fixed moneyness <br>
+    /// 每个到期日一个原始SVI切片，固定价值度下总方差按时间线性插值
+    #[default]
+    Svi,
+    /// One SSVI surface, ATM total variance interpolated linearly in time <br>
+    /// 单一SSVI曲面，ATM总方差按时间线性插值
+    Ssvi,
+}
+
+#[derive(Debug,Clone)]
+enum Fit{
+    Svi(Vec<SviSlice>),
+    Ssvi{params:SsviParams,atm_variances:Vec<f64>},
+}
+
+/// Arbitrage-checked implied volatility surface 经套利检查的隐含波动率曲面
+#[derive(Debug,Clone)]
+pub struct VolSurface{
+    spot:f64,
+    risk_free_rate:f64,
+    dividend_yield:f64,
+    expiries:Vec<f64>,
+    k_range:(f64,f64),  // 报价覆盖的对数价值度范围
+    fit:Fit,
+}
+
+impl VolSurface{
+    /// Fit the surface to the quotes and check it for static arbitrage; each expiry needs at
+    /// least 5 quotes for SVI and 3 for SSVI <br>
+    /// 将曲面拟合到报价并检查静态套利；SVI每个到期日至少5条报价，SSVI至少3条
+    pub fn from_quotes(
+        spot:f64,
+        risk_free_rate:f64,
+        dividend_yield:f64,
+        quotes:&[VolQuote],
+        model:SurfaceModel,
+    )->Result<Self>{
+        if spot<=0.0{
+            return Err(OptionError::InvalidParameter("Spot must be greater than zero.".into()));
+        }
+        if quotes.is_empty(){
+            return Err(OptionError::EmptyData);
+        }
+        let forward=|t:f64| spot*((risk_free_rate-dividend_yield)*t).exp();
+
+        // 按到期日分组，组内按对数价值度排序
+        let mut sorted=quotes.to_vec();
+        sorted.sort_by(|a,b| a.expiry.total_cmp(&b.expiry).then(a.strike.total_cmp(&b.strike)));
+        let mut expiries:Vec<f64>=Vec::new();
+        let mut slices:Vec<(Vec<f64>,Vec<f64>)>=Vec::new();
+        for quote in &sorted{
+            if expiries.last().is_none_or(|&t| (quote.expiry-t).abs()>1e-12){
+                expiries.push(quote.expiry);
+                slices.push((Vec::new(),Vec::new()));
+            }
+            let (ks,ws)=slices.last_mut().unwrap();
+            ks.push((quote.strike/forward(quote.expiry)).ln());
+            ws.push(quote.volatility*quote.volatility*quote.expiry);
+        }
+        let k_min=slices.iter().flat_map(|(ks,_)| ks.iter()).cloned().fold(f64::INFINITY,f64::min);
+        let k_max=slices.iter().flat_map(|(ks,_)| ks.iter()).cloned().fold(f64::NEG_INFINITY,f64::max);
+
+        let fit=match model{
+            SurfaceModel::Svi=>Fit::Svi(
+                slices.iter().map(|(ks,ws)| SviSlice::calibrate(ks,ws,None)).collect::<Result<_>>()?
+            ),
+            SurfaceModel::Ssvi=>{
+                // ATM总方差由报价插值得到，报价须位于远期两侧，避免外推
+                if let Some((t,_))=expiries.iter().zip(&slices).find(|(_,(ks,_))| ks[0]>0.0 || ks[ks.len()-1]<0.0){
+                    return Err(OptionError::InvalidParameter(format!(
+                        "SSVI needs quotes on both sides of the forward at expiry {}",t
+                    )));
+                }
+                let atm_variances=slices.iter().map(|(ks,ws)| quadratic_interpolate(0.0,ks,ws)).collect::<Result<Vec<_>>>()?;
+                let points:Vec<(f64,f64,f64)>=slices.iter().zip(&atm_variances)
+                    .flat_map(|((ks,ws),&theta)| ks.iter().zip(ws).map(move |(&k,&w)| (k,theta,w)))
+                    .collect();
+                Fit::Ssvi{params:SsviParams::calibrate(&points)?,atm_variances}
+            }
+        };
+        let surface=Self{spot,risk_free_rate,dividend_yield,expiries,k_range:(k_min,k_max),fit};
+        surface.check_arbitrage()?;
+        Ok(surface)
+    }
+
+    // Getter method
+    pub fn spot(&self)->f64{self.spot}
+    pub fn risk_free_rate(&self)->f64{self.risk_free_rate}
+    pub fn dividend_yield(&self)->f64{self.dividend_yield}
+    pub fn expiries(&self)->&[f64]{&self.expiries}
+
+    /// Fitted SVI slices (SVI model only) 拟合的SVI切片（仅SVI模型）
+    pub fn svi_slices(&self)->Option<&[SviSlice]>{
+        match &self.fit{
+            Fit::Svi(slices)=>Some(slices),
+            Fit::Ssvi{..}=>None,
+        }
+    }
+
+    /// Fitted SSVI parameters (SSVI model only) 拟合的SSVI参数（仅SSVI模型）
+    pub fn ssvi_params(&self)->Option<SsviParams>{
+        match &self.fit{
+            Fit::Svi(_)=>None,
+            Fit::Ssvi{params,..}=>Some(*params),
+        }
+    }
+
+    /// Forward price F(t) = S·e^{(r-q)t} 远期价格
+    pub fn forward(&self,t:f64)->f64{
+        self.spot*((self.risk_free_rate-self.dividend_yield)*t).exp()
+    }
+
+    // 第i个到期日在对数价值度k处的总方差
+    fn slice_variance(&self,i:usize,k:f64)->f64{
+        match &self.fit{
+            Fit::Svi(slices)=>slices[i].total_variance(k),
+            Fit::Ssvi{params,atm_variances}=>params.total_variance(k,atm_variances[i]),
+        }
+    }
+
+    /// Total implied variance σ²·t at log forward moneyness `k` and maturity `t`; before the
+    /// first expiry the variance is scaled by t/t₁, after the last it grows linearly in t <br>
+    /// 对数价值度`k`、期限`t`处的总隐含方差；首个到期日之前按t/t₁缩放，最后一个到期日之后随t线性增长
+    pub fn total_variance_at(&self,k:f64,t:f64)->f64{
+        match &self.fit{
+            Fit::Svi(_)=>self.interpolate_in_time(t,|i| self.slice_variance(i,k)),
+            // 对ATM总方差插值，保持SSVI结构
+            Fit::Ssvi{params,atm_variances}=>params.total_variance(k,self.interpolate_in_time(t,|i| atm_variances[i])),
+        }
+    }
+
+    // 按到期日线性插值，两端按时间比例外推
+    fn interpolate_in_time<F:Fn(usize)->f64>(&self,t:f64,value:F)->f64{
+        let n=self.expiries.len();
+        if t<=self.expiries[0]{
+            return value(0)*t/self.expiries[0];
+        }
+        if t>=self.expiries[n-1]{
+            return value(n-1)*t/self.expiries[n-1];
+        }
+        let upper=self.expiries.partition_point(|&e| e<t);
+        let (t0,t1)=(self.expiries[upper-1],self.expiries[upper]);
+        let weight=(t-t0)/(t1-t0);
+        (1.0-weight)*value(upper-1)+weight*value(upper)
+    }
+
+    /// Total implied variance for a strike and maturity 给定行权价与期限的总隐含方差
+    pub fn total_variance(&self,strike:f64,t:f64)->Result<f64>{
+        if strike<=0.0 || t<=0.0{
+            return Err(OptionError::InvalidParameter("Strike and maturity must be greater than zero.".into()));
+        }
+        Ok(self.total_variance_at((strike/self.forward(t)).ln(),t))
+    }
+
+    /// Interpolated Black-Scholes implied volatility for a strike and maturity <br>
+    /// 给定行权价与期限的插值Black-Scholes隐含波动率
+    pub fn volatility(&self,strike:f64,t:f64)->Result<f64>{
+        let variance=self.total_variance(strike,t)?;
+        if variance<=0.0{
+            return Err(OptionError::ArbitrationViolation(format!("Non-positive total variance at K={}, t={}",strike,t)));
+        }
+        Ok((variance/t).sqrt())
+    }
+
+    /// Pricing parameters for any engine, using the surface volatility at (strike, t) <br>
+    /// 以曲面在(strike, t)处的波动率构造可用于任意引擎的定价参数
+    pub fn common_params(&self,strike:f64,t:f64)->Result<CommonParams>{
+        CommonParams::new(self.spot,self.risk_free_rate,self.volatility(strike,t)?,self.dividend_yield,t)
+    }
+
+    /// Copy of `params` with the volatility replaced by the surface volatility at `strike`
+    /// and the maturity of `params` <br>
+    /// 将`params`的波动率替换为曲面在`strike`与`params`期限处的波动率
+    pub fn apply(&self,params:&CommonParams,strike:f64)->Result<CommonParams>{
+        params.with_volatility(self.volatility(strike,params.time_to_maturity())?)
+    }
+
+    /// Check for static arbitrage on the quoted moneyness range widened by half its width:
+    /// Durrleman's condition within each expiry and non-decreasing total variance across
+    /// expiries <br>
+    /// 在向两侧扩展一半宽度的报价价值度范围上检查静态套利：各到期日内的Durrleman条件，
+    /// 以及总方差随到期日单调不减
+    pub fn check_arbitrage(&self)->Result<()>{
+        let (k_min,k_max)=self.k_range;
+        let half=0.5*(k_max-k_min).max(0.1);
+        let ks:Vec<f64>=(0..CHECK_POINTS)
+            .map(|i| k_min-half+(k_max-k_min+2.0*half)*i as f64/(CHECK_POINTS-1) as f64)
+            .collect();
+        match &self.fit{
+            Fit::Svi(slices)=>{
+                for (slice,t) in slices.iter().zip(&self.expiries){
+                    slice.check_butterfly(&ks).map_err(|e| match e{
+                        OptionError::ArbitrationViolation(msg)=>OptionError::ArbitrationViolation(format!("{} (expiry {})",msg,t)),
+                        other=>other,
+                    })?;
+                }
+            }
+            Fit::Ssvi{params,atm_variances}=>{
+                params.check_butterfly()?;
+                if let Some(i)=(1..atm_variances.len()).find(|&i| atm_variances[i]<atm_variances[i-1]){
+                    return Err(OptionError::ArbitrationViolation(format!(
+                        "Calendar arbitrage: ATM total variance decreases between expiries {} and {}",
+                        self.expiries[i-1],self.expiries[i]
+                    )));
+                }
+            }
+        }
+        for i in 1..self.expiries.len(){
+            if let Some(&k)=ks.iter().find(|&&k| self.slice_variance(i,k)<self.slice_variance(i-1,k)-1e-10){
+                return Err(OptionError::ArbitrationViolation(format!(
+                    "Calendar arbitrage between expiries {} and {} at k={:.4}",
+                    self.expiries[i-1],self.expiries[i],k
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn surface(fit:Fit)->VolSurface{
+        VolSurface{spot:100.0,risk_free_rate:0.0,dividend_yield:0.0,expiries:vec![0.5,1.0],k_range:(-0.4,0.4),fit}
+    }
+
+    #[test]
+    fn test_check_arbitrage_detects_calendar_violation(){
+        let short=SviSlice::new(0.02,0.1,-0.4,0.05,0.2).unwrap();
+        let long=SviSlice::new(0.04,0.1,-0.4,0.05,0.2).unwrap();
+        assert!(surface(Fit::Svi(vec![short,long])).check_arbitrage().is_ok());
+        // 较长到期日的总方差低于较短到期日
+        let inverted=surface(Fit::Svi(vec![long,short]));
+        assert!(matches!(inverted.check_arbitrage(),Err(OptionError::ArbitrationViolation(_))));
+
+        let params=SsviParams::new(-0.3,1.0,0.4).unwrap();
+        let inverted=surface(Fit::Ssvi{params,atm_variances:vec![0.06,0.02]});
+        assert!(matches!(inverted.check_arbitrage(),Err(OptionError::ArbitrationViolation(_))));
+    }
+
+    #[test]
+    fn test_total_variance_time_interpolation(){
+        let params=SsviParams::new(-0.3,1.0,0.4).unwrap();
+        let ssvi=surface(Fit::Ssvi{params,atm_variances:vec![0.02,0.06]});
+        // ATM总方差在到期日之间线性插值，两端按时间比例外推
+        for (t,theta) in [(0.75,0.04),(0.25,0.01),(2.0,0.12)]{
+            assert!((ssvi.total_variance_at(0.1,t)-params.total_variance(0.1,theta)).abs()<1e-14);
+        }
+
+        let short=SviSlice::new(0.02,0.1,-0.4,0.05,0.2).unwrap();
+        let long=SviSlice::new(0.04,0.1,-0.4,0.05,0.2).unwrap();
+        let svi=surface(Fit::Svi(vec![short,long]));
+        let k=-0.2;
+        let midpoint=0.5*(short.total_variance(k)+long.total_variance(k));
+        assert!((svi.total_variance_at(k,0.75)-midpoint).abs()<1e-14);
+        assert!((svi.total_variance_at(k,0.25)-0.5*short.total_variance(k)).abs()<1e-14);
+    }
+
+    #[test]
+    fn test_ssvi_requires_quotes_around_the_forward(){
+        // 所有报价都高于远期时ATM总方差需外推
+        let quotes:Vec<VolQuote>=[105.0,110.0,115.0,120.0]
+            .iter()
+            .map(|&strike| VolQuote::new(strike,1.0,0.2).unwrap())
+            .collect();
+        let result=VolSurface::from_quotes(100.0,0.0,0.0,&quotes,SurfaceModel::Ssvi);
+        assert!(matches!(result,Err(OptionError::InvalidParameter(_))));
+    }
+}
@@ -0,0 +1,214 @@
+//! Raw SVI parameterisation of one expiry slice (Gatheral, 2004) <br>
+//! 单个到期日切片的原始SVI参数化（Gatheral, 2004）
+//!
+//! w(k) = a + b·(ρ·(k-m) + √((k-m)² + σ²)), where w is the total implied variance σ_BS²·t and
+//! k = ln(K/F) the log forward moneyness. <br>
+//! w为总隐含方差σ_BS²·t，k = ln(K/F)为远期对数价值度
+use crate::errors::*;
+use crate::utils::linear_algebra::least_squares;
+use crate::utils::optimization::nelder_mead;
+
+/// Raw SVI slice 原始SVI切片
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct SviSlice{
+    a:f64,      // 整体方差水平
+    b:f64,      // 两翼斜率
+    rho:f64,    // 偏斜（-1 < ρ < 1）
+    m:f64,      // 水平平移
+    sigma:f64,  // ATM曲率（σ > 0）
+}
+
+impl SviSlice{
+    /// Create a slice; requires b ≥ 0, |ρ| < 1, σ > 0 and a non-negative minimum variance
+    /// a + b·σ·√(1-ρ²) ≥ 0 <br>
+    /// 创建切片，要求b ≥ 0、|ρ| < 1、σ > 0且最小方差a + b·σ·√(1-ρ²)非负
+    pub fn new(a:f64,b:f64,rho:f64,m:f64,sigma:f64)->Result<Self>{
+        if b<0.0 || rho.abs()>=1.0 || sigma<=0.0{
+            return Err(OptionError::InvalidParameter("SVI needs b ≥ 0, |ρ| < 1 and σ > 0".into()));
+        }
+        if a+b*sigma*(1.0-rho*rho).sqrt()< -1e-12{
+            return Err(OptionError::ArbitrationViolation("SVI slice has negative total variance".into()));
+        }
+        Ok(Self{a,b,rho,m,sigma})
+    }
+
+    // Getter method
+    pub fn a(&self)->f64{self.a}
+    pub fn b(&self)->f64{self.b}
+    pub fn rho(&self)->f64{self.rho}
+    pub fn m(&self)->f64{self.m}
+    pub fn sigma(&self)->f64{self.sigma}
+
+    /// Total implied variance w(k) 总隐含方差
+    pub fn total_variance(&self,k:f64)->f64{
+        let x=k-self.m;
+        self.a+self.b*(self.rho*x+(x*x+self.sigma*self.sigma).sqrt())
+    }
+
+    /// First and second derivatives w'(k), w''(k) 一阶、二阶导数
+    pub fn derivatives(&self,k:f64)->(f64,f64){
+        let x=k-self.m;
+        let root=(x*x+self.sigma*self.sigma).sqrt();
+        (self.b*(self.rho+x/root),self.b*self.sigma*self.sigma/(root*root*root))
+    }
+
+    /// Black-Scholes implied volatility √(w(k)/t) 隐含波动率
+    pub fn implied_volatility(&self,k:f64,t:f64)->f64{
+        (self.total_variance(k).max(0.0)/t).sqrt()
+    }
+
+    /// Durrleman's density function g(k) = (1 - k·w'/(2w))² - w'²/4·(1/w + 1/4) + w''/2;
+    /// the slice is free of butterfly arbitrage where g ≥ 0 <br>
+    /// Durrleman密度函数，g ≥ 0处无蝶式套利
+    pub fn durrleman(&self,k:f64)->f64{
+        let w=self.total_variance(k);
+        let (first,second)=self.derivatives(k);
+        (1.0-k*first/(2.0*w)).powi(2)-0.25*first*first*(1.0/w+0.25)+0.5*second
+    }
+
+    /// Check g(k) ≥ 0 (and w > 0) at the given log-moneyness points <br>
+    /// 在给定对数价值度上检查g(k) ≥ 0（且w > 0）
+    pub fn check_butterfly(&self,log_moneyness:&[f64])->Result<()>{
+        for &k in log_moneyness{
+            if self.total_variance(k)<=0.0{
+                return Err(OptionError::ArbitrationViolation(format!("SVI total variance is not positive at k={:.4}",k)));
+            }
+            let g=self.durrleman(k);
+            if g< -1e-10{
+                return Err(OptionError::ArbitrationViolation(format!("Butterfly arbitrage in SVI slice at k={:.4} (g={:.3e})",k,g)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fit to total variances by the quasi-explicit method of Zeliade (2009): for fixed (m, σ)
+    /// the parameters (a, d, c) = (a, b·ρ·σ, b·σ) solve a linear least-squares problem on the
+    /// feasible domain 0 ≤ c ≤ 4σ, |d| ≤ min(c, 4σ-c), 0 ≤ a ≤ max w, and (m, σ) are found by
+    /// Nelder-Mead; `weights` default to 1 <br>
+    /// Zeliade (2009) 准显式法拟合总方差：固定(m, σ)时(a, d, c) = (a, b·ρ·σ, b·σ)为可行域
+    /// 0 ≤ c ≤ 4σ, |d| ≤ min(c, 4σ-c), 0 ≤ a ≤ max w上的线性最小二乘问题，(m, σ)由Nelder-Mead搜索；
+    /// `weights`默认为1
+    pub fn calibrate(log_moneyness:&[f64],total_variances:&[f64],weights:Option<&[f64]>)->Result<Self>{
+        let n=log_moneyness.len();
+        if n<5 || total_variances.len()!=n || weights.is_some_and(|w| w.len()!=n){
+            return Err(OptionError::InvalidParameter("SVI calibration needs at least 5 quotes with matching variances and weights".into()));
+        }
+        if total_variances.iter().any(|&w| w<=0.0){
+            return Err(OptionError::InvalidParameter("Total variances must be positive".into()));
+        }
+        let weight=|i:usize| weights.map_or(1.0,|w| w[i]);
+        let max_variance=total_variances.iter().cloned().fold(0.0,f64::max);
+
+        // 固定(m, σ)后在可行域 0 ≤ c ≤ 4σ, |d| ≤ min(c, 4σ-c), 0 ≤ a ≤ max w 上求解带约束的线性最小二乘。
+        // 以u = c+d、v = c-d代换后可行域为长方体 0 ≤ a ≤ max w, 0 ≤ u, v ≤ 4σ，凸二次目标的最小值
+        // 位于某个面的相对内部：各坐标自由或取上、下界，在可行的候选解中取误差最小者
+        let inner=|m:f64,sigma:f64|->Option<(f64,f64,f64,f64)>{
+            let columns:Vec<[f64;3]>=log_moneyness.iter().map(|k|{
+                let y=(k-m)/sigma;
+                let z=(y*y+1.0).sqrt();
+                [1.0,0.5*(z+y),0.5*(z-y)]
+            }).collect();
+            let bounds=[(0.0,max_variance),(0.0,4.0*sigma),(0.0,4.0*sigma)];
+            let error=|x:&[f64;3]| (0..n).map(|i|{
+                let fitted:f64=(0..3).map(|j| columns[i][j]*x[j]).sum();
+                weight(i)*(fitted-total_variances[i]).powi(2)
+            }).sum::<f64>();
+            // 状态0为自由，1、2为取下、上界
+            let solve_face=|states:[usize;3]|->Option<[f64;3]>{
+                let mut x=[0.0;3];
+                let free:Vec<usize>=(0..3).filter(|&j| states[j]==0).collect();
+                for j in 0..3{
+                    x[j]=match states[j]{1=>bounds[j].0,2=>bounds[j].1,_=>0.0};
+                }
+                if !free.is_empty(){
+                    let design:Vec<Vec<f64>>=(0..n).map(|i| free.iter().map(|&j| weight(i).sqrt()*columns[i][j]).collect()).collect();
+                    let target:Vec<f64>=(0..n).map(|i|{
+                        let fixed:f64=(0..3).map(|j| columns[i][j]*x[j]).sum();
+                        weight(i).sqrt()*(total_variances[i]-fixed)
+                    }).collect();
+                    let solution=least_squares(&design,&target).ok()?;
+                    for (&j,value) in free.iter().zip(solution){
+                        x[j]=value;
+                    }
+                }
+                let tolerance=1e-12*(1.0+max_variance+sigma);
+                if x.iter().zip(&bounds).any(|(x,(lower,upper))| *x<lower-tolerance || *x>upper+tolerance){
+                    return None;
+                }
+                // 去除舍入误差造成的越界
+                for (x,(lower,upper)) in x.iter_mut().zip(&bounds){
+                    *x=x.clamp(*lower,*upper);
+                }
+                Some(x)
+            };
+            // 无约束解可行时即为最优
+            let best=match solve_face([0,0,0]){
+                Some(x)=>x,
+                None=>{
+                    let mut best:Option<([f64;3],f64)>=None;
+                    for pattern in 1..27{
+                        let states=[pattern%3,pattern/3%3,pattern/9];
+                        if let Some(x)=solve_face(states){
+                            let value=error(&x);
+                            if best.is_none_or(|(_,best_value)| value<best_value){
+                                best=Some((x,value));
+                            }
+                        }
+                    }
+                    best?.0
+                },
+            };
+            let [a,u,v]=best;
+            Some((a,0.5*(u-v),0.5*(u+v),error(&best)))
+        };
+        let objective=|x:&[f64]| inner(x[0],x[1].exp()).map_or(f64::INFINITY,|r| r.3);
+
+        let k_min=log_moneyness.iter().cloned().fold(f64::INFINITY,f64::min);
+        let k_max=log_moneyness.iter().cloned().fold(f64::NEG_INFINITY,f64::max);
+        let width=(k_max-k_min).max(1e-4);
+        // 多个起点，取最优
+        let mut best:Option<(f64,Vec<f64>)>=None;
+        for m0 in [k_min+0.25*width,0.5*(k_min+k_max),k_max-0.25*width]{
+            for sigma0 in [0.1*width,0.5*width]{
+                let minimum=nelder_mead(objective,&[m0,sigma0.ln()],&[0.1*width,0.5],1e-16,2000)?;
+                if best.as_ref().is_none_or(|(value,_)| minimum.value<*value){
+                    best=Some((minimum.value,minimum.point));
+                }
+            }
+        }
+        let (_,point)=best.ok_or_else(|| OptionError::ConvergenceError("SVI calibration failed".into()))?;
+        let (m,sigma)=(point[0],point[1].exp());
+        let (a,d,c,_)=inner(m,sigma).ok_or_else(|| OptionError::ConvergenceError("SVI calibration failed".into()))?;
+        let b=c/sigma;
+        let rho=if c>0.0{(d/c).clamp(-0.999_999,0.999_999)}else{0.0};
+        Self::new(a,b,rho,m,sigma)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn test_svi_calibration_recovers_slice(){
+        let slice=SviSlice::new(0.02,0.1,-0.4,0.05,0.2).unwrap();
+        let ks:Vec<f64>=(0..15).map(|i| -0.6+0.08*i as f64).collect();
+        let ws:Vec<f64>=ks.iter().map(|&k| slice.total_variance(k)).collect();
+        let fitted=SviSlice::calibrate(&ks,&ws,None).unwrap();
+        for &k in &ks{
+            assert!((fitted.total_variance(k)-slice.total_variance(k)).abs()<1e-8);
+        }
+        assert!(fitted.check_butterfly(&ks).is_ok());
+
+        // 两翼斜率过大的切片存在蝶式套利
+        let steep=SviSlice::new(0.0,2.5,0.9,0.0,0.05).unwrap();
+        assert!(matches!(steep.check_butterfly(&ks),Err(OptionError::ArbitrationViolation(_))));
+
+        // 拟合结果位于可行域 0 ≤ c ≤ 4σ, |d| ≤ min(c, 4σ-c), a ≥ 0 内
+        let ws:Vec<f64>=ks.iter().map(|&k| steep.total_variance(k)).collect();
+        let fitted=SviSlice::calibrate(&ks,&ws,None).unwrap();
+        let (c,d)=(fitted.b()*fitted.sigma(),fitted.b()*fitted.rho()*fitted.sigma());
+        assert!(fitted.a()>=0.0 && c<=4.0*fitted.sigma()+1e-12);
+        assert!(d.abs()<=c.min(4.0*fitted.sigma()-c)+1e-12);
+    }
+}
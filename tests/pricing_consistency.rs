@@ -82,4 +82,1319 @@ fn test_dynamic_register_calculator() {
     let vanilla_calc = Arc::new(optionrs::core::analytic::calculators::VanillaCalculator) as optionrs::traits::engine::AnalyticCalculatorRef;
     analytic_engine.register_calculator(vanilla_calc);
     assert!(analytic_engine.get_calculator(optionrs::traits::payoff::AnalyticPayoffType::VanillaPut).is_some());
-}
\ No newline at end of file
+}
+#[test]
+fn test_lookback_analytic_vs_monte_carlo() {
+    use optionrs::products::lookback::{FixedStrikeLookback, FloatingStrikeLookback};
+    // 连续监测解析解与离散监测蒙特卡洛（步数足够多时）应接近
+    let analytic = EngineConfig::analytic().unwrap();
+    let mc = EngineConfig::monte_carlo(
+        20_000,
+        1000,
+        Some(Arc::new(GeometricBrownianMotion::from_financial_params(0.05, 0.0, 0.2).unwrap())),
+        true,
+        true,
+        42,
+    ).unwrap();
+
+    let floating = FloatingStrikeLookback::call(100.0, 0.05, 0.2, 0.0, 1.0, None).unwrap();
+    let seasoned = FixedStrikeLookback::call(100.0, 100.0, 0.05, 0.2, 0.0, 1.0, Some(110.0)).unwrap();
+    for (analytic_price, mc_price) in [
+        (analytic.price(&floating).unwrap(), mc.price(&floating).unwrap()),
+        (analytic.price(&seasoned).unwrap(), mc.price(&seasoned).unwrap()),
+    ] {
+        // 离散监测会低估极值，允许约3%的偏差
+        assert!((analytic_price - mc_price).abs() < 0.03 * analytic_price,
+            "analytic {} vs monte carlo {}", analytic_price, mc_price);
+    }
+}
+
+#[test]
+fn test_spread_analytic_vs_monte_carlo() {
+    use optionrs::core::monte_carlo::MonteCarloEngine;
+    use optionrs::params::two_asset::TwoAssetParams;
+    use optionrs::products::spread::SpreadOption;
+
+    let params = TwoAssetParams::new(110.0, 100.0, 0.3, 0.2, 0.02, 0.01, 0.5, 0.05, 1.0).unwrap();
+    let mc = MonteCarloEngine::new(100_000, 1, None, true, true, 7).unwrap();
+    let analytic_engine = AnalyticEngine::new();
+
+    // Margrabe在相关GBM下是精确解；Kirk近似在小执行价时误差很小
+    for option in [
+        SpreadOption::exchange(params).unwrap(),
+        SpreadOption::call(params, 5.0).unwrap(),
+        SpreadOption::put(params, 5.0).unwrap(),
+    ] {
+        let analytic = analytic_engine.price_two_asset(&option).unwrap();
+        let mc_price = mc.price_two_asset(&option).unwrap();
+        assert!((analytic - mc_price).abs() < 0.1, "analytic {} vs monte carlo {}", analytic, mc_price);
+    }
+}
+
+#[test]
+fn test_basket_and_rainbow_monte_carlo() {
+    use optionrs::core::analytic::calculators::{SpreadCalculator, VanillaCalculator};
+    use optionrs::core::monte_carlo::MonteCarloEngine;
+    use optionrs::params::common::CommonParams;
+    use optionrs::params::multi_asset::MultiAssetParams;
+    use optionrs::params::two_asset::TwoAssetParams;
+    use optionrs::traits::engine::AnalyticCalculator;
+    use optionrs::traits::payoff::{BasketCallPayoff, BestOfCallPayoff, CallPayoff, WorstOfCallPayoff};
+
+    let mc = MonteCarloEngine::new(100_000, 1, None, true, true, 11).unwrap();
+    let vanilla = VanillaCalculator
+        .calculate(&CommonParams::new(100.0, 0.05, 0.2, 0.0, 1.0).unwrap(), &CallPayoff::new(100.0))
+        .unwrap();
+
+    // 完全相关且参数相同的三资产篮子退化为单资产欧式期权
+    let ones = vec![vec![1.0; 3]; 3];
+    let params = MultiAssetParams::new(vec![100.0; 3], vec![0.2; 3], vec![0.0; 3], ones, 0.05, 1.0).unwrap();
+    let basket = mc
+        .calculate_multi_asset_price(&params, &BasketCallPayoff::new(vec![1.0 / 3.0; 3], 100.0))
+        .unwrap();
+    assert!((basket - vanilla).abs() < 0.1, "basket {} vs vanilla {}", basket, vanilla);
+
+    // 部分相关时：最差资产 ≤ 单资产 ≤ 最优资产，分散化使篮子价格低于单资产
+    let correlation = vec![vec![1.0, 0.5, 0.3], vec![0.5, 1.0, 0.4], vec![0.3, 0.4, 1.0]];
+    let params = MultiAssetParams::new(vec![100.0; 3], vec![0.2; 3], vec![0.0; 3], correlation, 0.05, 1.0).unwrap();
+    let best = mc.calculate_multi_asset_price(&params, &BestOfCallPayoff::new(100.0)).unwrap();
+    let worst = mc.calculate_multi_asset_price(&params, &WorstOfCallPayoff::new(100.0)).unwrap();
+    let basket = mc
+        .calculate_multi_asset_price(&params, &BasketCallPayoff::new(vec![1.0 / 3.0; 3], 100.0))
+        .unwrap();
+    assert!(worst < vanilla && vanilla < best);
+    assert!(basket < vanilla);
+
+    // 两资产参数经多资产引擎定价的交换期权与Margrabe公式一致
+    let two_asset = TwoAssetParams::new(110.0, 100.0, 0.3, 0.2, 0.02, 0.01, 0.5, 0.05, 1.0).unwrap();
+    let exchange = optionrs::traits::payoff::SpreadCallPayoff::new(0.0);
+    let margrabe = SpreadCalculator.calculate(&two_asset, &exchange).unwrap();
+    let basket_exchange = mc
+        .calculate_multi_asset_price(&two_asset.to_multi_asset().unwrap(), &BasketCallPayoff::new(vec![1.0, -1.0], 0.0))
+        .unwrap();
+    assert!((margrabe - basket_exchange).abs() < 0.1, "margrabe {} vs monte carlo {}", margrabe, basket_exchange);
+
+    // 篮子权重个数与资产个数不一致时报错
+    assert!(mc.calculate_multi_asset_price(&params, &BasketCallPayoff::new(vec![0.5; 2], 100.0)).is_err());
+}
+
+#[test]
+fn test_exotic_analytic_vs_binomial() {
+    use optionrs::products::exotic::{ChooserOption, CompoundOption, ForwardStartOption};
+    // 决策日的Payoff为所交付期权的BS价值，二叉树在决策日前按欧式期权回溯
+    let analytic = EngineConfig::analytic().unwrap();
+    let binomial = EngineConfig::binomial(1000).unwrap();
+
+    let compound = CompoundOption::call_on_call(100.0, 8.0, 100.0, 0.05, 0.25, 0.02, 0.5, 1.0).unwrap();
+    let compound_put = CompoundOption::put_on_put(100.0, 6.0, 105.0, 0.05, 0.25, 0.02, 0.5, 1.0).unwrap();
+    let simple = ChooserOption::simple(100.0, 100.0, 0.05, 0.25, 0.02, 0.5, 1.0).unwrap();
+    let complex = ChooserOption::complex(100.0, 105.0, 95.0, 0.05, 0.25, 0.02, 0.5, 1.0, 0.75).unwrap();
+    let forward = ForwardStartOption::call(100.0, 1.05, 0.05, 0.25, 0.02, 0.5, 1.0).unwrap();
+    for (analytic_price, binomial_price) in [
+        (analytic.price(&compound).unwrap(), binomial.price(&compound).unwrap()),
+        (analytic.price(&compound_put).unwrap(), binomial.price(&compound_put).unwrap()),
+        (analytic.price(&simple).unwrap(), binomial.price(&simple).unwrap()),
+        (analytic.price(&complex).unwrap(), binomial.price(&complex).unwrap()),
+        (analytic.price(&forward).unwrap(), binomial.price(&forward).unwrap()),
+    ] {
+        assert!((analytic_price - binomial_price).abs() < 0.02,
+            "analytic {} vs binomial {}", analytic_price, binomial_price);
+    }
+}
+
+#[test]
+fn test_binary_engines_consistency() {
+    use optionrs::products::binary::{BinaryOption, TouchOption};
+    let analytic = EngineConfig::analytic().unwrap();
+
+    // PDE终值按网格单元平均，数字期权不再出现与网格位置相关的振荡
+    let cash = BinaryOption::cash_or_nothing_call(100.0, 100.0, 10.0, 0.05, 0.2, 0.0, 1.0).unwrap();
+    let asset = BinaryOption::asset_or_nothing_put(100.0, 105.0, 0.05, 0.2, 0.01, 1.0).unwrap();
+    let gap = BinaryOption::gap_call(100.0, 95.0, 105.0, 0.05, 0.2, 0.01, 1.0).unwrap();
+    for option in [&cash, &asset, &gap] {
+        let pde = EngineConfig::pde(400, 2000, FiniteDifferenceMethod::Explicit, true, option.boundary_condition()).unwrap();
+        let analytic_price = analytic.price(option).unwrap();
+        let pde_price = pde.price(option).unwrap();
+        assert!((analytic_price - pde_price).abs() < 0.02, "analytic {} vs pde {}", analytic_price, pde_price);
+    }
+
+    // 布朗桥修正使粗时间步长的蒙特卡洛也逼近连续监测的解析解
+    let mc = EngineConfig::monte_carlo(
+        50_000,
+        20,
+        Some(Arc::new(GeometricBrownianMotion::from_financial_params(0.05, 0.01, 0.2).unwrap())),
+        false,
+        true,
+        5,
+    ).unwrap();
+    let one_touch = TouchOption::one_touch(100.0, 115.0, 1.0, 0.05, 0.2, 0.01, 1.0).unwrap();
+    let no_touch = TouchOption::no_touch(100.0, 90.0, 1.0, 0.05, 0.2, 0.01, 1.0).unwrap();
+    for option in [&one_touch, &no_touch] {
+        let analytic_price = analytic.price(option).unwrap();
+        let mc_price = mc.price(option).unwrap();
+        assert!((analytic_price - mc_price).abs() < 0.01, "analytic {} vs monte carlo {}", analytic_price, mc_price);
+    }
+
+    // 二叉树在每个节点监测障碍，离散监测偏差随步数递减
+    let binomial = EngineConfig::binomial(2000).unwrap();
+    for option in [&one_touch, &no_touch] {
+        let analytic_price = analytic.price(option).unwrap();
+        let binomial_price = binomial.price(option).unwrap();
+        assert!((analytic_price - binomial_price).abs() < 0.015, "analytic {} vs binomial {}", analytic_price, binomial_price);
+    }
+}
+
+#[test]
+fn test_american_exercise_boundary() {
+    use optionrs::core::analytic::calculators::VanillaCalculator;
+    use optionrs::products::american::{AmericanCall, AmericanPut};
+    use optionrs::traits::engine::{AnalyticCalculator, ExerciseBoundaryEngine};
+    use optionrs::traits::payoff::CallPayoff;
+
+    let put = AmericanPut::new(100.0, 100.0, 0.05, 0.2, 0.0, 1.0).unwrap();
+    let binomial = EngineConfig::binomial(1000).unwrap();
+    let pde = EngineConfig::pde(400, 2000, FiniteDifferenceMethod::Explicit, true, put.boundary_condition()).unwrap();
+
+    let (tree_price, tree_boundary) = binomial.price_with_boundary(&put).unwrap();
+    let (pde_price, pde_boundary) = pde.price_with_boundary(&put).unwrap();
+    // 经典基准：S=K=100, r=5%, σ=20%, T=1 美式看跌约为6.09
+    assert!((tree_price - 6.09).abs() < 0.01, "binomial {}", tree_price);
+    assert!((pde_price - tree_price).abs() < 0.02, "pde {} vs binomial {}", pde_price, tree_price);
+
+    // 看跌期权边界低于执行价，且随到期临近单调上升
+    let tree_points: Vec<(f64, f64)> = tree_boundary.times.iter().copied()
+        .zip(tree_boundary.critical_prices.iter().copied())
+        .filter_map(|(t, s)| s.map(|s| (t, s)))
+        .collect();
+    assert!(tree_points.len() > 900);
+    assert!(tree_points.iter().all(|&(_, s)| s < 100.0));
+    let (first, last) = (tree_points[0].1, tree_points[tree_points.len() - 1].1);
+    assert!(first < last && last > 95.0, "boundary {} -> {}", first, last);
+
+    // 两种引擎在t=0.5处的边界接近
+    let at_half = |times: &[f64], prices: &[Option<f64>]| {
+        let i = times.iter().position(|&t| t >= 0.5).unwrap();
+        prices[i].unwrap()
+    };
+    let tree_half = at_half(&tree_boundary.times, &tree_boundary.critical_prices);
+    let pde_half = at_half(&pde_boundary.times, &pde_boundary.critical_prices);
+    assert!((tree_half - pde_half).abs() < 1.5, "binomial {} vs pde {}", tree_half, pde_half);
+
+    // 无股息美式看涨期权不会提前行权，价格等于欧式
+    let call = AmericanCall::new(100.0, 100.0, 0.05, 0.2, 0.0, 1.0).unwrap();
+    let (call_price, call_boundary) = binomial.price_with_boundary(&call).unwrap();
+    assert!(call_boundary.critical_prices.iter().all(|s| s.is_none()));
+    let european = VanillaCalculator
+        .calculate(&CommonParams::new(100.0, 0.05, 0.2, 0.0, 1.0).unwrap(), &CallPayoff::new(100.0))
+        .unwrap();
+    assert!((call_price - european).abs() < 0.01);
+}
+
+#[test]
+fn test_american_approximations_vs_binomial() {
+    use optionrs::core::analytic::calculators::AmericanApproximation;
+    use optionrs::products::american::{AmericanCall, AmericanPut};
+
+    let binomial = EngineConfig::binomial(2000).unwrap();
+    let put = AmericanPut::new(90.0, 100.0, 0.08, 0.3, 0.04, 0.5).unwrap();
+    let call = AmericanCall::new(120.0, 100.0, 0.06, 0.3, 0.1, 1.0).unwrap();
+    let tree_put = binomial.price(&put).unwrap();
+    let tree_call = binomial.price(&call).unwrap();
+
+    // 各近似方法相对精细二叉树的误差约为0.05量级
+    for (approximation, tolerance) in [
+        (AmericanApproximation::BaroneAdesiWhaley, 0.06),
+        (AmericanApproximation::BjerksundStensland2002, 0.07),
+        (AmericanApproximation::JuZhong, 0.06),
+    ] {
+        let engine = EngineConfig::analytic_american(approximation).unwrap();
+        let put_price = engine.price(&put).unwrap();
+        let call_price = engine.price(&call).unwrap();
+        assert!((put_price - tree_put).abs() < tolerance, "{:?} put {} vs binomial {}", approximation, put_price, tree_put);
+        assert!((call_price - tree_call).abs() < tolerance, "{:?} call {} vs binomial {}", approximation, call_price, tree_call);
+    }
+
+    // 默认解析解引擎对美式期权使用Bjerksund-Stensland (2002)
+    let default_price = EngineConfig::analytic().unwrap().price(&put).unwrap();
+    let bs_price = EngineConfig::analytic_american(AmericanApproximation::BjerksundStensland2002).unwrap().price(&put).unwrap();
+    assert_eq!(default_price, bs_price);
+}
+
+#[test]
+fn test_integral_equation_engine() {
+    use optionrs::products::american::{AmericanCall, AmericanPut};
+    use optionrs::traits::engine::ExerciseBoundaryEngine;
+
+    let engine = EngineConfig::integral_equation(32, 32, 20, 64).unwrap();
+    let binomial = EngineConfig::binomial(2000).unwrap();
+    let put = AmericanPut::new(90.0, 100.0, 0.08, 0.3, 0.04, 0.5).unwrap();
+    let call = AmericanCall::new(120.0, 100.0, 0.06, 0.3, 0.1, 1.0).unwrap();
+
+    // 二叉树误差为O(1/N)，积分方程结果应与2000步二叉树接近
+    for (integral, tree) in [
+        (engine.price(&put).unwrap(), binomial.price(&put).unwrap()),
+        (engine.price(&call).unwrap(), binomial.price(&call).unwrap()),
+    ] {
+        assert!((integral - tree).abs() < 5e-3, "integral equation {} vs binomial {}", integral, tree);
+    }
+
+    // 提前行权边界与二叉树边界一致
+    let put = AmericanPut::new(100.0, 100.0, 0.05, 0.2, 0.0, 1.0).unwrap();
+    let (price, boundary) = engine.price_with_boundary(&put).unwrap();
+    let (_, tree_boundary) = binomial.price_with_boundary(&put).unwrap();
+    assert!((price - 6.0904).abs() < 1e-3);
+    let critical_at = |b: &optionrs::traits::exercise::ExerciseBoundary, t: f64| {
+        let i = b.times.iter().position(|&x| x >= t).unwrap();
+        b.critical_prices[i].unwrap()
+    };
+    for t in [0.25, 0.5, 0.75] {
+        let (integral, tree) = (critical_at(&boundary, t), critical_at(&tree_boundary, t));
+        assert!((integral - tree).abs() < 1.0, "boundary at {}: {} vs {}", t, integral, tree);
+    }
+    assert!(boundary.critical_prices.iter().all(|b| b.unwrap() <= 100.0));
+}
+
+#[test]
+fn test_bermudan_exercise() {
+    use optionrs::products::american::AmericanPut;
+    use optionrs::products::bermudan::BermudanPut;
+
+    let put = BermudanPut::new(100.0, 100.0, 0.05, 0.2, 0.0, 1.0, vec![0.25, 0.5, 0.75, 1.0]).unwrap();
+    let european = BermudanPut::new(100.0, 100.0, 0.05, 0.2, 0.0, 1.0, vec![1.0]).unwrap();
+    let american = AmericanPut::new(100.0, 100.0, 0.05, 0.2, 0.0, 1.0).unwrap();
+    let binomial = EngineConfig::binomial(2000).unwrap();
+    let pde = EngineConfig::pde(400, 2000, FiniteDifferenceMethod::Explicit, true, put.boundary_condition()).unwrap();
+    let mc = EngineConfig::monte_carlo(
+        20_000,
+        52,
+        Some(Arc::new(GeometricBrownianMotion::from_financial_params(0.05, 0.0, 0.2).unwrap())),
+        true,
+        true,
+        7,
+    ).unwrap();
+
+    // 欧式 < 百慕大 < 美式
+    let tree_price = binomial.price(&put).unwrap();
+    let tree_european = binomial.price(&european).unwrap();
+    let tree_american = binomial.price(&american).unwrap();
+    assert!((tree_european - 5.5735).abs() < 0.01, "single date Bermudan {} should be European", tree_european);
+    assert!(tree_european < tree_price && tree_price < tree_american);
+    assert!((tree_price - 5.957).abs() < 0.01);
+
+    let pde_price = pde.price(&put).unwrap();
+    assert!((pde_price - tree_price).abs() < 0.01, "pde {} vs binomial {}", pde_price, tree_price);
+    // Longstaff-Schwartz 为低偏估计，允许统计误差
+    let mc_price = mc.price(&put).unwrap();
+    assert!((mc_price - tree_price).abs() < 0.1, "monte carlo {} vs binomial {}", mc_price, tree_price);
+}
+
+#[test]
+fn test_time_varying_exercise_rules() {
+    use optionrs::products::american::AmericanBoundaryCondition;
+    use optionrs::traits::engine::BoundaryCondition;
+    use optionrs::traits::exercise::{LockoutExercise, PenaltyExercise, StrikeScheduleExercise, WindowExercise};
+    use optionrs::traits::payoff::PutPayoff;
+
+    let params = CommonParams::new(100.0, 0.06, 0.25, 0.0, 1.0).unwrap();
+    let payoff = PutPayoff::new(100.0);
+    let american: Arc<dyn ExerciseRule> = Arc::new(AmericanExercise);
+    let lockout = LockoutExercise::new(american.clone(), 0.5, 1.0).unwrap();
+    let windows = WindowExercise::new(american.clone(), vec![(0.2, 0.3), (0.7, 0.8)], 1.0).unwrap();
+    let schedule = StrikeScheduleExercise::new(american.clone(), vec![0.0, 0.5], vec![105.0, 100.0], false, 1.0).unwrap();
+    let penalty = PenaltyExercise::new(american.clone(), 0.5, 0.05).unwrap();
+
+    let binomial = EngineConfig::binomial(2000).unwrap();
+    let boundary: Arc<dyn BoundaryCondition> = Arc::new(AmericanBoundaryCondition::new(100.0, 0.06, 0.0, false).unwrap());
+    let pde = EngineConfig::pde(400, 3000, FiniteDifferenceMethod::Explicit, true, &boundary).unwrap();
+
+    let european = binomial.calculate_price(&params, &payoff, &EuropeanExercise).unwrap();
+    let unrestricted = binomial.calculate_price(&params, &payoff, american.as_ref()).unwrap();
+    let rules: [(&str, &dyn ExerciseRule); 4] = [("lockout", &lockout), ("windows", &windows), ("schedule", &schedule), ("penalty", &penalty)];
+    for (name, rule) in rules {
+        let tree_price = binomial.calculate_price(&params, &payoff, rule).unwrap();
+        let pde_price = pde.calculate_price(&params, &payoff, rule).unwrap();
+        assert!((tree_price - pde_price).abs() < 0.01, "{}: binomial {} vs pde {}", name, tree_price, pde_price);
+        // 锁定期、窗口与罚金只会降低美式期权价值；更高的早期执行价则提高价值
+        if name == "schedule" {
+            assert!(tree_price > unrestricted);
+        } else {
+            assert!(european < tree_price && tree_price < unrestricted, "{} {} not within [{}, {}]", name, tree_price, european, unrestricted);
+        }
+    }
+
+    // 执行价安排的最后一个执行价与payoff不同：到期按安排的执行价结算，欧式时即为K=110的看跌期权
+    let european_rule: Arc<dyn ExerciseRule> = Arc::new(EuropeanExercise);
+    let schedule = StrikeScheduleExercise::new(european_rule, vec![0.0, 0.5], vec![100.0, 110.0], false, 1.0).unwrap();
+    let reference = AnalyticEngine::new().calculate_price(&params, &PutPayoff::new(110.0), &EuropeanExercise).unwrap();
+    let boundary: Arc<dyn BoundaryCondition> = Arc::new(AmericanBoundaryCondition::new(110.0, 0.06, 0.0, false).unwrap());
+    let pde = EngineConfig::pde(400, 3000, FiniteDifferenceMethod::Explicit, true, &boundary).unwrap();
+    let mc = EngineConfig::monte_carlo(
+        100_000,
+        1,
+        Some(Arc::new(GeometricBrownianMotion::from_financial_params(0.06, 0.0, 0.25).unwrap())),
+        true,
+        true,
+        7,
+    ).unwrap();
+    let tree_price = binomial.calculate_price(&params, &payoff, &schedule).unwrap();
+    let pde_price = pde.calculate_price(&params, &payoff, &schedule).unwrap();
+    let mc_price = mc.calculate_price(&params, &payoff, &schedule).unwrap();
+    assert!((tree_price - reference).abs() < 0.01, "binomial {} vs {}", tree_price, reference);
+    assert!((pde_price - reference).abs() < 0.01, "pde {} vs {}", pde_price, reference);
+    assert!((mc_price - reference).abs() < 0.1, "monte carlo {} vs {}", mc_price, reference);
+}
+
+#[test]
+fn test_pde_lcp_solvers() {
+    use optionrs::core::pde::{LcpSolver, PDEEngine};
+    use optionrs::products::american::AmericanPut;
+
+    // 参考值：S=K=100, r=5%, σ=20%, T=1 美式看跌期权 6.0904
+    let put = AmericanPut::new(100.0, 100.0, 0.05, 0.2, 0.0, 1.0).unwrap();
+    let reference = 6.0904;
+    for method in [FiniteDifferenceMethod::Implicit, FiniteDifferenceMethod::CrankNicolson] {
+        let projected = PDEEngine::new(400, 100, method, true, put.boundary_condition().clone()).unwrap().price(&put).unwrap();
+        let prices: Vec<f64> = [LcpSolver::psor(), LcpSolver::BrennanSchwartz, LcpSolver::penalty()]
+            .into_iter()
+            .map(|solver| {
+                PDEEngine::new(400, 100, method, true, put.boundary_condition().clone())
+                    .unwrap()
+                    .with_lcp_solver(solver)
+                    .price(&put)
+                    .unwrap()
+            })
+            .collect();
+        // 三种线性互补求解器给出同一解，且比先解后投影更精确
+        for price in &prices {
+            assert!((price - prices[1]).abs() < 1e-6, "{:?}: {:?}", method, prices);
+        }
+        assert!((prices[1] - reference).abs() < (projected - reference).abs());
+    }
+    // Crank-Nicolson + Brennan-Schwartz 在100个时间步下已接近参考值
+    let cn = PDEEngine::new(400, 100, FiniteDifferenceMethod::CrankNicolson, true, put.boundary_condition().clone())
+        .unwrap()
+        .with_lcp_solver(LcpSolver::BrennanSchwartz)
+        .price(&put)
+        .unwrap();
+    assert!((cn - reference).abs() < 2e-3, "crank-nicolson {}", cn);
+}
+
+#[test]
+fn test_pde_non_uniform_grids() {
+    use optionrs::core::pde::{GridSpec, LcpSolver, PDEEngine};
+    use optionrs::products::american::AmericanPut;
+    use optionrs::products::european_call::EuropeanCall;
+
+    let call = EuropeanCall::new(100.0, 100.0, 0.05, 0.2, 0.0, 1.0).unwrap();
+    let bs = AnalyticEngine::new().price(&call).unwrap();
+    let sinh = GridSpec::Sinh { center: 100.0, concentration: 0.1, num_std: 5.0 };
+    let price = |grid: GridSpec| {
+        PDEEngine::new(100, 200, FiniteDifferenceMethod::CrankNicolson, true, call.boundary_condition().clone())
+            .unwrap()
+            .with_grid(grid)
+            .price(&call)
+            .unwrap()
+    };
+    // 相同节点数下，在执行价附近加密的网格误差更小
+    let uniform = price(GridSpec::default());
+    let std_devs = price(GridSpec::StandardDeviations { num_std: 5.0 });
+    let concentrated = price(sinh.clone());
+    assert!((concentrated - bs).abs() < (uniform - bs).abs());
+    assert!((concentrated - bs).abs() < (std_devs - bs).abs());
+    assert!((concentrated - bs).abs() < 5e-3, "sinh {} vs bs {}", concentrated, bs);
+
+    // 美式看跌期权：非均匀网格上的三种差分格式及价格空间
+    let put = AmericanPut::new(100.0, 100.0, 0.05, 0.2, 0.0, 1.0).unwrap();
+    for (method, x_steps, t_steps, use_log_space) in [
+        (FiniteDifferenceMethod::Explicit, 100, 2000, true),
+        (FiniteDifferenceMethod::Implicit, 200, 2000, true),
+        (FiniteDifferenceMethod::CrankNicolson, 200, 200, true),
+        (FiniteDifferenceMethod::CrankNicolson, 200, 200, false),
+    ] {
+        let price = PDEEngine::new(x_steps, t_steps, method, use_log_space, put.boundary_condition().clone())
+            .unwrap()
+            .with_grid(sinh.clone())
+            .with_lcp_solver(LcpSolver::BrennanSchwartz)
+            .price(&put)
+            .unwrap();
+        assert!((price - 6.0904).abs() < 0.01, "{:?} {}", method, price);
+    }
+
+    // 用户给定节点
+    let nodes: Vec<f64> = (1..=300).map(|i| i as f64).collect();
+    let custom = PDEEngine::new(100, 200, FiniteDifferenceMethod::CrankNicolson, false, call.boundary_condition().clone())
+        .unwrap()
+        .with_grid(GridSpec::Custom(nodes))
+        .price(&call)
+        .unwrap();
+    assert!((custom - bs).abs() < 0.01, "custom {} vs bs {}", custom, bs);
+
+    // 网格上边缘的看涨期权价值按股息率折现标的：S_max·e^{-qt} - K·e^{-rt}
+    let dividend_call = EuropeanCall::new(100.0, 100.0, 0.05, 0.2, 0.08, 2.0).unwrap();
+    let bs = AnalyticEngine::new().price(&dividend_call).unwrap();
+    let pde = PDEEngine::new(400, 400, FiniteDifferenceMethod::CrankNicolson, true, dividend_call.boundary_condition().clone())
+        .unwrap()
+        .price(&dividend_call)
+        .unwrap();
+    assert!((pde - bs).abs() < 5e-3, "pde {} vs bs {}", pde, bs);
+}
+
+#[test]
+fn test_pde_smooth_gamma() {
+    use optionrs::core::pde::{GridSpec, LcpSolver, PDEEngine};
+    use optionrs::products::american::AmericanPut;
+    use optionrs::products::european_call::EuropeanCall;
+    use optionrs::traits::engine::GreeksEngine;
+
+    // 短期平值看涨期权，时间步较粗：Crank-Nicolson的Gamma在执行价处振荡
+    let (s, k, r, sigma, t) = (100.0, 100.0, 0.05, 0.2, 0.1f64);
+    let call = EuropeanCall::new(s, k, r, sigma, 0.0, t).unwrap();
+    let d1 = ((s / k).ln() + (r + 0.5 * sigma * sigma) * t) / (sigma * t.sqrt());
+    let bs_gamma = (-0.5 * d1 * d1).exp() / (2.0 * std::f64::consts::PI).sqrt() / (s * sigma * t.sqrt());
+    let engine = |method| {
+        PDEEngine::new(800, 100, method, true, call.boundary_condition().clone())
+            .unwrap()
+            .with_grid(GridSpec::StandardDeviations { num_std: 6.0 })
+    };
+    let gamma = |engine: PDEEngine| engine.gamma(call.common(), call.payoff(), call.exercise_type()).unwrap();
+
+    let crank_nicolson = gamma(engine(FiniteDifferenceMethod::CrankNicolson));
+    assert!((crank_nicolson - bs_gamma).abs() > 0.01, "crank-nicolson {} bs {}", crank_nicolson, bs_gamma);
+    for (name, smoothed) in [
+        ("rannacher", gamma(engine(FiniteDifferenceMethod::CrankNicolson).with_rannacher_steps(2))),
+        ("bdf2", gamma(engine(FiniteDifferenceMethod::Bdf2))),
+        ("tr-bdf2", gamma(engine(FiniteDifferenceMethod::TrBdf2))),
+    ] {
+        assert!((smoothed - bs_gamma).abs() < 1e-4, "{} {} bs {}", name, smoothed, bs_gamma);
+    }
+
+    // 新格式同样适用于美式期权
+    let put = AmericanPut::new(100.0, 100.0, 0.05, 0.2, 0.0, 1.0).unwrap();
+    for method in [FiniteDifferenceMethod::Bdf2, FiniteDifferenceMethod::TrBdf2] {
+        let price = PDEEngine::new(400, 200, method, true, put.boundary_condition().clone())
+            .unwrap()
+            .with_lcp_solver(LcpSolver::BrennanSchwartz)
+            .with_rannacher_steps(2)
+            .price(&put)
+            .unwrap();
+        assert!((price - 6.0904).abs() < 5e-3, "{:?} {}", method, price);
+    }
+}
+
+#[test]
+fn test_adi_two_dimensional() {
+    use optionrs::core::analytic::calculators::SpreadCalculator;
+    use optionrs::core::pde::{AdiEngine, AdiScheme, GridSpec};
+    use optionrs::params::heston::HestonParams;
+    use optionrs::params::two_asset::TwoAssetParams;
+    use optionrs::traits::payoff::{PutPayoff, SpreadCallPayoff};
+
+    // Clarke & Parrott (1999) Heston美式看跌期权：K=10, v0=0.0625, κ=5, θ=0.16, ξ=0.9, ρ=0.1, r=0.1, T=0.25
+    let spots = [8.0, 9.0, 10.0, 11.0, 12.0];
+    let references = [2.0, 1.1076, 0.5200, 0.2137, 0.0820];
+    let put = PutPayoff::new(10.0);
+    for scheme in [AdiScheme::Douglas, AdiScheme::CraigSneyd, AdiScheme::HundsdorferVerwer] {
+        let engine = AdiEngine::new(100, 50, 50, scheme)
+            .unwrap()
+            .with_grid(GridSpec::Sinh { center: 10.0, concentration: 0.1, num_std: 5.0 });
+        for (spot, reference) in spots.iter().zip(references) {
+            let params = HestonParams::new(*spot, 0.0625, 5.0, 0.16, 0.9, 0.1, 0.1, 0.0, 0.25).unwrap();
+            let price = engine.price_heston(&params, &put, &AmericanExercise).unwrap();
+            assert!((price - reference).abs() < 2e-3, "{:?} S={} {} vs {}", scheme, spot, price, reference);
+        }
+    }
+
+    // 波动率的波动率趋于0时退化为Black-Scholes（σ=20%）
+    let params = HestonParams::new(100.0, 0.04, 1.0, 0.04, 1e-3, 0.0, 0.05, 0.0, 1.0).unwrap();
+    let engine = AdiEngine::new(200, 100, 100, AdiScheme::default()).unwrap();
+    let european = engine.price_heston(&params, &PutPayoff::new(100.0), &EuropeanExercise).unwrap();
+    let american = engine.price_heston(&params, &PutPayoff::new(100.0), &AmericanExercise).unwrap();
+    assert!((european - 5.5735).abs() < 5e-3, "european {}", european);
+    assert!((american - 6.0904).abs() < 0.015, "american {}", american);
+
+    // 两资产交换期权与Margrabe公式（正负相关）
+    for correlation in [0.6, -0.6] {
+        let params = TwoAssetParams::new(100.0, 95.0, 0.3, 0.2, 0.0, 0.02, correlation, 0.05, 1.0).unwrap();
+        let margrabe = SpreadCalculator.margrabe(&params, true).unwrap();
+        let adi = AdiEngine::new(100, 100, 50, AdiScheme::CraigSneyd)
+            .unwrap()
+            .calculate_two_asset_price(&params, &SpreadCallPayoff::new(0.0))
+            .unwrap();
+        assert!((adi - margrabe).abs() < 0.02, "ρ={} adi {} margrabe {}", correlation, adi, margrabe);
+    }
+}
+
+#[test]
+fn test_pde_rolling_layers() {
+    use optionrs::core::pde::PDEEngine;
+    use optionrs::products::american::AmericanPut;
+    use optionrs::products::european_call::EuropeanCall;
+    use optionrs::traits::engine::GreeksEngine;
+
+    // 滚动两层求解与保留完整历史的求解结果一致
+    let put = AmericanPut::new(100.0, 100.0, 0.05, 0.2, 0.0, 1.0).unwrap();
+    for method in [FiniteDifferenceMethod::CrankNicolson, FiniteDifferenceMethod::Bdf2] {
+        let engine = PDEEngine::new(200, 100, method, true, put.boundary_condition().clone())
+            .unwrap()
+            .with_rannacher_steps(2);
+        let price = engine.price(&put).unwrap();
+        let solution = engine.solve_with_history(put.common(), put.payoff(), put.exercise_type()).unwrap();
+        assert_eq!(solution.values.len(), 101);
+        assert_eq!(solution.times.len(), 101);
+        assert!((solution.times[100] - 1.0).abs() < 1e-12);
+        let log_spots: Vec<f64> = solution.spots.iter().map(|s| s.ln()).collect();
+        let from_history = optionrs::utils::math::quadratic_interpolate(100.0f64.ln(), &log_spots, &solution.values[0]).unwrap();
+        assert!((from_history - price).abs() < 1e-12, "{:?} {} {}", method, from_history, price);
+        // 任意时刻美式价值不低于内在价值
+        for layer in &solution.values {
+            for (value, s) in layer.iter().zip(&solution.spots) {
+                assert!(*value >= (100.0 - s).max(0.0) - 1e-9);
+            }
+        }
+    }
+
+    // Theta取自前两个时间层：Black-Scholes看涨期权Θ = -Sφ(d1)σ/(2√T) - rKe^{-rT}N(d2)
+    let call = EuropeanCall::new(100.0, 100.0, 0.05, 0.2, 0.0, 1.0).unwrap();
+    let (d1, d2) = (0.35f64, 0.15f64);
+    let pdf = (-0.5 * d1 * d1).exp() / (2.0 * std::f64::consts::PI).sqrt();
+    let cdf = statrs::distribution::ContinuousCDF::cdf(&statrs::distribution::Normal::standard(), d2);
+    let bs_theta = -100.0 * pdf * 0.2 / 2.0 - 0.05 * 100.0 * (-0.05f64).exp() * cdf;
+    let theta = PDEEngine::new(400, 400, FiniteDifferenceMethod::CrankNicolson, true, call.boundary_condition().clone())
+        .unwrap()
+        .with_rannacher_steps(2)
+        .theta(call.common(), call.payoff(), call.exercise_type())
+        .unwrap();
+    assert!((theta - bs_theta).abs() < 0.05, "theta {} vs {}", theta, bs_theta);
+}
+
+#[test]
+fn test_volatility_surface() {
+    use optionrs::traits::payoff::CallPayoff;
+    use optionrs::utils::statistics::black_scholes_price;
+    use optionrs::volatility::{SsviParams, SurfaceModel, VolQuote, VolSurface};
+
+    // 由已知的无套利SSVI曲面生成报价
+    let (spot, r, q) = (100.0, 0.03, 0.01);
+    let truth = SsviParams::new(-0.4, 1.2, 0.5).unwrap();
+    let expiries = [0.25, 0.5, 1.0, 2.0];
+    let thetas = [0.012, 0.022, 0.04, 0.075];
+    let strikes = [70.0, 80.0, 90.0, 95.0, 100.0, 105.0, 110.0, 120.0, 135.0];
+    let true_vol = |strike: f64, t: f64, theta: f64| {
+        let k = (strike / (spot * ((r - q) * t).exp())).ln();
+        (truth.total_variance(k, theta) / t).sqrt()
+    };
+    let quotes: Vec<VolQuote> = expiries.iter().zip(&thetas)
+        .flat_map(|(&t, &theta)| strikes.iter().map(move |&k| VolQuote::new(k, t, true_vol(k, t, theta)).unwrap()))
+        .collect();
+
+    let engine = EngineConfig::analytic().unwrap();
+    let exercise = EuropeanExercise;
+    for model in [SurfaceModel::Svi, SurfaceModel::Ssvi] {
+        let surface = VolSurface::from_quotes(spot, r, q, &quotes, model).unwrap();
+        for quote in &quotes {
+            let vol = surface.volatility(quote.strike, quote.expiry).unwrap();
+            assert!((vol - quote.volatility).abs() < 2e-4, "{:?} K={} t={}: {} vs {}", model, quote.strike, quote.expiry, vol, quote.volatility);
+        }
+        // 曲面波动率经CommonParams传入任意引擎
+        let params = surface.common_params(105.0, 1.0).unwrap();
+        let payoff = CallPayoff { strike: 105.0 };
+        let price = engine.calculate_price(&params, &payoff, &exercise).unwrap();
+        let expected = black_scholes_price(spot, 105.0, r, q, true_vol(105.0, 1.0, 0.04), 1.0, true);
+        assert!((price - expected).abs() < 1e-2, "{:?}: {} vs {}", model, price, expected);
+        let applied = surface.apply(&CommonParams::new(spot, r, 0.5, q, 1.0).unwrap(), 105.0).unwrap();
+        assert!((applied.volatility() - params.volatility()).abs() < 1e-14);
+        // 到期日之间插值的总方差单调
+        let mid = surface.total_variance(100.0, 0.75).unwrap();
+        assert!(mid > surface.total_variance(100.0, 0.5).unwrap() && mid < surface.total_variance(100.0, 1.0).unwrap());
+    }
+    // 价格报价转换为波动率报价
+    let price = black_scholes_price(spot, 90.0, r, q, 0.25, 0.5, false);
+    let quote = VolQuote::from_price(price, 90.0, 0.5, false, spot, r, q).unwrap();
+    assert!((quote.volatility - 0.25).abs() < 1e-10);
+
+    // 远期到期日的方差更低：日历套利
+    let inverted: Vec<VolQuote> = quotes.iter()
+        .map(|quote| {
+            let t = if quote.expiry == 2.0 { 0.3 } else { quote.expiry };
+            VolQuote::new(quote.strike, t, quote.volatility).unwrap()
+        })
+        .collect();
+    for model in [SurfaceModel::Svi, SurfaceModel::Ssvi] {
+        let result = VolSurface::from_quotes(spot, r, q, &inverted, model);
+        assert!(matches!(result, Err(OptionError::ArbitrationViolation(_))), "{:?}: {:?}", model, result);
+    }
+}
+
+#[test]
+fn test_heston_calibration() {
+    use optionrs::calibration::{HestonCalibrator, OptionQuote, Optimizer, WeightScheme};
+    use optionrs::core::analytic::HestonAnalyticEngine;
+    use optionrs::params::heston::HestonParams;
+
+    // 由已知Heston参数生成虚值期权报价（买卖价差0.04）
+    let truth = HestonParams::new(100.0, 0.05, 1.5, 0.07, 0.6, -0.65, 0.03, 0.01, 1.0).unwrap();
+    let engine = HestonAnalyticEngine::new();
+    let mut quotes = Vec::new();
+    for expiry in [0.25, 0.5, 1.0, 2.0] {
+        for strike in [80.0, 90.0, 100.0, 110.0, 120.0] {
+            let is_call = strike >= 100.0;
+            let price = engine.price(&truth.with_time(expiry).unwrap(), strike, is_call).unwrap();
+            quotes.push(OptionQuote::from_bid_ask(strike, expiry, price - 0.02, price + 0.02, is_call).unwrap());
+        }
+    }
+    for weights in [WeightScheme::Uniform, WeightScheme::Vega, WeightScheme::BidAsk] {
+        let calibration = HestonCalibrator::new(100.0, 0.03, 0.01).unwrap().with_weights(weights).calibrate(&quotes).unwrap();
+        let params = calibration.params;
+        assert_eq!(calibration.report.optimizer, Optimizer::LevenbergMarquardt);
+        assert!(calibration.report.converged);
+        assert_eq!(calibration.report.residuals.len(), quotes.len());
+        assert!(calibration.report.max_error() < 1e-8, "{:?}: {}", weights, calibration.report.max_error());
+        for (fitted, expected) in [
+            (params.variance(), 0.05),
+            (params.mean_reversion(), 1.5),
+            (params.long_run_variance(), 0.07),
+            (params.vol_of_vol(), 0.6),
+            (params.correlation(), -0.65),
+        ] {
+            assert!((fitted - expected).abs() < 1e-5, "{:?}: {} vs {}", weights, fitted, expected);
+        }
+    }
+
+    // Levenberg-Marquardt迭代次数不足时回退到Nelder-Mead
+    let calibration = HestonCalibrator::new(100.0, 0.03, 0.01).unwrap().with_max_iterations(1).calibrate(&quotes).unwrap();
+    assert_eq!(calibration.report.optimizer, Optimizer::NelderMead);
+    // 缺少买卖价时无法使用价差权重
+    let mids: Vec<OptionQuote> = quotes.iter().map(|q| OptionQuote::new(q.strike, q.expiry, q.price, q.is_call).unwrap()).collect();
+    assert!(HestonCalibrator::new(100.0, 0.03, 0.01).unwrap().with_weights(WeightScheme::BidAsk).calibrate(&mids).is_err());
+
+    // 报告按名称列出拟合参数
+    let names: Vec<&str> = calibration.report.parameters.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, ["variance", "mean_reversion", "long_run_variance", "vol_of_vol", "correlation"]);
+    assert_eq!(calibration.report.parameters[4].1, calibration.params.correlation());
+    // 低于无套利下界的报价无法反解隐含波动率：等权时仍可校准，vega权重报错
+    let mut noisy = mids.clone();
+    let intrinsic = 100.0 * (-0.01f64 * 0.25).exp() - 60.0 * (-0.03f64 * 0.25).exp();
+    noisy.push(OptionQuote::new(60.0, 0.25, intrinsic - 0.01, true).unwrap());
+    assert!(HestonCalibrator::new(100.0, 0.03, 0.01).unwrap().calibrate(&noisy).is_ok());
+    assert!(HestonCalibrator::new(100.0, 0.03, 0.01).unwrap().with_weights(WeightScheme::Vega).calibrate(&noisy).is_err());
+}
+
+#[test]
+fn test_heston_engine_config() {
+    use optionrs::core::analytic::HestonAnalyticEngine;
+    use optionrs::params::heston::{HestonModelParams, HestonParams};
+    use optionrs::traits::payoff::{CallPayoff, PutPayoff};
+
+    // 经EngineConfig定价：现价、利率与期限取自CommonParams，忽略其波动率
+    let model = HestonModelParams::new(0.05, 1.5, 0.07, 0.6, -0.65).unwrap();
+    let engine = EngineConfig::heston(model).unwrap();
+    let params = CommonParams::new(105.0, 0.04, 0.3, 0.02, 0.5).unwrap();
+    let market = HestonParams::from_model(105.0, model, 0.04, 0.02, 0.5).unwrap();
+    let reference = HestonAnalyticEngine::new();
+    let call = engine.calculate_price(&params, &CallPayoff { strike: 100.0 }, &EuropeanExercise).unwrap();
+    let put = engine.calculate_price(&params, &PutPayoff { strike: 100.0 }, &EuropeanExercise).unwrap();
+    assert!((call - reference.price(&market, 100.0, true).unwrap()).abs() < 1e-12);
+    assert!((put - reference.price(&market, 100.0, false).unwrap()).abs() < 1e-12);
+    assert!(engine.calculate_price(&params, &CallPayoff { strike: 100.0 }, &AmericanExercise).is_err());
+}
+
+#[test]
+fn test_generic_calibration() {
+    use optionrs::calibration::{
+        Calibratable, CalibrationInstrument, CalibrationOptimizer, Calibrator, Optimizer,
+    };
+    use optionrs::traits::payoff::{Payoff, PutPayoff};
+    use optionrs::utils::optimization::{levenberg_marquardt, Minimum};
+
+    // 参数化偏斜：σ(K) = level + slope·ln(K/S)，以二叉树为美式看跌期权定价
+    #[derive(Clone)]
+    struct SkewModel {
+        level: f64,
+        slope: f64,
+    }
+    impl Calibratable for SkewModel {
+        type Engine = EngineConfig;
+        fn parameter_names(&self) -> Vec<&'static str> {
+            vec!["level", "slope"]
+        }
+        fn parameters(&self) -> Vec<f64> {
+            vec![self.level, self.slope]
+        }
+        fn bounds(&self) -> Vec<(f64, f64)> {
+            vec![(0.01, 1.0), (-1.0, 1.0)]
+        }
+        fn with_parameters(&self, parameters: &[f64]) -> Result<Self> {
+            Ok(Self { level: parameters[0], slope: parameters[1] })
+        }
+        fn engine(&self) -> Result<EngineConfig> {
+            EngineConfig::binomial(100)
+        }
+        fn market_params(&self, params: &CommonParams, payoff: &dyn Payoff) -> Result<CommonParams> {
+            let strike = payoff.as_any().downcast_ref::<PutPayoff>().unwrap().strike;
+            params.with_volatility(self.level + self.slope * (strike / params.spot()).ln())
+        }
+    }
+
+    // 用户自定义优化器
+    struct Wrapped;
+    impl CalibrationOptimizer for Wrapped {
+        fn kind(&self) -> Optimizer {
+            Optimizer::Custom("wrapped")
+        }
+        fn minimize(&self, residuals: &dyn Fn(&[f64]) -> Vec<f64>, initial: &[f64], lower: &[f64], upper: &[f64]) -> Result<Minimum> {
+            levenberg_marquardt(residuals, initial, lower, upper, 1e-14, 100)
+        }
+    }
+
+    let truth = SkewModel { level: 0.25, slope: -0.1 };
+    let base = CommonParams::new(100.0, 0.05, 0.2, 0.0, 1.0).unwrap();
+    let instruments: Vec<CalibrationInstrument> = [80.0, 90.0, 100.0, 110.0, 120.0]
+        .iter()
+        .map(|&strike| {
+            let payoff: Arc<dyn Payoff> = Arc::new(PutPayoff::new(strike));
+            let exercise: Arc<dyn ExerciseRule> = Arc::new(AmericanExercise);
+            CalibrationInstrument::new(base, payoff, exercise, strike, 0.0)
+        })
+        .collect();
+    let market = Calibrator::model_prices(&truth, &instruments).unwrap();
+    let instruments: Vec<CalibrationInstrument> = instruments
+        .into_iter()
+        .zip(market)
+        .map(|(mut instrument, price)| {
+            instrument.market_price = price;
+            instrument
+        })
+        .collect();
+
+    let start = SkewModel { level: 0.4, slope: 0.2 };
+    for calibrator in [
+        Calibrator::new(),
+        Calibrator::new().with_optimizers(vec![Arc::new(Wrapped)]).unwrap(),
+    ] {
+        let result = calibrator.calibrate(&start, &instruments).unwrap();
+        assert!((result.model.level - 0.25).abs() < 1e-6, "{}", result.model.level);
+        assert!((result.model.slope + 0.1).abs() < 1e-6, "{}", result.model.slope);
+        assert!(result.report.max_error() < 1e-8);
+        assert!(result.report.converged);
+    }
+    let custom = Calibrator::new().with_optimizers(vec![Arc::new(Wrapped)]).unwrap().calibrate(&start, &instruments).unwrap();
+    assert_eq!(custom.report.optimizer, Optimizer::Custom("wrapped"));
+    assert_eq!(custom.report.parameters, vec![("level", custom.model.level), ("slope", custom.model.slope)]);
+    // 约束排除真实参数时停在边界上
+    let bounded = Calibrator::new().with_bounds(vec![(0.3, 1.0), (-1.0, 1.0)]).unwrap().calibrate(&start, &instruments).unwrap();
+    assert!((bounded.model.level - 0.3).abs() < 1e-12 && bounded.report.rmse > 1e-3);
+}
+
+#[test]
+fn test_sabr_model() {
+    use optionrs::core::monte_carlo::MonteCarloEngine;
+    use optionrs::core::pde::{AdiEngine, AdiScheme};
+    use optionrs::params::sabr::SabrParams;
+    use optionrs::simulation::stochastic_volatility::SabrProcess;
+    use optionrs::traits::payoff::{CallPayoff, PutPayoff};
+    use optionrs::utils::statistics::{black_scholes_implied_volatility, norm_cdf, norm_pdf};
+    use optionrs::volatility::sabr::{sabr_lognormal_volatility, sabr_normal_volatility, SabrExpansion, SabrSmile};
+
+    // α = 2, β = 0.5时ATM波动率约20%
+    let params = SabrParams::new(2.0, 0.5, -0.3, 0.4).unwrap();
+    let (forward, expiry) = (100.0, 1.0);
+    let strikes = [70.0, 80.0, 90.0, 100.0, 110.0, 120.0, 130.0];
+    let process = Arc::new(SabrProcess::new(params));
+    let engine = MonteCarloEngine::new(20000, 50, Some(process), true, false, 7).unwrap();
+    let common = CommonParams::new(forward, 0.0, 0.2, 0.0, expiry).unwrap();
+    let adi = AdiEngine::new(100, 50, 50, AdiScheme::default()).unwrap();
+    for strike in [80.0, 100.0, 120.0] {
+        let hagan = sabr_lognormal_volatility(&params, forward, strike, expiry, SabrExpansion::Hagan).unwrap();
+        let obloj = sabr_lognormal_volatility(&params, forward, strike, expiry, SabrExpansion::Obloj).unwrap();
+        assert!((hagan - obloj).abs() < 1e-3, "K={}: {} vs {}", strike, hagan, obloj);
+        // 蒙特卡洛价格的隐含波动率与展开式一致
+        let price = engine.calculate_price(&common, &CallPayoff::new(strike), &EuropeanExercise).unwrap();
+        let implied = black_scholes_implied_volatility(price, forward, strike, 0.0, 0.0, expiry, true).unwrap();
+        assert!((implied - obloj).abs() < 4e-3, "K={}: MC {} vs {}", strike, implied, obloj);
+        // ADI在(F, ln α)网格上的价格与展开式一致
+        let price = adi.price_sabr(&params, &common, &CallPayoff::new(strike), &EuropeanExercise).unwrap();
+        let implied = black_scholes_implied_volatility(price, forward, strike, 0.0, 0.0, expiry, true).unwrap();
+        assert!((implied - obloj).abs() < 2e-3, "K={}: ADI {} vs {}", strike, implied, obloj);
+        // 正态波动率的Bachelier价格与对数正态波动率的Black价格一致
+        let normal = sabr_normal_volatility(&params, forward, strike, expiry, SabrExpansion::Obloj).unwrap();
+        let d = (forward - strike) / (normal * expiry.sqrt());
+        let bachelier = (forward - strike) * norm_cdf(d) + normal * expiry.sqrt() * norm_pdf(d);
+        let from_normal = black_scholes_implied_volatility(bachelier, forward, strike, 0.0, 0.0, expiry, true).unwrap();
+        assert!((from_normal - obloj).abs() < 1e-3, "K={}: {} vs {}", strike, from_normal, obloj);
+    }
+
+    // 逐到期日校准α、ρ、ν（β固定）
+    for expansion in [SabrExpansion::Hagan, SabrExpansion::Obloj] {
+        let vols: Vec<f64> = strikes
+            .iter()
+            .map(|&k| sabr_lognormal_volatility(&params, forward, k, expiry, expansion).unwrap())
+            .collect();
+        let calibration = SabrSmile::calibrate(forward, expiry, 0.5, &strikes, &vols, expansion).unwrap();
+        let fitted = calibration.smile.params;
+        assert!(calibration.converged && calibration.rmse < 1e-10);
+        assert!((fitted.alpha() - 2.0).abs() < 1e-6 && (fitted.rho() + 0.3).abs() < 1e-6 && (fitted.nu() - 0.4).abs() < 1e-6, "{:?}", fitted);
+    }
+
+    // β = 0的正态SABR允许负远期与负行权价
+    let normal_params = SabrParams::new(0.01, 0.0, 0.2, 0.3).unwrap();
+    let vol = sabr_normal_volatility(&normal_params, -0.002, 0.001, 2.0, SabrExpansion::Hagan).unwrap();
+    assert!(vol > 0.0 && (vol - 0.01).abs() < 2e-3, "{}", vol);
+    assert!(sabr_lognormal_volatility(&normal_params, -0.002, 0.001, 2.0, SabrExpansion::Hagan).is_err());
+
+    // ν = 0、β = 1退化为Black-Scholes，美式价格不低于欧式
+    let lognormal = SabrParams::new(0.2, 1.0, 0.0, 0.0).unwrap();
+    let common = CommonParams::new(100.0, 0.05, 0.2, 0.0, 1.0).unwrap();
+    let adi = AdiEngine::new(200, 20, 100, AdiScheme::default()).unwrap();
+    let european = adi.price_sabr(&lognormal, &common, &PutPayoff::new(100.0), &EuropeanExercise).unwrap();
+    let american = adi.price_sabr(&lognormal, &common, &PutPayoff::new(100.0), &AmericanExercise).unwrap();
+    let reference = AnalyticEngine::new()
+        .calculate_price(&CommonParams::new(100.0, 0.05, 0.2, 0.05, 1.0).unwrap(), &PutPayoff::new(100.0), &EuropeanExercise)
+        .unwrap();
+    assert!((european - reference).abs() < 1e-2, "{} vs {}", european, reference);
+    assert!(american > european + 0.05, "{} vs {}", american, european);
+}
+
+#[test]
+fn test_bachelier_model() {
+    use optionrs::core::analytic::calculators::{BachelierCalculator, DisplacedDiffusionCalculator};
+    use optionrs::core::binomial::BinomialEngine;
+    use optionrs::traits::payoff::{CallPayoff, PutPayoff};
+    use optionrs::utils::statistics::{bachelier_implied_volatility, bachelier_price, black_scholes_price};
+
+    let engine = AnalyticEngine::new();
+    // 负现价只在正态动态下合法
+    assert!(CommonParams::new(-0.005, 0.0, 0.01, 0.0, 1.0).is_err());
+    let atm = CommonParams::new_with_dynamics(-0.005, 0.0, 0.01, 0.0, 1.0, Dynamics::Normal).unwrap();
+    // ATM: σ√T/√(2π)
+    let price = engine.calculate_price(&atm, &CallPayoff::new(-0.005), &EuropeanExercise).unwrap();
+    assert!((price - 0.01 / (2.0 * std::f64::consts::PI).sqrt()).abs() < 1e-14);
+    assert!(engine.calculate_price(&atm, &CallPayoff::new(-0.005), &AmericanExercise).is_err());
+    assert!(BinomialEngine::new(100).unwrap().calculate_price(&atm, &CallPayoff::new(-0.005), &EuropeanExercise).is_err());
+    let mc = EngineConfig::monte_carlo(1000, 10, Some(Arc::new(GeometricBrownianMotion::from_financial_params(0.0, 0.0, 0.01).unwrap())), false, false, 1).unwrap();
+    assert!(mc.calculate_price(&atm, &CallPayoff::new(-0.005), &EuropeanExercise).is_err());
+
+    // 希腊字母与有限差分一致，看涨看跌满足平价
+    let (spot, r, q, vol, t) = (-0.002, 0.03, 0.01, 0.008, 2.0);
+    let params = CommonParams::new_with_dynamics(spot, r, vol, q, t, Dynamics::Normal).unwrap();
+    for strike in [-0.01, 0.001, 0.012] {
+        let call = engine.calculate_price(&params, &CallPayoff::new(strike), &EuropeanExercise).unwrap();
+        let put = engine.calculate_price(&params, &PutPayoff::new(strike), &EuropeanExercise).unwrap();
+        let parity = spot * (-q * t).exp() - strike * (-r * t).exp();
+        assert!((call - put - parity).abs() < 1e-15, "K={}: parity", strike);
+        for is_call in [true, false] {
+            let price = |s: f64, r: f64, v: f64, t: f64| bachelier_price(s, strike, r, q, v, t, is_call);
+            let greeks = if is_call {
+                BachelierCalculator::greeks(&params, &CallPayoff::new(strike)).unwrap()
+            } else {
+                BachelierCalculator::greeks(&params, &PutPayoff::new(strike)).unwrap()
+            };
+            let (hs, hv, h) = (1e-6, 1e-6, 1e-5);
+            let delta = (price(spot + hs, r, vol, t) - price(spot - hs, r, vol, t)) / (2.0 * hs);
+            let gamma = (price(spot + hs, r, vol, t) - 2.0 * price(spot, r, vol, t) + price(spot - hs, r, vol, t)) / (hs * hs);
+            let vega = (price(spot, r, vol + hv, t) - price(spot, r, vol - hv, t)) / (2.0 * hv);
+            let theta = (price(spot, r, vol, t - h) - price(spot, r, vol, t + h)) / (2.0 * h);
+            let rho = (price(spot, r + h, vol, t) - price(spot, r - h, vol, t)) / (2.0 * h);
+            assert!((greeks.delta - delta).abs() < 1e-8, "K={} call={}: delta {} vs {}", strike, is_call, greeks.delta, delta);
+            assert!((greeks.gamma - gamma).abs() < 1e-3 * gamma.abs().max(1.0), "K={}: gamma {} vs {}", strike, greeks.gamma, gamma);
+            assert!((greeks.vega - vega).abs() < 1e-8, "K={}: vega {} vs {}", strike, greeks.vega, vega);
+            assert!((greeks.theta - theta).abs() < 1e-8, "K={}: theta {} vs {}", strike, greeks.theta, theta);
+            assert!((greeks.rho - rho).abs() < 1e-8, "K={}: rho {} vs {}", strike, greeks.rho, rho);
+
+            // 正态隐含波动率往返
+            let quote = price(spot, r, vol, t);
+            let implied = bachelier_implied_volatility(quote, spot, strike, r, q, t, is_call).unwrap();
+            assert!((implied - vol).abs() < 1e-10, "K={}: implied {} vs {}", strike, implied, vol);
+        }
+    }
+    assert!(bachelier_implied_volatility(-1e-3, spot, 0.0, r, q, t, true).is_err());
+
+    // 平移扩散：F + shift > 0才合法，价格即平移后的Black-Scholes价格
+    let shift = 0.03;
+    assert!(CommonParams::new_with_dynamics(-0.04, 0.02, 0.2, 0.0, 1.0, Dynamics::DisplacedDiffusion { shift }).is_err());
+    let shifted = CommonParams::new_with_dynamics(-0.01, 0.02, 0.2, 0.0, 1.0, Dynamics::DisplacedDiffusion { shift }).unwrap();
+    let lognormal = DisplacedDiffusionCalculator::shifted_params(&shifted).unwrap();
+    assert!((lognormal.forward() - (shifted.forward() + shift)).abs() < 1e-15);
+    for strike in [-0.02, -0.005, 0.01] {
+        let call = engine.calculate_price(&shifted, &CallPayoff::new(strike), &EuropeanExercise).unwrap();
+        let put = engine.calculate_price(&shifted, &PutPayoff::new(strike), &EuropeanExercise).unwrap();
+        let expected = black_scholes_price(lognormal.spot(), strike + shift, 0.02, 0.0, 0.2, 1.0, true);
+        assert!((call - expected).abs() < 1e-15, "K={}: {} vs {}", strike, call, expected);
+        assert!((call - put - (-0.02f64).exp() * (shifted.forward() - strike)).abs() < 1e-15);
+    }
+    // 行权价低于-shift时看涨期权必然行权
+    let deep = engine.calculate_price(&shifted, &CallPayoff::new(-0.05), &EuropeanExercise).unwrap();
+    assert!((deep - (-0.02f64).exp() * (shifted.forward() + 0.05)).abs() < 1e-15);
+    // 平移很大时平移对数正态趋于正态模型，σ_N ≈ σ·√((F + shift)(K + shift))
+    let (large, sigma) = (1000.0, 1e-5);
+    let displaced = shifted.with_dynamics(Dynamics::DisplacedDiffusion { shift: large }).unwrap().with_volatility(sigma).unwrap();
+    for strike in [-0.02, 0.0, 0.02] {
+        let normal_vol = sigma * ((shifted.forward() + large) * (strike + large)).sqrt();
+        let normal = shifted.with_dynamics(Dynamics::Normal).unwrap().with_volatility(normal_vol).unwrap();
+        let a = engine.calculate_price(&displaced, &CallPayoff::new(strike), &EuropeanExercise).unwrap();
+        let b = engine.calculate_price(&normal, &CallPayoff::new(strike), &EuropeanExercise).unwrap();
+        assert!((a - b).abs() < 1e-6 * b, "K={}: {} vs {}", strike, a, b);
+    }
+}
+
+#[test]
+fn test_black76_and_garman_kohlhagen() {
+    use optionrs::core::analytic::calculators::{Black76Calculator, GarmanKohlhagenCalculator};
+    use optionrs::core::binomial::BinomialEngine;
+    use optionrs::params::black76::Black76Params;
+    use optionrs::params::fx::{FxPremium, GarmanKohlhagenParams};
+    use optionrs::traits::engine::AnalyticCalculator;
+    use optionrs::traits::payoff::{CallPayoff, PutPayoff};
+
+    let analytic = AnalyticEngine::new();
+    // Hull: F = 20, K = 20, r = 9%, σ = 25%, T = 4个月的期货看跌期权约1.12
+    let futures = Black76Params::new(20.0, 0.09, 0.25, 4.0 / 12.0).unwrap();
+    let put = Black76Calculator::price(&futures, &PutPayoff::new(20.0)).unwrap();
+    assert!((put - 1.1166).abs() < 1e-4, "Black-76 put {}", put);
+    // 通用引擎经to_common得到相同价格；美式期货期权不低于欧式
+    let common = futures.to_common().unwrap();
+    assert!((common.forward() - 20.0).abs() < 1e-12);
+    let engine_put = analytic.calculate_price(&common, &PutPayoff::new(20.0), &EuropeanExercise).unwrap();
+    assert!((engine_put - put).abs() < 1e-12);
+    // 作为AnalyticCalculator经from_common读取同一合约；行权价必须为正
+    assert!((Black76Calculator.calculate(&common, &PutPayoff::new(20.0)).unwrap() - put).abs() < 1e-12);
+    assert!(Black76Calculator::price(&futures, &CallPayoff::new(0.0)).is_err());
+    let binomial = BinomialEngine::new(1000).unwrap();
+    let european = binomial.calculate_price(&common, &PutPayoff::new(20.0), &EuropeanExercise).unwrap();
+    let american = binomial.calculate_price(&common, &PutPayoff::new(20.0), &AmericanExercise).unwrap();
+    assert!((european - put).abs() < 5e-3, "binomial {} vs {}", european, put);
+    assert!(american > european + 1e-3, "American {} vs European {}", american, european);
+
+    let bump = 1e-4;
+    for (strike, is_call) in [(18.0, true), (22.0, false)] {
+        let payoff: Box<dyn optionrs::traits::payoff::Payoff> =
+            if is_call { Box::new(CallPayoff::new(strike)) } else { Box::new(PutPayoff::new(strike)) };
+        let greeks = Black76Calculator::greeks(&futures, payoff.as_ref()).unwrap();
+        let price = |p: &Black76Params| Black76Calculator::price(p, payoff.as_ref()).unwrap();
+        let (f, r, v, t) = (20.0, 0.09, 0.25, 4.0 / 12.0);
+        let at = |f: f64, r: f64, v: f64, t: f64| price(&Black76Params::new(f, r, v, t).unwrap());
+        let delta = (at(f + bump, r, v, t) - at(f - bump, r, v, t)) / (2.0 * bump);
+        let gamma = (at(f + bump, r, v, t) - 2.0 * at(f, r, v, t) + at(f - bump, r, v, t)) / (bump * bump);
+        let vega = (at(f, r, v + bump, t) - at(f, r, v - bump, t)) / (2.0 * bump);
+        let theta = (at(f, r, v, t - bump) - at(f, r, v, t + bump)) / (2.0 * bump);
+        let rho = (at(f, r + bump, v, t) - at(f, r - bump, v, t)) / (2.0 * bump);
+        for (name, exact, numeric) in [
+            ("delta", greeks.delta, delta), ("gamma", greeks.gamma, gamma), ("vega", greeks.vega, vega),
+            ("theta", greeks.theta, theta), ("rho", greeks.rho, rho),
+        ] {
+            assert!((exact - numeric).abs() < 1e-5, "Black-76 K={} {}: {} vs {}", strike, name, exact, numeric);
+        }
+    }
+
+    // EUR/USD：S = 1.10, r_d = 5%, r_f = 3%
+    let fx = GarmanKohlhagenParams::new(1.10, 0.05, 0.03, 0.10, 0.5).unwrap();
+    let strike = 1.12;
+    let call = GarmanKohlhagenCalculator::price(&fx, &CallPayoff::new(strike), FxPremium::DomesticPips).unwrap();
+    let put = GarmanKohlhagenCalculator::price(&fx, &PutPayoff::new(strike), FxPremium::DomesticPips).unwrap();
+    assert!((call - put - (fx.spot() * fx.foreign_discount() - strike * fx.domestic_discount())).abs() < 1e-14);
+    let engine_call = analytic.calculate_price(&fx.to_common().unwrap(), &CallPayoff::new(strike), &EuropeanExercise).unwrap();
+    assert!((engine_call - call).abs() < 1e-14);
+    assert!((GarmanKohlhagenCalculator.calculate(&fx.to_common().unwrap(), &CallPayoff::new(strike)).unwrap() - call).abs() < 1e-14);
+    assert!(GarmanKohlhagenCalculator::price(&fx, &PutPayoff::new(-1.0), FxPremium::DomesticPips).is_err());
+    // 报价约定互相换算
+    for premium in [FxPremium::DomesticPips, FxPremium::ForeignPercent, FxPremium::DomesticPercent, FxPremium::ForeignPips] {
+        let quoted = GarmanKohlhagenCalculator::price(&fx, &CallPayoff::new(strike), premium).unwrap();
+        assert!((premium.to_domestic_pips(quoted, fx.spot(), strike) - call).abs() < 1e-15);
+    }
+    // 外币点数报价等于反向货币对（USD/EUR，利率互换）上行权价1/K的看跌期权价值
+    let inverse = GarmanKohlhagenParams::new(1.0 / 1.10, 0.03, 0.05, 0.10, 0.5).unwrap();
+    let inverse_put = GarmanKohlhagenCalculator::price(&inverse, &PutPayoff::new(1.0 / strike), FxPremium::DomesticPips).unwrap();
+    let foreign_pips = GarmanKohlhagenCalculator::price(&fx, &CallPayoff::new(strike), FxPremium::ForeignPips).unwrap();
+    assert!((foreign_pips - inverse_put).abs() < 1e-14, "{} vs {}", foreign_pips, inverse_put);
+
+    let bump = 1e-5;
+    for (strike, is_call) in [(1.05, true), (1.15, false)] {
+        let payoff: Box<dyn optionrs::traits::payoff::Payoff> =
+            if is_call { Box::new(CallPayoff::new(strike)) } else { Box::new(PutPayoff::new(strike)) };
+        let greeks = GarmanKohlhagenCalculator::greeks(&fx, payoff.as_ref()).unwrap();
+        let at = |s: f64, rd: f64, rf: f64, v: f64, t: f64| {
+            let params = GarmanKohlhagenParams::new(s, rd, rf, v, t).unwrap();
+            GarmanKohlhagenCalculator::price(&params, payoff.as_ref(), FxPremium::DomesticPips).unwrap()
+        };
+        let (s, rd, rf, v, t) = (1.10, 0.05, 0.03, 0.10, 0.5);
+        let delta = (at(s + bump, rd, rf, v, t) - at(s - bump, rd, rf, v, t)) / (2.0 * bump);
+        let gamma = (at(s + bump, rd, rf, v, t) - 2.0 * at(s, rd, rf, v, t) + at(s - bump, rd, rf, v, t)) / (bump * bump);
+        let vega = (at(s, rd, rf, v + bump, t) - at(s, rd, rf, v - bump, t)) / (2.0 * bump);
+        let theta = (at(s, rd, rf, v, t - bump) - at(s, rd, rf, v, t + bump)) / (2.0 * bump);
+        let rho_d = (at(s, rd + bump, rf, v, t) - at(s, rd - bump, rf, v, t)) / (2.0 * bump);
+        let rho_f = (at(s, rd, rf + bump, v, t) - at(s, rd, rf - bump, v, t)) / (2.0 * bump);
+        for (name, exact, numeric) in [
+            ("delta", greeks.delta, delta), ("gamma", greeks.gamma, gamma), ("vega", greeks.vega, vega),
+            ("theta", greeks.theta, theta), ("domestic rho", greeks.domestic_rho, rho_d),
+            ("foreign rho", greeks.foreign_rho, rho_f),
+        ] {
+            assert!((exact - numeric).abs() < 1e-4, "GK K={} {}: {} vs {}", strike, name, exact, numeric);
+        }
+        assert!((greeks.forward_delta - greeks.delta / fx.foreign_discount()).abs() < 1e-14);
+    }
+}
+
+#[test]
+fn test_fx_smile() {
+    use optionrs::core::analytic::calculators::GarmanKohlhagenCalculator;
+    use optionrs::params::fx::{FxAtmConvention, FxDeltaConvention, FxPremium, GarmanKohlhagenParams};
+    use optionrs::traits::payoff::{CallPayoff, PutPayoff};
+    use optionrs::volatility::fx::{FxButterfly, FxDeltaQuote, FxSmile, FxSmileConventions, FxSmileModel};
+
+    let (spot, rd, rf, expiry, atm) = (1.10, 0.05, 0.03, 0.5, 0.10);
+    let market = GarmanKohlhagenParams::new(spot, rd, rf, atm, expiry).unwrap();
+    let quotes = [FxDeltaQuote::new(0.25, -0.01, 0.0035), FxDeltaQuote::new(0.10, -0.019, 0.011)];
+    let strangle = |call_params: &GarmanKohlhagenParams, put_params: &GarmanKohlhagenParams, call: f64, put: f64| {
+        GarmanKohlhagenCalculator::price(call_params, &CallPayoff::new(call), FxPremium::DomesticPips).unwrap()
+            + GarmanKohlhagenCalculator::price(put_params, &PutPayoff::new(put), FxPremium::DomesticPips).unwrap()
+    };
+    for convention in [
+        FxDeltaConvention::Spot,
+        FxDeltaConvention::Forward,
+        FxDeltaConvention::PremiumAdjustedSpot,
+        FxDeltaConvention::PremiumAdjustedForward,
+    ] {
+        // delta与行权价互逆
+        for (delta, is_call) in [(0.25, true), (0.10, true), (-0.25, false), (-0.10, false)] {
+            let strike = GarmanKohlhagenCalculator::strike_from_delta(&market, delta, is_call, convention).unwrap();
+            let back = GarmanKohlhagenCalculator::delta(&market, strike, is_call, convention).unwrap();
+            assert!((back - delta).abs() < 1e-12, "{:?} {}: {} vs {}", convention, delta, back, delta);
+        }
+        // delta中性跨式
+        let dns = GarmanKohlhagenCalculator::atm_strike(&market, FxAtmConvention::DeltaNeutral, convention);
+        let straddle = GarmanKohlhagenCalculator::delta(&market, dns, true, convention).unwrap()
+            + GarmanKohlhagenCalculator::delta(&market, dns, false, convention).unwrap();
+        assert!(straddle.abs() < 1e-12, "{:?}: straddle delta {}", convention, straddle);
+
+        for butterfly in [FxButterfly::Smile, FxButterfly::Broker] {
+            let conventions = FxSmileConventions { delta: convention, atm: FxAtmConvention::DeltaNeutral, butterfly };
+            let vanna_volga = FxSmile::build(spot, rd, rf, expiry, atm, &quotes[..1], conventions, FxSmileModel::VannaVolga).unwrap();
+            let sabr = FxSmile::build(spot, rd, rf, expiry, atm, &quotes, conventions, FxSmileModel::Sabr { beta: 1.0 }).unwrap();
+            assert!((dns - vanna_volga.atm_strike()).abs() < 1e-15);
+            // Vanna-Volga精确穿过三个支点
+            assert!((vanna_volga.volatility(dns).unwrap() - atm).abs() < 1e-12);
+            for pillar in vanna_volga.pillars() {
+                assert!((vanna_volga.volatility(pillar.strike).unwrap() - pillar.volatility).abs() < 1e-12);
+                let is_call = pillar.delta > 0.0;
+                let params = vanna_volga.params(pillar.strike).unwrap();
+                let delta = GarmanKohlhagenCalculator::delta(&params, pillar.strike, is_call, convention).unwrap();
+                assert!((delta - pillar.delta).abs() < 1e-12, "{:?}: pillar delta {} vs {}", convention, delta, pillar.delta);
+                if butterfly == FxButterfly::Smile {
+                    let rr = if is_call { 0.5 } else { -0.5 } * quotes[0].risk_reversal;
+                    assert!((pillar.volatility - (atm + quotes[0].butterfly + rr)).abs() < 1e-15);
+                }
+            }
+            // SABR以三个参数拟合全部五个支点，残差约在10bp以内
+            for pillar in sabr.pillars() {
+                let error = sabr.volatility(pillar.strike).unwrap() - pillar.volatility;
+                assert!(error.abs() < 1e-3, "{:?} {:?} {}: SABR error {}", convention, butterfly, pillar.delta, error);
+            }
+            if butterfly == FxButterfly::Broker {
+                // 微笑重现以单一波动率σ_ATM + BF定价的市场宽跨式
+                for (smile, quotes) in [(&vanna_volga, &quotes[..1]), (&sabr, &quotes[..])] {
+                    for quote in quotes {
+                        let one_vol = market.with_volatility(atm + quote.butterfly).unwrap();
+                        let call = GarmanKohlhagenCalculator::strike_from_delta(&one_vol, quote.delta, true, convention).unwrap();
+                        let put = GarmanKohlhagenCalculator::strike_from_delta(&one_vol, -quote.delta, false, convention).unwrap();
+                        let target = strangle(&one_vol, &one_vol, call, put);
+                        let repriced = strangle(&smile.params(call).unwrap(), &smile.params(put).unwrap(), call, put);
+                        assert!((repriced - target).abs() < 1e-9, "{:?} {}: {} vs {}", convention, quote.delta, repriced, target);
+                    }
+                    assert!(smile.smile_butterflies()[0] > quotes[0].butterfly);
+                }
+            }
+        }
+    }
+    assert!(FxSmile::build(spot, rd, rf, expiry, atm, &quotes, FxSmileConventions::default(), FxSmileModel::VannaVolga).is_err());
+}
+
+#[test]
+fn test_vanna_volga() {
+    use optionrs::core::vanna_volga::{VannaVolgaEngine, VannaVolgaPivot, VannaVolgaWeighting};
+    use optionrs::params::fx::GarmanKohlhagenParams;
+    use optionrs::traits::payoff::{CallPayoff, DownAndOutCallPayoff, NoTouchPayoff, OneTouchPayoff};
+    use optionrs::utils::statistics::black_scholes_price;
+    use optionrs::volatility::fx::{FxDeltaQuote, FxSmile, FxSmileConventions, FxSmileModel};
+
+    let (spot, rd, rf, expiry, atm) = (1.10, 0.05, 0.03, 0.5, 0.10);
+    let quotes = [FxDeltaQuote::new(0.25, -0.01, 0.0035)];
+    let smile = FxSmile::build(spot, rd, rf, expiry, atm, &quotes, FxSmileConventions::default(), FxSmileModel::VannaVolga).unwrap();
+    let engine = VannaVolgaEngine::from_smile(AnalyticEngine::new(), &smile).unwrap();
+    let params = GarmanKohlhagenParams::new(spot, rd, rf, atm, expiry).unwrap().to_common().unwrap();
+
+    // 支点本身被精确复制；其他普通期权与Castagna-Mercurio微笑一致
+    for pivot in engine.pivots() {
+        let price = engine.calculate_price(&params, &CallPayoff::new(pivot.strike), &EuropeanExercise).unwrap();
+        let market = black_scholes_price(spot, pivot.strike, rd, rf, pivot.volatility, expiry, true);
+        assert!((price - market).abs() < 1e-8, "pivot {}: {} vs {}", pivot.strike, price, market);
+    }
+    for strike in [1.0, 1.08, 1.2] {
+        let price = engine.calculate_price(&params, &CallPayoff::new(strike), &EuropeanExercise).unwrap();
+        let market = black_scholes_price(spot, strike, rd, rf, smile.volatility(strike).unwrap(), expiry, true);
+        assert!((price - market).abs() < 5e-5, "K={}: {} vs {}", strike, price, market);
+    }
+
+    // 一触即付与无触碰之和为贴现的支付额；负风险逆转使向上触碰更便宜
+    let one_touch = engine.adjustment(&params, &OneTouchPayoff::new(1.20, 1.0, true), &EuropeanExercise).unwrap();
+    let no_touch = engine.adjustment(&params, &NoTouchPayoff::new(1.20, 1.0, true), &EuropeanExercise).unwrap();
+    assert!((one_touch.price + no_touch.price - (-rd * expiry).exp()).abs() < 1e-8);
+    assert!(one_touch.price < one_touch.flat_price);
+
+    // 按无触碰概率缩放对冲成本
+    let barrier = DownAndOutCallPayoff { strike: 1.10, barrier: 1.05 };
+    let full = engine.adjustment(&params, &barrier, &EuropeanExercise).unwrap();
+    let survival = engine.clone().with_weighting(VannaVolgaWeighting::Survival)
+        .adjustment(&params, &barrier, &EuropeanExercise).unwrap();
+    assert!(survival.scaling > 0.0 && survival.scaling < 1.0);
+    assert!((survival.price - survival.flat_price - survival.scaling * (full.price - full.flat_price)).abs() < 1e-15);
+    assert!(engine.clone().with_weighting(VannaVolgaWeighting::Survival)
+        .calculate_price(&params, &CallPayoff::new(1.1), &EuropeanExercise).is_err());
+
+    // 平坦微笑没有修正
+    let flat = VannaVolgaEngine::new(AnalyticEngine::new(), [
+        VannaVolgaPivot { strike: 1.05, volatility: atm },
+        VannaVolgaPivot { strike: 1.11, volatility: atm },
+        VannaVolgaPivot { strike: 1.17, volatility: atm },
+    ]).unwrap();
+    let adjustment = flat.adjustment(&params, &barrier, &EuropeanExercise).unwrap();
+    assert_eq!(adjustment.price, adjustment.flat_price);
+}
+
+#[test]
+fn test_variance_swap() {
+    use optionrs::core::analytic::heston::HestonAnalyticEngine;
+    use optionrs::core::binomial::BinomialEngine;
+    use optionrs::core::monte_carlo::MonteCarloEngine;
+    use optionrs::core::variance_swap::{heston_realized_variance_moments, volatility_swap_strike, VarianceSwapEngine};
+    use optionrs::params::heston::HestonParams;
+    use optionrs::simulation::stochastic_volatility::HestonProcess;
+    use optionrs::traits::payoff::{RealizedVariancePayoff, RealizedVolatilityPayoff};
+    use optionrs::volatility::{SurfaceModel, VolQuote, VolSurface};
+
+    let (spot, r, q, t, vol) = (100.0, 0.05, 0.02, 1.0, 0.25);
+    let params = CommonParams::new(spot, r, vol, q, t).unwrap();
+    let forward = spot * ((r - q) * t).exp();
+    let engine = VarianceSwapEngine::uniform(10.0, 400.0, 781).unwrap();
+
+    // 平坦波动率下复制得到σ²；互换价值为贴现的N·(K_fair - K_var)
+    assert!((engine.fair_variance(&params).unwrap() - vol * vol).abs() < 1e-5);
+    let swap = RealizedVariancePayoff::from_volatility_strike(0.2, 1.0, t);
+    let value = engine.calculate_price(&params, &swap, &EuropeanExercise).unwrap();
+    assert!((value - (-r * t).exp() * (vol * vol - 0.04) / 0.4).abs() < 1e-5);
+    assert!(engine.calculate_price(&params, &RealizedVolatilityPayoff::new(0.2, 1.0, t), &EuropeanExercise).is_err());
+    let mismatched = RealizedVariancePayoff::from_volatility_strike(0.2, 1.0, 2.0 * t);
+    assert!(engine.calculate_price(&params, &mismatched, &EuropeanExercise).is_err());
+    assert!(VarianceSwapEngine::uniform(110.0, 400.0, 100).unwrap().fair_variance(&params).is_err());
+
+    // 偏斜曲面使公允方差高于平值方差
+    let log_moneyness = |k: f64| (k / 100.0_f64).ln();
+    let quotes: Vec<VolQuote> = [0.5, 1.0]
+        .iter()
+        .flat_map(|&t| {
+            [70.0, 85.0, 100.0, 115.0, 130.0]
+                .iter()
+                .map(move |&k| VolQuote::new(k, t, 0.25 - 0.1 * log_moneyness(k) + 0.05 * log_moneyness(k).powi(2)).unwrap())
+        })
+        .collect();
+    let surface = VolSurface::from_quotes(spot, r, q, &quotes, SurfaceModel::Svi).unwrap();
+    let atm_variance = surface.volatility(forward, t).unwrap().powi(2);
+    let skewed = engine.clone().with_surface(surface).fair_variance(&params).unwrap();
+    assert!(skewed > atm_variance + 5e-3, "{} vs {}", skewed, atm_variance);
+
+    // Heston：期权复制与已实现方差的解析均值一致，蒙特卡洛与复制及凸性修正一致
+    let heston = HestonParams::new(spot, 0.04, 2.0, 0.06, 0.4, -0.7, r, q, t).unwrap();
+    let pricer = HestonAnalyticEngine::new();
+    let replicated = engine.fair_variance_with(forward, r, t, |k, is_call| pricer.price(&heston, k, is_call)).unwrap();
+    let (mean, variance) = heston_realized_variance_moments(&heston).unwrap();
+    assert!((replicated - mean).abs() < 2e-5, "{} vs {}", replicated, mean);
+
+    let discount = (-r * t).exp();
+    let process = HestonProcess::new(*heston.model(), r, q);
+    let mc = MonteCarloEngine::new(10_000, 100, Some(Arc::new(process)), true, true, 42).unwrap();
+    let mc_variance = mc.calculate_price(&params, &RealizedVariancePayoff::new(0.0, 1.0, t), &EuropeanExercise).unwrap() / discount;
+    let mc_volatility = mc.calculate_price(&params, &RealizedVolatilityPayoff::new(0.0, 1.0, t), &EuropeanExercise).unwrap() / discount;
+    let convexity = volatility_swap_strike(mean, variance).unwrap();
+    assert!((mc_variance - mean).abs() < 1e-3, "{} vs {}", mc_variance, mean);
+    assert!((mc_volatility - convexity).abs() < 3e-3, "{} vs {}", mc_volatility, convexity);
+    assert!(convexity < mean.sqrt());
+    // 已实现方差只能在整条路径上估值
+    assert!(BinomialEngine::new(100).unwrap().calculate_price(&params, &swap, &EuropeanExercise).is_err());
+    assert!(mc.calculate_price(&params, &swap, &AmericanExercise).is_err());
+}
+
+#[test]
+fn test_fourier_engine() {
+    use optionrs::core::analytic::heston::HestonAnalyticEngine;
+    use optionrs::core::binomial::BinomialEngine;
+    use optionrs::core::fourier::{
+        BatesModel, BlackScholesModel, CgmyModel, FourierEngine, NigModel, VarianceGammaModel,
+    };
+    use optionrs::params::heston::HestonParams;
+    use optionrs::traits::characteristic::CharacteristicFunction;
+    use optionrs::traits::payoff::{CallPayoff, PutPayoff};
+
+    // Fang-Oosterlee (2008) 的VG与CGMY参考价格
+    let params = CommonParams::new(100.0, 0.1, 0.2, 0.0, 1.0).unwrap();
+    let references: [(Arc<dyn CharacteristicFunction>, f64, f64); 3] = [
+        (Arc::new(VarianceGammaModel::new(0.12, 0.2, -0.14).unwrap()), 90.0, 19.099354724),
+        (Arc::new(CgmyModel::new(1.0, 5.0, 5.0, 0.5).unwrap()), 100.0, 19.812948843),
+        (Arc::new(CgmyModel::new(1.0, 5.0, 5.0, 1.5).unwrap()), 100.0, 49.790905469),
+    ];
+    for (model, strike, reference) in references {
+        let cos = FourierEngine::cos(model.clone()).calculate_price(&params, &CallPayoff::new(strike), &EuropeanExercise).unwrap();
+        let fft = FourierEngine::carr_madan(model).calculate_price(&params, &CallPayoff::new(strike), &EuropeanExercise).unwrap();
+        assert!((cos - reference).abs() < 1e-8, "COS {} vs {}", cos, reference);
+        assert!((fft - reference).abs() < 1e-6, "FFT {} vs {}", fft, reference);
+    }
+    // CommonParams的波动率不参与定价
+    let model: Arc<dyn CharacteristicFunction> = Arc::new(VarianceGammaModel::new(0.12, 0.2, -0.14).unwrap());
+    let repriced = FourierEngine::cos(model)
+        .calculate_price(&params.with_volatility(0.6).unwrap(), &CallPayoff::new(90.0), &EuropeanExercise)
+        .unwrap();
+    assert!((repriced - 19.099354724).abs() < 1e-8);
+
+    // Heston与半解析定价一致；一次FFT得到整条行权价序列
+    let heston = HestonParams::new(100.0, 0.04, 1.5, 0.06, 0.5, -0.7, 0.03, 0.01, 1.0).unwrap();
+    let model = *heston.model();
+    let params = CommonParams::new(100.0, 0.03, 0.2, 0.01, 1.0).unwrap();
+    let analytic = HestonAnalyticEngine::new();
+    let strikes = [70.0, 85.0, 100.0, 115.0, 130.0];
+    let strip = FourierEngine::carr_madan(Arc::new(model)).price_strip(&params, &strikes, false).unwrap();
+    for (&strike, &fft) in strikes.iter().zip(&strip) {
+        let reference = analytic.price(&heston, strike, false).unwrap();
+        let cos = FourierEngine::cos(Arc::new(model)).calculate_price(&params, &PutPayoff::new(strike), &EuropeanExercise).unwrap();
+        assert!((cos - reference).abs() < 1e-7, "K={} COS {} vs {}", strike, cos, reference);
+        assert!((fft - reference).abs() < 5e-5, "K={} FFT {} vs {}", strike, fft, reference);
+    }
+
+    // 无跳跃的Bates即Heston；跳跃模型的两种方法一致
+    let no_jumps = FourierEngine::cos(Arc::new(BatesModel::new(model, 0.0, -0.1, 0.15).unwrap()));
+    let price = no_jumps.calculate_price(&params, &CallPayoff::new(100.0), &EuropeanExercise).unwrap();
+    assert!((price - analytic.price(&heston, 100.0, true).unwrap()).abs() < 1e-7);
+    let jump_models: [Arc<dyn CharacteristicFunction>; 2] = [
+        Arc::new(BatesModel::new(model, 0.5, -0.1, 0.15).unwrap()),
+        Arc::new(NigModel::new(15.0, -5.0, 0.5).unwrap()),
+    ];
+    for model in jump_models {
+        let cos = FourierEngine::cos(model.clone()).calculate_price(&params, &CallPayoff::new(100.0), &EuropeanExercise).unwrap();
+        let fft = FourierEngine::carr_madan(model).calculate_price(&params, &CallPayoff::new(100.0), &EuropeanExercise).unwrap();
+        assert!((cos - fft).abs() < 1e-6, "{} vs {}", cos, fft);
+    }
+
+    // 百慕大COS递推：单个行权日即欧式，多个行权日与二叉树一致
+    let black_scholes = FourierEngine::cos(Arc::new(BlackScholesModel::new(0.2).unwrap()));
+    let params = CommonParams::new(100.0, 0.1, 0.2, 0.0, 1.0).unwrap();
+    let put = PutPayoff::new(110.0);
+    let european = black_scholes.calculate_price(&params, &put, &EuropeanExercise).unwrap();
+    let single = black_scholes.calculate_price(&params, &put, &BermudanExercise::periodic(1.0, 1).unwrap()).unwrap();
+    assert!((single - european).abs() < 1e-10);
+    let schedule = BermudanExercise::periodic(1.0, 10).unwrap();
+    let bermudan = black_scholes.calculate_price(&params, &put, &schedule).unwrap();
+    let tree = BinomialEngine::new(5000).unwrap().calculate_price(&params, &put, &schedule).unwrap();
+    assert!((bermudan - 10.479520123).abs() < 1e-6, "{}", bermudan);
+    assert!((bermudan - tree).abs() < 1e-3, "{} vs {}", bermudan, tree);
+
+    // 非Lévy模型、FFT方法与美式行权不支持百慕大递推
+    assert!(FourierEngine::cos(Arc::new(model)).calculate_price(&params, &put, &schedule).is_err());
+    assert!(FourierEngine::carr_madan(Arc::new(BlackScholesModel::new(0.2).unwrap())).calculate_price(&params, &put, &schedule).is_err());
+    assert!(black_scholes.calculate_price(&params, &put, &AmericanExercise).is_err());
+}